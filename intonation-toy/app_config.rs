@@ -34,6 +34,47 @@ pub const USER_PITCH_LINE_THICKNESS_MAX: f32 = 20.0;
 pub const USER_PITCH_LINE_TRANSPARENCY_MIN: f32 = 0.0;
 pub const USER_PITCH_LINE_TRANSPARENCY_MAX: f32 = 1.0;
 
+/// Pitch history trace configuration
+pub const PITCH_HISTORY_DURATION_SECONDS: f64 = 5.0;
+/// Samples further apart than this are drawn as a break in the trace
+pub const PITCH_HISTORY_MAX_GAP_SECONDS: f64 = 0.25;
+
+/// Spectrum overlay configuration, frequencies are drawn on a logarithmic axis
+pub const SPECTRUM_MIN_FREQUENCY_HZ: f32 = 50.0;
+pub const SPECTRUM_MAX_FREQUENCY_HZ: f32 = 8000.0;
+/// Levels at or below this are drawn at the bottom of the overlay
+pub const SPECTRUM_FLOOR_DB: f32 = -80.0;
+/// Fraction of the viewport height covered by the overlay
+pub const SPECTRUM_OVERLAY_HEIGHT_RATIO: f32 = 0.25;
+pub const SPECTRUM_COLUMN_WIDTH_PX: f32 = 4.0;
+pub const SPECTRUM_MAX_HARMONICS: u32 = 16;
+
+/// Pitch view configuration, ranges are the interval from the center to the edge in octaves
+pub const PITCH_VIEW_ZOOM_LEVELS: [f32; 7] = [1.0 / 12.0, 0.25, 0.5, 1.0, 1.5, 2.0, 3.0];
+/// Index into `PITCH_VIEW_ZOOM_LEVELS` of the initial ±1 octave view
+pub const DEFAULT_PITCH_VIEW_ZOOM_LEVEL: usize = 3;
+/// Panning moves the view by this fraction of its half range
+pub const PITCH_VIEW_PAN_STEP_RATIO: f32 = 0.5;
+/// Auto-follow keeps the pitch within this fraction of the half range around the center
+pub const PITCH_VIEW_FOLLOW_MARGIN_RATIO: f32 = 0.7;
+/// Farthest the view center can move from the root in octaves
+pub const PITCH_VIEW_MAX_CENTER_OCTAVES: f32 = 5.0;
+
+/// Cents grid configuration, offsets are drawn above and below each tuning line
+pub const CENTS_GRID_OFFSETS: [f32; 3] = [5.0, 10.0, 25.0];
+/// Offsets closer than this to their tuning line are left out, e.g. when zoomed out
+pub const CENTS_GRID_MIN_SPACING_PX: f32 = 4.0;
+pub const CENTS_GRID_LINE_THICKNESS: f32 = 1.0;
+pub const CENTS_GRID_ALPHA: f32 = 0.25;
+
+/// Target note playback configuration, a click on a tuning line plays its note this long
+pub const TARGET_NOTE_DURATION_SECONDS: f32 = 1.5;
+pub const TARGET_NOTE_VOLUME: f32 = 0.3;
+pub const TARGET_NOTE_ATTACK_SECONDS: f32 = 0.02;
+pub const TARGET_NOTE_RELEASE_SECONDS: f32 = 0.4;
+/// Clicks farther than this from every tuning line are ignored
+pub const TUNING_LINE_CLICK_TOLERANCE_PX: f32 = 12.0;
+
 /// Octave line thickness configuration
 pub const OCTAVE_LINE_THICKNESS: f32 = 3.0;
 pub const REGULAR_LINE_THICKNESS: f32 = 1.0;
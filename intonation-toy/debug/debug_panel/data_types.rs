@@ -3,6 +3,8 @@
 
 use crate::engine::audio::AudioWorkletState;
 use crate::engine::audio::buffer::AUDIO_CHUNK_SIZE;
+use crate::engine::audio::pitch_analyzer::AlgorithmMetrics;
+use crate::shared_types::PitchDetectionAlgorithm;
 
 /// Performance metrics for display
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +19,10 @@ pub struct PerformanceMetrics {
     pub memory_usage_percent: f64,
     /// Audio processing latency in milliseconds (from PitchAnalyzer)
     pub audio_latency: f64,
+    /// Pitch detection algorithm in use (from PitchAnalyzer)
+    pub pitch_algorithm: PitchDetectionAlgorithm,
+    /// Per-algorithm detection statistics (from PitchAnalyzer)
+    pub algorithm_metrics: Vec<AlgorithmMetrics>,
 }
 
 impl Default for PerformanceMetrics {
@@ -26,6 +32,8 @@ impl Default for PerformanceMetrics {
             memory_usage_mb: 0.0,
             memory_usage_percent: 0.0,
             audio_latency: 0.0,
+            pitch_algorithm: PitchDetectionAlgorithm::McLeod,
+            algorithm_metrics: Vec::new(),
         }
     }
 }
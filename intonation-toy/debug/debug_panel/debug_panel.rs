@@ -7,18 +7,14 @@ use crate::engine::audio::{
     buffer::AUDIO_CHUNK_SIZE,
 };
 use crate::debug::debug_data::DebugData;
-use crate::shared_types::{TuningSystem, MidiNote, increment_midi_note, decrement_midi_note};
+use crate::shared_types::{TuningSystem, MidiNote, PitchDetectionAlgorithm, Glide, TestSignalShape, TestWaveform, increment_midi_note, decrement_midi_note};
 
-/// Get just the note name (without octave) from a MIDI note number
-fn midi_note_to_display_name(midi_note: MidiNote) -> String {
-    let full_name = crate::shared_types::midi_note_to_name(midi_note);
-    // Extract just the note name by removing the octave number
-    let note_end = full_name.chars().position(|c| c.is_numeric() || c == '-').unwrap_or(full_name.len());
-    full_name[..note_end].to_string()
-}
 use std::rc::Rc;
 use std::cell::RefCell;
 
+/// Number of harmonics that can be set for a custom test signal spectrum
+const TEST_SIGNAL_HARMONIC_COUNT: usize = 8;
+
 pub struct DebugPanel {
     debug_data: DebugData,
     presenter: Rc<RefCell<crate::presentation::Presenter>>,
@@ -29,6 +25,19 @@ pub struct DebugPanel {
     test_signal_volume: f32,
     test_signal_midi_note: MidiNote,
     test_signal_nudge_percent: f32,
+    /// Selected waveform, the harmonic amplitudes are kept separately
+    test_signal_waveform: TestWaveform,
+    test_signal_harmonics: [f32; TEST_SIGNAL_HARMONIC_COUNT],
+    test_signal_vibrato_rate_hz: f32,
+    test_signal_vibrato_depth_cents: f32,
+    test_signal_glide_enabled: bool,
+    /// Glide target relative to the test signal frequency
+    test_signal_glide_semitones: f32,
+    test_signal_glide_seconds: f32,
+    test_signal_noise_enabled: bool,
+    test_signal_snr_db: f32,
+    pitch_algorithm: PitchDetectionAlgorithm,
+    compare_algorithms: bool,
 }
 
 impl DebugPanel {
@@ -62,6 +71,17 @@ impl DebugPanel {
             test_signal_volume: 15.0,
             test_signal_midi_note: initial_midi_note,
             test_signal_nudge_percent: 0.0,
+            test_signal_waveform: TestWaveform::Sine,
+            test_signal_harmonics: std::array::from_fn(|k| 1.0 / (k + 1) as f32),
+            test_signal_vibrato_rate_hz: 5.5,
+            test_signal_vibrato_depth_cents: 0.0,
+            test_signal_glide_enabled: false,
+            test_signal_glide_semitones: 12.0,
+            test_signal_glide_seconds: 2.0,
+            test_signal_noise_enabled: false,
+            test_signal_snr_db: 20.0,
+            pitch_algorithm: PitchDetectionAlgorithm::McLeod,
+            compare_algorithms: false,
         }
     }
     
@@ -124,8 +144,12 @@ impl DebugPanel {
                 self.render_pitch_detection_section(ui);
                 ui.separator();
                 
+                // Pitch Detection Algorithm Section (debug actions and per-algorithm metrics)
+                self.render_pitch_algorithm_section(ui);
+                ui.separator();
+                
                 // Accuracy Section (core data via interface)
-                self.render_accuracy_section(ui);
+                self.render_accuracy_section(ui, model_data);
                 ui.separator();
                 
                 // Test Signal Controls Section (debug actions)
@@ -372,17 +396,83 @@ impl DebugPanel {
             });
     }
     
+    /// Render pitch detection algorithm selection and comparison (debug actions)
+    fn render_pitch_algorithm_section(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Pitch Detection Algorithm")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut changed = false;
+                
+                ui.horizontal(|ui| {
+                    ui.label("Algorithm:");
+                    egui::ComboBox::from_id_source("pitch_algorithm")
+                        .selected_text(self.pitch_algorithm.name())
+                        .show_ui(ui, |ui| {
+                            for algorithm in PitchDetectionAlgorithm::ALL {
+                                changed |= ui.selectable_value(&mut self.pitch_algorithm, algorithm, algorithm.name()).changed();
+                            }
+                        });
+                });
+                
+                changed |= ui.checkbox(&mut self.compare_algorithms, "Compare all algorithms").changed();
+                
+                if changed {
+                    self.send_pitch_detection_action();
+                }
+                
+                let metrics = &self.debug_data.performance_metrics;
+                if metrics.pitch_algorithm != self.pitch_algorithm {
+                    ui.colored_label(Color32::YELLOW, format!("Engine is using {}", metrics.pitch_algorithm.name()));
+                }
+                
+                egui::Grid::new("pitch_algorithm_metrics")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Algorithm");
+                        ui.label("Frequency");
+                        ui.label("Clarity");
+                        ui.label("Success");
+                        ui.label("Time");
+                        ui.end_row();
+                        
+                        for algorithm_metrics in metrics.algorithm_metrics.iter().filter(|m| m.analysis_cycles > 0) {
+                            let color = if algorithm_metrics.algorithm == metrics.pitch_algorithm {
+                                Color32::GREEN
+                            } else {
+                                Color32::GRAY
+                            };
+                            ui.colored_label(color, algorithm_metrics.algorithm.name());
+                            match algorithm_metrics.last_frequency {
+                                Some(frequency) => ui.label(format!("{:.2} Hz", frequency)),
+                                None => ui.label("--"),
+                            };
+                            ui.label(format!("{:.2}", algorithm_metrics.average_clarity));
+                            ui.label(format!("{:.0}%", algorithm_metrics.success_rate * 100.0));
+                            ui.label(format!("{:.0} µs", algorithm_metrics.average_processing_time_us));
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+    
+    /// Name a note like the presenter does, spelled for the current root and scale in written pitch
+    fn note_name(&self, midi_note: MidiNote, model_data: &crate::shared_types::ModelUpdateResult) -> String {
+        let system = self.presenter.try_borrow()
+            .map(|presenter| presenter.note_naming_system())
+            .unwrap_or_default();
+        crate::theory::note_naming::written_note_name(midi_note, model_data.root_note, model_data.scale, system, model_data.transposition)
+    }
+
     /// Render accuracy section (core data via interface)
-    fn render_accuracy_section(&self, ui: &mut Ui) {
+    fn render_accuracy_section(&self, ui: &mut Ui, model_data: &crate::shared_types::ModelUpdateResult) {
         egui::CollapsingHeader::new("Intonation")
             .default_open(true)
             .show(ui, |ui| {
                 // Always reserve space for consistent height
                 if let Some(intonation) = self.debug_data.get_intonation_data() {
                     // Display closest MIDI note
-                    let note_name = midi_note_to_display_name(intonation.closest_midi_note);
-                    let octave = (intonation.closest_midi_note as i16 / 12) - 1;
-                    ui.label(format!("Closest Note: {}{}", note_name, octave));
+                    let note_name = self.note_name(intonation.closest_midi_note, model_data);
+                    ui.label(format!("Closest Note: {}", note_name));
                     
                     // Display cents offset with color coding
                     ui.horizontal(|ui| {
@@ -397,13 +487,30 @@ impl DebugPanel {
                         };
                         ui.colored_label(color, display_text);
                     });
+
+                    // Display the just ratio the pitch was matched to
+                    if let Some(ratio) = model_data.matched_ratio {
+                        ui.label(format!("Ratio: {}", ratio.label_with_cents(intonation.cents_offset)));
+                    }
+
+                    // Display the measured inharmonicity and the active stretch curve
+                    if let Some(inharmonicity) = model_data.inharmonicity {
+                        ui.label(format!("Inharmonicity: B = {:.5}", inharmonicity));
+                    }
+                    if model_data.stretch_tuning != crate::shared_types::StretchTuning::None {
+                        ui.label(format!("Stretch: {}", model_data.stretch_tuning.name()));
+                    }
                     
                     // Display interval information
                     ui.horizontal(|ui| {
                         ui.label("Interval:");
                         if let (Some(interval_semitones), Some(_)) = 
                             (self.debug_data.get_interval_semitones(), self.debug_data.get_root_note()) {
-                            let interval_name = crate::shared_types::interval_name_from_semitones(interval_semitones);
+                            let interval_name = crate::shared_types::interval_name_from_steps(
+                                model_data.interval_steps,
+                                model_data.tuning_system.tuning().degrees_per_octave(),
+                                interval_semitones,
+                            );
                             let (color, display_text) = if interval_semitones == 0 {
                                 (Color32::GREEN, format!("{} ({:+} st)", interval_name, interval_semitones))
                             } else if interval_semitones.abs() == 12 || interval_semitones.abs() == 7 || interval_semitones.abs() == 5 {
@@ -441,9 +548,8 @@ impl DebugPanel {
                     ui.label("MIDI Note:");
                     
                     // Display current MIDI note name
-                    let note_name = midi_note_to_display_name(self.test_signal_midi_note);
-                    let octave = (self.test_signal_midi_note as i16 / 12) - 1;
-                    ui.label(format!("{}{} ({})", note_name, octave, self.test_signal_midi_note));
+                    let note_name = self.note_name(self.test_signal_midi_note, model_data);
+                    ui.label(format!("{} ({})", note_name, self.test_signal_midi_note));
                     
                     // Decrement button with bounds checking
                     let can_decrement = self.test_signal_midi_note > 0;
@@ -474,7 +580,8 @@ impl DebugPanel {
                     // Display current frequency with error handling
                     match self.calculate_midi_note_frequency_safe(
                         self.test_signal_midi_note, 
-                        model_data.root_note, 
+                        model_data.root_note,
+                        model_data.reference_frequency,
                         model_data.tuning_system.clone()
                     ) {
                         Ok(frequency) => {
@@ -529,6 +636,7 @@ impl DebugPanel {
                         self.test_signal_midi_note,
                         self.test_signal_nudge_percent,
                         model_data.root_note,
+                        model_data.reference_frequency,
                         model_data.tuning_system.clone()
                     ) {
                         Ok((base_freq, final_freq)) => {
@@ -562,9 +670,88 @@ impl DebugPanel {
                     volume_response.on_hover_text(format!("Amplitude: {:.3}", amplitude));
                 });
                 
+                if self.render_test_signal_shape_controls(ui) && self.test_signal_enabled {
+                    self.send_test_signal_action(model_data);
+                }
             });
     }
     
+    /// Render waveform, vibrato, glide and noise controls, returns whether any changed
+    fn render_test_signal_shape_controls(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        
+        ui.horizontal(|ui| {
+            ui.label("Waveform:");
+            egui::ComboBox::from_id_source("test_signal_waveform")
+                .selected_text(self.test_signal_waveform.name())
+                .show_ui(ui, |ui| {
+                    let harmonics = TestWaveform::Harmonics(self.test_signal_harmonics.to_vec());
+                    for waveform in [TestWaveform::Sine, TestWaveform::Sawtooth, TestWaveform::Square, harmonics] {
+                        let selected = self.test_signal_waveform.name() == waveform.name();
+                        if ui.selectable_label(selected, waveform.name()).clicked() && !selected {
+                            self.test_signal_waveform = waveform;
+                            changed = true;
+                        }
+                    }
+                });
+        });
+        
+        if matches!(self.test_signal_waveform, TestWaveform::Harmonics(_)) {
+            ui.horizontal(|ui| {
+                for (k, amplitude) in self.test_signal_harmonics.iter_mut().enumerate() {
+                    changed |= ui.add(
+                        egui::Slider::new(amplitude, 0.0..=1.0)
+                            .vertical()
+                            .text(format!("{}", k + 1))
+                            .show_value(false)
+                    ).changed();
+                }
+            });
+        }
+        
+        ui.horizontal(|ui| {
+            ui.label("Vibrato:");
+            changed |= ui.add(egui::Slider::new(&mut self.test_signal_vibrato_rate_hz, 0.0..=10.0).suffix(" Hz")).changed();
+            changed |= ui.add(egui::Slider::new(&mut self.test_signal_vibrato_depth_cents, 0.0..=100.0).suffix(" ¢")).changed();
+        });
+        
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut self.test_signal_glide_enabled, "Glide").changed();
+            ui.add_enabled_ui(self.test_signal_glide_enabled, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.test_signal_glide_semitones, -24.0..=24.0).step_by(1.0).suffix(" st")).changed();
+                changed |= ui.add(egui::Slider::new(&mut self.test_signal_glide_seconds, 0.1..=10.0).suffix(" s")).changed();
+            });
+        });
+        
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut self.test_signal_noise_enabled, "Noise").changed();
+            ui.add_enabled_ui(self.test_signal_noise_enabled, |ui| {
+                changed |= ui.add(egui::Slider::new(&mut self.test_signal_snr_db, -10.0..=60.0).suffix(" dB SNR")).changed();
+            });
+        });
+        
+        if changed
+            && let TestWaveform::Harmonics(amplitudes) = &mut self.test_signal_waveform
+        {
+            *amplitudes = self.test_signal_harmonics.to_vec();
+        }
+        changed
+    }
+    
+    /// Shape of the test signal from the panel controls, gliding away from `frequency`
+    fn test_signal_shape(&self, frequency: f32) -> TestSignalShape {
+        TestSignalShape {
+            waveform: self.test_signal_waveform.clone(),
+            vibrato_rate_hz: self.test_signal_vibrato_rate_hz,
+            vibrato_depth_cents: self.test_signal_vibrato_depth_cents,
+            glide: self.test_signal_glide_enabled.then(|| Glide {
+                to_frequency: (frequency * 2.0_f32.powf(self.test_signal_glide_semitones / 12.0)).clamp(20.0, 20_000.0),
+                seconds: self.test_signal_glide_seconds,
+            }),
+            snr_db: self.test_signal_noise_enabled.then_some(self.test_signal_snr_db),
+        }
+    }
+    
     
     // Debug action helper methods
     
@@ -576,6 +763,7 @@ impl DebugPanel {
                 self.test_signal_midi_note,
                 self.test_signal_nudge_percent,
                 model_data.root_note,
+                model_data.reference_frequency,
                 model_data.tuning_system.clone()
             ) {
                 Ok((_, final_frequency)) => {
@@ -586,6 +774,7 @@ impl DebugPanel {
                         self.test_signal_enabled,
                         clamped_frequency,
                         self.test_signal_volume,
+                        self.test_signal_shape(clamped_frequency),
                     );
                 }
                 Err(e) => {
@@ -597,18 +786,27 @@ impl DebugPanel {
                         false,
                         440.0, // Default to A4
                         self.test_signal_volume,
+                        TestSignalShape::default(),
                     );
                 }
             }
         }
     }
     
+    #[cfg(debug_assertions)]
+    fn send_pitch_detection_action(&self) {
+        if let Ok(mut presenter) = self.presenter.try_borrow_mut() {
+            presenter.on_pitch_detection_configured(self.pitch_algorithm, self.compare_algorithms);
+        }
+    }
+    
     /// Convert MIDI note to frequency considering the tuning system
     /// 
     /// # Arguments
     /// 
     /// * `midi_note` - The MIDI note number (0-127)
     /// * `root_note` - The root note for the tuning system
+    /// * `reference_frequency` - The A4 reference frequency in Hz
     /// * `tuning_system` - The tuning system to use
     /// 
     /// # Returns
@@ -618,11 +816,12 @@ impl DebugPanel {
         &self,
         midi_note: MidiNote,
         root_note: MidiNote,
+        reference_frequency: f32,
         tuning_system: TuningSystem,
     ) -> f32 {
-        let root_frequency = crate::theory::tuning::midi_note_to_standard_frequency(root_note);
+        let root_frequency = crate::theory::tuning::midi_note_to_frequency_with_reference(root_note, reference_frequency);
         let interval_semitones = (midi_note as i32) - (root_note as i32);
-        crate::theory::tuning::interval_frequency(tuning_system, root_frequency, interval_semitones)
+        tuning_system.tuning().interval_frequency(root_frequency, interval_semitones)
     }
     
     /// Find the closest MIDI note for a given frequency
@@ -637,25 +836,17 @@ impl DebugPanel {
     /// The closest MIDI note (0-127)
     fn frequency_to_closest_midi_note(
         frequency: f32,
-        tuning_system: TuningSystem,
+        _tuning_system: TuningSystem,
     ) -> MidiNote {
         // Handle edge cases
         if frequency <= 0.0 {
             return 69; // Default to A4
         }
         
-        // For Equal Temperament, use the standard formula
+        // All tuning systems use the Equal Temperament formula to find the
+        // closest note for initialization purposes
         // MIDI note = 69 + 12 * log2(frequency / 440)
-        let midi_note_float = match tuning_system {
-            TuningSystem::EqualTemperament => {
-                69.0 + 12.0 * (frequency / 440.0).log2()
-            }
-            TuningSystem::JustIntonation => {
-                // For Just Intonation, we still use Equal Temperament 
-                // to find the closest note for initialization purposes
-                69.0 + 12.0 * (frequency / 440.0).log2()
-            }
-        };
+        let midi_note_float = 69.0 + 12.0 * (frequency / 440.0).log2();
         
         // Round to nearest integer and clamp to valid MIDI range
         let midi_note = midi_note_float.round() as i32;
@@ -673,6 +864,7 @@ impl DebugPanel {
         &self,
         midi_note: MidiNote,
         root_note: MidiNote,
+        reference_frequency: f32,
         tuning_system: TuningSystem,
     ) -> Result<f32, &'static str> {
         // Validate MIDI notes
@@ -680,7 +872,7 @@ impl DebugPanel {
             return Err("Invalid MIDI note");
         }
         
-        let frequency = self.midi_note_to_frequency_with_tuning(midi_note, root_note, tuning_system);
+        let frequency = self.midi_note_to_frequency_with_tuning(midi_note, root_note, reference_frequency, tuning_system);
         
         // Validate frequency is in reasonable range
         if frequency <= 0.0 || frequency > 20_000.0 {
@@ -696,10 +888,11 @@ impl DebugPanel {
         midi_note: MidiNote,
         nudge_percent: f32,
         root_note: MidiNote,
+        reference_frequency: f32,
         tuning_system: TuningSystem,
     ) -> Result<(f32, f32), &'static str> {
         // Calculate base frequency
-        let base_frequency = self.calculate_midi_note_frequency_safe(midi_note, root_note, tuning_system)?;
+        let base_frequency = self.calculate_midi_note_frequency_safe(midi_note, root_note, reference_frequency, tuning_system)?;
         
         // Validate nudge percentage
         if nudge_percent < -50.0 || nudge_percent > 50.0 {
@@ -53,7 +53,7 @@ pub enum WindowFunction {
 }
 
 /// Generate window coefficients for a given size and window function
-fn generate_window(size: usize, window_fn: WindowFunction) -> Vec<f32> {
+pub fn generate_window(size: usize, window_fn: WindowFunction) -> Vec<f32> {
     match window_fn {
         WindowFunction::None => vec![1.0; size],
         WindowFunction::Hamming => {
@@ -589,6 +589,8 @@ pub struct AudioSystemContext {
     initialization_error: Option<String>,
     /// Current microphone permission state
     permission_state: std::cell::Cell<super::AudioPermission>,
+    /// Whether the spectrum is copied into the audio analysis
+    spectrum_analysis: bool,
 }
 
 impl AudioSystemContext {
@@ -610,6 +612,7 @@ impl AudioSystemContext {
             is_initialized: false,
             initialization_error: None,
             permission_state: std::cell::Cell::new(super::AudioPermission::Uninitialized),
+            spectrum_analysis: false,
         }
     }
 
@@ -622,6 +625,7 @@ impl AudioSystemContext {
             is_initialized: false,
             initialization_error: None,
             permission_state: std::cell::Cell::new(super::AudioPermission::Uninitialized),
+            spectrum_analysis: false,
         }
     }
 
@@ -868,6 +872,11 @@ impl AudioSystemContext {
         self.pitch_analyzer.as_ref().cloned()
     }
 
+    /// Set whether the spectrum is copied into the collected audio analysis
+    pub fn set_spectrum_analysis(&mut self, enabled: bool) {
+        self.spectrum_analysis = enabled;
+    }
+
     /// Check if AudioContext is supported
     pub fn is_audio_context_supported() -> bool {
         AudioContextManager::is_supported()
@@ -931,20 +940,38 @@ impl AudioSystemContext {
         let volume = convert_volume_data(volume_data);
         
         // Collect pitch data from PitchAnalyzer
-        let pitch_data = if let Some(ref analyzer) = self.pitch_analyzer {
+        let (pitch_data, polyphonic_pitches, fft_data) = if let Some(ref analyzer) = self.pitch_analyzer {
             match analyzer.try_borrow() {
-                Ok(borrowed) => borrowed.get_latest_pitch_data(),
-                Err(_) => None
+                Ok(borrowed) => (
+                    borrowed.get_latest_pitch_data(),
+                    convert_polyphonic_pitches(borrowed.latest_polyphonic_pitches()),
+                    self.spectrum_analysis.then(|| convert_spectrum(borrowed.latest_spectrum())),
+                ),
+                Err(_) => (None, Vec::new(), None)
             }
         } else {
-            None
+            (None, Vec::new(), None)
         };
         
         // Convert pitch data to interface type
         let pitch = convert_pitch_data(pitch_data);
         
         // Merge the data into AudioAnalysis
-        merge_audio_analysis(volume, pitch, timestamp)
+        merge_audio_analysis(volume, pitch, timestamp).map(|analysis| crate::shared_types::AudioAnalysis {
+            polyphonic_pitches,
+            fft_data,
+            ..analysis
+        })
+    }
+
+    /// Collect note starts measured against the metronome (return-based pattern)
+    /// 
+    /// Also keeps the metronome clicks scheduled ahead.
+    pub fn collect_onset_timings(&mut self) -> Vec<crate::shared_types::OnsetTiming> {
+        self.audioworklet_manager
+            .as_mut()
+            .map(|worklet| worklet.collect_onset_timings())
+            .unwrap_or_default()
     }
 
     /// Collect current audio errors (return-based pattern)
@@ -1064,6 +1091,7 @@ impl AudioAnalysisMerger {
             volume_level: self.current_volume.borrow().clone(),
             pitch: self.current_pitch.borrow().clone(),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: self.last_timestamp.get().max(js_sys::Date::now()),
         }
     }
@@ -1093,6 +1121,25 @@ pub fn convert_pitch_data(pitch_data: Option<super::data_types::PitchData>) -> O
     })
 }
 
+/// Convert polyphonic estimates to PolyphonicPitch interface types
+pub fn convert_polyphonic_pitches(estimates: &[super::multi_pitch::MultiPitchEstimate]) -> Vec<crate::shared_types::PolyphonicPitch> {
+    estimates
+        .iter()
+        .map(|estimate| crate::shared_types::PolyphonicPitch {
+            frequency: estimate.frequency,
+            salience: estimate.salience,
+        })
+        .collect()
+}
+
+/// Convert the latest spectrum to the Spectrum interface type
+pub fn convert_spectrum(analyzer: &super::spectrum::SpectrumAnalyzer) -> crate::shared_types::Spectrum {
+    crate::shared_types::Spectrum {
+        magnitudes: analyzer.magnitudes().to_vec(),
+        bin_width_hz: analyzer.bin_width_hz(),
+    }
+}
+
 /// Merge volume and pitch data into AudioAnalysis
 /// 
 /// This function combines separate volume and pitch data into a unified
@@ -1109,6 +1156,7 @@ pub fn merge_audio_analysis(
             volume_level: volume.unwrap_or(crate::shared_types::Volume { peak_amplitude: -60.0, rms_amplitude: -60.0 }),
             pitch: pitch.unwrap_or(crate::shared_types::Pitch::NotDetected),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: timestamp.max(js_sys::Date::now()),
         })
     } else {
@@ -14,6 +14,7 @@
 //   let js_message = serializer.serialize_envelope(&return_msg)?;
 
 use crate::engine::audio::signal_generator::SignalGeneratorConfig;
+use crate::shared_types::{Glide, TestSignalShape, TestWaveform};
 use js_sys::{Object, Reflect};
 use wasm_bindgen::{JsValue, JsCast};
 
@@ -92,6 +93,9 @@ pub struct AudioDataBatch {
     
     /// Buffer pool statistics bundled with the audio data
     pub buffer_pool_stats: Option<BufferPoolStats>,
+    
+    /// AudioContext frame of the first sample in the batch
+    pub start_frame: Option<f64>,
 }
 
 /// Processor status information
@@ -954,6 +958,11 @@ impl ToJsMessage for AudioDataBatch {
                 .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set bufferPoolStats: {:?}", e)))?;
         }
         
+        if let Some(start_frame) = self.start_frame {
+            Reflect::set(&obj, &"startFrame".into(), &start_frame.into())
+                .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set startFrame: {:?}", e)))?;
+        }
+        
         Ok(obj)
     }
 }
@@ -1010,6 +1019,14 @@ impl FromJsMessage for AudioDataBatch {
             _ => None,
         };
         
+        let start_frame = match Reflect::get(obj, &"startFrame".into()) {
+            Ok(value) if !value.is_undefined() && !value.is_null() => {
+                Some(value.as_f64()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("startFrame must be number".to_string()))?)
+            }
+            _ => None,
+        };
+        
         Ok(AudioDataBatch {
             sample_rate,
             sample_count,
@@ -1018,6 +1035,7 @@ impl FromJsMessage for AudioDataBatch {
             sequence_number,
             buffer_id,
             buffer_pool_stats,
+            start_frame,
         })
     }
 }
@@ -1423,6 +1441,9 @@ impl ToJsMessage for SignalGeneratorConfig {
         Reflect::set(&obj, &"sampleRate".into(), &self.sample_rate.into())
             .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set sampleRate: {:?}", e)))?;
         
+        let shape_obj = self.shape.to_js_object()?;
+        Reflect::set(&obj, &"shape".into(), &shape_obj.into())
+            .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set shape: {:?}", e)))?;
         
         Ok(obj)
     }
@@ -1453,11 +1474,21 @@ impl FromJsMessage for SignalGeneratorConfig {
             .ok_or_else(|| SerializationError::InvalidPropertyType("sampleRate must be number".to_string()))?
             as u32;
         
+        let shape = match Reflect::get(obj, &"shape".into()) {
+            Ok(value) if !value.is_undefined() => {
+                let shape_obj = value.dyn_ref::<Object>()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("shape must be object".to_string()))?;
+                TestSignalShape::from_js_object(shape_obj)?
+            }
+            _ => TestSignalShape::default(),
+        };
+        
         Ok(SignalGeneratorConfig {
             enabled,
             frequency,
             amplitude,
             sample_rate,
+            shape,
         })
     }
 }
@@ -1473,6 +1504,138 @@ impl MessageValidator for SignalGeneratorConfig {
         if self.sample_rate == 0 {
             return Err(SerializationError::ValidationFailed("sample_rate must be positive".to_string()));
         }
+        self.shape.validate()
+    }
+}
+
+impl ToJsMessage for TestSignalShape {
+    fn to_js_object(&self) -> SerializationResult<Object> {
+        let obj = Object::new();
+        
+        Reflect::set(&obj, &"waveform".into(), &self.waveform.name().into())
+            .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set waveform: {:?}", e)))?;
+        
+        if let TestWaveform::Harmonics(amplitudes) = &self.waveform {
+            let js_array = js_sys::Array::new();
+            for amplitude in amplitudes {
+                js_array.push(&(*amplitude).into());
+            }
+            Reflect::set(&obj, &"harmonics".into(), &js_array.into())
+                .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set harmonics: {:?}", e)))?;
+        }
+        
+        Reflect::set(&obj, &"vibratoRateHz".into(), &self.vibrato_rate_hz.into())
+            .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set vibratoRateHz: {:?}", e)))?;
+        Reflect::set(&obj, &"vibratoDepthCents".into(), &self.vibrato_depth_cents.into())
+            .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set vibratoDepthCents: {:?}", e)))?;
+        
+        if let Some(glide) = &self.glide {
+            Reflect::set(&obj, &"glideToFrequency".into(), &glide.to_frequency.into())
+                .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set glideToFrequency: {:?}", e)))?;
+            Reflect::set(&obj, &"glideSeconds".into(), &glide.seconds.into())
+                .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set glideSeconds: {:?}", e)))?;
+        }
+        
+        if let Some(snr_db) = self.snr_db {
+            Reflect::set(&obj, &"snrDb".into(), &snr_db.into())
+                .map_err(|e| SerializationError::PropertySetFailed(format!("Failed to set snrDb: {:?}", e)))?;
+        }
+        
+        Ok(obj)
+    }
+}
+
+impl FromJsMessage for TestSignalShape {
+    fn from_js_object(obj: &Object) -> SerializationResult<Self> {
+        let waveform_name = Reflect::get(obj, &"waveform".into())
+            .map_err(|e| SerializationError::PropertyGetFailed(format!("Failed to get waveform: {:?}", e)))?
+            .as_string()
+            .ok_or_else(|| SerializationError::InvalidPropertyType("waveform must be string".to_string()))?;
+        
+        let waveform = match waveform_name.as_str() {
+            "Sine" => TestWaveform::Sine,
+            "Sawtooth" => TestWaveform::Sawtooth,
+            "Square" => TestWaveform::Square,
+            "Harmonics" => {
+                let value = Reflect::get(obj, &"harmonics".into())
+                    .map_err(|e| SerializationError::PropertyGetFailed(format!("Failed to get harmonics: {:?}", e)))?;
+                let array = value.dyn_ref::<js_sys::Array>()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("harmonics must be array".to_string()))?;
+                let mut amplitudes = Vec::new();
+                for i in 0..array.length() {
+                    let amplitude = array.get(i).as_f64()
+                        .ok_or_else(|| SerializationError::InvalidPropertyType("harmonics must contain numbers".to_string()))?;
+                    amplitudes.push(amplitude as f32);
+                }
+                TestWaveform::Harmonics(amplitudes)
+            }
+            _ => return Err(SerializationError::InvalidPropertyType(format!("Unknown waveform: {}", waveform_name))),
+        };
+        
+        let vibrato_rate_hz = Reflect::get(obj, &"vibratoRateHz".into())
+            .map_err(|e| SerializationError::PropertyGetFailed(format!("Failed to get vibratoRateHz: {:?}", e)))?
+            .as_f64()
+            .ok_or_else(|| SerializationError::InvalidPropertyType("vibratoRateHz must be number".to_string()))?
+            as f32;
+        
+        let vibrato_depth_cents = Reflect::get(obj, &"vibratoDepthCents".into())
+            .map_err(|e| SerializationError::PropertyGetFailed(format!("Failed to get vibratoDepthCents: {:?}", e)))?
+            .as_f64()
+            .ok_or_else(|| SerializationError::InvalidPropertyType("vibratoDepthCents must be number".to_string()))?
+            as f32;
+        
+        let glide = match Reflect::get(obj, &"glideToFrequency".into()) {
+            Ok(value) if !value.is_undefined() => {
+                let to_frequency = value.as_f64()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("glideToFrequency must be number".to_string()))?
+                    as f32;
+                let seconds = Reflect::get(obj, &"glideSeconds".into())
+                    .map_err(|e| SerializationError::PropertyGetFailed(format!("Failed to get glideSeconds: {:?}", e)))?
+                    .as_f64()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("glideSeconds must be number".to_string()))?
+                    as f32;
+                Some(Glide { to_frequency, seconds })
+            }
+            _ => None,
+        };
+        
+        let snr_db = match Reflect::get(obj, &"snrDb".into()) {
+            Ok(value) if !value.is_undefined() => {
+                Some(value.as_f64()
+                    .ok_or_else(|| SerializationError::InvalidPropertyType("snrDb must be number".to_string()))?
+                    as f32)
+            }
+            _ => None,
+        };
+        
+        Ok(TestSignalShape {
+            waveform,
+            vibrato_rate_hz,
+            vibrato_depth_cents,
+            glide,
+            snr_db,
+        })
+    }
+}
+
+impl MessageValidator for TestSignalShape {
+    fn validate(&self) -> SerializationResult<()> {
+        if let TestWaveform::Harmonics(amplitudes) = &self.waveform
+            && (amplitudes.is_empty() || amplitudes.iter().any(|amplitude| !amplitude.is_finite()))
+        {
+            return Err(SerializationError::ValidationFailed("harmonics must be finite and not empty".to_string()));
+        }
+        if self.vibrato_rate_hz < 0.0 || self.vibrato_depth_cents < 0.0 {
+            return Err(SerializationError::ValidationFailed("vibrato rate and depth cannot be negative".to_string()));
+        }
+        if let Some(glide) = &self.glide
+            && (glide.to_frequency <= 0.0 || glide.seconds < 0.0)
+        {
+            return Err(SerializationError::ValidationFailed("glide needs a positive frequency and a non-negative duration".to_string()));
+        }
+        if self.snr_db.is_some_and(|snr_db| !snr_db.is_finite()) {
+            return Err(SerializationError::ValidationFailed("snr_db must be finite".to_string()));
+        }
         Ok(())
     }
 }
@@ -2100,6 +2263,7 @@ impl AudioDataBatch {
             sequence_number,
             buffer_id: None,
             buffer_pool_stats: None,
+            start_frame: None,
         };
         
         batch.validate().map_err(|e| MessageConstructionError::ValidationFailed(e.to_string()))?;
@@ -2122,6 +2286,7 @@ impl AudioDataBatch {
             sequence_number,
             buffer_id: None,
             buffer_pool_stats: None,
+            start_frame: None,
         };
         
         batch.validate().map_err(|e| MessageConstructionError::ValidationFailed(e.to_string()))?;
@@ -2367,6 +2532,7 @@ impl SignalGeneratorConfig {
             frequency,
             amplitude,
             sample_rate,
+            shape: TestSignalShape::default(),
         };
         
         config.validate().map_err(|e| MessageConstructionError::ValidationFailed(e.to_string()))?;
@@ -2649,6 +2815,7 @@ mod tests {
             sequence_number: Some(42),
             buffer_id: None,
             buffer_pool_stats: None,
+            start_frame: None,
         };
         
         assert_eq!(batch.sample_rate, STANDARD_SAMPLE_RATE);
@@ -2728,6 +2895,7 @@ mod tests {
             sequence_number: Some(42),
             buffer_id: None,
             buffer_pool_stats: None,
+            start_frame: Some(4096.0),
         };
         let msg = FromWorkletMessage::AudioDataBatch { data: data.clone() };
         let obj = msg.to_js_object().unwrap();
@@ -2776,12 +2944,30 @@ mod tests {
             frequency: 440.0,
             amplitude: 0.2,
             sample_rate: STANDARD_SAMPLE_RATE,
+            shape: TestSignalShape::default(),
         };
         
         let obj = config.to_js_object().unwrap();
         let deserialized = SignalGeneratorConfig::from_js_object(&obj).unwrap();
         
         assert_eq!(config, deserialized);
+        
+        let shaped_config = SignalGeneratorConfig {
+            shape: TestSignalShape {
+                waveform: TestWaveform::Harmonics(vec![1.0, 0.5, 0.25]),
+                vibrato_rate_hz: 5.5,
+                vibrato_depth_cents: 30.0,
+                glide: Some(Glide { to_frequency: 880.0, seconds: 1.5 }),
+                snr_db: Some(20.0),
+            },
+            ..config
+        };
+        let obj = shaped_config.to_js_object().unwrap();
+        let deserialized = SignalGeneratorConfig::from_js_object(&obj).unwrap();
+        assert_eq!(shaped_config, deserialized);
+        
+        let invalid_shape = TestSignalShape { vibrato_depth_cents: -10.0, ..TestSignalShape::default() };
+        assert!(invalid_shape.validate().is_err());
     }
 
     #[wasm_bindgen_test]
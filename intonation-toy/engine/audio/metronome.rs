@@ -0,0 +1,342 @@
+use crate::shared_types::{MetronomeConfig, OnsetTiming};
+
+/// Kind of a metronome click, each with its own sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClickKind {
+    /// First beat of the bar, when accents are on
+    Accent,
+    Beat,
+    /// Click between two beats
+    Subdivision,
+}
+
+impl ClickKind {
+    pub const ALL: [ClickKind; 3] = [ClickKind::Accent, ClickKind::Beat, ClickKind::Subdivision];
+
+    fn frequency(&self) -> f32 {
+        match self {
+            ClickKind::Accent => 1760.0,
+            ClickKind::Beat => 1320.0,
+            ClickKind::Subdivision => 880.0,
+        }
+    }
+
+    fn level(&self) -> f32 {
+        match self {
+            ClickKind::Accent => 1.0,
+            ClickKind::Beat => 0.7,
+            ClickKind::Subdivision => 0.4,
+        }
+    }
+}
+
+/// Length of a rendered click
+pub const CLICK_DURATION_SECONDS: f32 = 0.03;
+
+/// Render the sound of a click
+///
+/// A sine burst with a short linear attack and an exponential decay, so the
+/// click has a sharp but not clipped start that marks the exact click time.
+pub fn render_click(kind: ClickKind, sample_rate: f32) -> Vec<f32> {
+    let length = (CLICK_DURATION_SECONDS * sample_rate).round() as usize;
+    let attack = (0.001 * sample_rate).max(1.0);
+    let decay = CLICK_DURATION_SECONDS / 5.0;
+    (0..length)
+        .map(|n| {
+            let t = n as f32 / sample_rate;
+            let envelope = (n as f32 / attack).min(1.0) * (-t / decay).exp();
+            kind.level() * envelope * (2.0 * std::f32::consts::PI * kind.frequency() * t).sin()
+        })
+        .collect()
+}
+
+/// The click grid of a running metronome in AudioContext time
+///
+/// Click `0` is the downbeat of the first bar at `start_time`, and clicks follow
+/// each other at a fixed interval, so any click time is computed from its index
+/// without accumulating rounding errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetronomeSchedule {
+    config: MetronomeConfig,
+    start_time: f64,
+}
+
+impl MetronomeSchedule {
+    pub fn new(config: MetronomeConfig, start_time: f64) -> Self {
+        Self { config, start_time }
+    }
+
+    pub fn config(&self) -> &MetronomeConfig {
+        &self.config
+    }
+
+    /// Replace settings that don't move the click grid, i.e. anything but the tempo, meter and subdivision
+    pub fn set_config(&mut self, config: MetronomeConfig) {
+        debug_assert!(!self.grid_changed_by(&config));
+        self.config = config;
+    }
+
+    /// Whether `config` needs a new click grid
+    pub fn grid_changed_by(&self, config: &MetronomeConfig) -> bool {
+        self.config.bpm != config.bpm
+            || self.config.beats_per_bar != config.beats_per_bar
+            || self.config.subdivision != config.subdivision
+    }
+
+    /// Seconds between two clicks
+    pub fn click_interval(&self) -> f64 {
+        60.0 / (self.config.bpm as f64 * self.config.subdivision as f64)
+    }
+
+    pub fn click_time(&self, index: u64) -> f64 {
+        self.start_time + index as f64 * self.click_interval()
+    }
+
+    pub fn click_kind(&self, index: u64) -> ClickKind {
+        match self.click_position(index) {
+            (_, _, subdivision) if subdivision != 0 => ClickKind::Subdivision,
+            (_, 0, _) if self.config.accent => ClickKind::Accent,
+            _ => ClickKind::Beat,
+        }
+    }
+
+    /// Bar, beat within the bar and subdivision within the beat of a click
+    fn click_position(&self, index: u64) -> (u32, u32, u32) {
+        let subdivision = self.config.subdivision as u64;
+        let beats_per_bar = self.config.beats_per_bar as u64;
+        let beat = index / subdivision;
+        ((beat / beats_per_bar) as u32, (beat % beats_per_bar) as u32, (index % subdivision) as u32)
+    }
+
+    /// Timing of a note start at `onset_time` against the nearest click
+    ///
+    /// The latency compensation is subtracted from the onset first. Returns `None`
+    /// for onsets more than half a click interval before the first click, and for
+    /// onsets while a click is sounding: without headphones the clicks reach the
+    /// microphone and would otherwise be measured as notes right on the click.
+    pub fn onset_timing(&self, onset_time: f64) -> Option<OnsetTiming> {
+        let time = onset_time - self.config.latency_compensation_ms as f64 / 1000.0;
+        let index = ((time - self.start_time) / self.click_interval()).round();
+        if index < 0.0 {
+            return None;
+        }
+        let index = index as u64;
+        let offset = time - self.click_time(index);
+        if (0.0..=CLICK_DURATION_SECONDS as f64).contains(&offset) {
+            return None;
+        }
+        let (bar, beat, subdivision) = self.click_position(index);
+        Some(OnsetTiming {
+            bar,
+            beat,
+            subdivision,
+            offset_ms: (offset * 1000.0) as f32,
+        })
+    }
+}
+
+/// Energy based note start detector
+///
+/// The input is cut into hops of [`Self::HOP_SIZE`] samples. A hop starts a note
+/// when its energy jumps well above the slowly following background energy and
+/// is loud enough not to be noise. Onsets are reported with the resolution of
+/// one hop, and never closer together than [`Self::MIN_ONSET_GAP_SECONDS`].
+#[derive(Debug, Clone)]
+pub struct OnsetDetector {
+    sample_rate: f32,
+    /// Smoothed mean square of the previous hops
+    background: f32,
+    last_onset_frame: Option<f64>,
+    /// Samples of the hop being filled
+    hop: Vec<f32>,
+    /// Frame of the first sample in `hop`
+    hop_start_frame: f64,
+}
+
+impl OnsetDetector {
+    pub const HOP_SIZE: usize = 128;
+    /// Energy ratio between a hop and the background that starts a note (about 6 dB)
+    const RISE_RATIO: f32 = 4.0;
+    /// RMS below which a hop never starts a note
+    const MIN_RMS: f32 = 0.02;
+    /// How fast the background follows the hop energy, per hop
+    const BACKGROUND_SMOOTHING: f32 = 0.05;
+    pub const MIN_ONSET_GAP_SECONDS: f32 = 0.08;
+
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            background: 0.0,
+            last_onset_frame: None,
+            hop: Vec::with_capacity(Self::HOP_SIZE),
+            hop_start_frame: 0.0,
+        }
+    }
+
+    /// Feed samples whose first sample is at AudioContext frame `start_frame`
+    ///
+    /// Returns the AudioContext times in seconds of the note starts found. A gap
+    /// between calls discards the partially filled hop.
+    pub fn process(&mut self, samples: &[f32], start_frame: f64) -> Vec<f64> {
+        if start_frame != self.hop_start_frame + self.hop.len() as f64 {
+            self.hop.clear();
+            self.hop_start_frame = start_frame;
+        }
+
+        let mut onsets = Vec::new();
+        for &sample in samples {
+            self.hop.push(sample);
+            if self.hop.len() == Self::HOP_SIZE {
+                if let Some(frame) = self.process_hop() {
+                    onsets.push(frame / self.sample_rate as f64);
+                }
+                self.hop_start_frame += Self::HOP_SIZE as f64;
+                self.hop.clear();
+            }
+        }
+        onsets
+    }
+
+    fn process_hop(&mut self) -> Option<f64> {
+        let energy = self.hop.iter().map(|x| x * x).sum::<f32>() / self.hop.len() as f32;
+        let rises = energy > Self::RISE_RATIO * self.background && energy > Self::MIN_RMS * Self::MIN_RMS;
+        self.background += (energy - self.background) * Self::BACKGROUND_SMOOTHING;
+
+        let min_gap = (Self::MIN_ONSET_GAP_SECONDS * self.sample_rate) as f64;
+        let rested = self.last_onset_frame.is_none_or(|frame| self.hop_start_frame - frame >= min_gap);
+        if rises && rested {
+            self.last_onset_frame = Some(self.hop_start_frame);
+            Some(self.hop_start_frame)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn config(bpm: f32, beats_per_bar: u32, subdivision: u32) -> MetronomeConfig {
+        MetronomeConfig { enabled: true, bpm, beats_per_bar, subdivision, ..MetronomeConfig::default() }
+    }
+
+    /// Silence with short tones starting at the given sample positions
+    fn notes(starts: &[usize], length: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; length];
+        for &start in starts {
+            for (n, sample) in samples.iter_mut().enumerate().skip(start).take(4800) {
+                *sample = 0.5 * (2.0 * std::f32::consts::PI * 440.0 * n as f32 / SAMPLE_RATE).sin();
+            }
+        }
+        samples
+    }
+
+    #[wasm_bindgen_test]
+    fn test_click_grid() {
+        let schedule = MetronomeSchedule::new(config(120.0, 3, 2), 10.0);
+        assert_eq!(schedule.click_interval(), 0.25);
+        assert_eq!(schedule.click_time(4), 11.0);
+
+        let kinds: Vec<ClickKind> = (0..7).map(|index| schedule.click_kind(index)).collect();
+        assert_eq!(kinds, vec![
+            ClickKind::Accent, ClickKind::Subdivision,
+            ClickKind::Beat, ClickKind::Subdivision,
+            ClickKind::Beat, ClickKind::Subdivision,
+            ClickKind::Accent,
+        ]);
+
+        let unaccented = MetronomeSchedule::new(MetronomeConfig { accent: false, ..config(120.0, 3, 2) }, 10.0);
+        assert_eq!(unaccented.click_kind(0), ClickKind::Beat);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_onset_timing() {
+        let schedule = MetronomeSchedule::new(config(120.0, 4, 1), 2.0);
+
+        // 40 ms late on the third beat of the second bar
+        let timing = schedule.onset_timing(2.0 + 6.0 * 0.5 + 0.04).unwrap();
+        assert_eq!((timing.bar, timing.beat, timing.subdivision), (1, 2, 0));
+        assert!((timing.offset_ms - 40.0).abs() < 0.01);
+
+        // 30 ms early on the downbeat of the second bar
+        let timing = schedule.onset_timing(2.0 + 4.0 * 0.5 - 0.03).unwrap();
+        assert_eq!((timing.bar, timing.beat), (1, 0));
+        assert!((timing.offset_ms + 30.0).abs() < 0.01);
+
+        // Well before the first click
+        assert_eq!(schedule.onset_timing(1.5), None);
+
+        // The latency is taken off before matching
+        let compensated = MetronomeSchedule::new(MetronomeConfig { latency_compensation_ms: 50.0, ..config(120.0, 4, 1) }, 2.0);
+        let timing = compensated.onset_timing(2.5 + 0.05 - 0.01).unwrap();
+        assert_eq!(timing.beat, 1);
+        assert!((timing.offset_ms + 10.0).abs() < 0.01);
+
+        // The clicks themselves, picked up by the microphone after the latency
+        for onset_time in [2.5 + 0.05, 2.5 + 0.05 + 0.5 * CLICK_DURATION_SECONDS as f64, 2.5 + 0.05 + CLICK_DURATION_SECONDS as f64] {
+            assert_eq!(compensated.onset_timing(onset_time), None, "onset at {}", onset_time);
+        }
+        assert!(compensated.onset_timing(2.5 + 0.05 + 0.04).is_some());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_grid_changes() {
+        let schedule = MetronomeSchedule::new(config(120.0, 4, 1), 0.0);
+        assert!(!schedule.grid_changed_by(&MetronomeConfig { volume: 0.1, accent: false, ..config(120.0, 4, 1) }));
+        assert!(schedule.grid_changed_by(&config(121.0, 4, 1)));
+        assert!(schedule.grid_changed_by(&config(120.0, 3, 1)));
+        assert!(schedule.grid_changed_by(&config(120.0, 4, 2)));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_render_click() {
+        let accent = render_click(ClickKind::Accent, SAMPLE_RATE);
+        let subdivision = render_click(ClickKind::Subdivision, SAMPLE_RATE);
+        assert_eq!(accent.len(), 1440);
+        assert_eq!(accent[0], 0.0);
+
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+        assert!(peak(&accent) <= 1.0);
+        assert!(peak(&subdivision) < peak(&accent));
+        assert!(peak(&accent[1200..]) < 0.1 * peak(&accent));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_onset_detector_finds_note_starts() {
+        let starts = [4800, 24000, 40000];
+        let samples = notes(&starts, 48000);
+        let mut detector = OnsetDetector::new(SAMPLE_RATE);
+
+        // Fed in uneven batches, starting at context frame 96000
+        let mut onsets = Vec::new();
+        for (index, batch) in samples.chunks(1000).enumerate() {
+            onsets.extend(detector.process(batch, 96000.0 + index as f64 * 1000.0));
+        }
+
+        assert_eq!(onsets.len(), starts.len());
+        for (onset, start) in onsets.iter().zip(starts) {
+            let expected = (96000 + start) as f64 / SAMPLE_RATE as f64;
+            assert!(*onset <= expected && expected - onset < OnsetDetector::HOP_SIZE as f64 / SAMPLE_RATE as f64,
+                "onset at {} s, note starts at {} s", onset, expected);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_onset_detector_ignores_quiet_and_close_notes() {
+        let mut detector = OnsetDetector::new(SAMPLE_RATE);
+        let quiet: Vec<f32> = notes(&[4800], 24000).iter().map(|x| x * 0.02).collect();
+        assert!(detector.process(&quiet, 0.0).is_empty());
+
+        // A restrike 40 ms after a note start counts as the same note
+        let mut detector = OnsetDetector::new(SAMPLE_RATE);
+        let mut samples = notes(&[4800], 24000);
+        for sample in samples.iter_mut().skip(4800 + 1920).take(4800) {
+            *sample *= 4.0;
+        }
+        assert_eq!(detector.process(&samples, 0.0).len(), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use web_sys::{AudioBuffer, AudioBufferSourceNode, AudioContext, AudioScheduledSourceNode, GainNode};
+use crate::common::dev_log;
+use crate::shared_types::{MetronomeConfig, OnsetTiming};
+use super::microphone::AudioError;
+use super::metronome::{render_click, ClickKind, MetronomeSchedule, CLICK_DURATION_SECONDS};
+
+/// Sample-accurate metronome
+///
+/// Clicks are pre-rendered buffers started at exact AudioContext times, so the
+/// timing doesn't depend on when the render loop runs. `schedule_ahead()` must be
+/// called regularly and keeps the clicks of the next [`Self::LOOKAHEAD_SECONDS`]
+/// scheduled. Like the root note audio, the clicks connect directly to the speakers
+/// and only reach the analyzed input through the microphone, where
+/// [`MetronomeSchedule::onset_timing`] ignores them.
+pub struct MetronomeNode {
+    /// Reference to the AudioContext
+    audio_context: AudioContext,
+    /// Master gain carrying the click volume
+    gain_node: GainNode,
+    /// One rendered click per kind, in the order of `ClickKind::ALL`
+    click_buffers: Vec<AudioBuffer>,
+    schedule: MetronomeSchedule,
+    /// Index of the first click that is not scheduled yet
+    next_click: u64,
+    /// Scheduled clicks with their start times, oldest first
+    scheduled_clicks: VecDeque<(AudioBufferSourceNode, f64)>,
+}
+
+impl MetronomeNode {
+    /// How far ahead of the current context time clicks are scheduled
+    pub const LOOKAHEAD_SECONDS: f64 = 0.2;
+    /// Delay before the first click of a new click grid
+    const START_DELAY_SECONDS: f64 = 0.1;
+
+    /// Create the metronome and schedule its first clicks
+    ///
+    /// # Arguments
+    /// * `audio_context` - The Web Audio context
+    /// * `config` - Tempo, meter, accent, subdivision and volume
+    pub fn new(audio_context: &AudioContext, config: MetronomeConfig) -> Result<Self, AudioError> {
+        dev_log!("[MetronomeNode] Starting metronome at {} bpm with {} beats per bar", config.bpm, config.beats_per_bar);
+
+        let sample_rate = audio_context.sample_rate();
+        let click_buffers = ClickKind::ALL
+            .iter()
+            .map(|&kind| {
+                let samples = render_click(kind, sample_rate);
+                let buffer = audio_context.create_buffer(1, samples.len() as u32, sample_rate)
+                    .map_err(|e| AudioError::Generic(format!("Failed to create click buffer: {:?}", e)))?;
+                buffer.copy_to_channel(&samples, 0)
+                    .map_err(|e| AudioError::Generic(format!("Failed to fill click buffer: {:?}", e)))?;
+                Ok(buffer)
+            })
+            .collect::<Result<Vec<_>, AudioError>>()?;
+
+        let gain_node = audio_context.create_gain()
+            .map_err(|e| AudioError::Generic(format!("Failed to create gain node: {:?}", e)))?;
+        gain_node.gain().set_value(config.volume);
+        gain_node.connect_with_audio_node(&audio_context.destination())
+            .map_err(|e| AudioError::Generic(format!("Failed to connect gain to destination: {:?}", e)))?;
+
+        let start_time = audio_context.current_time() + Self::START_DELAY_SECONDS;
+        let mut node = Self {
+            audio_context: audio_context.clone(),
+            gain_node,
+            click_buffers,
+            schedule: MetronomeSchedule::new(config, start_time),
+            next_click: 0,
+            scheduled_clicks: VecDeque::new(),
+        };
+        node.schedule_ahead();
+        Ok(node)
+    }
+
+    /// Apply new settings
+    ///
+    /// A change of tempo, meter or subdivision drops the clicks that haven't
+    /// sounded yet and starts a new click grid, other changes keep the grid.
+    pub fn update_config(&mut self, config: MetronomeConfig) {
+        self.gain_node.gain().set_value(config.volume);
+        if self.schedule.grid_changed_by(&config) {
+            dev_log!("[MetronomeNode] Restarting metronome at {} bpm with {} beats per bar", config.bpm, config.beats_per_bar);
+            self.cancel_pending_clicks();
+            let start_time = self.audio_context.current_time() + Self::START_DELAY_SECONDS;
+            self.schedule = MetronomeSchedule::new(config, start_time);
+            self.next_click = 0;
+        } else {
+            self.schedule.set_config(config);
+        }
+        self.schedule_ahead();
+    }
+
+    /// Schedule all clicks up to the lookahead and forget the ones that have sounded
+    pub fn schedule_ahead(&mut self) {
+        let now = self.audio_context.current_time();
+        let finished_before = now - CLICK_DURATION_SECONDS as f64;
+        while self.scheduled_clicks.front().is_some_and(|(_, time)| *time < finished_before) {
+            self.scheduled_clicks.pop_front();
+        }
+
+        // After a stall (e.g. a background tab) skip the clicks that are already late
+        while self.schedule.click_time(self.next_click) < now {
+            self.next_click += 1;
+        }
+
+        while self.schedule.click_time(self.next_click) < now + Self::LOOKAHEAD_SECONDS {
+            let time = self.schedule.click_time(self.next_click);
+            let kind = self.schedule.click_kind(self.next_click);
+            match self.start_click(kind, time) {
+                Ok(source) => self.scheduled_clicks.push_back((source, time)),
+                Err(e) => {
+                    dev_log!("[MetronomeNode] Warning: Failed to schedule click: {:?}", e);
+                }
+            }
+            self.next_click += 1;
+        }
+    }
+
+    fn start_click(&self, kind: ClickKind, time: f64) -> Result<AudioBufferSourceNode, AudioError> {
+        let buffer_index = ClickKind::ALL.iter().position(|&k| k == kind).unwrap_or(0);
+        let source = self.audio_context.create_buffer_source()
+            .map_err(|e| AudioError::Generic(format!("Failed to create click source: {:?}", e)))?;
+        source.set_buffer(Some(&self.click_buffers[buffer_index]));
+        source.connect_with_audio_node(&self.gain_node)
+            .map_err(|e| AudioError::Generic(format!("Failed to connect click source: {:?}", e)))?;
+        source.start_with_when(time)
+            .map_err(|e| AudioError::Generic(format!("Failed to start click: {:?}", e)))?;
+        Ok(source)
+    }
+
+    /// Timing of a note start at AudioContext time `onset_time` against the nearest click
+    pub fn onset_timing(&self, onset_time: f64) -> Option<OnsetTiming> {
+        self.schedule.onset_timing(onset_time)
+    }
+
+    fn cancel_pending_clicks(&mut self) {
+        for (source, _) in self.scheduled_clicks.drain(..) {
+            let scheduled_source: &AudioScheduledSourceNode = &source;
+            let _ = scheduled_source.stop();
+        }
+    }
+}
+
+impl Drop for MetronomeNode {
+    fn drop(&mut self) {
+        dev_log!("[MetronomeNode] Stopping metronome");
+        self.cancel_pending_clicks();
+        let _ = self.gain_node.disconnect();
+    }
+}
@@ -41,13 +41,22 @@ pub mod buffer;
 pub mod buffer_analyzer;
 pub mod commands;
 pub mod pitch_detector;
+pub mod pitch_algorithms;
 pub mod pitch_analyzer;
+pub mod multi_pitch;
+pub mod spectrum;
+pub mod wav;
+pub mod offline_analysis;
 pub mod volume_detector;
 pub mod signal_generator;
+pub mod test_sequence;
 pub mod message_protocol;
 pub mod data_types;
 pub mod root_note_audio_node;
 pub mod test_signal_node;
+pub mod target_note_node;
+pub mod metronome;
+pub mod metronome_node;
 
 use crate::common::dev_log;
 use crate::engine::audio::stream::{StreamState, StreamError, StreamConfig, StreamReconnectionHandler};
@@ -220,7 +229,7 @@ pub use microphone::{connect_microphone_to_audioworklet_with_context, Microphone
 pub use context::{AudioSystemContext, convert_volume_data, convert_pitch_data, merge_audio_analysis, AudioDevices};
 pub use worklet::AudioWorkletState;
 pub(crate) use commands::register_audio_commands;
-pub use signal_generator::{SignalGeneratorConfig, RootNoteAudioConfig};
+pub use signal_generator::{SignalGeneratorConfig, RootNoteAudioConfig, TargetNoteConfig};
 pub use data_types::{VolumeLevelData, PitchData, AudioWorkletStatus};
 pub use permission::AudioPermission;
 pub use root_note_audio_node::RootNoteAudioNode;
@@ -334,7 +343,7 @@ mod tests {
     #[wasm_bindgen_test]
     fn test_audio_stream_info_default() {
         let info = AudioStreamInfo::default();
-        assert_eq!(info.sample_rate, STANDARD_SAMPLE_RATE as f32);
+        assert_eq!(info.sample_rate, STANDARD_SAMPLE_RATE);
         assert_eq!(info.buffer_size, 1024);
         assert!(info.device_id.is_none());
         assert!(info.device_label.is_none());
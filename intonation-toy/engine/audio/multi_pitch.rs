@@ -0,0 +1,382 @@
+// Polyphonic pitch detection
+//
+// Estimates several simultaneous pitches, e.g. double stops or two voices
+// singing an interval, from the magnitude spectrum of the most recent audio.
+//
+// 1. The latest `analysis_size` samples are Hamming windowed and zero padded.
+// 2. Local maxima of the spectrum are picked and refined by parabolic interpolation.
+// 3. Every peak is a fundamental candidate, scored by the weighted amplitudes of
+//    the peaks found at its harmonics.
+// 4. The best candidate, or a nearly as strong candidate it is a harmonic of, is
+//    accepted and the peaks it explains are removed. This repeats until
+//    `max_pitches` are found or the candidates get too weak.
+//
+// Unisons and octaves cannot be told apart from the harmonics of a single tone,
+// so the upper note of an octave double stop is reported as part of the lower one.
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+use super::buffer_analyzer::{generate_window, WindowFunction};
+
+/// Fraction of the best candidate's score a subharmonic candidate needs to be preferred
+const SUBHARMONIC_PREFERENCE: f32 = 0.5;
+
+/// Configuration of the polyphonic pitch detector
+#[derive(Debug, Clone)]
+pub struct MultiPitchConfig {
+    /// Number of most recent samples analyzed; longer windows separate closer pitches
+    pub analysis_size: usize,
+    /// Maximum number of simultaneous pitches reported
+    pub max_pitches: usize,
+    pub min_frequency: f32,
+    pub max_frequency: f32,
+    /// Peaks further below the strongest peak than this (in dB) are ignored
+    pub peak_threshold_db: f32,
+    /// Peaks below this absolute amplitude are ignored
+    pub min_amplitude: f32,
+    /// Number of harmonics considered when scoring a candidate
+    pub harmonics: usize,
+    /// How far a peak may be from an exact harmonic, in cents
+    pub harmonic_tolerance_cents: f32,
+    /// Candidates weaker than this fraction of the strongest pitch are dropped
+    pub min_salience: f32,
+}
+
+impl Default for MultiPitchConfig {
+    fn default() -> Self {
+        Self {
+            analysis_size: 8192,
+            max_pitches: 4,
+            min_frequency: 60.0,
+            max_frequency: 2000.0,
+            peak_threshold_db: -35.0,
+            min_amplitude: 0.001,
+            harmonics: 8,
+            harmonic_tolerance_cents: 35.0,
+            min_salience: 0.15,
+        }
+    }
+}
+
+/// One of several simultaneous pitches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiPitchEstimate {
+    pub frequency: f32,
+    /// Harmonic strength relative to the strongest pitch in the same window (0.0-1.0)
+    pub salience: f32,
+}
+
+/// Interpolated spectral peak
+#[derive(Debug, Clone, Copy)]
+struct SpectralPeak {
+    frequency: f32,
+    amplitude: f32,
+}
+
+/// Polyphonic pitch detector based on spectral peaks and harmonic grouping
+pub struct MultiPitchDetector {
+    config: MultiPitchConfig,
+    sample_rate: u32,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    window_gain: f32,
+    /// The most recent `analysis_size` samples, oldest first
+    history: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+}
+
+impl MultiPitchDetector {
+    pub fn new(config: MultiPitchConfig, sample_rate: u32) -> Result<Self, String> {
+        if config.analysis_size < 64 || !config.analysis_size.is_power_of_two() {
+            return Err(format!("Analysis size must be a power of two of at least 64, got {}", config.analysis_size));
+        }
+        if config.max_pitches == 0 || config.harmonics == 0 {
+            return Err("Maximum pitches and harmonics must be at least 1".to_string());
+        }
+        if config.min_frequency <= 0.0 || config.max_frequency <= config.min_frequency {
+            return Err(format!("Invalid frequency range {}-{} Hz", config.min_frequency, config.max_frequency));
+        }
+        if sample_rate == 0 {
+            return Err("Sample rate must be positive".to_string());
+        }
+
+        // Zero padding to twice the window length refines the peak positions
+        let fft_size = config.analysis_size * 2;
+        let fft = FftPlanner::new().plan_fft_forward(fft_size);
+        let window = generate_window(config.analysis_size, WindowFunction::Hamming);
+        let window_gain = window.iter().sum::<f32>() / 2.0;
+
+        Ok(Self {
+            history: vec![0.0; config.analysis_size],
+            spectrum: vec![Complex::new(0.0, 0.0); fft_size],
+            config,
+            sample_rate,
+            fft,
+            window,
+            window_gain,
+        })
+    }
+
+    pub fn config(&self) -> &MultiPitchConfig {
+        &self.config
+    }
+
+    /// Forget previously received samples
+    pub fn reset(&mut self) {
+        self.history.fill(0.0);
+    }
+
+    /// Add new samples and estimate the pitches sounding at the end of them
+    ///
+    /// Returns the pitches ordered by ascending frequency.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<MultiPitchEstimate> {
+        let size = self.history.len();
+        if samples.len() >= size {
+            self.history.copy_from_slice(&samples[samples.len() - size..]);
+        } else {
+            self.history.rotate_left(samples.len());
+            self.history[size - samples.len()..].copy_from_slice(samples);
+        }
+
+        let peaks = self.spectral_peaks();
+        let mut pitches = self.group_harmonics(&peaks);
+        pitches.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        pitches
+    }
+
+    /// Windowed, zero padded spectrum of the history and its interpolated peaks
+    fn spectral_peaks(&mut self) -> Vec<SpectralPeak> {
+        for (bin, (sample, weight)) in self.spectrum.iter_mut().zip(self.history.iter().zip(&self.window)) {
+            *bin = Complex::new(sample * weight, 0.0);
+        }
+        for bin in self.spectrum[self.history.len()..].iter_mut() {
+            *bin = Complex::new(0.0, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let fft_size = self.spectrum.len();
+        let bin_width = self.sample_rate as f32 / fft_size as f32;
+        let max_harmonic_frequency = (self.config.max_frequency * self.config.harmonics as f32).min(self.sample_rate as f32 / 2.0);
+        let first_bin = ((self.config.min_frequency / bin_width) as usize).max(2);
+        let last_bin = ((max_harmonic_frequency / bin_width) as usize).min(fft_size / 2 - 2);
+        if first_bin >= last_bin {
+            return Vec::new();
+        }
+
+        // Amplitude in dB, normalized so that a full scale sine reads 0 dB
+        let levels: Vec<f32> = self.spectrum[..=last_bin + 1]
+            .iter()
+            .map(|bin| 20.0 * (bin.norm() / self.window_gain).max(1e-10).log10())
+            .collect();
+        let strongest = levels[first_bin..=last_bin].iter().copied().fold(f32::MIN, f32::max);
+        let threshold = (strongest + self.config.peak_threshold_db).max(20.0 * self.config.min_amplitude.log10());
+
+        // The main lobe of the padded Hamming window spans about 8 bins
+        const NEIGHBORHOOD: usize = 3;
+        (first_bin..=last_bin)
+            .filter(|&bin| {
+                let level = levels[bin];
+                level >= threshold
+                    && (bin.saturating_sub(NEIGHBORHOOD)..bin).all(|other| levels[other] < level)
+                    && (bin + 1..=(bin + NEIGHBORHOOD).min(last_bin + 1)).all(|other| levels[other] <= level)
+            })
+            .map(|bin| {
+                let (left, center, right) = (levels[bin - 1], levels[bin], levels[bin + 1]);
+                let denominator = left - 2.0 * center + right;
+                let offset = if denominator.abs() > f32::EPSILON { 0.5 * (left - right) / denominator } else { 0.0 };
+                let level = center - 0.25 * (left - right) * offset;
+                SpectralPeak {
+                    frequency: (bin as f32 + offset) * bin_width,
+                    amplitude: 10f32.powf(level / 20.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Greedily group peaks into harmonic series
+    fn group_harmonics(&self, peaks: &[SpectralPeak]) -> Vec<MultiPitchEstimate> {
+        let mut explained = vec![false; peaks.len()];
+        let mut pitches = Vec::new();
+        let mut strongest_score = 0.0;
+
+        while pitches.len() < self.config.max_pitches {
+            let candidates: Vec<(usize, f32)> = peaks
+                .iter()
+                .enumerate()
+                .filter(|(index, peak)| {
+                    !explained[*index]
+                        && peak.frequency >= self.config.min_frequency
+                        && peak.frequency <= self.config.max_frequency
+                })
+                .map(|(index, peak)| (index, self.harmonic_score(peak.frequency, peaks, &explained)))
+                .collect();
+
+            let Some(&(best_index, best_score)) = candidates.iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+                break;
+            };
+
+            // A tone with a strong upper partial would otherwise be reported as that
+            // partial plus a weaker fundamental, so prefer the lowest candidate that
+            // has the best one among its harmonics and is nearly as strong
+            let (index, score) = candidates
+                .iter()
+                .copied()
+                .filter(|&(index, score)| {
+                    index == best_index
+                        || (score >= best_score * SUBHARMONIC_PREFERENCE
+                            && self.harmonic_number(peaks[index].frequency, peaks[best_index].frequency).is_some())
+                })
+                .min_by(|a, b| peaks[a.0].frequency.total_cmp(&peaks[b.0].frequency))
+                .unwrap_or((best_index, best_score));
+
+            if pitches.is_empty() {
+                strongest_score = score;
+            } else if score < strongest_score * self.config.min_salience {
+                break;
+            }
+
+            let fundamental = peaks[index].frequency;
+            for (other, peak) in peaks.iter().enumerate() {
+                if self.harmonic_number(fundamental, peak.frequency).is_some() {
+                    explained[other] = true;
+                }
+            }
+            pitches.push(MultiPitchEstimate {
+                frequency: fundamental,
+                salience: if strongest_score > 0.0 { score / strongest_score } else { 0.0 },
+            });
+        }
+        pitches
+    }
+
+    /// Sum of the amplitudes at the harmonics of a candidate, weighted by 1/h
+    ///
+    /// Only peaks that are not yet explained by an accepted pitch count.
+    fn harmonic_score(&self, fundamental: f32, peaks: &[SpectralPeak], explained: &[bool]) -> f32 {
+        let mut best_per_harmonic = vec![0.0f32; self.config.harmonics];
+        for (peak, _) in peaks.iter().zip(explained).filter(|(_, explained)| !**explained) {
+            if let Some(harmonic) = self.harmonic_number(fundamental, peak.frequency) {
+                let slot = &mut best_per_harmonic[harmonic - 1];
+                *slot = slot.max(peak.amplitude);
+            }
+        }
+        best_per_harmonic
+            .iter()
+            .enumerate()
+            .map(|(index, amplitude)| amplitude / (index + 1) as f32)
+            .sum()
+    }
+
+    /// Which harmonic of `fundamental` the frequency is, if it is one within tolerance
+    fn harmonic_number(&self, fundamental: f32, frequency: f32) -> Option<usize> {
+        let harmonic = (frequency / fundamental).round();
+        if harmonic < 1.0 || harmonic > self.config.harmonics as f32 {
+            return None;
+        }
+        let cents = 1200.0 * (frequency / (fundamental * harmonic)).log2();
+        (cents.abs() <= self.config.harmonic_tolerance_cents).then_some(harmonic as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    /// Harmonic tone with amplitudes falling off as 1/h
+    fn harmonic_tone(frequency: f32, amplitude: f32, harmonics: usize, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                (1..=harmonics)
+                    .map(|h| amplitude / h as f32 * (2.0 * std::f32::consts::PI * frequency * h as f32 * t).sin())
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn mix(a: &[f32], b: &[f32]) -> Vec<f32> {
+        a.iter().zip(b).map(|(x, y)| x + y).collect()
+    }
+
+    fn detect(samples: &[f32]) -> Vec<MultiPitchEstimate> {
+        let mut detector = MultiPitchDetector::new(MultiPitchConfig::default(), SAMPLE_RATE).unwrap();
+        detector.process(samples)
+    }
+
+    fn cents(a: f32, b: f32) -> f32 {
+        1200.0 * (a / b).log2()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_single_harmonic_tone() {
+        let pitches = detect(&harmonic_tone(220.0, 0.3, 6, 8192));
+        assert_eq!(pitches.len(), 1, "{:?}", pitches);
+        assert!(cents(pitches[0].frequency, 220.0).abs() < 5.0, "{:?}", pitches);
+        assert_eq!(pitches[0].salience, 1.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_double_stop_fifth() {
+        // Open G and D strings of a violin
+        let samples = mix(&harmonic_tone(196.0, 0.3, 6, 8192), &harmonic_tone(293.66, 0.25, 6, 8192));
+        let pitches = detect(&samples);
+        assert_eq!(pitches.len(), 2, "{:?}", pitches);
+        assert!(cents(pitches[0].frequency, 196.0).abs() < 5.0, "{:?}", pitches);
+        assert!(cents(pitches[1].frequency, 293.66).abs() < 5.0, "{:?}", pitches);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_triad() {
+        let c4 = harmonic_tone(261.63, 0.2, 5, 8192);
+        let e4 = harmonic_tone(329.63, 0.2, 5, 8192);
+        let g4 = harmonic_tone(392.0, 0.2, 5, 8192);
+        let pitches = detect(&mix(&mix(&c4, &e4), &g4));
+        assert_eq!(pitches.len(), 3, "{:?}", pitches);
+        for (pitch, expected) in pitches.iter().zip([261.63, 329.63, 392.0]) {
+            assert!(cents(pitch.frequency, expected).abs() < 5.0, "{:?}", pitches);
+            assert!(pitch.salience > 0.5, "{:?}", pitches);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_strong_upper_partial_is_not_a_separate_pitch() {
+        let samples: Vec<f32> = (0..8192)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * 200.0 * i as f32 / SAMPLE_RATE as f32;
+                0.1 * phase.sin() + 0.4 * (2.0 * phase).sin() + 0.2 * (3.0 * phase).sin() + 0.1 * (4.0 * phase).sin()
+            })
+            .collect();
+        let pitches = detect(&samples);
+        assert_eq!(pitches.len(), 1, "{:?}", pitches);
+        assert!(cents(pitches[0].frequency, 200.0).abs() < 5.0, "{:?}", pitches);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_silence_and_short_chunks() {
+        let mut detector = MultiPitchDetector::new(MultiPitchConfig::default(), SAMPLE_RATE).unwrap();
+        assert!(detector.process(&vec![0.0; 4096]).is_empty());
+
+        // Chunks shorter than the analysis size accumulate in the history
+        let tone = harmonic_tone(440.0, 0.3, 4, 8192);
+        detector.process(&tone[..4096]);
+        let pitches = detector.process(&tone[4096..]);
+        assert_eq!(pitches.len(), 1);
+        assert!(cents(pitches[0].frequency, 440.0).abs() < 5.0);
+
+        detector.reset();
+        assert!(detector.process(&[0.0; 128]).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_invalid_config() {
+        let invalid_size = MultiPitchConfig { analysis_size: 1000, ..MultiPitchConfig::default() };
+        assert!(MultiPitchDetector::new(invalid_size, SAMPLE_RATE).is_err());
+        let invalid_range = MultiPitchConfig { min_frequency: 500.0, max_frequency: 100.0, ..MultiPitchConfig::default() };
+        assert!(MultiPitchDetector::new(invalid_range, SAMPLE_RATE).is_err());
+        assert!(MultiPitchDetector::new(MultiPitchConfig::default(), 0).is_err());
+    }
+}
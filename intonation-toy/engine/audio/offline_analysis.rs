@@ -0,0 +1,253 @@
+// Offline analysis of recorded audio
+//
+// Runs a recording through the same PitchAnalyzer and VolumeDetector that process
+// live microphone input, and collects the results into a timeline. `RecordingAnalysis`
+// does the work a few windows at a time, so the app can spread a long recording over
+// many frames. Nothing here depends on the browser, so recordings can be analyzed
+// natively, for example to regression-test the analysis pipeline against reference
+// files (see tests/offline_analysis.rs).
+
+use super::pitch_analyzer::PitchAnalyzer;
+use super::pitch_detector::{PitchDetectorConfig, PitchResult};
+use super::volume_detector::{VolumeAnalysis, VolumeDetector, VolumeDetectorConfig};
+use super::wav::WavHeader;
+use crate::shared_types::{AudioAnalysis, Pitch, Volume};
+
+/// Configuration of an offline analysis run
+#[derive(Debug, Clone)]
+pub struct OfflineAnalysisConfig {
+    pub pitch_detector: PitchDetectorConfig,
+    pub volume_detector: VolumeDetectorConfig,
+    /// Overlap between consecutive analysis windows (0.0 = none, 0.9 = maximum)
+    pub overlap_factor: f32,
+}
+
+impl Default for OfflineAnalysisConfig {
+    fn default() -> Self {
+        Self {
+            pitch_detector: PitchDetectorConfig::default(),
+            volume_detector: VolumeDetectorConfig::default(),
+            overlap_factor: 0.75,
+        }
+    }
+}
+
+/// Pitch and volume of one analysis window
+#[derive(Debug, Clone)]
+pub struct TimelineFrame {
+    /// Center of the window, in seconds from the start of the recording
+    pub time_seconds: f64,
+    pub volume: VolumeAnalysis,
+    /// Detected pitch; its timestamp is the window center in milliseconds
+    pub pitch: Option<PitchResult>,
+}
+
+impl TimelineFrame {
+    /// The frame in the form the model receives live analysis in, timestamped in milliseconds
+    pub fn audio_analysis(&self) -> AudioAnalysis {
+        AudioAnalysis {
+            volume_level: Volume {
+                peak_amplitude: self.volume.peak_amplitude,
+                rms_amplitude: self.volume.rms_amplitude,
+            },
+            pitch: match &self.pitch {
+                Some(pitch) => Pitch::Detected(pitch.frequency, pitch.clarity),
+                None => Pitch::NotDetected,
+            },
+            fft_data: None,
+            polyphonic_pitches: Vec::new(),
+            timestamp: self.time_seconds * 1000.0,
+        }
+    }
+}
+
+/// Pitch and volume over the full length of a recording
+#[derive(Debug, Clone)]
+pub struct AnalysisTimeline {
+    pub sample_rate: u32,
+    pub duration_seconds: f64,
+    pub frames: Vec<TimelineFrame>,
+}
+
+impl AnalysisTimeline {
+    /// Frames in which a pitch was detected
+    pub fn voiced_frames(&self) -> impl Iterator<Item = &TimelineFrame> {
+        self.frames.iter().filter(|frame| frame.pitch.is_some())
+    }
+
+    /// Fraction of frames in which a pitch was detected
+    pub fn voiced_ratio(&self) -> f32 {
+        if self.frames.is_empty() {
+            return 0.0;
+        }
+        self.voiced_frames().count() as f32 / self.frames.len() as f32
+    }
+
+    /// The frame whose window center is closest to the given time
+    pub fn frame_at(&self, time_seconds: f64) -> Option<&TimelineFrame> {
+        self.frames.iter().min_by(|a, b| {
+            (a.time_seconds - time_seconds).abs().total_cmp(&(b.time_seconds - time_seconds).abs())
+        })
+    }
+}
+
+/// Decode a WAV file and analyze it
+pub fn analyze_wav(bytes: &[u8], config: &OfflineAnalysisConfig) -> Result<AnalysisTimeline, String> {
+    RecordingAnalysis::from_wav(bytes.to_vec(), config)?.analyze_all()
+}
+
+/// Analyze mono samples recorded at the given sample rate
+pub fn analyze_recording(
+    samples: &[f32],
+    sample_rate: u32,
+    config: &OfflineAnalysisConfig,
+) -> Result<AnalysisTimeline, String> {
+    RecordingAnalysis::from_samples(samples.to_vec(), sample_rate, config)?.analyze_all()
+}
+
+/// Audio of a recording under analysis
+enum RecordingSource {
+    Samples(Vec<f32>),
+    /// A WAV file, decoded one window at a time
+    Wav { bytes: Vec<u8>, header: WavHeader },
+}
+
+impl RecordingSource {
+    fn len(&self) -> usize {
+        match self {
+            RecordingSource::Samples(samples) => samples.len(),
+            RecordingSource::Wav { header, .. } => header.frame_count(),
+        }
+    }
+
+    /// Fill `window` with the samples from `offset`, padding with silence past the end
+    fn read_window(&self, offset: usize, window: &mut [f32]) {
+        window.fill(0.0);
+        let end = offset + window.len();
+        match self {
+            RecordingSource::Samples(samples) => {
+                let available = &samples[offset.min(samples.len())..end.min(samples.len())];
+                window[..available.len()].copy_from_slice(available);
+            }
+            RecordingSource::Wav { bytes, header } => {
+                for (sample, decoded) in window.iter_mut().zip(header.decode_frames(bytes, offset..end)) {
+                    *sample = decoded;
+                }
+            }
+        }
+    }
+}
+
+/// Analysis of a recording that runs a limited number of windows at a time
+///
+/// The recording is split into overlapping windows, with the same step as
+/// `PitchAnalyzer::analyze_windows_with_overlap`. The end of the recording is
+/// padded with silence so that the last samples are covered by a window. Long
+/// recordings are analyzed over several calls to `analyze_windows`, so that the
+/// caller can keep rendering and show the progress in between.
+pub struct RecordingAnalysis {
+    source: RecordingSource,
+    sample_rate: u32,
+    pitch_analyzer: PitchAnalyzer,
+    volume_detector: VolumeDetector,
+    window: Vec<f32>,
+    step_size: usize,
+    window_count: usize,
+    next_window: usize,
+}
+
+impl RecordingAnalysis {
+    /// Analysis of a WAV file; the samples are decoded as the windows reach them
+    pub fn from_wav(bytes: Vec<u8>, config: &OfflineAnalysisConfig) -> Result<Self, String> {
+        let header = WavHeader::parse(&bytes).map_err(|e| format!("Failed to decode WAV file: {}", e))?;
+        let sample_rate = header.sample_rate;
+        Self::new(RecordingSource::Wav { bytes, header }, sample_rate, config)
+    }
+
+    /// Analysis of mono samples recorded at the given sample rate
+    pub fn from_samples(samples: Vec<f32>, sample_rate: u32, config: &OfflineAnalysisConfig) -> Result<Self, String> {
+        Self::new(RecordingSource::Samples(samples), sample_rate, config)
+    }
+
+    fn new(source: RecordingSource, sample_rate: u32, config: &OfflineAnalysisConfig) -> Result<Self, String> {
+        if !(0.0..=0.9).contains(&config.overlap_factor) {
+            return Err(format!("Overlap factor {} is outside 0.0-0.9", config.overlap_factor));
+        }
+        let pitch_analyzer = PitchAnalyzer::new(config.pitch_detector.clone(), sample_rate)?;
+        let volume_detector = VolumeDetector::new(VolumeDetectorConfig {
+            sample_rate,
+            ..config.volume_detector.clone()
+        })?;
+
+        let window_size = config.pitch_detector.sample_window_size;
+        let step_size = pitch_analyzer.overlap_step_size(config.overlap_factor);
+        if step_size == 0 {
+            return Err(format!("Window of {} samples is too small to step through", window_size));
+        }
+        let window_count = 1 + source.len().saturating_sub(window_size).div_ceil(step_size);
+
+        Ok(Self {
+            source,
+            sample_rate,
+            pitch_analyzer,
+            volume_detector,
+            window: vec![0.0; window_size],
+            step_size,
+            window_count,
+            next_window: 0,
+        })
+    }
+
+    /// Analyze up to `max_windows` more windows and return their frames
+    pub fn analyze_windows(&mut self, max_windows: usize) -> Result<Vec<TimelineFrame>, String> {
+        let end = self.window_count.min(self.next_window.saturating_add(max_windows));
+        let mut frames = Vec::with_capacity(end - self.next_window);
+
+        for index in self.next_window..end {
+            let offset = index * self.step_size;
+            self.source.read_window(offset, &mut self.window);
+            let time_seconds = (offset + self.window.len() / 2) as f64 / self.sample_rate as f64;
+            let pitch = self.pitch_analyzer.analyze_samples(&self.window)?;
+            frames.push(TimelineFrame {
+                time_seconds,
+                volume: self.volume_detector.process_buffer(&self.window),
+                pitch: pitch.map(|result| PitchResult {
+                    timestamp: time_seconds * 1000.0,
+                    ..result
+                }),
+            });
+        }
+
+        self.next_window = end;
+        Ok(frames)
+    }
+
+    /// Fraction of the windows analyzed so far, from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        self.next_window as f32 / self.window_count as f32
+    }
+
+    /// Whether every window has been analyzed
+    pub fn is_finished(&self) -> bool {
+        self.next_window >= self.window_count
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Length of the recording in seconds
+    pub fn duration_seconds(&self) -> f64 {
+        self.source.len() as f64 / self.sample_rate as f64
+    }
+
+    /// Analyze every window at once and collect the timeline
+    fn analyze_all(mut self) -> Result<AnalysisTimeline, String> {
+        let frames = self.analyze_windows(usize::MAX)?;
+        Ok(AnalysisTimeline {
+            sample_rate: self.sample_rate,
+            duration_seconds: self.duration_seconds(),
+            frames,
+        })
+    }
+}
@@ -0,0 +1,487 @@
+// Pitch detection algorithms
+//
+// Every algorithm implements the `Detector` trait so that `PitchDetector` can swap
+// between them at runtime. McLeod and autocorrelation wrap the implementations in the
+// pitch-detection crate. YIN and probabilistic YIN (pYIN) are implemented here on a shared
+// cumulative mean normalized difference function, so their clarities come from values we
+// compute ourselves. Instead of a single YIN threshold, pYIN evaluates a distribution of
+// thresholds, which yields several period candidates with probabilities, and picks the
+// candidate that best continues the pitch of the previous frame.
+
+use pitch_detection::detector::{
+    autocorrelation::AutocorrelationDetector,
+    mcleod::McLeodDetector,
+    PitchDetector as PitchDetectorTrait,
+};
+use crate::shared_types::PitchDetectionAlgorithm;
+
+/// Number of YIN thresholds evaluated by the probabilistic YIN detector
+const PYIN_THRESHOLD_COUNT: usize = 100;
+/// Beta distribution parameters of the threshold prior (mean 0.1, as in the pYIN paper)
+const PYIN_PRIOR_ALPHA: f32 = 2.0;
+const PYIN_PRIOR_BETA: f32 = 18.0;
+/// Width in cents of the penalty for pitch jumps between consecutive frames
+const PYIN_TRANSITION_WIDTH_CENTS: f32 = 200.0;
+/// Lowest transition weight, so that a strong candidate can still win after a large jump
+const PYIN_MIN_TRANSITION_WEIGHT: f32 = 0.05;
+
+/// Raw pitch estimate produced by a detection algorithm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchEstimate {
+    pub frequency: f32,
+    pub clarity: f32,
+}
+
+/// Common interface of the pitch detection algorithms
+pub trait Detector {
+    /// The algorithm implemented by this detector
+    fn algorithm(&self) -> PitchDetectionAlgorithm;
+
+    /// Estimate the pitch of a window of samples
+    ///
+    /// Returns `None` when the signal power is below `power_threshold` or the
+    /// estimate is less clear than `clarity_threshold`.
+    fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        power_threshold: f32,
+        clarity_threshold: f32,
+    ) -> Option<PitchEstimate>;
+
+    /// Estimate of the memory held by the detector's internal buffers
+    fn memory_usage_bytes(&self) -> usize;
+}
+
+/// Create a detector for the given algorithm and window configuration
+pub fn create_detector(
+    algorithm: PitchDetectionAlgorithm,
+    window_size: usize,
+    padding_size: usize,
+) -> Box<dyn Detector> {
+    match algorithm {
+        PitchDetectionAlgorithm::McLeod => Box::new(LibraryDetector::new(
+            algorithm,
+            McLeodDetector::new(window_size, padding_size),
+            window_size + padding_size,
+        )),
+        PitchDetectionAlgorithm::Yin => Box::new(YinDetector::new(window_size)),
+        PitchDetectionAlgorithm::Autocorrelation => Box::new(LibraryDetector::new(
+            algorithm,
+            AutocorrelationDetector::new(window_size, padding_size),
+            window_size + padding_size,
+        )),
+        PitchDetectionAlgorithm::ProbabilisticYin => Box::new(ProbabilisticYinDetector::new(window_size)),
+    }
+}
+
+/// Adapter for the detectors of the pitch-detection crate
+struct LibraryDetector<D> {
+    algorithm: PitchDetectionAlgorithm,
+    detector: D,
+    buffer_size: usize,
+}
+
+impl<D> LibraryDetector<D> {
+    fn new(algorithm: PitchDetectionAlgorithm, detector: D, buffer_size: usize) -> Self {
+        Self {
+            algorithm,
+            detector,
+            buffer_size,
+        }
+    }
+}
+
+impl<D: PitchDetectorTrait<f32>> Detector for LibraryDetector<D> {
+    fn algorithm(&self) -> PitchDetectionAlgorithm {
+        self.algorithm
+    }
+
+    fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        power_threshold: f32,
+        clarity_threshold: f32,
+    ) -> Option<PitchEstimate> {
+        self.detector
+            .get_pitch(samples, sample_rate as usize, power_threshold, clarity_threshold)
+            .map(|pitch| PitchEstimate {
+                frequency: pitch.frequency,
+                clarity: pitch.clarity,
+            })
+    }
+
+    fn memory_usage_bytes(&self) -> usize {
+        // The crate keeps real and complex FFT buffers of the padded window size
+        std::mem::size_of::<D>() + self.buffer_size * std::mem::size_of::<f32>() * 4
+    }
+}
+
+/// Cumulative mean normalized difference function `d'` of a window
+///
+/// Shared by the YIN detectors. Index `tau` holds the normalized difference at a
+/// lag of `tau` samples; `d'(0)` is defined as 1.
+struct NormalizedDifference {
+    values: Vec<f32>,
+}
+
+impl NormalizedDifference {
+    fn new(window_size: usize) -> Self {
+        Self {
+            values: vec![0.0; window_size / 2],
+        }
+    }
+
+    /// Compute `d'` for the samples and return the number of valid lags
+    fn compute(&mut self, samples: &[f32]) -> usize {
+        let window = self.values.len().min(samples.len() / 2);
+
+        for tau in 0..window {
+            self.values[tau] = (0..window)
+                .map(|i| {
+                    let delta = samples[i] - samples[i + tau];
+                    delta * delta
+                })
+                .sum();
+        }
+
+        let mut running_sum = 0.0;
+        if window > 0 {
+            self.values[0] = 1.0;
+        }
+        for tau in 1..window {
+            running_sum += self.values[tau];
+            self.values[tau] = if running_sum > 0.0 {
+                self.values[tau] * tau as f32 / running_sum
+            } else {
+                1.0
+            };
+        }
+        window
+    }
+
+    /// Index of the dip that a YIN search with the given threshold would select
+    fn dip_below(&self, threshold: f32, window: usize) -> Option<usize> {
+        let mut tau = 2;
+        while tau < window {
+            if self.values[tau] < threshold {
+                while tau + 1 < window && self.values[tau + 1] < self.values[tau] {
+                    tau += 1;
+                }
+                return Some(tau);
+            }
+            tau += 1;
+        }
+        None
+    }
+
+    /// Period of a dip refined by parabolic interpolation
+    fn refined_period(&self, tau: usize, window: usize) -> f32 {
+        if tau == 0 || tau + 1 >= window {
+            return tau as f32;
+        }
+        let (s0, s1, s2) = (self.values[tau - 1], self.values[tau], self.values[tau + 1]);
+        let denominator = s0 - 2.0 * s1 + s2;
+        if denominator.abs() < f32::EPSILON {
+            tau as f32
+        } else {
+            tau as f32 + (s0 - s2) / (2.0 * denominator)
+        }
+    }
+
+    fn memory_usage_bytes(&self) -> usize {
+        self.values.capacity() * std::mem::size_of::<f32>()
+    }
+}
+
+/// YIN detector
+///
+/// Selects the first dip of `d'` below `1 - clarity_threshold` and reports
+/// `1 - d'` at that dip as the clarity, so clarity is always in 0..1.
+pub struct YinDetector {
+    difference: NormalizedDifference,
+}
+
+impl YinDetector {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            difference: NormalizedDifference::new(window_size),
+        }
+    }
+}
+
+impl Detector for YinDetector {
+    fn algorithm(&self) -> PitchDetectionAlgorithm {
+        PitchDetectionAlgorithm::Yin
+    }
+
+    fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        power_threshold: f32,
+        clarity_threshold: f32,
+    ) -> Option<PitchEstimate> {
+        let power: f32 = samples.iter().map(|s| s * s).sum();
+        if power < power_threshold {
+            return None;
+        }
+
+        let window = self.difference.compute(samples);
+        let tau = self.difference.dip_below(1.0 - clarity_threshold, window)?;
+        let frequency = sample_rate as f32 / self.difference.refined_period(tau, window);
+        if !frequency.is_finite() || frequency <= 0.0 {
+            return None;
+        }
+
+        Some(PitchEstimate {
+            frequency,
+            clarity: (1.0 - self.difference.values[tau]).clamp(0.0, 1.0),
+        })
+    }
+
+    fn memory_usage_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.difference.memory_usage_bytes()
+    }
+}
+
+/// Probabilistic YIN detector with frame-to-frame candidate tracking
+///
+/// Each threshold of a Beta-distributed prior selects the first dip of the
+/// cumulative mean normalized difference function below it. The prior mass of
+/// the thresholds is accumulated per dip, giving a probability for each period
+/// candidate. The reported clarity is the probability of the chosen candidate.
+pub struct ProbabilisticYinDetector {
+    difference: NormalizedDifference,
+    threshold_weights: Vec<f32>,
+    candidates: Vec<(usize, f32)>,
+    previous_frequency: Option<f32>,
+}
+
+impl ProbabilisticYinDetector {
+    pub fn new(window_size: usize) -> Self {
+        let thresholds = (1..=PYIN_THRESHOLD_COUNT).map(|k| k as f32 / PYIN_THRESHOLD_COUNT as f32);
+        let densities: Vec<f32> = thresholds
+            .map(|s| s.powf(PYIN_PRIOR_ALPHA - 1.0) * (1.0 - s).powf(PYIN_PRIOR_BETA - 1.0))
+            .collect();
+        let total: f32 = densities.iter().sum();
+
+        Self {
+            difference: NormalizedDifference::new(window_size),
+            threshold_weights: densities.iter().map(|d| d / total).collect(),
+            candidates: Vec::with_capacity(PYIN_THRESHOLD_COUNT),
+            previous_frequency: None,
+        }
+    }
+
+    /// Frequency of the previously tracked pitch, if the last frame was voiced
+    pub fn tracked_frequency(&self) -> Option<f32> {
+        self.previous_frequency
+    }
+
+    /// Forget the tracked pitch so the next frame is judged on its own
+    pub fn reset(&mut self) {
+        self.previous_frequency = None;
+    }
+
+    fn transition_weight(&self, frequency: f32) -> f32 {
+        match self.previous_frequency {
+            Some(previous) => {
+                let cents = 1200.0 * (frequency / previous).log2();
+                let distance = cents / PYIN_TRANSITION_WIDTH_CENTS;
+                (-0.5 * distance * distance).exp().max(PYIN_MIN_TRANSITION_WEIGHT)
+            }
+            None => 1.0,
+        }
+    }
+}
+
+impl Detector for ProbabilisticYinDetector {
+    fn algorithm(&self) -> PitchDetectionAlgorithm {
+        PitchDetectionAlgorithm::ProbabilisticYin
+    }
+
+    fn detect(
+        &mut self,
+        samples: &[f32],
+        sample_rate: u32,
+        power_threshold: f32,
+        clarity_threshold: f32,
+    ) -> Option<PitchEstimate> {
+        let power: f32 = samples.iter().map(|s| s * s).sum();
+        if power < power_threshold {
+            self.previous_frequency = None;
+            return None;
+        }
+
+        let window = self.difference.compute(samples);
+
+        self.candidates.clear();
+        for (k, &weight) in self.threshold_weights.iter().enumerate() {
+            let threshold = (k + 1) as f32 / PYIN_THRESHOLD_COUNT as f32;
+            if let Some(tau) = self.difference.dip_below(threshold, window) {
+                match self.candidates.iter_mut().find(|(candidate, _)| *candidate == tau) {
+                    Some((_, probability)) => *probability += weight,
+                    None => self.candidates.push((tau, weight)),
+                }
+            }
+        }
+
+        let best = self
+            .candidates
+            .iter()
+            .map(|&(tau, probability)| {
+                let frequency = sample_rate as f32 / self.difference.refined_period(tau, window);
+                (frequency, probability, probability * self.transition_weight(frequency))
+            })
+            .filter(|(frequency, _, _)| frequency.is_finite() && *frequency > 0.0)
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        match best {
+            Some((frequency, probability, _)) if probability >= clarity_threshold => {
+                self.previous_frequency = Some(frequency);
+                Some(PitchEstimate {
+                    frequency,
+                    clarity: probability.min(1.0),
+                })
+            }
+            _ => {
+                self.previous_frequency = None;
+                None
+            }
+        }
+    }
+
+    fn memory_usage_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.difference.memory_usage_bytes()
+            + self.threshold_weights.capacity() * std::mem::size_of::<f32>()
+            + self.candidates.capacity() * std::mem::size_of::<(usize, f32)>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const WINDOW_SIZE: usize = 2048;
+
+    fn harmonic_tone(frequency: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                (1..=4)
+                    .map(|h| (2.0 * std::f32::consts::PI * frequency * h as f32 * t).sin() / h as f32)
+                    .sum::<f32>()
+                    * 0.5
+            })
+            .collect()
+    }
+
+    fn cents_between(a: f32, b: f32) -> f32 {
+        1200.0 * (a / b).log2()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_all_algorithms_detect_harmonic_tone() {
+        let samples = harmonic_tone(220.0, WINDOW_SIZE);
+
+        for algorithm in PitchDetectionAlgorithm::ALL {
+            let mut detector = create_detector(algorithm, WINDOW_SIZE, WINDOW_SIZE / 2);
+            assert_eq!(detector.algorithm(), algorithm);
+
+            let estimate = detector
+                .detect(&samples, SAMPLE_RATE, 5.0, 0.7)
+                .unwrap_or_else(|| panic!("{} should detect a 220 Hz tone", algorithm.name()));
+            assert!(
+                cents_between(estimate.frequency, 220.0).abs() < 10.0,
+                "{} detected {} Hz",
+                algorithm.name(),
+                estimate.frequency
+            );
+            assert!(
+                estimate.clarity >= 0.7 && estimate.clarity <= 1.0,
+                "{} clarity {}",
+                algorithm.name(),
+                estimate.clarity
+            );
+            assert!(detector.memory_usage_bytes() > 0);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_all_algorithms_reject_silence() {
+        let silence = vec![0.0; WINDOW_SIZE];
+
+        for algorithm in PitchDetectionAlgorithm::ALL {
+            let mut detector = create_detector(algorithm, WINDOW_SIZE, WINDOW_SIZE / 2);
+            assert!(detector.detect(&silence, SAMPLE_RATE, 5.0, 0.7).is_none(), "{}", algorithm.name());
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_yin_clarity_is_one_minus_the_normalized_difference() {
+        let samples = harmonic_tone(220.0, WINDOW_SIZE);
+        let mut detector = YinDetector::new(WINDOW_SIZE);
+        let estimate = detector.detect(&samples, SAMPLE_RATE, 5.0, 0.7).unwrap();
+
+        let period = (SAMPLE_RATE as f32 / estimate.frequency).round() as usize;
+        let dip = (period - 1..=period + 1)
+            .map(|tau| detector.difference.values[tau])
+            .fold(f32::INFINITY, f32::min);
+        assert!((estimate.clarity - (1.0 - dip)).abs() < 1e-6);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_probabilistic_yin_threshold_prior_is_normalized() {
+        let detector = ProbabilisticYinDetector::new(WINDOW_SIZE);
+        let total: f32 = detector.threshold_weights.iter().sum();
+        assert!((total - 1.0).abs() < 1e-4);
+
+        // The Beta(2, 18) prior peaks near a threshold of 0.06
+        let peak = detector
+            .threshold_weights
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(k, _)| (k + 1) as f32 / PYIN_THRESHOLD_COUNT as f32)
+            .unwrap();
+        assert!((peak - 0.06).abs() < 0.011);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_probabilistic_yin_tracks_pitch() {
+        let mut detector = ProbabilisticYinDetector::new(WINDOW_SIZE);
+        assert_eq!(detector.tracked_frequency(), None);
+
+        let first = detector.detect(&harmonic_tone(330.0, WINDOW_SIZE), SAMPLE_RATE, 5.0, 0.5).unwrap();
+        assert!(cents_between(first.frequency, 330.0).abs() < 10.0);
+        assert!(first.clarity > 0.5 && first.clarity <= 1.0);
+        assert_eq!(detector.tracked_frequency(), Some(first.frequency));
+
+        let second = detector.detect(&harmonic_tone(335.0, WINDOW_SIZE), SAMPLE_RATE, 5.0, 0.5).unwrap();
+        assert!(cents_between(second.frequency, 335.0).abs() < 10.0);
+
+        // Silence ends the tracked note
+        assert!(detector.detect(&vec![0.0; WINDOW_SIZE], SAMPLE_RATE, 5.0, 0.5).is_none());
+        assert_eq!(detector.tracked_frequency(), None);
+
+        detector.detect(&harmonic_tone(440.0, WINDOW_SIZE), SAMPLE_RATE, 5.0, 0.5);
+        detector.reset();
+        assert_eq!(detector.tracked_frequency(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_probabilistic_yin_transition_weight() {
+        let mut detector = ProbabilisticYinDetector::new(WINDOW_SIZE);
+        assert_eq!(detector.transition_weight(440.0), 1.0);
+
+        detector.previous_frequency = Some(440.0);
+        assert!((detector.transition_weight(440.0) - 1.0).abs() < 1e-6);
+        assert!(detector.transition_weight(466.16) > detector.transition_weight(880.0));
+        assert_eq!(detector.transition_weight(1760.0), PYIN_MIN_TRANSITION_WEIGHT);
+    }
+}
@@ -1,12 +1,18 @@
 use super::pitch_detector::{PitchDetector, PitchDetectorConfig, PitchResult};
-use super::buffer_analyzer::{BufferAnalyzer, BufferProcessor};
+use super::multi_pitch::{MultiPitchConfig, MultiPitchDetector, MultiPitchEstimate};
+use super::buffer_analyzer::{BufferAnalyzer, BufferProcessor, WindowFunction};
+use super::spectrum::SpectrumAnalyzer;
 use super::buffer::CircularBuffer;
 use super::volume_detector::VolumeAnalysis;
 use crate::common::dev_log;
 use crate::app_config::{POWER_THRESHOLD, CLARITY_THRESHOLD};
+use crate::shared_types::PitchDetectionAlgorithm;
 
 pub type PitchAnalysisError = String;
 
+/// Window applied before computing the spectrum, Blackman keeps the sidelobes of strong partials low
+const SPECTRUM_WINDOW_FUNCTION: WindowFunction = WindowFunction::Blackman;
+
 /// Performance metrics for pitch analysis monitoring
 #[derive(Debug, Clone)]
 pub struct PitchPerformanceMetrics {
@@ -30,8 +36,15 @@ pub struct PitchPerformanceMetrics {
     pub latency_violations: u64,
     /// Success rate (successful_detections / analysis_cycles)
     pub success_rate: f32,
-    /// Time spent in YIN algorithm specifically (microseconds)
+    /// Time spent in the detection algorithm specifically (microseconds)
     pub yin_processing_time_us: f64,
+    /// Algorithm used for the reported pitch
+    pub active_algorithm: PitchDetectionAlgorithm,
+    /// Per-algorithm statistics, one entry per `PitchDetectionAlgorithm::ALL`
+    ///
+    /// Only the active algorithm is updated unless algorithm comparison is enabled,
+    /// in which case every algorithm analyzes the same windows.
+    pub algorithm_metrics: Vec<AlgorithmMetrics>,
 }
 
 impl Default for PitchPerformanceMetrics {
@@ -48,7 +61,73 @@ impl Default for PitchPerformanceMetrics {
             latency_violations: 0,
             success_rate: 0.0,
             yin_processing_time_us: 0.0,
+            active_algorithm: PitchDetectionAlgorithm::McLeod,
+            algorithm_metrics: PitchDetectionAlgorithm::ALL.iter().map(|&algorithm| AlgorithmMetrics::new(algorithm)).collect(),
+        }
+    }
+}
+
+/// Detection statistics for a single pitch detection algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlgorithmMetrics {
+    pub algorithm: PitchDetectionAlgorithm,
+    /// Number of windows analyzed by this algorithm
+    pub analysis_cycles: u64,
+    /// Number of windows in which this algorithm detected a pitch
+    pub successful_detections: u64,
+    /// Success rate (successful_detections / analysis_cycles)
+    pub success_rate: f32,
+    /// Processing time of the latest window (microseconds)
+    pub processing_time_us: f64,
+    /// Average processing time over recent windows (microseconds)
+    pub average_processing_time_us: f64,
+    /// Frequency detected in the latest window
+    pub last_frequency: Option<f32>,
+    /// Clarity of the latest detection
+    pub last_clarity: Option<f32>,
+    /// Average clarity of successful detections
+    pub average_clarity: f32,
+}
+
+impl AlgorithmMetrics {
+    pub fn new(algorithm: PitchDetectionAlgorithm) -> Self {
+        Self {
+            algorithm,
+            analysis_cycles: 0,
+            successful_detections: 0,
+            success_rate: 0.0,
+            processing_time_us: 0.0,
+            average_processing_time_us: 0.0,
+            last_frequency: None,
+            last_clarity: None,
+            average_clarity: 0.0,
+        }
+    }
+
+    /// Record the outcome of analyzing one window
+    fn record(&mut self, processing_time_us: f64, result: Option<&PitchResult>) {
+        // Exponential moving average for responsiveness, seeded by the first value
+        let alpha = 0.1;
+
+        self.analysis_cycles += 1;
+        self.processing_time_us = processing_time_us;
+        self.average_processing_time_us = if self.analysis_cycles == 1 {
+            processing_time_us
+        } else {
+            alpha * processing_time_us + (1.0 - alpha) * self.average_processing_time_us
+        };
+
+        self.last_frequency = result.map(|r| r.frequency);
+        self.last_clarity = result.map(|r| r.clarity);
+        if let Some(result) = result {
+            self.successful_detections += 1;
+            self.average_clarity = if self.successful_detections == 1 {
+                result.clarity
+            } else {
+                alpha as f32 * result.clarity + (1.0 - alpha as f32) * self.average_clarity
+            };
         }
+        self.success_rate = self.successful_detections as f32 / self.analysis_cycles as f32;
     }
 }
 
@@ -85,6 +164,13 @@ pub struct PitchAnalyzer {
     analysis_buffer: Vec<f32>,
     // Volume analysis for tracking
     last_volume_analysis: Option<VolumeAnalysis>,
+    // Detectors for the inactive algorithms, present while comparison is enabled
+    comparison_detectors: Vec<PitchDetector>,
+    // Polyphonic detector, present while polyphonic detection is enabled
+    multi_pitch_detector: Option<MultiPitchDetector>,
+    last_polyphonic_pitches: Vec<MultiPitchEstimate>,
+    // Magnitude spectrum of every analyzed window
+    spectrum_analyzer: SpectrumAnalyzer,
 }
 
 impl PitchAnalyzer {
@@ -98,16 +184,100 @@ impl PitchAnalyzer {
         
         // Pre-allocate buffer for zero-allocation processing
         let analysis_buffer = vec![0.0; config.sample_window_size];
+        let spectrum_analyzer = SpectrumAnalyzer::new(config.sample_window_size, sample_rate, SPECTRUM_WINDOW_FUNCTION)
+            .map_err(|e| format!("Failed to create spectrum analyzer: {}", e))?;
         
         Ok(Self {
             pitch_detector,
-            metrics: PitchPerformanceMetrics::default(),
+            metrics: PitchPerformanceMetrics {
+                active_algorithm: config.algorithm,
+                ..PitchPerformanceMetrics::default()
+            },
             last_detection: None,
             analysis_buffer,
             last_volume_analysis: None,
+            comparison_detectors: Vec::new(),
+            multi_pitch_detector: None,
+            last_polyphonic_pitches: Vec::new(),
+            spectrum_analyzer,
         })
     }
 
+    /// Switch the pitch detection algorithm
+    pub fn set_algorithm(&mut self, algorithm: PitchDetectionAlgorithm) -> Result<(), PitchAnalysisError> {
+        let mut config = self.config().clone();
+        config.algorithm = algorithm;
+        self.update_config(config)
+    }
+
+    /// Enable or disable running all algorithms on every analyzed window
+    /// 
+    /// When enabled, the inactive algorithms analyze the same samples as the active
+    /// one and their results are recorded in `PitchPerformanceMetrics::algorithm_metrics`.
+    /// Only the active algorithm's result is returned from the analysis methods.
+    pub fn set_algorithm_comparison(&mut self, enabled: bool) -> Result<(), PitchAnalysisError> {
+        self.comparison_detectors.clear();
+        if enabled {
+            let active = self.config().algorithm;
+            for algorithm in PitchDetectionAlgorithm::ALL.into_iter().filter(|&a| a != active) {
+                let config = super::pitch_detector::PitchDetectorConfig {
+                    algorithm,
+                    ..self.config().clone()
+                };
+                let detector = PitchDetector::new(config, self.pitch_detector.sample_rate())
+                    .map_err(|e| format!("Failed to create {} detector: {}", algorithm.name(), e))?;
+                self.comparison_detectors.push(detector);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether all algorithms analyze every window
+    pub fn is_algorithm_comparison_enabled(&self) -> bool {
+        !self.comparison_detectors.is_empty()
+    }
+
+    /// Enable or disable polyphonic detection alongside the single pitch detector
+    /// 
+    /// When enabled, every analyzed window is also passed to a `MultiPitchDetector`
+    /// limited to the configured frequency range. The results are available from
+    /// `latest_polyphonic_pitches()`.
+    pub fn set_polyphonic_detection(&mut self, enabled: bool) -> Result<(), PitchAnalysisError> {
+        self.last_polyphonic_pitches.clear();
+        self.multi_pitch_detector = if enabled {
+            let config = MultiPitchConfig {
+                min_frequency: self.config().min_frequency,
+                max_frequency: self.config().max_frequency,
+                ..MultiPitchConfig::default()
+            };
+            Some(MultiPitchDetector::new(config, self.pitch_detector.sample_rate())
+                .map_err(|e| format!("Failed to create polyphonic detector: {}", e))?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Check whether polyphonic detection runs on every window
+    pub fn is_polyphonic_detection_enabled(&self) -> bool {
+        self.multi_pitch_detector.is_some()
+    }
+
+    /// Pitches found by polyphonic detection in the latest window, ordered by frequency
+    pub fn latest_polyphonic_pitches(&self) -> &[MultiPitchEstimate] {
+        &self.last_polyphonic_pitches
+    }
+
+    /// Magnitude spectrum of the latest analyzed window
+    pub fn latest_spectrum(&self) -> &SpectrumAnalyzer {
+        &self.spectrum_analyzer
+    }
+
+    /// Get the statistics of a single algorithm
+    pub fn algorithm_metrics(&self, algorithm: PitchDetectionAlgorithm) -> Option<&AlgorithmMetrics> {
+        self.metrics.algorithm_metrics.iter().find(|m| m.algorithm == algorithm)
+    }
+
 
 
 
@@ -132,7 +302,7 @@ impl PitchAnalyzer {
         // Copy samples to pre-allocated buffer (minimal allocation)
         self.analysis_buffer.copy_from_slice(samples);
         
-        // Measure YIN algorithm performance specifically
+        // Measure detection algorithm performance specifically
         let yin_start = self.get_high_resolution_time();
         let pitch_result = match self.pitch_detector.analyze(&self.analysis_buffer) {
             Ok(result) => result,
@@ -147,6 +317,13 @@ impl PitchAnalyzer {
 
         let end_time = self.get_high_resolution_time();
 
+        self.record_algorithm_result(self.config().algorithm, yin_time_us, pitch_result.as_ref());
+        self.run_comparison_detectors();
+        if let Some(detector) = self.multi_pitch_detector.as_mut() {
+            self.last_polyphonic_pitches = detector.process(&self.analysis_buffer);
+        }
+        self.spectrum_analyzer.process(&self.analysis_buffer)?;
+
         // Process the result and publish events
         match pitch_result {
             Some(result) => {
@@ -170,7 +347,29 @@ impl PitchAnalyzer {
 
     /// Reset performance metrics
     pub fn reset_metrics(&mut self) {
-        self.metrics = PitchPerformanceMetrics::default();
+        self.metrics = PitchPerformanceMetrics {
+            active_algorithm: self.config().algorithm,
+            ..PitchPerformanceMetrics::default()
+        };
+    }
+
+    /// Record one window's result in the statistics of the given algorithm
+    fn record_algorithm_result(&mut self, algorithm: PitchDetectionAlgorithm, processing_time_us: f64, result: Option<&PitchResult>) {
+        if let Some(metrics) = self.metrics.algorithm_metrics.iter_mut().find(|m| m.algorithm == algorithm) {
+            metrics.record(processing_time_us, result);
+        }
+    }
+
+    /// Run the inactive algorithms on the current analysis buffer
+    fn run_comparison_detectors(&mut self) {
+        let mut detectors = std::mem::take(&mut self.comparison_detectors);
+        for detector in detectors.iter_mut() {
+            let start = self.get_high_resolution_time();
+            let result = detector.analyze(&self.analysis_buffer).ok().flatten();
+            let time_us = (self.get_high_resolution_time() - start) * 1000.0;
+            self.record_algorithm_result(detector.algorithm(), time_us, result.as_ref());
+        }
+        self.comparison_detectors = detectors;
     }
 
     /// Update performance metrics with new timing data
@@ -209,7 +408,8 @@ impl PitchAnalyzer {
         
         // Update memory usage estimate (pre-allocated buffer + detector memory)
         let buffer_memory = self.analysis_buffer.len() * std::mem::size_of::<f32>();
-        let detector_memory = self.pitch_detector.memory_usage_bytes();
+        let detector_memory = self.pitch_detector.memory_usage_bytes()
+            + self.comparison_detectors.iter().map(|d| d.memory_usage_bytes()).sum::<usize>();
         let analyzer_memory = std::mem::size_of::<Self>();
         self.metrics.memory_usage_bytes = buffer_memory + detector_memory + analyzer_memory;
     }
@@ -245,6 +445,7 @@ impl PitchAnalyzer {
                     padding_size: window_size / 2,
                     min_frequency: 80.0,
                     max_frequency: 2000.0,
+                    algorithm: self.config().algorithm,
                 };
                 
                 if let Ok(mut detector) = super::pitch_detector::PitchDetector::new(config, sample_rate as u32) {
@@ -360,11 +561,21 @@ impl PitchAnalyzer {
     pub fn update_config(&mut self, config: PitchDetectorConfig) -> Result<(), PitchAnalysisError> {
         self.pitch_detector.update_config(config.clone())
             .map_err(|e| format!("Failed to update pitch detector config: {}", e))?;
-        
+        self.metrics.active_algorithm = config.algorithm;
         
         // Resize analysis buffer if needed
         if config.sample_window_size != self.analysis_buffer.len() {
             self.analysis_buffer.resize(config.sample_window_size, 0.0);
+            self.spectrum_analyzer = SpectrumAnalyzer::new(config.sample_window_size, self.pitch_detector.sample_rate(), SPECTRUM_WINDOW_FUNCTION)
+                .map_err(|e| format!("Failed to create spectrum analyzer: {}", e))?;
+        }
+
+        // Keep comparison and polyphonic detectors in step with the new configuration
+        if self.is_algorithm_comparison_enabled() {
+            self.set_algorithm_comparison(true)?;
+        }
+        if self.is_polyphonic_detection_enabled() {
+            self.set_polyphonic_detection(true)?;
         }
 
         Ok(())
@@ -420,7 +631,7 @@ impl PitchAnalyzer {
             return Ok(None); // Not enough data available
         }
 
-        // Measure YIN algorithm performance specifically
+        // Measure detection algorithm performance specifically
         let yin_start = self.get_high_resolution_time();
         let pitch_result = match self.pitch_detector.analyze(&self.analysis_buffer) {
             Ok(result) => result,
@@ -435,6 +646,9 @@ impl PitchAnalyzer {
 
         let end_time = self.get_high_resolution_time();
 
+        self.record_algorithm_result(self.config().algorithm, yin_time_us, pitch_result.as_ref());
+        self.run_comparison_detectors();
+
         // Process the result and publish events
         match pitch_result {
             Some(result) => {
@@ -558,34 +772,52 @@ impl PitchAnalyzer {
         batch_data: &[f32], 
         overlap_factor: f32
     ) -> Result<Vec<PitchResult>, PitchAnalysisError> {
+        let results: Vec<PitchResult> = self.analyze_windows_with_overlap(batch_data, overlap_factor)?
+            .into_iter()
+            .filter_map(|(_, result)| result)
+            .collect();
+        
+        // Publish metrics update if we processed any chunks
+        if !results.is_empty() {
+            self.publish_metrics_update();
+        }
+        
+        Ok(results)
+    }
+
+    /// Analyze every overlapping window of a batch, keeping windows without a pitch
+    /// 
+    /// Windows are placed as in `analyze_batch_with_overlap`. Each entry holds the
+    /// offset of the window's first sample in `batch_data` and its result, so
+    /// callers can place the results on a timeline.
+    pub fn analyze_windows_with_overlap(
+        &mut self,
+        batch_data: &[f32],
+        overlap_factor: f32
+    ) -> Result<Vec<(usize, Option<PitchResult>)>, PitchAnalysisError> {
         let window_size = self.analysis_buffer.len();
-        let overlap_factor = overlap_factor.clamp(0.0, 0.9); // Max 90% overlap
-        let step_size = ((window_size as f32) * (1.0 - overlap_factor)) as usize;
+        let step_size = self.overlap_step_size(overlap_factor);
         
         if step_size == 0 || batch_data.len() < window_size {
             return Ok(Vec::new());
         }
         
-        let mut results = Vec::new();
+        let mut windows = Vec::with_capacity((batch_data.len() - window_size) / step_size + 1);
         let mut position = 0;
         
         while position + window_size <= batch_data.len() {
             let chunk = &batch_data[position..position + window_size];
-            
-            match self.analyze_samples(chunk)? {
-                Some(result) => results.push(result),
-                None => {} // No pitch detected
-            }
-            
+            windows.push((position, self.analyze_samples(chunk)?));
             position += step_size;
         }
         
-        // Publish metrics update if we processed any chunks
-        if !results.is_empty() {
-            self.publish_metrics_update();
-        }
-        
-        Ok(results)
+        Ok(windows)
+    }
+
+    /// Distance in samples between consecutive windows for an overlap factor
+    pub fn overlap_step_size(&self, overlap_factor: f32) -> usize {
+        let overlap_factor = overlap_factor.clamp(0.0, 0.9); // Max 90% overlap
+        ((self.analysis_buffer.len() as f32) * (1.0 - overlap_factor)) as usize
     }
 
     // Private helper methods
@@ -682,9 +914,10 @@ mod tests {
     fn create_test_config() -> PitchDetectorConfig {
         PitchDetectorConfig {
             sample_window_size: 2048, // Updated to match new accuracy-focused default
-            threshold: 0.15,
+            clarity_threshold: 0.15,
             min_frequency: 80.0,
             max_frequency: 2000.0,
+            ..PitchDetectorConfig::default()
         }
     }
 
@@ -706,12 +939,12 @@ mod tests {
 
         let mut new_config = create_test_config();
         new_config.sample_window_size = 2048;
-        new_config.threshold = 0.2;
+        new_config.clarity_threshold = 0.2;
 
         let result = analyzer.update_config(new_config);
         assert!(result.is_ok());
         assert_eq!(analyzer.config().sample_window_size, 2048);
-        assert_eq!(analyzer.config().threshold, 0.2);
+        assert_eq!(analyzer.config().clarity_threshold, 0.2);
     }
 
     #[wasm_bindgen_test]
@@ -1387,9 +1620,10 @@ mod tests {
         
         let config = PitchDetectorConfig {
             sample_window_size: 2048,
-            threshold: 0.15,
+            clarity_threshold: 0.15,
             min_frequency: 80.0,
             max_frequency: 2000.0,
+            ..PitchDetectorConfig::default()
         };
         
         let mut analyzer = PitchAnalyzer::new(config, sample_rate).unwrap();
@@ -1401,4 +1635,106 @@ mod tests {
             assert!((pitch_result.frequency - 440.0).abs() < 20.0, "Should detect 440Hz accurately");
         }
     }
-}
\ No newline at end of file
+
+    #[wasm_bindgen_test]
+    fn test_algorithm_comparison_on_same_input() {
+        let frequency = 330.0;
+        let sample_rate = STANDARD_SAMPLE_RATE;
+        let config = PitchDetectorConfig::default();
+        let samples: Vec<f32> = (0..config.sample_window_size)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                (2.0 * std::f32::consts::PI * frequency * t).sin()
+            })
+            .collect();
+
+        let mut analyzer = PitchAnalyzer::new(config, sample_rate).unwrap();
+        assert!(!analyzer.is_algorithm_comparison_enabled());
+        assert_eq!(analyzer.metrics().algorithm_metrics.len(), PitchDetectionAlgorithm::ALL.len());
+
+        // Without comparison only the active algorithm is measured
+        analyzer.analyze_samples(&samples).unwrap();
+        assert_eq!(analyzer.algorithm_metrics(PitchDetectionAlgorithm::McLeod).unwrap().analysis_cycles, 1);
+        assert_eq!(analyzer.algorithm_metrics(PitchDetectionAlgorithm::Yin).unwrap().analysis_cycles, 0);
+
+        analyzer.set_algorithm_comparison(true).unwrap();
+        assert!(analyzer.is_algorithm_comparison_enabled());
+        analyzer.analyze_samples(&samples).unwrap();
+
+        for algorithm in PitchDetectionAlgorithm::ALL {
+            let metrics = analyzer.algorithm_metrics(algorithm).unwrap();
+            assert!(metrics.analysis_cycles >= 1, "{} was not run", algorithm.name());
+            let detected = metrics.last_frequency
+                .unwrap_or_else(|| panic!("{} detected no pitch", algorithm.name()));
+            assert!((detected - frequency).abs() < 2.0, "{} detected {}Hz", algorithm.name(), detected);
+            assert!(metrics.success_rate > 0.0);
+        }
+
+        analyzer.set_algorithm_comparison(false).unwrap();
+        assert!(!analyzer.is_algorithm_comparison_enabled());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_set_algorithm() {
+        let mut analyzer = PitchAnalyzer::new(PitchDetectorConfig::default(), STANDARD_SAMPLE_RATE).unwrap();
+        assert_eq!(analyzer.metrics().active_algorithm, PitchDetectionAlgorithm::McLeod);
+
+        analyzer.set_algorithm_comparison(true).unwrap();
+        analyzer.set_algorithm(PitchDetectionAlgorithm::ProbabilisticYin).unwrap();
+        assert_eq!(analyzer.config().algorithm, PitchDetectionAlgorithm::ProbabilisticYin);
+        assert_eq!(analyzer.metrics().active_algorithm, PitchDetectionAlgorithm::ProbabilisticYin);
+        assert_eq!(analyzer.pitch_detector().algorithm(), PitchDetectionAlgorithm::ProbabilisticYin);
+        assert!(analyzer.is_algorithm_comparison_enabled());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_polyphonic_detection() {
+        let mut analyzer = PitchAnalyzer::new(PitchDetectorConfig::default(), STANDARD_SAMPLE_RATE).unwrap();
+        assert!(!analyzer.is_polyphonic_detection_enabled());
+
+        // A fifth, G3 and D4, spanning two analysis windows
+        let window_size = analyzer.config().sample_window_size;
+        let samples: Vec<f32> = (0..window_size * 2)
+            .map(|i| {
+                let t = i as f32 / STANDARD_SAMPLE_RATE as f32;
+                0.3 * (2.0 * std::f32::consts::PI * 196.0 * t).sin() + 0.3 * (2.0 * std::f32::consts::PI * 293.66 * t).sin()
+            })
+            .collect();
+
+        analyzer.analyze_samples(&samples[..window_size]).unwrap();
+        assert!(analyzer.latest_polyphonic_pitches().is_empty());
+
+        analyzer.set_polyphonic_detection(true).unwrap();
+        assert!(analyzer.is_polyphonic_detection_enabled());
+        analyzer.analyze_samples(&samples[..window_size]).unwrap();
+        analyzer.analyze_samples(&samples[window_size..]).unwrap();
+        let pitches = analyzer.latest_polyphonic_pitches();
+        assert_eq!(pitches.len(), 2, "{:?}", pitches);
+        assert!((pitches[0].frequency - 196.0).abs() < 1.0);
+        assert!((pitches[1].frequency - 293.66).abs() < 1.5);
+
+        analyzer.set_polyphonic_detection(false).unwrap();
+        assert!(analyzer.latest_polyphonic_pitches().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_spectrum_of_analyzed_window() {
+        let mut analyzer = PitchAnalyzer::new(PitchDetectorConfig::default(), STANDARD_SAMPLE_RATE).unwrap();
+        let window_size = analyzer.config().sample_window_size;
+        assert_eq!(analyzer.latest_spectrum().magnitudes().len(), window_size / 2 + 1);
+
+        let samples: Vec<f32> = (0..window_size)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / STANDARD_SAMPLE_RATE as f32).sin())
+            .collect();
+        analyzer.analyze_samples(&samples).unwrap();
+
+        let spectrum = analyzer.latest_spectrum();
+        let peak_bin = spectrum.magnitudes()
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(bin, _)| bin)
+            .unwrap();
+        assert!((peak_bin as f32 * spectrum.bin_width_hz() - 440.0).abs() <= spectrum.bin_width_hz());
+    }
+}
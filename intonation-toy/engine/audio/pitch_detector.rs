@@ -1,7 +1,8 @@
-use pitch_detection::detector::{mcleod::McLeodDetector, PitchDetector as PitchDetectorTrait};
 use crate::app_config::{CLARITY_THRESHOLD, POWER_THRESHOLD};
+use crate::shared_types::PitchDetectionAlgorithm;
 
 use super::buffer::BUFFER_SIZE;
+use super::pitch_algorithms::{create_detector, Detector};
 
 pub type PitchDetectionError = String;
 
@@ -31,6 +32,7 @@ pub struct PitchDetectorConfig {
     pub padding_size: usize,
     pub min_frequency: f32,
     pub max_frequency: f32,
+    pub algorithm: PitchDetectionAlgorithm,
 }
 
 impl Default for PitchDetectorConfig {
@@ -42,13 +44,14 @@ impl Default for PitchDetectorConfig {
             padding_size: BUFFER_SIZE / 2, // Zero-padding size
             min_frequency: 80.0,
             max_frequency: 2000.0,
+            algorithm: PitchDetectionAlgorithm::McLeod,
         }
     }
 }
 
 pub struct PitchDetector {
     config: PitchDetectorConfig,
-    detector: McLeodDetector<f32>,
+    detector: Box<dyn Detector>,
     sample_rate: u32,
 }
 
@@ -104,12 +107,12 @@ impl PitchDetector {
             ));
         }
 
-        let mcleod_detector = McLeodDetector::new(config.sample_window_size, config.padding_size);
+        let detector = create_detector(config.algorithm, config.sample_window_size, config.padding_size);
 
 
         Ok(Self {
             config,
-            detector: mcleod_detector,
+            detector,
             sample_rate,
         })
     }
@@ -123,8 +126,7 @@ impl PitchDetector {
             ));
         }
 
-        // Use McLeod analysis
-        let result = self.detector.get_pitch(samples, self.sample_rate as usize, self.config.power_threshold, self.config.clarity_threshold);
+        let result = self.detector.detect(samples, self.sample_rate, self.config.power_threshold, self.config.clarity_threshold);
         
 
         match result {
@@ -281,8 +283,11 @@ impl PitchDetector {
             ));
         }
 
-        if new_config.sample_window_size != self.config.sample_window_size || new_config.padding_size != self.config.padding_size {
-            self.detector = McLeodDetector::new(new_config.sample_window_size, new_config.padding_size);
+        if new_config.sample_window_size != self.config.sample_window_size
+            || new_config.padding_size != self.config.padding_size
+            || new_config.algorithm != self.config.algorithm
+        {
+            self.detector = create_detector(new_config.algorithm, new_config.sample_window_size, new_config.padding_size);
         }
 
 
@@ -298,18 +303,21 @@ impl PitchDetector {
         self.sample_rate
     }
 
+    /// Get the detection algorithm currently in use
+    pub fn algorithm(&self) -> PitchDetectionAlgorithm {
+        self.detector.algorithm()
+    }
+
     /// Get memory usage estimate for this detector instance
     pub fn memory_usage_bytes(&self) -> usize {
         // Calculate memory usage for the detector
         let config_size = std::mem::size_of::<PitchDetectorConfig>();
-        let detector_size = std::mem::size_of::<McLeodDetector<f32>>();
         let base_size = std::mem::size_of::<Self>();
         
-        // Estimate McLeod detector internal buffer size
-        // McLeod uses autocorrelation and FFT buffers
-        let mcleod_internal_buffers = (self.config.sample_window_size + self.config.padding_size) * std::mem::size_of::<f32>() * 4;
+        // The algorithm reports its own internal buffers
+        let detector_size = self.detector.memory_usage_bytes();
         
-        base_size + config_size + detector_size + mcleod_internal_buffers
+        base_size + config_size + detector_size
     }
 
     /// Validate that the detector can meet performance requirements
@@ -374,6 +382,7 @@ mod tests {
         assert_eq!(config.padding_size, BUFFER_SIZE / 2);
         assert_eq!(config.min_frequency, 80.0);
         assert_eq!(config.max_frequency, 2000.0);
+        assert_eq!(config.algorithm, PitchDetectionAlgorithm::McLeod);
     }
 
     #[wasm_bindgen_test]
@@ -385,6 +394,7 @@ mod tests {
             padding_size: 1024,
             min_frequency: 60.0,
             max_frequency: 4000.0,
+            algorithm: PitchDetectionAlgorithm::Yin,
         };
         
         assert_eq!(config.sample_window_size, 2048);
@@ -800,4 +810,43 @@ mod tests {
             assert!((pitch_result.frequency - fundamental).abs() < 20.0);
         }
     }
-}
\ No newline at end of file
+
+    #[wasm_bindgen_test]
+    fn test_pitch_detector_algorithm_selection() {
+        let fundamental = 220.0;
+        let samples: Vec<f32> = (0..BUFFER_SIZE)
+            .map(|i| {
+                let t = i as f32 / STANDARD_SAMPLE_RATE as f32;
+                (2.0 * std::f32::consts::PI * fundamental * t).sin()
+                    + 0.5 * (2.0 * std::f32::consts::PI * fundamental * 2.0 * t).sin()
+            })
+            .collect();
+
+        for algorithm in PitchDetectionAlgorithm::ALL {
+            let config = PitchDetectorConfig {
+                algorithm,
+                ..PitchDetectorConfig::default()
+            };
+            let mut detector = PitchDetector::new(config, STANDARD_SAMPLE_RATE).unwrap();
+            assert_eq!(detector.algorithm(), algorithm);
+
+            let result = detector.analyze(&samples).unwrap();
+            let pitch_result = result.unwrap_or_else(|| panic!("{} detected no pitch", algorithm.name()));
+            assert!((pitch_result.frequency - fundamental).abs() < 2.0,
+                "{} detected {}Hz", algorithm.name(), pitch_result.frequency);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_pitch_detector_update_config_switches_algorithm() {
+        let mut detector = PitchDetector::new(PitchDetectorConfig::default(), STANDARD_SAMPLE_RATE).unwrap();
+        assert_eq!(detector.algorithm(), PitchDetectionAlgorithm::McLeod);
+
+        let mut new_config = detector.config().clone();
+        new_config.algorithm = PitchDetectionAlgorithm::ProbabilisticYin;
+        assert!(detector.update_config(new_config).is_ok());
+        assert_eq!(detector.algorithm(), PitchDetectionAlgorithm::ProbabilisticYin);
+        assert_eq!(detector.config().algorithm, PitchDetectionAlgorithm::ProbabilisticYin);
+        assert!(detector.memory_usage_bytes() > 0);
+    }
+}
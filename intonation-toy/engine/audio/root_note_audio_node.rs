@@ -1,21 +1,68 @@
 use std::sync::Arc;
 
 use wasm_bindgen::JsValue;
-use web_sys::{AudioContext, AudioParam, GainNode, OscillatorNode, OscillatorType};
+use web_sys::{AudioBufferSourceNode, AudioContext, AudioScheduledSourceNode, AudioParam, GainNode, OscillatorNode, OscillatorType};
 use crate::common::dev_log;
+use crate::shared_types::DroneTimbre;
 use super::microphone::AudioError;
-use super::signal_generator::RootNoteAudioConfig;
+use super::signal_generator::{drone_timbre_harmonics, drone_voice_levels, pluck_envelope, RootNoteAudioConfig};
+
+/// One oscillator of the drone with its own level
+struct DroneVoiceNode {
+    oscillator: OscillatorNode,
+    gain_node: GainNode,
+    /// Looping pluck envelope that drives the voice gain, `None` for sustained voices
+    envelope_source: Option<AudioBufferSourceNode>,
+}
+
+impl DroneVoiceNode {
+    fn stop(&self) {
+        if let Err(e) = self.oscillator.stop() {
+            dev_log!("[RootNoteAudioNode] Warning: Failed to stop voice oscillator: {:?}", e);
+        }
+        if let Some(envelope_source) = &self.envelope_source {
+            // The source node's own stop() is deprecated in favor of the base class method
+            let scheduled_source: &AudioScheduledSourceNode = envelope_source;
+            let _ = scheduled_source.stop();
+            let _ = envelope_source.disconnect();
+        }
+        let _ = self.oscillator.disconnect();
+        let _ = self.gain_node.disconnect();
+    }
+}
+
+/// Set the waveform of an oscillator from a drone timbre
+pub(crate) fn set_oscillator_timbre(audio_context: &AudioContext, oscillator: &OscillatorNode, timbre: DroneTimbre) {
+    if timbre == DroneTimbre::Sine {
+        oscillator.set_type(OscillatorType::Sine);
+        return;
+    }
+    // Index 0 is the DC offset
+    let mut real = vec![0.0; drone_timbre_harmonics(timbre).len() + 1];
+    let mut imag: Vec<f32> = std::iter::once(0.0).chain(drone_timbre_harmonics(timbre)).collect();
+    match audio_context.create_periodic_wave(&mut real, &mut imag) {
+        Ok(wave) => oscillator.set_periodic_wave(&wave),
+        Err(e) => {
+            dev_log!("[RootNoteAudioNode] Failed to create periodic wave: {:?}", e);
+            oscillator.set_type(OscillatorType::Sine);
+        }
+    }
+}
 
 /// Dedicated root note audio node using Web Audio API's OscillatorNode
 /// 
 /// This node creates a separate audio path that connects directly to speakers,
 /// independent of the main AudioWorklet processing pipeline. This ensures
 /// root note audio is always audible regardless of main output settings.
+/// 
+/// Each drone voice has its own oscillator and gain, and all voices are mixed
+/// into one gain node for the volume. Plucked voices are driven by a looping
+/// envelope buffer, so the pluck cycle runs without any scheduling from Rust.
 pub struct RootNoteAudioNode {
     /// Reference to the AudioContext
     audio_context: AudioContext,
-    /// The oscillator node for generating root note audio
-    oscillator: OscillatorNode,
+    /// One oscillator per drone voice
+    voices: Vec<DroneVoiceNode>,
     /// Gain node for volume control
     gain_node: GainNode,
     /// Current configuration
@@ -35,17 +82,7 @@ impl RootNoteAudioNode {
     /// * `Ok(RootNoteAudioNode)` - Successfully created node
     /// * `Err(AudioError)` - Failed to create node
     pub fn new(audio_context: &AudioContext, config: RootNoteAudioConfig) -> Result<Self, AudioError> {
-        dev_log!("[RootNoteAudioNode] Creating new root note audio node with frequency: {} Hz", config.frequency);
-        
-        // Create oscillator node
-        let oscillator = audio_context.create_oscillator()
-            .map_err(|e| AudioError::Generic(format!("Failed to create oscillator: {:?}", e)))?;
-        
-        // Set oscillator to sine wave
-        oscillator.set_type(OscillatorType::Sine);
-        
-        // Set initial frequency
-        oscillator.frequency().set_value(config.frequency);
+        dev_log!("[RootNoteAudioNode] Creating new root note audio node with frequency: {} Hz and {} voices", config.frequency, config.voices.len());
         
         // Create gain node for volume control
         let gain_node = audio_context.create_gain()
@@ -54,37 +91,95 @@ impl RootNoteAudioNode {
         // Set initial volume
         gain_node.gain().set_value(config.volume);
         
-        // Connect oscillator -> gain -> destination
-        oscillator.connect_with_audio_node(&gain_node)
-            .map_err(|e| AudioError::Generic(format!("Failed to connect oscillator to gain: {:?}", e)))?;
-        
+        // Connect voices -> gain -> destination
         gain_node.connect_with_audio_node(&audio_context.destination())
             .map_err(|e| AudioError::Generic(format!("Failed to connect gain to destination: {:?}", e)))?;
         
-        // Start the oscillator
-        oscillator.start()
-            .map_err(|e| AudioError::Generic(format!("Failed to start oscillator: {:?}", e)))?;
-        
-        dev_log!("[RootNoteAudioNode] Successfully created and started root note audio node - gain: {}", 
-                config.volume);
-        
-        Ok(Self {
+        let mut node = Self {
             audio_context: audio_context.clone(),
-            oscillator,
+            voices: Vec::new(),
             gain_node,
             config,
             is_connected: true,
-        })
+        };
+        node.voices = node.create_voices()?;
+        
+        dev_log!("[RootNoteAudioNode] Successfully created and started root note audio node - gain: {}", 
+                node.config.volume);
+        
+        Ok(node)
     }
     
-    /// Update the frequency of the root note oscillator
+    /// Create and start the oscillators of all voices in the current configuration
+    fn create_voices(&self) -> Result<Vec<DroneVoiceNode>, AudioError> {
+        let levels = drone_voice_levels(&self.config.voices);
+        let sample_rate = self.audio_context.sample_rate();
+        let envelope = pluck_envelope(&self.config.envelope, sample_rate as u32);
+        let now = self.audio_context.current_time();
+        
+        let mut voices = Vec::with_capacity(self.config.voices.len());
+        for (index, (voice, level)) in self.config.voices.iter().zip(levels).enumerate() {
+            let oscillator = self.audio_context.create_oscillator()
+                .map_err(|e| AudioError::Generic(format!("Failed to create oscillator: {:?}", e)))?;
+            set_oscillator_timbre(&self.audio_context, &oscillator, self.config.timbre);
+            oscillator.frequency().set_value(self.config.frequency * voice.ratio);
+            
+            let gain_node = self.audio_context.create_gain()
+                .map_err(|e| AudioError::Generic(format!("Failed to create voice gain: {:?}", e)))?;
+            
+            oscillator.connect_with_audio_node(&gain_node)
+                .map_err(|e| AudioError::Generic(format!("Failed to connect oscillator to voice gain: {:?}", e)))?;
+            gain_node.connect_with_audio_node(&self.gain_node)
+                .map_err(|e| AudioError::Generic(format!("Failed to connect voice gain to gain: {:?}", e)))?;
+            
+            let envelope_source = match self.config.envelope.pluck_period_seconds {
+                Some(period_seconds) if !envelope.is_empty() => {
+                    // The envelope buffer adds to the gain, which stays silent between plucks
+                    gain_node.gain().set_value(0.0);
+                    let samples: Vec<f32> = envelope.iter().map(|value| value * level).collect();
+                    let buffer = self.audio_context.create_buffer(1, samples.len() as u32, sample_rate)
+                        .map_err(|e| AudioError::Generic(format!("Failed to create pluck envelope buffer: {:?}", e)))?;
+                    buffer.copy_to_channel(&samples, 0)
+                        .map_err(|e| AudioError::Generic(format!("Failed to fill pluck envelope buffer: {:?}", e)))?;
+                    
+                    let source = self.audio_context.create_buffer_source()
+                        .map_err(|e| AudioError::Generic(format!("Failed to create pluck envelope source: {:?}", e)))?;
+                    source.set_buffer(Some(&buffer));
+                    source.set_loop(true);
+                    source.connect_with_audio_param(&gain_node.gain())
+                        .map_err(|e| AudioError::Generic(format!("Failed to connect pluck envelope to voice gain: {:?}", e)))?;
+                    
+                    // Pluck the voices one after another
+                    let offset = period_seconds as f64 * index as f64 / self.config.voices.len() as f64;
+                    source.start_with_when(now + offset)
+                        .map_err(|e| AudioError::Generic(format!("Failed to start pluck envelope: {:?}", e)))?;
+                    Some(source)
+                }
+                _ => {
+                    gain_node.gain().set_value(0.0);
+                    let _ = gain_node.gain().set_target_at_time(level, now, self.config.envelope.attack_seconds as f64 / 3.0);
+                    None
+                }
+            };
+            
+            oscillator.start()
+                .map_err(|e| AudioError::Generic(format!("Failed to start oscillator: {:?}", e)))?;
+            
+            voices.push(DroneVoiceNode { oscillator, gain_node, envelope_source });
+        }
+        Ok(voices)
+    }
+    
+    /// Update the frequency of the root note oscillators
     /// 
     /// # Arguments
-    /// * `frequency` - New frequency in Hz
+    /// * `frequency` - New root frequency in Hz, each voice keeps its ratio to it
     pub fn set_frequency(&mut self, frequency: f32) {
         if (self.config.frequency - frequency).abs() > f32::EPSILON {
             dev_log!("[RootNoteAudioNode] Updating frequency from {} Hz to {} Hz", self.config.frequency, frequency);
-            self.oscillator.frequency().set_value(frequency);
+            for (voice_node, voice) in self.voices.iter().zip(&self.config.voices) {
+                voice_node.oscillator.frequency().set_value(frequency * voice.ratio);
+            }
             self.config.frequency = frequency;
         }
     }
@@ -121,6 +216,22 @@ impl RootNoteAudioNode {
     pub fn update_config(&mut self, config: RootNoteAudioConfig) {
         //dev_log!("[RootNoteAudioNode] Updating configuration - enabled: {}, frequency: {} Hz, volume: {}", config.enabled, config.frequency, config.volume);
         
+        // Rebuild the voices if the drone itself changed
+        if config.voices != self.config.voices || config.timbre != self.config.timbre || config.envelope != self.config.envelope {
+            dev_log!("[RootNoteAudioNode] Rebuilding drone with {} voices", config.voices.len());
+            for voice in self.voices.drain(..) {
+                voice.stop();
+            }
+            self.config.voices = config.voices.clone();
+            self.config.timbre = config.timbre;
+            self.config.envelope = config.envelope;
+            self.config.frequency = config.frequency;
+            match self.create_voices() {
+                Ok(voices) => self.voices = voices,
+                Err(e) => { dev_log!("[RootNoteAudioNode] Failed to rebuild drone: {:?}", e); }
+            }
+        }
+        
         // Update frequency if changed
         self.set_frequency(config.frequency);
         
@@ -139,13 +250,10 @@ impl RootNoteAudioNode {
         if self.is_connected {
             dev_log!("[RootNoteAudioNode] Cleaning up root note audio node");
             
-            // Stop the oscillator
-            if let Err(e) = self.oscillator.stop() {
-                dev_log!("[RootNoteAudioNode] Warning: Failed to stop oscillator: {:?}", e);
+            // Stop the oscillators and disconnect nodes
+            for voice in self.voices.drain(..) {
+                voice.stop();
             }
-            
-            // Disconnect nodes
-            let _ = self.oscillator.disconnect();
             let _ = self.gain_node.disconnect();
             
             self.is_connected = false;
@@ -1,5 +1,6 @@
 
 use super::buffer::STANDARD_SAMPLE_RATE;
+use crate::shared_types::{DroneEnvelope, DroneTimbre, TestSignalShape, TestWaveform, TunedDroneVoice};
 
 /// Configuration for test signal generation
 #[derive(Debug, Clone, PartialEq)]
@@ -12,6 +13,8 @@ pub struct SignalGeneratorConfig {
     pub amplitude: f32,
     /// Sample rate for generation
     pub sample_rate: u32,
+    /// Waveform, vibrato, glide and noise
+    pub shape: TestSignalShape,
 }
 
 /// Configuration for root note audio generation
@@ -21,6 +24,24 @@ pub struct RootNoteAudioConfig {
     pub frequency: f32,
     /// Volume amplitude (0.0-1.0)
     pub volume: f32,
+    /// Voices relative to the root frequency, sharing the volume
+    pub voices: Vec<TunedDroneVoice>,
+    pub timbre: DroneTimbre,
+    pub envelope: DroneEnvelope,
+}
+
+/// Configuration for a one-shot target note
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetNoteConfig {
+    /// Note frequency in Hz
+    pub frequency: f32,
+    /// Volume amplitude (0.0-1.0) between the attack and the release
+    pub volume: f32,
+    /// Time from the start of the attack to the end of the release
+    pub duration_seconds: f32,
+    pub timbre: DroneTimbre,
+    /// Attack and release, the pluck period is ignored
+    pub envelope: DroneEnvelope,
 }
 
 impl Default for SignalGeneratorConfig {
@@ -30,6 +51,7 @@ impl Default for SignalGeneratorConfig {
             frequency: 440.0,
             amplitude: 0.15,
             sample_rate: STANDARD_SAMPLE_RATE,
+            shape: TestSignalShape::default(),
         }
     }
 }
@@ -39,6 +61,347 @@ impl Default for RootNoteAudioConfig {
         Self {
             frequency: 220.0,
             volume: 0.0,
+            voices: vec![TunedDroneVoice::ROOT],
+            timbre: DroneTimbre::Sine,
+            envelope: DroneEnvelope::SUSTAINED,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Amplitudes of the first `count` harmonics of a waveform, starting at the fundamental
+///
+/// Sawtooth and square waves are built from their Fourier series, so they are band
+/// limited like the Web Audio oscillators when `count` stops below Nyquist. Custom
+/// spectra are scaled so that the harmonics add up to a peak of at most 1.0.
+pub fn waveform_harmonics(waveform: &TestWaveform, count: usize) -> Vec<f32> {
+    use std::f32::consts::PI;
+    match waveform {
+        TestWaveform::Sine => (1..=count).map(|k| if k == 1 { 1.0 } else { 0.0 }).collect(),
+        TestWaveform::Sawtooth => (1..=count)
+            .map(|k| {
+                let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+                sign * 2.0 / (PI * k as f32)
+            })
+            .collect(),
+        TestWaveform::Square => (1..=count)
+            .map(|k| if k % 2 == 1 { 4.0 / (PI * k as f32) } else { 0.0 })
+            .collect(),
+        TestWaveform::Harmonics(amplitudes) => {
+            let total: f32 = amplitudes.iter().map(|amplitude| amplitude.abs()).sum();
+            let scale = if total > 1.0 { 1.0 / total } else { 1.0 };
+            (0..count).map(|k| amplitudes.get(k).copied().unwrap_or(0.0) * scale).collect()
+        }
+    }
+}
+
+/// Amplitudes of the harmonics of a drone timbre, starting at the fundamental
+pub fn drone_timbre_harmonics(timbre: DroneTimbre) -> Vec<f32> {
+    match timbre {
+        DroneTimbre::Sine => vec![1.0],
+        DroneTimbre::Organ => vec![1.0, 0.5, 0.3, 0.15, 0.08],
+        DroneTimbre::Reed => (1..=11).map(|k| if k % 2 == 1 { 1.0 / k as f32 } else { 0.1 / k as f32 }).collect(),
+        DroneTimbre::Tanpura => (1..=24).map(|k| 1.0 / (k as f32).powf(0.6)).collect(),
+    }
+}
+
+/// Level of each voice relative to the drone volume, so that all voices together peak at it
+pub fn drone_voice_levels(voices: &[TunedDroneVoice]) -> Vec<f32> {
+    let total_gain: f32 = voices.iter().map(|voice| voice.gain).sum();
+    voices.iter().map(|voice| voice.gain / total_gain.max(1.0)).collect()
+}
+
+/// Gain curve of one pluck, lasting one pluck period
+///
+/// Rises linearly over the attack, then decays exponentially by 60 dB over the
+/// release time. Empty for a sustained envelope.
+pub fn pluck_envelope(envelope: &DroneEnvelope, sample_rate: u32) -> Vec<f32> {
+    let Some(period_seconds) = envelope.pluck_period_seconds else {
+        return Vec::new();
+    };
+    let sample_rate = sample_rate as f32;
+    let attack_samples = (envelope.attack_seconds * sample_rate).max(1.0);
+    // Natural log of 1000, the amplitude ratio of 60 dB
+    let decay_rate = 6.907_755 / (envelope.release_seconds.max(1e-3) * sample_rate);
+    (0..(period_seconds * sample_rate).round() as usize)
+        .map(|i| {
+            let i = i as f32;
+            if i < attack_samples { i / attack_samples } else { (-(i - attack_samples) * decay_rate).exp() }
+        })
+        .collect()
+}
+
+/// Gain automation of a target note as (seconds after the start, gain) points
+///
+/// The gain ramps linearly between the points: up over the attack, held, and
+/// down over the release. Attack and release are shortened proportionally when
+/// they do not fit into the duration together.
+pub fn target_note_gain_points(config: &TargetNoteConfig) -> [(f32, f32); 4] {
+    let duration = config.duration_seconds.max(0.0);
+    let (attack, release) = (config.envelope.attack_seconds.max(0.0), config.envelope.release_seconds.max(0.0));
+    let fit = if attack + release > duration { duration / (attack + release) } else { 1.0 };
+    [
+        (0.0, 0.0),
+        (attack * fit, config.volume),
+        (duration - release * fit, config.volume),
+        (duration, 0.0),
+    ]
+}
+
+/// RMS level of a waveform at amplitude 1.0 with `count` harmonics
+pub fn waveform_rms(waveform: &TestWaveform, count: usize) -> f32 {
+    let power: f32 = waveform_harmonics(waveform, count).iter().map(|amplitude| amplitude * amplitude / 2.0).sum();
+    power.sqrt()
+}
+
+/// RMS level of the added noise relative to the amplitude, 0.0 without noise
+///
+/// The noise is set against the RMS level of the waveform with all harmonics
+/// below Nyquist at the base frequency, so the SNR holds for any waveform.
+pub fn noise_level(config: &SignalGeneratorConfig) -> f32 {
+    match config.shape.snr_db {
+        Some(snr_db) => {
+            let harmonics = harmonics_below_nyquist(config.frequency, config.sample_rate);
+            waveform_rms(&config.shape.waveform, harmonics) / 10.0_f32.powf(snr_db / 20.0)
+        }
+        None => 0.0,
+    }
+}
+
+/// Number of harmonics of `frequency` below the Nyquist frequency, at least one
+fn harmonics_below_nyquist(frequency: f32, sample_rate: u32) -> usize {
+    ((sample_rate as f32 / 2.0 / frequency.max(1.0)) as usize).max(1)
+}
+
+/// Deterministic white noise with a normal distribution and unit variance
+///
+/// Seeded so that rendered test signals are reproducible.
+#[derive(Debug, Clone)]
+pub struct NoiseGenerator {
+    state: u32,
+    spare: Option<f32>,
+}
+
+impl NoiseGenerator {
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed.max(1), spare: None }
+    }
+
+    /// Uniformly distributed in (0.0, 1.0]
+    fn next_uniform(&mut self) -> f32 {
+        // xorshift32
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1u32 << 24) as f32 + 1.0 / (1u32 << 25) as f32
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        if let Some(spare) = self.spare.take() {
+            return spare;
+        }
+        // Box-Muller transform, which yields two samples at a time
+        let radius = (-2.0 * self.next_uniform().ln()).sqrt();
+        let angle = 2.0 * std::f32::consts::PI * self.next_uniform();
+        self.spare = Some(radius * angle.sin());
+        radius * angle.cos()
+    }
+}
+
+/// Renders the test signal into sample buffers without the browser
+///
+/// Produces the same waveform, vibrato, glide and noise as `TestSignalAudioNode`,
+/// so the analysis can be exercised with realistic input in native tests.
+#[derive(Debug, Clone)]
+pub struct TestSignalRenderer {
+    config: SignalGeneratorConfig,
+    /// Phase of the fundamental in cycles (0.0 - 1.0)
+    phase: f64,
+    rendered_samples: u64,
+    noise: NoiseGenerator,
+    /// Harmonic amplitudes of the waveform, extended when the frequency drops and more fit below Nyquist
+    harmonics: Vec<f32>,
+}
+
+impl TestSignalRenderer {
+    pub fn new(config: SignalGeneratorConfig) -> Self {
+        Self { config, phase: 0.0, rendered_samples: 0, noise: NoiseGenerator::new(0x5eed), harmonics: Vec::new() }
+    }
+
+    pub fn config(&self) -> &SignalGeneratorConfig {
+        &self.config
+    }
+
+    /// Continue with a new config, starting its glide and vibrato over
+    ///
+    /// The phase carries over, so the waveform stays continuous across the change.
+    pub fn set_config(&mut self, config: SignalGeneratorConfig) {
+        self.config = config;
+        self.rendered_samples = 0;
+        self.harmonics.clear();
+    }
+
+    /// Instantaneous frequency `seconds` after the signal started
+    pub fn frequency_at(&self, seconds: f32) -> f32 {
+        let shape = &self.config.shape;
+        let glided = match shape.glide {
+            Some(glide) if glide.seconds > 0.0 => {
+                let progress = (seconds / glide.seconds).clamp(0.0, 1.0);
+                self.config.frequency * (glide.to_frequency / self.config.frequency).powf(progress)
+            }
+            Some(glide) => glide.to_frequency,
+            None => self.config.frequency,
+        };
+        let vibrato_cents = shape.vibrato_depth_cents * (2.0 * std::f32::consts::PI * shape.vibrato_rate_hz * seconds).sin();
+        glided * 2.0_f32.powf(vibrato_cents / 1200.0)
+    }
+
+    /// Fill `output` with the next samples, silence while the signal is disabled
+    pub fn render(&mut self, output: &mut [f32]) {
+        let sample_rate = self.config.sample_rate as f32;
+        let noise_level = noise_level(&self.config);
+        for sample in output.iter_mut() {
+            if !self.config.enabled {
+                *sample = 0.0;
+                continue;
+            }
+            let frequency = self.frequency_at(self.rendered_samples as f32 / sample_rate);
+            // The first harmonics don't depend on how many there are, so a longer list serves lower counts too
+            let count = harmonics_below_nyquist(frequency, self.config.sample_rate);
+            if self.harmonics.len() < count {
+                self.harmonics = waveform_harmonics(&self.config.shape.waveform, count);
+            }
+            let tone: f32 = self.harmonics[..count]
+                .iter()
+                .enumerate()
+                .filter(|(_, amplitude)| **amplitude != 0.0)
+                .map(|(k, amplitude)| amplitude * (2.0 * std::f64::consts::PI * (k + 1) as f64 * self.phase).sin() as f32)
+                .sum();
+            let noise = if noise_level > 0.0 { noise_level * self.noise.next_sample() } else { 0.0 };
+            *sample = self.config.amplitude * (tone + noise);
+
+            self.phase = (self.phase + frequency as f64 / sample_rate as f64).fract();
+            self.rendered_samples += 1;
+        }
+    }
+
+    /// Render the next `seconds` of the signal
+    pub fn render_seconds(&mut self, seconds: f32) -> Vec<f32> {
+        let mut samples = vec![0.0; (seconds * self.config.sample_rate as f32).round() as usize];
+        self.render(&mut samples);
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_types::Glide;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn config(frequency: f32, shape: TestSignalShape) -> SignalGeneratorConfig {
+        SignalGeneratorConfig { enabled: true, frequency, amplitude: 0.5, sample_rate: STANDARD_SAMPLE_RATE, shape }
+    }
+
+    /// Magnitude of a single DFT bin at `frequency`, normalized to the amplitude of a sine
+    fn magnitude_at(samples: &[f32], frequency: f32) -> f32 {
+        let (mut re, mut im) = (0.0_f64, 0.0_f64);
+        for (n, &sample) in samples.iter().enumerate() {
+            let angle = 2.0 * std::f64::consts::PI * frequency as f64 * n as f64 / STANDARD_SAMPLE_RATE as f64;
+            re += sample as f64 * angle.cos();
+            im -= sample as f64 * angle.sin();
+        }
+        (2.0 * (re * re + im * im).sqrt() / samples.len() as f64) as f32
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_render_waveforms() {
+        let sine = TestSignalRenderer::new(config(441.0, TestSignalShape::default())).render_seconds(1.0);
+        assert!((magnitude_at(&sine, 441.0) - 0.5).abs() < 0.01);
+        assert!((rms(&sine) - 0.5 / 2.0_f32.sqrt()).abs() < 0.01);
+
+        // A square wave has only odd harmonics, a sawtooth has all of them
+        let square = TestSignalRenderer::new(config(441.0, TestSignalShape { waveform: TestWaveform::Square, ..Default::default() })).render_seconds(1.0);
+        assert!(magnitude_at(&square, 3.0 * 441.0) > 0.15);
+        assert!(magnitude_at(&square, 2.0 * 441.0) < 0.01);
+        let sawtooth = TestSignalRenderer::new(config(441.0, TestSignalShape { waveform: TestWaveform::Sawtooth, ..Default::default() })).render_seconds(1.0);
+        assert!(magnitude_at(&sawtooth, 2.0 * 441.0) > 0.1);
+
+        // Custom spectra are scaled to stay within the amplitude
+        let harmonics = TestWaveform::Harmonics(vec![0.0, 1.0, 1.0]);
+        let missing_fundamental = TestSignalRenderer::new(config(441.0, TestSignalShape { waveform: harmonics, ..Default::default() })).render_seconds(1.0);
+        assert!(magnitude_at(&missing_fundamental, 441.0) < 0.01);
+        assert!((magnitude_at(&missing_fundamental, 2.0 * 441.0) - 0.25).abs() < 0.01);
+        assert!(missing_fundamental.iter().all(|sample| sample.abs() <= 0.5 + 1e-4));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_vibrato_and_glide() {
+        let vibrato = TestSignalRenderer::new(config(440.0, TestSignalShape { vibrato_rate_hz: 5.0, vibrato_depth_cents: 50.0, ..Default::default() }));
+        assert!((vibrato.frequency_at(0.05) - 440.0 * 2.0_f32.powf(50.0 / 1200.0)).abs() < 0.01);
+        assert!((vibrato.frequency_at(0.15) - 440.0 * 2.0_f32.powf(-50.0 / 1200.0)).abs() < 0.01);
+        assert!((vibrato.frequency_at(0.2) - 440.0).abs() < 0.01);
+
+        // Half way through the glide is half way in pitch
+        let glide = TestSignalRenderer::new(config(220.0, TestSignalShape { glide: Some(Glide { to_frequency: 880.0, seconds: 2.0 }), ..Default::default() }));
+        assert_eq!(glide.frequency_at(0.0), 220.0);
+        assert!((glide.frequency_at(1.0) - 440.0).abs() < 0.01);
+        assert!((glide.frequency_at(3.0) - 880.0).abs() < 0.01);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_drone_voices_and_pluck_envelope() {
+        let chord = [TunedDroneVoice::ROOT, TunedDroneVoice { ratio: 1.25, gain: 0.5 }, TunedDroneVoice { ratio: 1.5, gain: 0.5 }];
+        assert_eq!(drone_voice_levels(&chord), vec![0.5, 0.25, 0.25]);
+        // A single quiet voice is not raised to the full volume
+        assert_eq!(drone_voice_levels(&[TunedDroneVoice { ratio: 1.0, gain: 0.5 }]), vec![0.5]);
+        assert_eq!(drone_voice_levels(&RootNoteAudioConfig::default().voices), vec![1.0]);
+
+        assert_eq!(drone_timbre_harmonics(DroneTimbre::Sine), vec![1.0]);
+        assert!(drone_timbre_harmonics(DroneTimbre::Tanpura).len() > drone_timbre_harmonics(DroneTimbre::Organ).len());
+
+        assert!(pluck_envelope(&DroneEnvelope::SUSTAINED, 1000).is_empty());
+        let pluck = pluck_envelope(&DroneEnvelope { attack_seconds: 0.01, release_seconds: 1.0, pluck_period_seconds: Some(2.0) }, 1000);
+        assert_eq!(pluck.len(), 2000);
+        assert_eq!(pluck[0], 0.0);
+        assert!((pluck[10] - 1.0).abs() < 1e-6);
+        assert!((pluck[1010] - 0.001).abs() < 1e-4);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_target_note_gain_points() {
+        let mut config = TargetNoteConfig {
+            frequency: 440.0,
+            volume: 0.3,
+            duration_seconds: 1.5,
+            timbre: DroneTimbre::Sine,
+            envelope: DroneEnvelope { attack_seconds: 0.1, release_seconds: 0.4, pluck_period_seconds: None },
+        };
+        assert_eq!(target_note_gain_points(&config), [(0.0, 0.0), (0.1, 0.3), (1.1, 0.3), (1.5, 0.0)]);
+
+        // A note shorter than its envelope keeps the attack to release proportion
+        config.duration_seconds = 0.25;
+        let points = target_note_gain_points(&config);
+        assert!((points[1].0 - 0.05).abs() < 1e-6);
+        assert!((points[2].0 - points[1].0).abs() < 1e-6);
+        assert_eq!(points[3], (0.25, 0.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_noise_at_snr() {
+        let clean = TestSignalRenderer::new(config(441.0, TestSignalShape::default())).render_seconds(1.0);
+        let noisy = TestSignalRenderer::new(config(441.0, TestSignalShape { snr_db: Some(10.0), ..Default::default() })).render_seconds(1.0);
+        let noise: Vec<f32> = noisy.iter().zip(&clean).map(|(noisy, clean)| noisy - clean).collect();
+        let snr_db = 20.0 * (rms(&clean) / rms(&noise)).log10();
+        assert!((snr_db - 10.0).abs() < 0.3, "SNR {} dB", snr_db);
+
+        // Rendering is reproducible and silent while disabled
+        let again = TestSignalRenderer::new(config(441.0, TestSignalShape { snr_db: Some(10.0), ..Default::default() })).render_seconds(1.0);
+        assert_eq!(noisy, again);
+        let mut disabled = TestSignalRenderer::new(SignalGeneratorConfig { enabled: false, ..config(441.0, TestSignalShape::default()) });
+        assert!(disabled.render_seconds(0.1).iter().all(|sample| *sample == 0.0));
+    }
+}
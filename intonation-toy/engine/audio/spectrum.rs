@@ -0,0 +1,118 @@
+// Magnitude spectrum of analysis blocks
+//
+// Every block passed to the pitch analyzer is windowed and transformed so that the
+// presentation layer can draw the harmonic content of the input. Magnitudes are
+// normalized so that a full scale sine centered on a bin reads 1.0.
+
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+use super::buffer_analyzer::{generate_window, WindowFunction};
+
+/// Windowed FFT magnitude spectrum of fixed size blocks
+pub struct SpectrumAnalyzer {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    window_gain: f32,
+    sample_rate: u32,
+    buffer: Vec<Complex<f32>>,
+    magnitudes: Vec<f32>,
+}
+
+impl SpectrumAnalyzer {
+    pub fn new(block_size: usize, sample_rate: u32, window_fn: WindowFunction) -> Result<Self, String> {
+        if block_size < 2 {
+            return Err(format!("Block size must be at least 2, got {}", block_size));
+        }
+        if sample_rate == 0 {
+            return Err("Sample rate must be positive".to_string());
+        }
+
+        let window = generate_window(block_size, window_fn);
+        let window_gain = window.iter().sum::<f32>() / 2.0;
+
+        Ok(Self {
+            fft: FftPlanner::new().plan_fft_forward(block_size),
+            window,
+            window_gain,
+            sample_rate,
+            buffer: vec![Complex::new(0.0, 0.0); block_size],
+            magnitudes: vec![0.0; block_size / 2 + 1],
+        })
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Distance between two bins in Hz
+    pub fn bin_width_hz(&self) -> f32 {
+        self.sample_rate as f32 / self.block_size() as f32
+    }
+
+    /// Magnitudes of the most recent block, from 0 Hz up to the Nyquist frequency
+    pub fn magnitudes(&self) -> &[f32] {
+        &self.magnitudes
+    }
+
+    /// Compute the magnitude spectrum of a block
+    pub fn process(&mut self, samples: &[f32]) -> Result<&[f32], String> {
+        if samples.len() != self.block_size() {
+            return Err(format!("Expected {} samples, got {}", self.block_size(), samples.len()));
+        }
+
+        for (bin, (sample, weight)) in self.buffer.iter_mut().zip(samples.iter().zip(&self.window)) {
+            *bin = Complex::new(sample * weight, 0.0);
+        }
+        self.fft.process(&mut self.buffer);
+
+        for (magnitude, bin) in self.magnitudes.iter_mut().zip(&self.buffer) {
+            *magnitude = bin.norm() / self.window_gain;
+        }
+        Ok(&self.magnitudes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    fn sine(frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_sine_peak_and_normalization() {
+        let mut analyzer = SpectrumAnalyzer::new(2048, SAMPLE_RATE, WindowFunction::Blackman).unwrap();
+        assert_eq!(analyzer.magnitudes().len(), 1025);
+        assert!((analyzer.bin_width_hz() - 23.4375).abs() < 1e-4);
+
+        // 40 bins exactly
+        let magnitudes = analyzer.process(&sine(937.5, 0.5, 2048)).unwrap();
+        let (peak_bin, peak) = magnitudes
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(peak_bin, 40);
+        assert!((peak - 0.5).abs() < 0.01, "peak magnitude {}", peak);
+
+        // Far from the peak the Blackman sidelobes are negligible
+        assert!(magnitudes[200] < 1e-4);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_invalid_input() {
+        assert!(SpectrumAnalyzer::new(1, SAMPLE_RATE, WindowFunction::Hamming).is_err());
+        assert!(SpectrumAnalyzer::new(1024, 0, WindowFunction::Hamming).is_err());
+
+        let mut analyzer = SpectrumAnalyzer::new(1024, SAMPLE_RATE, WindowFunction::Hamming).unwrap();
+        assert!(analyzer.process(&[0.0; 512]).is_err());
+        assert!(analyzer.process(&[0.0; 1024]).unwrap().iter().all(|&m| m == 0.0));
+    }
+}
@@ -0,0 +1,100 @@
+use web_sys::{AudioContext, AudioScheduledSourceNode, GainNode, OscillatorNode};
+use crate::common::dev_log;
+use super::microphone::AudioError;
+use super::root_note_audio_node::set_oscillator_timbre;
+use super::signal_generator::{target_note_gain_points, TargetNoteConfig};
+
+/// One-shot tone that plays a target note once
+///
+/// Like the root note audio node, the tone connects directly to the speakers.
+/// The whole envelope is scheduled on the gain when the node is created and the
+/// oscillator stops itself at the end, so a finished node needs no cleanup and
+/// can simply be dropped.
+pub struct TargetNoteNode {
+    /// Reference to the AudioContext
+    audio_context: AudioContext,
+    /// The oscillator node generating the note
+    oscillator: OscillatorNode,
+    /// Gain node carrying the envelope
+    gain_node: GainNode,
+    /// Context time at which the release ends
+    end_time: f64,
+}
+
+impl TargetNoteNode {
+    /// Fade out time when a note is cut short by `stop()`
+    const STOP_FADE_SECONDS: f64 = 0.02;
+
+    /// Create and start a target note
+    ///
+    /// # Arguments
+    /// * `audio_context` - The Web Audio context
+    /// * `config` - Frequency, volume, duration, timbre and envelope of the note
+    pub fn new(audio_context: &AudioContext, config: &TargetNoteConfig) -> Result<Self, AudioError> {
+        dev_log!("[TargetNoteNode] Playing target note at {} Hz for {} s", config.frequency, config.duration_seconds);
+
+        let oscillator = audio_context.create_oscillator()
+            .map_err(|e| AudioError::Generic(format!("Failed to create oscillator: {:?}", e)))?;
+        set_oscillator_timbre(audio_context, &oscillator, config.timbre);
+        oscillator.frequency().set_value(config.frequency);
+
+        let gain_node = audio_context.create_gain()
+            .map_err(|e| AudioError::Generic(format!("Failed to create gain node: {:?}", e)))?;
+
+        // Schedule the envelope from the current context time
+        let start_time = audio_context.current_time();
+        let gain = gain_node.gain();
+        for (index, (seconds, value)) in target_note_gain_points(config).into_iter().enumerate() {
+            let time = start_time + seconds as f64;
+            let result = if index == 0 {
+                gain.set_value_at_time(value, time)
+            } else {
+                gain.linear_ramp_to_value_at_time(value, time)
+            };
+            result.map_err(|e| AudioError::Generic(format!("Failed to schedule target note envelope: {:?}", e)))?;
+        }
+        let end_time = start_time + config.duration_seconds as f64;
+
+        // Connect oscillator -> gain -> destination
+        oscillator.connect_with_audio_node(&gain_node)
+            .map_err(|e| AudioError::Generic(format!("Failed to connect oscillator to gain: {:?}", e)))?;
+        gain_node.connect_with_audio_node(&audio_context.destination())
+            .map_err(|e| AudioError::Generic(format!("Failed to connect gain to destination: {:?}", e)))?;
+
+        let source: &AudioScheduledSourceNode = &oscillator;
+        source.start()
+            .map_err(|e| AudioError::Generic(format!("Failed to start oscillator: {:?}", e)))?;
+        source.stop_with_when(end_time)
+            .map_err(|e| AudioError::Generic(format!("Failed to schedule oscillator stop: {:?}", e)))?;
+
+        Ok(Self {
+            audio_context: audio_context.clone(),
+            oscillator,
+            gain_node,
+            end_time,
+        })
+    }
+
+    /// Whether the release has ended
+    pub fn is_finished(&self) -> bool {
+        self.audio_context.current_time() >= self.end_time
+    }
+
+    /// Fade the note out quickly, e.g. when the next target note starts
+    pub fn stop(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        let now = self.audio_context.current_time();
+        let gain = self.gain_node.gain();
+        let _ = gain.cancel_scheduled_values(now);
+        let _ = gain.set_value_at_time(gain.value(), now);
+        let _ = gain.linear_ramp_to_value_at_time(0.0, now + Self::STOP_FADE_SECONDS);
+
+        let source: &AudioScheduledSourceNode = &self.oscillator;
+        if let Err(e) = source.stop_with_when(now + Self::STOP_FADE_SECONDS) {
+            dev_log!("[TargetNoteNode] Warning: Failed to stop oscillator: {:?}", e);
+        }
+        self.end_time = now + Self::STOP_FADE_SECONDS;
+    }
+}
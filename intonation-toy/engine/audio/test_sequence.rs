@@ -0,0 +1,633 @@
+// Scripted test signal sequences
+//
+// A sequence is a list of steps such as "A3 for 1s, glide to C#4 over 200ms,
+// silence 300ms, vibrato E4". It is rendered with the TestSignalRenderer and run
+// through the offline analysis, which yields the EngineUpdateResult stream that the
+// model would receive from live input. SequenceReport then checks the resulting
+// ModelUpdateResult stream against the note and cents each step should produce.
+//
+// Format:
+// - Steps are separated by commas, semicolons or new lines; `#` starts a comment line
+// - `<note> [for <duration>]` plays a steady note, e.g. `A3 for 1s` or `Eb4+15c 500ms`
+// - `glide to <note> over <duration>` glides from the previous note
+// - `silence <duration>` plays nothing
+// - `vibrato <note> [for <duration>] [<rate>Hz] [<depth>c]` adds vibrato to a note
+// - Durations are given in `s` or `ms`, notes may carry a cents offset of up to ±50c
+
+use super::offline_analysis::{analyze_recording, OfflineAnalysisConfig};
+use super::signal_generator::{SignalGeneratorConfig, TestSignalRenderer};
+use crate::shared_types::{
+    midi_note_to_name, EngineUpdateResult, Glide, MidiNote, ModelUpdateResult, PermissionState,
+    Pitch, TestSignalShape, TestWaveform,
+};
+use crate::theory::tuning::midi_note_to_frequency_with_reference;
+
+/// Duration of a note or vibrato step without an explicit duration
+const DEFAULT_STEP_SECONDS: f32 = 1.0;
+
+const DEFAULT_VIBRATO_RATE_HZ: f32 = 5.5;
+
+const DEFAULT_VIBRATO_DEPTH_CENTS: f32 = 30.0;
+
+/// Largest cents offset of a note, beyond it the closest note would change
+const MAX_NOTE_CENTS: f32 = 50.0;
+
+/// Number of failed frames described per step in a report
+const MAX_REPORTED_FAILURES: usize = 3;
+
+/// Errors for malformed sequences
+///
+/// Step numbers are 1-based and count the steps in the order they are written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceError {
+    /// The sequence has no steps
+    EmptySequence,
+    /// A note is not a name like `C#4` or `Bb2-10c` within the MIDI range
+    InvalidNote { step: usize, text: String },
+    /// A value is neither a duration, a vibrato rate nor a vibrato depth
+    InvalidValue { step: usize, text: String },
+    /// A note, glide or vibrato step has no note
+    MissingNote { step: usize },
+    /// A glide or silence step has no duration
+    MissingDuration { step: usize },
+    /// A glide is not preceded by a note to glide from
+    GlideWithoutStart { step: usize },
+    /// The rendered sequence could not be analyzed
+    AnalysisFailed(String),
+}
+
+impl std::fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SequenceError::EmptySequence => write!(f, "Sequence has no steps"),
+            SequenceError::InvalidNote { step, text } => write!(f, "Invalid note in step {}: '{}'", step, text),
+            SequenceError::InvalidValue { step, text } => write!(f, "Invalid value in step {}: '{}'", step, text),
+            SequenceError::MissingNote { step } => write!(f, "Missing note in step {}", step),
+            SequenceError::MissingDuration { step } => write!(f, "Missing duration in step {}", step),
+            SequenceError::GlideWithoutStart { step } => write!(f, "Glide in step {} has no note to start from", step),
+            SequenceError::AnalysisFailed(message) => write!(f, "Failed to analyze sequence: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+/// A note with an offset in cents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SequenceNote {
+    pub midi_note: MidiNote,
+    pub cents: f32,
+}
+
+impl SequenceNote {
+    pub fn new(midi_note: MidiNote, cents: f32) -> Self {
+        Self { midi_note, cents }
+    }
+
+    /// Equal tempered frequency with A4 at `reference_frequency`
+    pub fn frequency(&self, reference_frequency: f32) -> f32 {
+        midi_note_to_frequency_with_reference(self.midi_note, reference_frequency) * 2.0_f32.powf(self.cents / 1200.0)
+    }
+
+    /// Parse a note name such as `A3`, `C#4`, `Bb-1` or `Eb4+15c`
+    fn parse(text: &str) -> Option<Self> {
+        let mut chars = text.chars();
+        let pitch_class: i32 = match chars.next()?.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return None,
+        };
+        let rest = chars.as_str();
+        let accidentals = rest.len() - rest.trim_start_matches(['#', 'b']).len();
+        let alteration = rest[..accidentals].chars().map(|c| if c == '#' { 1 } else { -1 }).sum::<i32>();
+        let rest = &rest[accidentals..];
+
+        // A sign after the first character starts the cents, a leading one belongs to the octave
+        let (octave, cents) = match rest.strip_suffix('c') {
+            Some(without_unit) => {
+                let split = without_unit.char_indices().skip(1).filter(|(_, c)| *c == '+' || *c == '-').last()?.0;
+                (&without_unit[..split], without_unit[split..].trim_start_matches('+').parse::<f32>().ok()?)
+            }
+            None => (rest, 0.0),
+        };
+        let octave: i32 = octave.parse().ok()?;
+        let midi_note = (octave + 1) * 12 + pitch_class + alteration;
+        if !(0..=127).contains(&midi_note) || !cents.is_finite() || cents.abs() > MAX_NOTE_CENTS {
+            return None;
+        }
+        Some(Self::new(midi_note as MidiNote, cents))
+    }
+}
+
+impl std::fmt::Display for SequenceNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", midi_note_to_name(self.midi_note))?;
+        if self.cents != 0.0 {
+            write!(f, "{:+}c", self.cents)?;
+        }
+        Ok(())
+    }
+}
+
+/// One step of a test signal sequence
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceStep {
+    Note { note: SequenceNote, seconds: f32 },
+    /// Exponential glide from the previous note
+    Glide { to: SequenceNote, seconds: f32 },
+    Vibrato { note: SequenceNote, seconds: f32, rate_hz: f32, depth_cents: f32 },
+    Silence { seconds: f32 },
+}
+
+impl SequenceStep {
+    pub fn seconds(&self) -> f32 {
+        match self {
+            SequenceStep::Note { seconds, .. }
+            | SequenceStep::Glide { seconds, .. }
+            | SequenceStep::Vibrato { seconds, .. }
+            | SequenceStep::Silence { seconds } => *seconds,
+        }
+    }
+
+    /// The note the step ends on, `None` for silence
+    fn end_note(&self) -> Option<SequenceNote> {
+        match self {
+            SequenceStep::Note { note, .. } | SequenceStep::Vibrato { note, .. } => Some(*note),
+            SequenceStep::Glide { to, .. } => Some(*to),
+            SequenceStep::Silence { .. } => None,
+        }
+    }
+
+    fn parse(step: usize, text: &str, previous_note: Option<SequenceNote>) -> Result<Self, SequenceError> {
+        let mut words = text.split_whitespace().peekable();
+        let keyword = words.peek().map(|word| word.to_ascii_lowercase());
+        if matches!(keyword.as_deref(), Some("glide" | "silence" | "vibrato")) {
+            words.next();
+        }
+
+        let mut note = None;
+        let mut seconds = None;
+        let mut rate_hz = None;
+        let mut depth_cents = None;
+        for word in words {
+            let lowercase = word.to_ascii_lowercase();
+            if matches!(lowercase.as_str(), "for" | "over" | "to" | "at") {
+                continue;
+            }
+            if !lowercase.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+                note = Some(SequenceNote::parse(word).ok_or_else(|| SequenceError::InvalidNote { step, text: word.to_string() })?);
+                continue;
+            }
+
+            let invalid_value = || SequenceError::InvalidValue { step, text: word.to_string() };
+            let (number, unit) = lowercase.split_at(lowercase.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(invalid_value)?);
+            let value: f32 = number.parse().map_err(|_| invalid_value())?;
+            if !value.is_finite() || value < 0.0 {
+                return Err(invalid_value());
+            }
+            match unit {
+                "s" => seconds = Some(value),
+                "ms" => seconds = Some(value / 1000.0),
+                "hz" => rate_hz = Some(value),
+                "c" => depth_cents = Some(value),
+                _ => return Err(invalid_value()),
+            }
+        }
+
+        let seconds = seconds.filter(|seconds| *seconds > 0.0);
+        match keyword.as_deref() {
+            Some("silence") => Ok(SequenceStep::Silence { seconds: seconds.ok_or(SequenceError::MissingDuration { step })? }),
+            Some("glide") => {
+                let to = note.ok_or(SequenceError::MissingNote { step })?;
+                let seconds = seconds.ok_or(SequenceError::MissingDuration { step })?;
+                if previous_note.is_none() {
+                    return Err(SequenceError::GlideWithoutStart { step });
+                }
+                Ok(SequenceStep::Glide { to, seconds })
+            }
+            Some("vibrato") => Ok(SequenceStep::Vibrato {
+                note: note.ok_or(SequenceError::MissingNote { step })?,
+                seconds: seconds.unwrap_or(DEFAULT_STEP_SECONDS),
+                rate_hz: rate_hz.unwrap_or(DEFAULT_VIBRATO_RATE_HZ),
+                depth_cents: depth_cents.unwrap_or(DEFAULT_VIBRATO_DEPTH_CENTS),
+            }),
+            _ => Ok(SequenceStep::Note {
+                note: note.ok_or(SequenceError::MissingNote { step })?,
+                seconds: seconds.unwrap_or(DEFAULT_STEP_SECONDS),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for SequenceStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SequenceStep::Note { note, seconds } => write!(f, "{} for {}", note, format_seconds(*seconds)),
+            SequenceStep::Glide { to, seconds } => write!(f, "glide to {} over {}", to, format_seconds(*seconds)),
+            SequenceStep::Vibrato { note, seconds, rate_hz, depth_cents } => {
+                write!(f, "vibrato {} for {} at {}Hz {}c", note, format_seconds(*seconds), rate_hz, depth_cents)
+            }
+            SequenceStep::Silence { seconds } => write!(f, "silence {}", format_seconds(*seconds)),
+        }
+    }
+}
+
+/// Format a duration the way it is written in a sequence
+fn format_seconds(seconds: f32) -> String {
+    if seconds < 1.0 {
+        format!("{}ms", (seconds * 1000.0).round())
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// A test signal sequence and the waveform it is played with
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestSequence {
+    pub steps: Vec<SequenceStep>,
+    pub waveform: TestWaveform,
+    /// Signal to noise ratio of added white noise, `None` for a clean signal
+    pub snr_db: Option<f32>,
+    /// Peak amplitude of the signal (0.0 - 1.0)
+    pub amplitude: f32,
+}
+
+/// Analysis input for one window of a rendered sequence
+#[derive(Debug, Clone)]
+pub struct SequenceFrame {
+    /// Center of the analysis window, in seconds from the start of the sequence
+    pub time_seconds: f64,
+    pub engine_update: EngineUpdateResult,
+}
+
+impl TestSequence {
+    /// Parse a sequence, played as a sine wave at half amplitude without noise
+    pub fn parse(text: &str) -> Result<Self, SequenceError> {
+        let mut steps: Vec<SequenceStep> = Vec::new();
+        let step_texts = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split([',', ';']))
+            .map(str::trim)
+            .filter(|step_text| !step_text.is_empty());
+        for (index, step_text) in step_texts.enumerate() {
+            let previous_note = steps.iter().rev().find_map(SequenceStep::end_note);
+            steps.push(SequenceStep::parse(index + 1, step_text, previous_note)?);
+        }
+        if steps.is_empty() {
+            return Err(SequenceError::EmptySequence);
+        }
+
+        Ok(Self {
+            steps,
+            waveform: TestWaveform::Sine,
+            snr_db: None,
+            amplitude: 0.5,
+        })
+    }
+
+    pub fn duration_seconds(&self) -> f32 {
+        self.steps.iter().map(SequenceStep::seconds).sum()
+    }
+
+    /// Start and end of every step, in seconds from the start of the sequence
+    pub fn step_times(&self) -> Vec<(f32, f32)> {
+        let mut start = 0.0;
+        self.steps
+            .iter()
+            .map(|step| {
+                let times = (start, start + step.seconds());
+                start += step.seconds();
+                times
+            })
+            .collect()
+    }
+
+    /// Render the sequence with A4 at `reference_frequency`
+    ///
+    /// Steps are rendered one after the other by the same renderer, so the waveform
+    /// stays continuous from one note to the next.
+    pub fn render(&self, reference_frequency: f32, sample_rate: u32) -> Vec<f32> {
+        let mut renderer: Option<TestSignalRenderer> = None;
+        let mut previous_note: Option<SequenceNote> = None;
+        let mut samples = Vec::new();
+        for step in &self.steps {
+            let (frequency, shape) = match step {
+                SequenceStep::Note { note, .. } => (note.frequency(reference_frequency), TestSignalShape::default()),
+                SequenceStep::Glide { to, seconds } => (
+                    previous_note.map_or(to.frequency(reference_frequency), |note| note.frequency(reference_frequency)),
+                    TestSignalShape {
+                        glide: Some(Glide { to_frequency: to.frequency(reference_frequency), seconds: *seconds }),
+                        ..TestSignalShape::default()
+                    },
+                ),
+                SequenceStep::Vibrato { note, rate_hz, depth_cents, .. } => (
+                    note.frequency(reference_frequency),
+                    TestSignalShape { vibrato_rate_hz: *rate_hz, vibrato_depth_cents: *depth_cents, ..TestSignalShape::default() },
+                ),
+                SequenceStep::Silence { .. } => (previous_note.map_or(reference_frequency, |note| note.frequency(reference_frequency)), TestSignalShape::default()),
+            };
+            let config = SignalGeneratorConfig {
+                enabled: !matches!(step, SequenceStep::Silence { .. }),
+                frequency,
+                amplitude: self.amplitude,
+                sample_rate,
+                shape: TestSignalShape { waveform: self.waveform.clone(), snr_db: self.snr_db, ..shape },
+            };
+            let renderer = match renderer.as_mut() {
+                Some(renderer) => {
+                    renderer.set_config(config);
+                    renderer
+                }
+                None => renderer.insert(TestSignalRenderer::new(config)),
+            };
+            samples.extend(renderer.render_seconds(step.seconds()));
+            previous_note = step.end_note().or(previous_note);
+        }
+        samples
+    }
+
+    /// Render the sequence and analyze it like live input
+    ///
+    /// Returns one engine update per analysis window, in the form the model
+    /// receives from `AudioEngine::update`.
+    pub fn run(
+        &self,
+        reference_frequency: f32,
+        sample_rate: u32,
+        config: &OfflineAnalysisConfig,
+    ) -> Result<Vec<SequenceFrame>, SequenceError> {
+        let samples = self.render(reference_frequency, sample_rate);
+        let timeline = analyze_recording(&samples, sample_rate, config).map_err(SequenceError::AnalysisFailed)?;
+
+        Ok(timeline
+            .frames
+            .into_iter()
+            .map(|frame| SequenceFrame {
+                time_seconds: frame.time_seconds,
+                engine_update: EngineUpdateResult {
+                    audio_analysis: Some(frame.audio_analysis()),
+                    audio_errors: Vec::new(),
+                    permission_state: PermissionState::Granted,
+                    onset_timings: Vec::new(),
+                    recording_analysis: None,
+                },
+            })
+            .collect())
+    }
+}
+
+/// How strictly a `SequenceReport` checks the model results
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SequenceTolerance {
+    /// Largest accepted difference from the expected cents offset
+    pub cents: f32,
+    /// Frames this close to a change of note are not checked, as their window spans both
+    pub settle_seconds: f32,
+    /// Fraction of the checked frames of a step that have to pass
+    pub min_pass_ratio: f32,
+}
+
+impl Default for SequenceTolerance {
+    fn default() -> Self {
+        Self {
+            cents: 10.0,
+            settle_seconds: 0.1,
+            min_pass_ratio: 0.9,
+        }
+    }
+}
+
+/// Outcome of the checks of one sequence step
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepReport {
+    pub description: String,
+    pub start_seconds: f32,
+    pub end_seconds: f32,
+    pub checked_frames: usize,
+    pub passed_frames: usize,
+    /// Mean difference between the measured and expected cents, `None` if no pitch was checked
+    pub mean_cents_error: Option<f32>,
+    /// Descriptions of the first failed frames
+    pub failures: Vec<String>,
+    /// A step without checked frames, e.g. one shorter than the settle time, passes
+    pub passed: bool,
+}
+
+/// Comparison of a `ModelUpdateResult` stream with the notes of a sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceReport {
+    pub steps: Vec<StepReport>,
+}
+
+impl SequenceReport {
+    /// Check model results, each with its time in seconds from the start of the sequence
+    ///
+    /// Steady notes need the expected closest note and cents offset, vibrato may
+    /// deviate by its depth but has to average out to the expected offset, glides
+    /// have to stay between their start and end note, and silence must not be
+    /// detected as a pitch.
+    pub fn check(sequence: &TestSequence, results: &[(f64, ModelUpdateResult)], tolerance: &SequenceTolerance) -> Self {
+        let mut previous_note: Option<SequenceNote> = None;
+        let steps = sequence
+            .steps
+            .iter()
+            .zip(sequence.step_times())
+            .map(|(step, (start_seconds, end_seconds))| {
+                // A glide continues from the previous note, so its start needs no settling
+                let settle_start = if matches!(step, SequenceStep::Glide { .. }) { 0.0 } else { tolerance.settle_seconds };
+                let checked: Vec<&(f64, ModelUpdateResult)> = results
+                    .iter()
+                    .filter(|(time, _)| {
+                        *time as f32 >= start_seconds + settle_start && (*time as f32) < end_seconds - tolerance.settle_seconds
+                    })
+                    .collect();
+
+                let mut failures = Vec::new();
+                let mut cents_errors = Vec::new();
+                for (time, result) in &checked {
+                    match check_frame(step, previous_note, result, tolerance.cents) {
+                        Ok(cents_error) => cents_errors.extend(cents_error),
+                        Err(failure) => failures.push(format!("{:.2}s: {}", time, failure)),
+                    }
+                }
+
+                let passed_frames = checked.len() - failures.len();
+                let mean_cents_error = (!cents_errors.is_empty()).then(|| cents_errors.iter().sum::<f32>() / cents_errors.len() as f32);
+                let mut passed = checked.is_empty() || passed_frames as f32 >= tolerance.min_pass_ratio * checked.len() as f32;
+                if let (SequenceStep::Vibrato { .. }, Some(mean_cents_error)) = (step, mean_cents_error)
+                    && mean_cents_error.abs() > tolerance.cents
+                {
+                    passed = false;
+                    failures.insert(0, format!("vibrato centered {:+.1}c from the note", mean_cents_error));
+                }
+                failures.truncate(MAX_REPORTED_FAILURES);
+                previous_note = step.end_note().or(previous_note);
+
+                StepReport {
+                    description: step.to_string(),
+                    start_seconds,
+                    end_seconds,
+                    checked_frames: checked.len(),
+                    passed_frames,
+                    mean_cents_error,
+                    failures,
+                    passed,
+                }
+            })
+            .collect();
+
+        Self { steps }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.steps.iter().all(|step| step.passed)
+    }
+
+    /// One line per step, followed by the failures of failed steps
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            let status = match (step.passed, step.checked_frames) {
+                (true, 0) => "SKIP",
+                (true, _) => "PASS",
+                (false, _) => "FAIL",
+            };
+            let mut line = format!(
+                "{} {}. {} ({:.2}s-{:.2}s): {}/{} frames",
+                status, index + 1, step.description, step.start_seconds, step.end_seconds, step.passed_frames, step.checked_frames
+            );
+            if let Some(mean_cents_error) = step.mean_cents_error {
+                line.push_str(&format!(", mean error {:+.1}c", mean_cents_error));
+            }
+            lines.push(line);
+            if !step.passed {
+                lines.extend(step.failures.iter().map(|failure| format!("    {}", failure)));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Check one model result against a step, returning the cents error of a checked pitch
+fn check_frame(
+    step: &SequenceStep,
+    previous_note: Option<SequenceNote>,
+    result: &ModelUpdateResult,
+    tolerance_cents: f32,
+) -> Result<Option<f32>, String> {
+    let frequency = match (&result.pitch, step) {
+        (Pitch::NotDetected, SequenceStep::Silence { .. }) => return Ok(None),
+        (Pitch::Detected(frequency, _), SequenceStep::Silence { .. }) => return Err(format!("expected silence, detected {:.1} Hz", frequency)),
+        (Pitch::NotDetected, _) => return Err("no pitch detected".to_string()),
+        (Pitch::Detected(frequency, _), _) => *frequency,
+    };
+    let measured = format!("{} {:+.1}c", midi_note_to_name(result.closest_midi_note), result.cents_offset);
+
+    match step {
+        SequenceStep::Note { note, .. } | SequenceStep::Vibrato { note, .. } => {
+            let allowed_cents = match step {
+                SequenceStep::Vibrato { depth_cents, .. } => tolerance_cents + depth_cents,
+                _ => tolerance_cents,
+            };
+            // Compare in cents from the expected note, so a vibrato may cross into the next note
+            let cents_error = 1200.0 * (frequency / note.frequency(result.reference_frequency)).log2();
+            let note_matches = matches!(step, SequenceStep::Vibrato { .. }) || result.closest_midi_note == note.midi_note;
+            if note_matches && cents_error.abs() <= allowed_cents {
+                Ok(Some(cents_error))
+            } else {
+                Err(format!("expected {}, got {}", note, measured))
+            }
+        }
+        SequenceStep::Glide { to, .. } => {
+            let from = previous_note.unwrap_or(*to);
+            let cents_from = 1200.0 * (frequency / from.frequency(result.reference_frequency)).log2();
+            let glide_cents = 1200.0 * (to.frequency(result.reference_frequency) / from.frequency(result.reference_frequency)).log2();
+            let (low, high) = if glide_cents < 0.0 { (glide_cents, 0.0) } else { (0.0, glide_cents) };
+            if cents_from >= low - tolerance_cents && cents_from <= high + tolerance_cents {
+                Ok(None)
+            } else {
+                Err(format!("expected between {} and {}, got {}", from, to, measured))
+            }
+        }
+        SequenceStep::Silence { .. } => unreachable!("silence is checked above"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::audio::buffer::STANDARD_SAMPLE_RATE;
+    use crate::model::DataModel;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Run a sequence through the analysis and the model with default settings
+    fn run_through_model(sequence: &TestSequence) -> Vec<(f64, ModelUpdateResult)> {
+        let mut model = DataModel::create().unwrap();
+        sequence
+            .run(440.0, STANDARD_SAMPLE_RATE, &OfflineAnalysisConfig::default())
+            .unwrap()
+            .into_iter()
+            .map(|frame| (frame.time_seconds, model.update(frame.time_seconds * 1000.0, frame.engine_update)))
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_sequence() {
+        let sequence = TestSequence::parse("A3 for 1s, glide to C#4 over 200ms, silence 300ms, vibrato E4").unwrap();
+        assert_eq!(sequence.steps, vec![
+            SequenceStep::Note { note: SequenceNote::new(57, 0.0), seconds: 1.0 },
+            SequenceStep::Glide { to: SequenceNote::new(61, 0.0), seconds: 0.2 },
+            SequenceStep::Silence { seconds: 0.3 },
+            SequenceStep::Vibrato { note: SequenceNote::new(64, 0.0), seconds: 1.0, rate_hz: 5.5, depth_cents: 30.0 },
+        ]);
+        assert!((sequence.duration_seconds() - 2.5).abs() < 1e-6);
+        assert_eq!(sequence.steps[1].to_string(), "glide to Db4 over 200ms");
+
+        let sequence = TestSequence::parse("# warm up\nBb-1+20c 500ms\nvibrato G4 2s 6Hz 40c; C4-15c").unwrap();
+        assert_eq!(sequence.steps[0], SequenceStep::Note { note: SequenceNote::new(10, 20.0), seconds: 0.5 });
+        assert_eq!(sequence.steps[1], SequenceStep::Vibrato { note: SequenceNote::new(67, 0.0), seconds: 2.0, rate_hz: 6.0, depth_cents: 40.0 });
+        assert_eq!(sequence.steps[2], SequenceStep::Note { note: SequenceNote::new(60, -15.0), seconds: 1.0 });
+        assert_eq!(sequence.steps[0].to_string(), "Bb-1+20c for 500ms");
+
+        assert_eq!(TestSequence::parse(" , "), Err(SequenceError::EmptySequence));
+        assert_eq!(TestSequence::parse("A3, H4"), Err(SequenceError::InvalidNote { step: 2, text: "H4".to_string() }));
+        assert_eq!(TestSequence::parse("A3+60c"), Err(SequenceError::InvalidNote { step: 1, text: "A3+60c".to_string() }));
+        assert_eq!(TestSequence::parse("A3 1min"), Err(SequenceError::InvalidValue { step: 1, text: "1min".to_string() }));
+        assert_eq!(TestSequence::parse("silence"), Err(SequenceError::MissingDuration { step: 1 }));
+        assert_eq!(TestSequence::parse("silence 1s, glide to A4 over 1s"), Err(SequenceError::GlideWithoutStart { step: 2 }));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_sequence_passes_through_pipeline() {
+        let mut sequence = TestSequence::parse("A3 for 1s, glide to C#4 over 200ms, silence 300ms, vibrato E4, Bb3+20c for 1s").unwrap();
+        sequence.waveform = TestWaveform::Sawtooth;
+        sequence.snr_db = Some(20.0);
+
+        let report = SequenceReport::check(&sequence, &run_through_model(&sequence), &SequenceTolerance::default());
+        assert!(report.passed(), "{}", report.summary());
+        assert_eq!(report.steps.len(), 5);
+        assert!(report.steps.iter().all(|step| step.checked_frames > 0), "{}", report.summary());
+        assert!(report.steps[4].mean_cents_error.unwrap().abs() < 5.0);
+        assert!(report.summary().starts_with("PASS 1. A3 for 1s"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_report_detects_wrong_notes() {
+        // Expect a different note and cents than what is played
+        let played = TestSequence::parse("A3 for 1s, silence 500ms").unwrap();
+        let expected = TestSequence::parse("A3+25c for 1s, A3 for 500ms").unwrap();
+
+        let report = SequenceReport::check(&expected, &run_through_model(&played), &SequenceTolerance::default());
+        assert!(!report.passed());
+        assert!(report.steps.iter().all(|step| !step.passed));
+        assert!(report.steps[0].failures[0].contains("expected A3+25c, got A3"), "{}", report.summary());
+        assert_eq!(report.steps[1].failures[0].split(": ").nth(1), Some("no pitch detected"));
+        assert!(report.summary().lines().any(|line| line.starts_with("FAIL 2.")));
+    }
+}
@@ -1,12 +1,23 @@
-use web_sys::{AudioContext, OscillatorNode, GainNode, OscillatorType, AudioNode};
+use web_sys::{AudioContext, OscillatorNode, GainNode, OscillatorType, AudioNode, AudioBufferSourceNode, AudioScheduledSourceNode};
 use crate::common::dev_log;
+use crate::shared_types::{TestSignalShape, TestWaveform};
 use super::microphone::AudioError;
-use super::signal_generator::SignalGeneratorConfig;
+use super::signal_generator::{noise_level, NoiseGenerator, SignalGeneratorConfig};
+
+/// Length of the looped noise buffer
+const NOISE_BUFFER_SECONDS: f32 = 2.0;
 
 pub struct TestSignalAudioNode {
     audio_context: AudioContext,
     oscillator: OscillatorNode,
     gain_node: GainNode,
+    /// Low frequency oscillator that modulates the oscillator detune for vibrato
+    vibrato_oscillator: OscillatorNode,
+    /// Vibrato depth in cents
+    vibrato_gain: GainNode,
+    noise_source: AudioBufferSourceNode,
+    /// Noise level relative to the amplitude
+    noise_gain: GainNode,
     config: SignalGeneratorConfig,
     is_connected: bool,
 }
@@ -23,11 +34,19 @@ impl TestSignalAudioNode {
             .create_gain()
             .map_err(|_| AudioError::Generic("Failed to create gain node".to_string()))?;
         
-        oscillator.set_type(OscillatorType::Sine);
+        let vibrato_oscillator = audio_context
+            .create_oscillator()
+            .map_err(|_| AudioError::Generic("Failed to create vibrato oscillator node".to_string()))?;
         
-        oscillator
-            .frequency()
-            .set_value(config.frequency);
+        let vibrato_gain = audio_context
+            .create_gain()
+            .map_err(|_| AudioError::Generic("Failed to create vibrato gain node".to_string()))?;
+        
+        let noise_source = Self::create_noise_source(audio_context, config.sample_rate)?;
+        
+        let noise_gain = audio_context
+            .create_gain()
+            .map_err(|_| AudioError::Generic("Failed to create noise gain node".to_string()))?;
         
         let amplitude = if config.enabled { config.amplitude } else { 0.0 };
         gain_node
@@ -38,6 +57,22 @@ impl TestSignalAudioNode {
             .connect_with_audio_node(&gain_node)
             .map_err(|_| AudioError::Generic("Failed to connect oscillator to gain node".to_string()))?;
         
+        vibrato_oscillator
+            .connect_with_audio_node(&vibrato_gain)
+            .map_err(|_| AudioError::Generic("Failed to connect vibrato oscillator to vibrato gain node".to_string()))?;
+        
+        vibrato_gain
+            .connect_with_audio_param(&oscillator.detune())
+            .map_err(|_| AudioError::Generic("Failed to connect vibrato to oscillator detune".to_string()))?;
+        
+        noise_source
+            .connect_with_audio_node(&noise_gain)
+            .map_err(|_| AudioError::Generic("Failed to connect noise source to noise gain node".to_string()))?;
+        
+        noise_gain
+            .connect_with_audio_node(&gain_node)
+            .map_err(|_| AudioError::Generic("Failed to connect noise gain node to gain node".to_string()))?;
+        
         if connect_to_destination {
             gain_node
                 .connect_with_audio_node(&audio_context.destination())
@@ -48,21 +83,102 @@ impl TestSignalAudioNode {
             .start()
             .map_err(|_| AudioError::Generic("Failed to start oscillator".to_string()))?;
         
-        dev_log!("TestSignalAudioNode created successfully");
+        vibrato_oscillator
+            .start()
+            .map_err(|_| AudioError::Generic("Failed to start vibrato oscillator".to_string()))?;
+        
+        noise_source
+            .start()
+            .map_err(|_| AudioError::Generic("Failed to start noise source".to_string()))?;
         
-        Ok(Self {
+        let mut node = Self {
             audio_context: audio_context.clone(),
             oscillator,
             gain_node,
+            vibrato_oscillator,
+            vibrato_gain,
+            noise_source,
+            noise_gain,
             config,
             is_connected: connect_to_destination,
-        })
+        };
+        node.apply_shape();
+        
+        dev_log!("TestSignalAudioNode created successfully");
+        
+        Ok(node)
+    }
+    
+    /// Looping buffer of white noise with unit variance
+    fn create_noise_source(audio_context: &AudioContext, sample_rate: u32) -> Result<AudioBufferSourceNode, AudioError> {
+        let length = (NOISE_BUFFER_SECONDS * sample_rate as f32) as u32;
+        let buffer = audio_context
+            .create_buffer(1, length, sample_rate as f32)
+            .map_err(|_| AudioError::Generic("Failed to create noise buffer".to_string()))?;
+        
+        let mut generator = NoiseGenerator::new(1);
+        let samples: Vec<f32> = (0..length).map(|_| generator.next_sample()).collect();
+        buffer
+            .copy_to_channel(&samples, 0)
+            .map_err(|_| AudioError::Generic("Failed to fill noise buffer".to_string()))?;
+        
+        let noise_source = audio_context
+            .create_buffer_source()
+            .map_err(|_| AudioError::Generic("Failed to create noise source node".to_string()))?;
+        noise_source.set_buffer(Some(&buffer));
+        noise_source.set_loop(true);
+        Ok(noise_source)
+    }
+    
+    /// Set waveform, vibrato, noise and frequency from the config, restarting any glide
+    fn apply_shape(&mut self) {
+        let shape = &self.config.shape;
+        
+        match &shape.waveform {
+            TestWaveform::Sine => self.oscillator.set_type(OscillatorType::Sine),
+            TestWaveform::Sawtooth => self.oscillator.set_type(OscillatorType::Sawtooth),
+            TestWaveform::Square => self.oscillator.set_type(OscillatorType::Square),
+            TestWaveform::Harmonics(amplitudes) => {
+                // Index 0 is the DC offset, sine terms give the same phases as the renderer
+                let mut real = vec![0.0; amplitudes.len() + 1];
+                let mut imag = std::iter::once(0.0).chain(amplitudes.iter().copied()).collect::<Vec<f32>>();
+                match self.audio_context.create_periodic_wave(&mut real, &mut imag) {
+                    Ok(wave) => self.oscillator.set_periodic_wave(&wave),
+                    Err(e) => {
+                        dev_log!("Failed to create periodic wave: {:?}", e);
+                    }
+                }
+            }
+        }
+        
+        self.vibrato_oscillator.frequency().set_value(shape.vibrato_rate_hz);
+        self.vibrato_gain.gain().set_value(shape.vibrato_depth_cents);
+        self.noise_gain.gain().set_value(noise_level(&self.config));
+        
+        let frequency = self.oscillator.frequency();
+        let now = self.audio_context.current_time();
+        let _ = frequency.cancel_scheduled_values(now);
+        let _ = frequency.set_value_at_time(self.config.frequency, now);
+        if let Some(glide) = shape.glide
+            && let Err(e) = frequency.exponential_ramp_to_value_at_time(glide.to_frequency, now + glide.seconds as f64)
+        {
+            dev_log!("Failed to schedule glide: {:?}", e);
+        }
+    }
+    
+    pub fn set_shape(&mut self, shape: TestSignalShape) {
+        dev_log!("Setting test signal shape to: {:?}", shape);
+        self.config.shape = shape;
+        self.apply_shape();
     }
     
     pub fn update_config(&mut self, new_config: SignalGeneratorConfig) {
         dev_log!("Updating TestSignalAudioNode config: {:?}", new_config);
         
-        if new_config.frequency != self.config.frequency {
+        if new_config.shape != self.config.shape {
+            self.config.frequency = new_config.frequency;
+            self.set_shape(new_config.shape.clone());
+        } else if new_config.frequency != self.config.frequency {
             self.set_frequency(new_config.frequency);
         }
         
@@ -85,8 +201,9 @@ impl TestSignalAudioNode {
     pub fn set_frequency(&mut self, frequency: f32) {
         if frequency != self.config.frequency {
             dev_log!("Setting frequency to: {}", frequency);
-            self.oscillator.frequency().set_value(frequency);
             self.config.frequency = frequency;
+            // Noise follows the number of harmonics below Nyquist, glides start over
+            self.apply_shape();
         }
     }
     
@@ -161,6 +278,32 @@ impl TestSignalAudioNode {
                 dev_log!("Error disconnecting oscillator: {:?}", e);
             }
             
+            if let Err(e) = self.vibrato_oscillator.stop() {
+                dev_log!("Error stopping vibrato oscillator: {:?}", e);
+            }
+            
+            if let Err(e) = self.vibrato_oscillator.disconnect() {
+                dev_log!("Error disconnecting vibrato oscillator: {:?}", e);
+            }
+            
+            if let Err(e) = self.vibrato_gain.disconnect() {
+                dev_log!("Error disconnecting vibrato gain node: {:?}", e);
+            }
+            
+            // The source node's own stop() is deprecated in favor of the base class method
+            let noise_source: &AudioScheduledSourceNode = &self.noise_source;
+            if let Err(e) = noise_source.stop() {
+                dev_log!("Error stopping noise source: {:?}", e);
+            }
+            
+            if let Err(e) = self.noise_source.disconnect() {
+                dev_log!("Error disconnecting noise source: {:?}", e);
+            }
+            
+            if let Err(e) = self.noise_gain.disconnect() {
+                dev_log!("Error disconnecting noise gain node: {:?}", e);
+            }
+            
             if let Err(e) = self.gain_node.disconnect() {
                 dev_log!("Error disconnecting gain node: {:?}", e);
             }
@@ -0,0 +1,425 @@
+// WAV file decoding
+//
+// Decodes RIFF/WAVE files into mono f32 samples for offline analysis. Integer PCM
+// (16, 24 and 32 bit) and IEEE float (32 and 64 bit) data are supported, in both the
+// plain and the WAVE_FORMAT_EXTENSIBLE header layouts. Multi-channel audio is mixed
+// down to mono by averaging the channels. `WavHeader` locates the sample data without
+// decoding it, so long recordings can be decoded a window at a time.
+
+use std::fmt;
+use std::ops::Range;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Errors that can occur while decoding a WAV file
+#[derive(Debug, Clone, PartialEq)]
+pub enum WavError {
+    /// The data does not start with a RIFF/WAVE header
+    NotRiffWave,
+    /// The file has no "fmt " chunk before its "data" chunk
+    MissingFormatChunk,
+    /// The file has no "data" chunk
+    MissingDataChunk,
+    /// The "fmt " chunk is shorter than its fields
+    TruncatedFormatChunk,
+    /// The channel count, sample rate or block alignment is zero or inconsistent
+    InvalidFormat(&'static str),
+    /// The sample encoding is not one of the supported formats
+    UnsupportedFormat { format_tag: u16, bits_per_sample: u16 },
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WavError::NotRiffWave => write!(f, "not a RIFF/WAVE file"),
+            WavError::MissingFormatChunk => write!(f, "missing \"fmt \" chunk"),
+            WavError::MissingDataChunk => write!(f, "missing \"data\" chunk"),
+            WavError::TruncatedFormatChunk => write!(f, "truncated \"fmt \" chunk"),
+            WavError::InvalidFormat(reason) => write!(f, "invalid format: {}", reason),
+            WavError::UnsupportedFormat { format_tag, bits_per_sample } => write!(
+                f,
+                "unsupported sample format (format tag {:#06x}, {} bits per sample)",
+                format_tag, bits_per_sample
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WavError {}
+
+/// Sample encoding of a WAV file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavSampleFormat {
+    Pcm16,
+    Pcm24,
+    Pcm32,
+    Float32,
+    Float64,
+}
+
+impl WavSampleFormat {
+    fn from_header(format_tag: u16, bits_per_sample: u16) -> Result<Self, WavError> {
+        match (format_tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 16) => Ok(WavSampleFormat::Pcm16),
+            (WAVE_FORMAT_PCM, 24) => Ok(WavSampleFormat::Pcm24),
+            (WAVE_FORMAT_PCM, 32) => Ok(WavSampleFormat::Pcm32),
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => Ok(WavSampleFormat::Float32),
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => Ok(WavSampleFormat::Float64),
+            _ => Err(WavError::UnsupportedFormat { format_tag, bits_per_sample }),
+        }
+    }
+
+    /// Size of one sample of one channel in bytes
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            WavSampleFormat::Pcm16 => 2,
+            WavSampleFormat::Pcm24 => 3,
+            WavSampleFormat::Pcm32 | WavSampleFormat::Float32 => 4,
+            WavSampleFormat::Float64 => 8,
+        }
+    }
+
+    /// Decode one sample to the -1.0..1.0 range
+    fn decode(&self, bytes: &[u8]) -> f32 {
+        match self {
+            WavSampleFormat::Pcm16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            WavSampleFormat::Pcm24 => {
+                // Place the 24 bits in the top of an i32 so the shift sign-extends them
+                let value = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+                value as f32 / 8_388_608.0
+            }
+            WavSampleFormat::Pcm32 => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2_147_483_648.0
+            }
+            WavSampleFormat::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            WavSampleFormat::Float64 => f64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]) as f32,
+        }
+    }
+}
+
+/// Decoded WAV file
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavFile {
+    pub sample_rate: u32,
+    /// Number of channels in the file, before mixdown
+    pub channels: u16,
+    pub format: WavSampleFormat,
+    /// Mono samples, the average of all channels
+    pub samples: Vec<f32>,
+}
+
+impl WavFile {
+    /// Decode a WAV file
+    pub fn parse(bytes: &[u8]) -> Result<Self, WavError> {
+        let header = WavHeader::parse(bytes)?;
+        Ok(Self {
+            sample_rate: header.sample_rate,
+            channels: header.channels,
+            format: header.format,
+            samples: header.decode_frames(bytes, 0..header.frame_count()).collect(),
+        })
+    }
+
+    /// Length of the recording in seconds
+    pub fn duration_seconds(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+}
+
+/// Format of a WAV file and the location of its sample data
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavHeader {
+    pub sample_rate: u32,
+    /// Number of channels in the file, before mixdown
+    pub channels: u16,
+    pub format: WavSampleFormat,
+    block_align: usize,
+    /// Bytes of the "data" chunk, up to the last complete frame
+    data: Range<usize>,
+}
+
+impl WavHeader {
+    /// Find the format and the sample data of a WAV file
+    ///
+    /// A "data" chunk that claims more bytes than the file contains is read up to
+    /// the last complete frame, since recorders that are interrupted often leave
+    /// the header unfinished.
+    pub fn parse(bytes: &[u8]) -> Result<Self, WavError> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(WavError::NotRiffWave);
+        }
+
+        let mut format = None;
+        let mut position = 12;
+
+        while position + 8 <= bytes.len() {
+            let chunk_id = &bytes[position..position + 4];
+            let chunk_size = read_u32(bytes, position + 4) as usize;
+            let body_start = position + 8;
+            let body_end = body_start.saturating_add(chunk_size).min(bytes.len());
+            let body = &bytes[body_start..body_end];
+
+            match chunk_id {
+                b"fmt " => format = Some(FormatChunk::parse(body)?),
+                b"data" => {
+                    let format = format.ok_or(WavError::MissingFormatChunk)?;
+                    let frame_count = body.len() / format.block_align;
+                    return Ok(Self {
+                        sample_rate: format.sample_rate,
+                        channels: format.channels,
+                        format: format.format,
+                        block_align: format.block_align,
+                        data: body_start..body_start + frame_count * format.block_align,
+                    });
+                }
+                _ => {}
+            }
+
+            // Chunks are padded to an even number of bytes
+            position = body_start.saturating_add(chunk_size).saturating_add(chunk_size % 2);
+        }
+
+        Err(WavError::MissingDataChunk)
+    }
+
+    /// Number of mono samples in the file
+    pub fn frame_count(&self) -> usize {
+        self.data.len() / self.block_align
+    }
+
+    /// Mono samples of a range of frames
+    ///
+    /// `bytes` must be the file the header was parsed from. Frames past the end
+    /// of the data are left out.
+    pub fn decode_frames<'a>(&self, bytes: &'a [u8], frames: Range<usize>) -> impl Iterator<Item = f32> + 'a {
+        let end = frames.end.min(self.frame_count());
+        let start = frames.start.min(end);
+        let data = &bytes[self.data.start + start * self.block_align..self.data.start + end * self.block_align];
+        let (channels, format, sample_size) = (self.channels as usize, self.format, self.format.bytes_per_sample());
+        data.chunks_exact(self.block_align).map(move |frame| {
+            let sum: f32 = (0..channels).map(|channel| format.decode(&frame[channel * sample_size..])).sum();
+            sum / channels as f32
+        })
+    }
+}
+
+/// Fields of the "fmt " chunk needed for decoding
+#[derive(Debug, Clone, Copy)]
+struct FormatChunk {
+    sample_rate: u32,
+    channels: u16,
+    block_align: usize,
+    format: WavSampleFormat,
+}
+
+impl FormatChunk {
+    fn parse(body: &[u8]) -> Result<Self, WavError> {
+        if body.len() < 16 {
+            return Err(WavError::TruncatedFormatChunk);
+        }
+
+        let mut format_tag = read_u16(body, 0);
+        let channels = read_u16(body, 2);
+        let sample_rate = read_u32(body, 4);
+        let block_align = read_u16(body, 12) as usize;
+        let bits_per_sample = read_u16(body, 14);
+
+        if format_tag == WAVE_FORMAT_EXTENSIBLE {
+            // The real format tag is the first two bytes of the sub-format GUID
+            if body.len() < 26 {
+                return Err(WavError::TruncatedFormatChunk);
+            }
+            format_tag = read_u16(body, 24);
+        }
+
+        if channels == 0 {
+            return Err(WavError::InvalidFormat("zero channels"));
+        }
+        if sample_rate == 0 {
+            return Err(WavError::InvalidFormat("zero sample rate"));
+        }
+
+        let format = WavSampleFormat::from_header(format_tag, bits_per_sample)?;
+        if block_align < format.bytes_per_sample() * channels as usize {
+            return Err(WavError::InvalidFormat("block alignment smaller than one frame"));
+        }
+
+        Ok(Self {
+            sample_rate,
+            channels,
+            block_align,
+            format,
+        })
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Build a WAV file from per-channel sample bytes
+    fn wav_bytes(format_tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8], extensible: bool) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&(if extensible { WAVE_FORMAT_EXTENSIBLE } else { format_tag }).to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        if extensible {
+            fmt.extend_from_slice(&22u16.to_le_bytes());
+            fmt.extend_from_slice(&bits.to_le_bytes());
+            fmt.extend_from_slice(&0u32.to_le_bytes());
+            fmt.extend_from_slice(&format_tag.to_le_bytes());
+            fmt.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&((4 + 8 + fmt.len() + 8 + data.len()) as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&fmt);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    const VALUES: [f32; 4] = [0.0, 0.5, -0.5, -1.0];
+
+    fn assert_samples(samples: &[f32], expected: &[f32], tolerance: f32) {
+        assert_eq!(samples.len(), expected.len());
+        for (actual, expected) in samples.iter().zip(expected) {
+            assert!((actual - expected).abs() < tolerance, "expected {}, got {}", expected, actual);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_pcm_16() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| ((v * 32768.0).max(-32768.0) as i16).to_le_bytes()).collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 1, 44100, 16, &data, false)).unwrap();
+        assert_eq!(wav.format, WavSampleFormat::Pcm16);
+        assert_eq!(wav.sample_rate, 44100);
+        assert_eq!(wav.channels, 1);
+        assert_samples(&wav.samples, &VALUES, 1e-4);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_pcm_24() {
+        let data: Vec<u8> = VALUES
+            .iter()
+            .flat_map(|v| {
+                let bytes = ((v * 8_388_608.0) as i32).to_le_bytes();
+                [bytes[0], bytes[1], bytes[2]]
+            })
+            .collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 1, 48000, 24, &data, false)).unwrap();
+        assert_eq!(wav.format, WavSampleFormat::Pcm24);
+        assert_samples(&wav.samples, &VALUES, 1e-6);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_pcm_32_extensible() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| ((*v as f64 * 2_147_483_648.0).max(i32::MIN as f64) as i32).to_le_bytes()).collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 1, 48000, 32, &data, true)).unwrap();
+        assert_eq!(wav.format, WavSampleFormat::Pcm32);
+        assert_samples(&wav.samples, &VALUES, 1e-6);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_float_formats() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 1, 48000, 32, &data, false)).unwrap();
+        assert_eq!(wav.format, WavSampleFormat::Float32);
+        assert_samples(&wav.samples, &VALUES, 1e-7);
+
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| (*v as f64).to_le_bytes()).collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 1, 48000, 64, &data, true)).unwrap();
+        assert_eq!(wav.format, WavSampleFormat::Float64);
+        assert_samples(&wav.samples, &VALUES, 1e-7);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_stereo_mixdown_and_duration() {
+        // Left channel 0.5, right channel -0.25 for 100 frames
+        let data: Vec<u8> = (0..100).flat_map(|_| [0.5f32, -0.25f32]).flat_map(|v| v.to_le_bytes()).collect();
+        let wav = WavFile::parse(&wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 2, 1000, 32, &data, false)).unwrap();
+        assert_eq!(wav.channels, 2);
+        assert_eq!(wav.samples.len(), 100);
+        assert!(wav.samples.iter().all(|s| (s - 0.125).abs() < 1e-7));
+        assert!((wav.duration_seconds() - 0.1).abs() < 1e-9);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_skips_unknown_chunks_and_truncated_data() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut bytes = wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 1, 48000, 32, &data, false);
+
+        // Insert an odd-sized LIST chunk (with pad byte) before "fmt "
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), &[1, 2, 3, 0]].concat();
+        bytes.splice(12..12, list);
+        assert_samples(&WavFile::parse(&bytes).unwrap().samples, &VALUES, 1e-7);
+
+        // Drop the last sample and half of the one before it
+        bytes.truncate(bytes.len() - 6);
+        assert_samples(&WavFile::parse(&bytes).unwrap().samples, &VALUES[..2], 1e-7);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_decode_frame_ranges() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| [*v, -*v]).flat_map(|v| v.to_le_bytes()).collect();
+        let bytes = wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 2, 48000, 32, &data, false);
+        let header = WavHeader::parse(&bytes).unwrap();
+        assert_eq!(header.frame_count(), 4);
+        assert_samples(&header.decode_frames(&bytes, 1..3).collect::<Vec<_>>(), &[0.0, 0.0], 1e-7);
+
+        let data: Vec<u8> = VALUES.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let bytes = wav_bytes(WAVE_FORMAT_IEEE_FLOAT, 1, 48000, 32, &data, false);
+        let header = WavHeader::parse(&bytes).unwrap();
+        assert_samples(&header.decode_frames(&bytes, 1..3).collect::<Vec<_>>(), &VALUES[1..3], 1e-7);
+        assert_samples(&header.decode_frames(&bytes, 3..10).collect::<Vec<_>>(), &VALUES[3..], 1e-7);
+        assert_eq!(header.decode_frames(&bytes, 6..10).count(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_parse_errors() {
+        assert_eq!(WavFile::parse(b"not a wav file"), Err(WavError::NotRiffWave));
+
+        let data: Vec<u8> = vec![0; 8];
+        assert_eq!(
+            WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 1, 48000, 8, &data, false)),
+            Err(WavError::UnsupportedFormat { format_tag: WAVE_FORMAT_PCM, bits_per_sample: 8 })
+        );
+        assert_eq!(
+            WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 0, 48000, 16, &data, false)),
+            Err(WavError::InvalidFormat("zero channels"))
+        );
+        assert_eq!(
+            WavFile::parse(&wav_bytes(WAVE_FORMAT_PCM, 1, 0, 16, &data, false)),
+            Err(WavError::InvalidFormat("zero sample rate"))
+        );
+
+        let bytes = wav_bytes(WAVE_FORMAT_PCM, 1, 48000, 16, &data, false);
+        let without_data = &bytes[..bytes.len() - data.len() - 8];
+        assert_eq!(WavFile::parse(without_data), Err(WavError::MissingDataChunk));
+
+        let data_only = [b"RIFF".as_slice(), &12u32.to_le_bytes(), b"WAVE", b"data", &0u32.to_le_bytes()].concat();
+        assert_eq!(WavFile::parse(&data_only), Err(WavError::MissingFormatChunk));
+    }
+}
@@ -58,9 +58,13 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use crate::common::dev_log;
 use super::{AudioError, context::AudioContextManager, VolumeDetector, VolumeAnalysis, SignalGeneratorConfig};
-use super::signal_generator::RootNoteAudioConfig;
+use super::signal_generator::{RootNoteAudioConfig, TargetNoteConfig};
 use super::root_note_audio_node::RootNoteAudioNode;
 use super::test_signal_node::TestSignalAudioNode;
+use super::target_note_node::TargetNoteNode;
+use super::metronome::OnsetDetector;
+use super::metronome_node::MetronomeNode;
+use crate::shared_types::{MetronomeConfig, OnsetTiming};
 use super::message_protocol::{AudioWorkletMessageFactory, ToWorkletMessage, FromWorkletMessage, MessageEnvelope, MessageSerializer, FromJsMessage};
 use super::buffer::AUDIO_CHUNK_SIZE;
 
@@ -113,6 +117,10 @@ struct AudioWorkletSharedData {
     buffer_pool_stats: Option<super::message_protocol::BufferPoolStats>,
     last_volume_analysis: Option<super::VolumeAnalysis>,
     batch_size: u32,
+    /// Note start detector, only present while the metronome runs
+    onset_detector: Option<OnsetDetector>,
+    /// AudioContext times of note starts not collected yet
+    onset_times: Vec<f64>,
 }
 
 impl AudioWorkletSharedData {
@@ -124,6 +132,8 @@ impl AudioWorkletSharedData {
             buffer_pool_stats: None,
             last_volume_analysis: None,
             batch_size: crate::engine::audio::buffer::BUFFER_SIZE as u32, // Default batch size
+            onset_detector: None,
+            onset_times: Vec::new(),
         }
     }
 }
@@ -185,6 +195,10 @@ pub struct AudioWorkletManager {
     batch_size: u32,
     // Dedicated root note audio node
     root_note_node: Option<RootNoteAudioNode>,
+    // One-shot target note, kept until the next one starts
+    target_note_node: Option<TargetNoteNode>,
+    // Metronome clicks, present while the metronome is enabled
+    metronome_node: Option<MetronomeNode>,
     // Test signal audio node for local signal generation
     test_signal_node: Option<TestSignalAudioNode>,
     // Mixer gain node for combining microphone and test signal
@@ -218,6 +232,8 @@ impl AudioWorkletManager {
             ping_pong_enabled: true, // Enable ping-pong buffer recycling by default
             batch_size: crate::engine::audio::buffer::BUFFER_SIZE as u32, // Default batch size
             root_note_node: None,
+            target_note_node: None,
+            metronome_node: None,
             test_signal_node: None,
             mixer_gain: None,
             microphone_gain: None,
@@ -261,6 +277,8 @@ impl AudioWorkletManager {
             ping_pong_enabled: true, // Enable ping-pong buffer recycling by default
             batch_size: crate::engine::audio::buffer::BUFFER_SIZE as u32, // Default batch size
             root_note_node: None,
+            target_note_node: None,
+            metronome_node: None,
             test_signal_node: None,
             mixer_gain: None,
             microphone_gain: None,
@@ -421,6 +439,9 @@ impl AudioWorkletManager {
             } else {
                 dev_log!("✗ Warning: No pitch analyzer available during AudioWorklet initialization");
             }
+            if let (Some(_), Some(audio_context)) = (&self.metronome_node, &self.audio_context) {
+                shared_data.borrow_mut().onset_detector = Some(OnsetDetector::new(audio_context.sample_rate()));
+            }
             
             // Capture only the specific fields needed for the message handler
             let shared_data_clone = shared_data.clone();
@@ -615,6 +636,9 @@ impl AudioWorkletManager {
                     
                     // Perform actual audio processing
                     Self::process_audio_samples(&audio_samples, shared_data);
+                    if let Some(start_frame) = data.start_frame {
+                        Self::detect_onsets(&audio_samples, start_frame, shared_data);
+                    }
                     
                     // Return buffer to worklet for recycling (ping-pong pattern)
                     if let Some(buffer_id) = data.buffer_id {
@@ -694,6 +718,20 @@ impl AudioWorkletManager {
         }
     }
     
+    /// Look for note starts while the metronome runs
+    fn detect_onsets(
+        audio_samples: &[f32],
+        start_frame: f64,
+        shared_data: &std::rc::Rc<std::cell::RefCell<AudioWorkletSharedData>>
+    ) {
+        let mut shared_data_mut = shared_data.borrow_mut();
+        let Some(detector) = shared_data_mut.onset_detector.as_mut() else {
+            return;
+        };
+        let onsets = detector.process(audio_samples, start_frame);
+        shared_data_mut.onset_times.extend(onsets);
+    }
+    
     /// Publish AudioWorklet status update to Live Data Panel (static version)
     fn publish_status_update_static(
         _shared_data: &std::rc::Rc<std::cell::RefCell<AudioWorkletSharedData>>,
@@ -882,6 +920,10 @@ impl AudioWorkletManager {
             dev_log!("[AudioWorkletManager] Cleaning up root note audio node");
             self.root_note_node = None; // Drop triggers cleanup
         }
+        if let Some(mut node) = self.target_note_node.take() {
+            node.stop();
+        }
+        self.metronome_node = None;
         
         self.worklet_node = None;
         self.state = AudioWorkletState::Uninitialized;
@@ -1175,6 +1217,79 @@ impl AudioWorkletManager {
         
     }
 
+    /// Play a target note once
+    /// 
+    /// Like root note audio, the note connects directly to speakers. A target note
+    /// that is still playing is faded out so that only the latest one is heard.
+    pub fn play_target_note(&mut self, config: TargetNoteConfig) {
+        let Some(ref audio_context) = self.audio_context else {
+            dev_log!("[AudioWorkletManager] No audio context available for target note");
+            return;
+        };
+        if let Some(mut node) = self.target_note_node.take() {
+            node.stop();
+        }
+        match TargetNoteNode::new(audio_context, &config) {
+            Ok(node) => self.target_note_node = Some(node),
+            Err(e) => {
+                dev_log!("[AudioWorkletManager] Failed to create target note node: {:?}", e);
+            }
+        }
+    }
+
+    /// Start, reconfigure or stop the metronome
+    /// 
+    /// Note start detection runs only while the metronome is enabled, since note
+    /// starts are measured against its clicks.
+    pub fn configure_metronome(&mut self, config: MetronomeConfig) {
+        if !config.enabled {
+            self.metronome_node = None;
+            if let Some(ref shared_data) = self.shared_data {
+                let mut shared_data_mut = shared_data.borrow_mut();
+                shared_data_mut.onset_detector = None;
+                shared_data_mut.onset_times.clear();
+            }
+            return;
+        }
+        
+        let Some(ref audio_context) = self.audio_context else {
+            dev_log!("[AudioWorkletManager] No audio context available for metronome");
+            return;
+        };
+        if let Some(ref mut node) = self.metronome_node {
+            node.update_config(config);
+        } else {
+            match MetronomeNode::new(audio_context, config) {
+                Ok(node) => self.metronome_node = Some(node),
+                Err(e) => {
+                    dev_log!("[AudioWorkletManager] Failed to create metronome node: {:?}", e);
+                    return;
+                }
+            }
+        }
+        if let Some(ref shared_data) = self.shared_data {
+            let mut shared_data_mut = shared_data.borrow_mut();
+            if shared_data_mut.onset_detector.is_none() {
+                shared_data_mut.onset_detector = Some(OnsetDetector::new(audio_context.sample_rate()));
+            }
+        }
+    }
+
+    /// Keep the metronome clicks scheduled and collect the note starts measured against them
+    /// 
+    /// Must be called regularly while the metronome runs, e.g. once per engine update.
+    pub fn collect_onset_timings(&mut self) -> Vec<OnsetTiming> {
+        let Some(ref mut node) = self.metronome_node else {
+            return Vec::new();
+        };
+        node.schedule_ahead();
+        let Some(ref shared_data) = self.shared_data else {
+            return Vec::new();
+        };
+        let onset_times = std::mem::take(&mut shared_data.borrow_mut().onset_times);
+        onset_times.into_iter().filter_map(|time| node.onset_timing(time)).collect()
+    }
+
     /// Check if root note audio is currently enabled
     /// 
     /// This method checks the current state of the root note audio node to determine
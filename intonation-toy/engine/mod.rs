@@ -59,13 +59,20 @@
 pub mod audio;
 pub(crate) mod platform;
 
-use crate::shared_types::EngineUpdateResult;
+use crate::shared_types::{EngineUpdateResult, RecordingAnalysisChunk};
 use crate::model::ModelLayerActions;
 use audio::buffer::STANDARD_SAMPLE_RATE;
+use audio::offline_analysis::{OfflineAnalysisConfig, RecordingAnalysis};
+
+/// Analysis windows of a loaded recording processed per update
+///
+/// Several times the live analysis load, so a recording is analyzed faster than it
+/// plays while the render loop keeps its frame rate.
+const RECORDING_ANALYSIS_WINDOWS_PER_UPDATE: usize = 8;
 
 // Debug-only imports for conditional compilation
 #[cfg(debug_assertions)]
-use crate::presentation::{DebugLayerActions, ConfigureTestSignal};
+use crate::presentation::{DebugLayerActions, ConfigureTestSignal, ConfigurePitchDetection};
 #[cfg(debug_assertions)]
 use self::audio::{AudioDevices, AudioWorkletStatus, message_protocol::BufferPoolStats};
 
@@ -106,6 +113,14 @@ pub struct ExecuteTestSignalConfiguration {
     pub enabled: bool,
     pub frequency: f32,
     pub volume: f32,
+    pub shape: crate::shared_types::TestSignalShape,
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutePitchDetectionConfiguration {
+    pub algorithm: crate::shared_types::PitchDetectionAlgorithm,
+    pub compare_algorithms: bool,
 }
 
 
@@ -118,6 +133,7 @@ pub struct ExecuteTestSignalConfiguration {
 /// Debug actions provide privileged access to engine internals for testing purposes:
 /// - Direct test signal generation control
 /// - Direct speaker output manipulation
+/// - Direct pitch detection algorithm selection
 /// 
 /// These actions should only be used for debugging and testing purposes.
 #[cfg(debug_assertions)]
@@ -125,6 +141,8 @@ pub struct ExecuteTestSignalConfiguration {
 pub struct DebugEngineActions {
     /// Executed test signal configurations
     pub test_signal_executions: Vec<ExecuteTestSignalConfiguration>,
+    /// Executed pitch detection configurations
+    pub pitch_detection_executions: Vec<ExecutePitchDetectionConfiguration>,
 }
 
 #[cfg(debug_assertions)]
@@ -136,6 +154,7 @@ impl DebugEngineActions {
     pub fn new() -> Self {
         Self {
             test_signal_executions: Vec::new(),
+            pitch_detection_executions: Vec::new(),
         }
     }
 }
@@ -161,6 +180,9 @@ impl DebugEngineActions {
 pub struct AudioEngine {
     /// Audio system context for managing audio processing
     audio_context: Option<std::rc::Rc<std::cell::RefCell<audio::AudioSystemContext>>>,
+    /// Analysis of a loaded recording that continues with each update, or the
+    /// error that stopped it waiting to be returned by the next update
+    recording_analysis: Option<Result<RecordingAnalysis, String>>,
 }
 
 impl AudioEngine {
@@ -196,12 +218,14 @@ impl AudioEngine {
                     let root_note_config = crate::engine::audio::RootNoteAudioConfig {
                         frequency: default_frequency,
                         volume: 0.0,
+                        ..Default::default()
                     };
                     borrowed_context.configure_root_note_audio(root_note_config);
                 }
                 
                 Ok(Self {
                     audio_context: Some(audio_context_rc),
+                    recording_analysis: None,
                 })
             }
             Err(e) => {
@@ -210,6 +234,7 @@ impl AudioEngine {
                 // This allows the application to continue running
                 Ok(Self {
                     audio_context: None,
+                    recording_analysis: None,
                 })
             }
         }
@@ -231,15 +256,21 @@ impl AudioEngine {
     /// - Raw audio analysis (frequency in Hz, volume amplitude)
     /// - Audio system errors and status
     /// - Microphone permission state
+    /// - Note starts measured against the metronome
+    /// - The next windows of a loaded recording, while one is being analyzed
     /// 
     /// Note: All musical interpretation (tuning systems, intervals, pitch relationships)
     /// is handled by the model layer that processes this raw data.
     pub fn update(&mut self, timestamp: f64) -> EngineUpdateResult {
+        let recording_analysis = self.analyze_recording_windows();
+
         if let Some(ref context) = self.audio_context {
+            let onset_timings = context.borrow_mut().collect_onset_timings();
+            
             // Borrow once and collect all data to avoid multiple borrows
             let borrowed_context = context.borrow();
             let audio_analysis = borrowed_context.collect_audio_analysis(timestamp);
-            let mut audio_errors = borrowed_context.collect_audio_errors();
+            let audio_errors = borrowed_context.collect_audio_errors();
             let permission_state = borrowed_context.collect_permission_state();
             
             
@@ -247,6 +278,8 @@ impl AudioEngine {
                 audio_analysis,
                 audio_errors,
                 permission_state,
+                onset_timings,
+                recording_analysis,
             }
         } else {
             // No audio context available
@@ -254,10 +287,37 @@ impl AudioEngine {
                 audio_analysis: None,
                 audio_errors: vec![crate::shared_types::Error::ProcessingError("Audio system not initialized".to_string())],
                 permission_state: crate::shared_types::PermissionState::NotRequested,
+                onset_timings: Vec::new(),
+                recording_analysis,
             }
         }
     }
     
+    /// Analyze the next windows of a loaded recording
+    /// 
+    /// The analysis is dropped once it is finished or has failed, so each
+    /// outcome is returned once.
+    fn analyze_recording_windows(&mut self) -> Option<Result<RecordingAnalysisChunk, String>> {
+        let mut analysis = match self.recording_analysis.take()? {
+            Ok(analysis) => analysis,
+            Err(e) => return Some(Err(e)),
+        };
+        let frames = match analysis.analyze_windows(RECORDING_ANALYSIS_WINDOWS_PER_UPDATE) {
+            Ok(frames) => frames,
+            Err(e) => return Some(Err(e)),
+        };
+        
+        let chunk = RecordingAnalysisChunk {
+            frames: frames.iter().map(|frame| frame.audio_analysis()).collect(),
+            progress: analysis.progress(),
+            finished: analysis.is_finished(),
+        };
+        if !chunk.finished {
+            self.recording_analysis = Some(Ok(analysis));
+        }
+        Some(Ok(chunk))
+    }
+    
     #[cfg(debug_assertions)]
     pub fn get_debug_audio_devices(&self) -> Option<AudioDevices> {
         self.audio_context.as_ref().map(|ctx| {
@@ -366,6 +426,9 @@ impl AudioEngine {
                     let audio_config = crate::engine::audio::signal_generator::RootNoteAudioConfig {
                         frequency: config.frequency,
                         volume: config.volume,
+                        voices: config.voices.clone(),
+                        timbre: config.timbre,
+                        envelope: config.envelope,
                     };
                     
                     // Use the separate root note audio node architecture
@@ -389,6 +452,72 @@ impl AudioEngine {
             crate::common::dev_log!("Engine layer: ✓ Executed {} root note audio configurations", total_root_note_audio);
         }
         
+        // Process polyphonic detection configurations
+        for config in &model_actions.polyphonic_detection_configurations {
+            let Some(ref audio_context) = self.audio_context else {
+                return Err("Audio context not available for polyphonic detection configuration".to_string());
+            };
+            let Some(pitch_analyzer) = audio_context.borrow().get_pitch_analyzer_clone() else {
+                crate::common::dev_log!("Engine layer: ⚠ Pitch analyzer not available for polyphonic detection");
+                continue;
+            };
+            pitch_analyzer.borrow_mut().set_polyphonic_detection(config.enabled)?;
+            crate::common::dev_log!("Engine layer: ✓ Polyphonic detection {}", if config.enabled { "enabled" } else { "disabled" });
+        }
+        
+        // Process spectrum analysis configurations
+        for config in &model_actions.spectrum_analysis_configurations {
+            let Some(ref audio_context) = self.audio_context else {
+                return Err("Audio context not available for spectrum analysis configuration".to_string());
+            };
+            audio_context.borrow_mut().set_spectrum_analysis(config.enabled);
+            crate::common::dev_log!("Engine layer: ✓ Spectrum analysis {}", if config.enabled { "enabled" } else { "disabled" });
+        }
+        
+        // Process target note playbacks
+        for playback in &model_actions.target_note_playbacks {
+            let Some(ref audio_context) = self.audio_context else {
+                return Err("Audio context not available for target note playback".to_string());
+            };
+            let mut borrowed_context = audio_context.borrow_mut();
+            let Some(worklet_manager) = borrowed_context.get_audioworklet_manager_mut() else {
+                crate::common::dev_log!("Engine layer: ⚠ AudioWorkletManager not available for target note playback");
+                continue;
+            };
+            worklet_manager.play_target_note(crate::engine::audio::TargetNoteConfig {
+                frequency: playback.frequency,
+                volume: playback.volume,
+                duration_seconds: playback.duration_seconds,
+                timbre: playback.timbre,
+                envelope: crate::shared_types::DroneEnvelope {
+                    attack_seconds: crate::app_config::TARGET_NOTE_ATTACK_SECONDS,
+                    release_seconds: crate::app_config::TARGET_NOTE_RELEASE_SECONDS,
+                    pluck_period_seconds: None,
+                },
+            });
+            crate::common::dev_log!("Engine layer: ✓ Target note played at {} Hz", playback.frequency);
+        }
+        
+        // Start analyzing loaded recordings; updates return the analysis a few windows at a time
+        for recording in &model_actions.recording_analyses {
+            crate::common::dev_log!("Engine layer: Analyzing recording of {} bytes", recording.wav_file.len());
+            self.recording_analysis = Some(RecordingAnalysis::from_wav(recording.wav_file.clone(), &OfflineAnalysisConfig::default()));
+        }
+        
+        // Process metronome configurations
+        for metronome in &model_actions.metronome_configurations {
+            let Some(ref audio_context) = self.audio_context else {
+                return Err("Audio context not available for metronome configuration".to_string());
+            };
+            let mut borrowed_context = audio_context.borrow_mut();
+            let Some(worklet_manager) = borrowed_context.get_audioworklet_manager_mut() else {
+                crate::common::dev_log!("Engine layer: ⚠ AudioWorkletManager not available for metronome");
+                continue;
+            };
+            worklet_manager.configure_metronome(metronome.config);
+            crate::common::dev_log!("Engine layer: ✓ Metronome {}", if metronome.config.enabled { "configured" } else { "stopped" });
+        }
+        
         crate::common::dev_log!("Engine layer: Action execution completed");
         
         Ok(())
@@ -419,6 +548,7 @@ impl AudioEngine {
     /// 
     /// - Test signal generation: Direct control over audio worklet test signals
     /// - Speaker output: Direct manipulation of speaker output routing
+    /// - Pitch detection: Direct selection of the detection algorithm
     #[cfg(debug_assertions)]
     pub fn execute_debug_actions_sync(&mut self, debug_actions: DebugLayerActions) -> Result<DebugEngineActions, String> {
         crate::common::dev_log!("[DEBUG] Engine layer executing debug actions");
@@ -431,7 +561,14 @@ impl AudioEngine {
             &mut debug_engine_actions
         )?;
         
-        let total_executed = debug_engine_actions.test_signal_executions.len();
+        // Execute pitch detection configurations with privileged access
+        self.execute_pitch_detection_configurations(
+            &debug_actions.pitch_detection_configurations,
+            &mut debug_engine_actions
+        )?;
+        
+        let total_executed = debug_engine_actions.test_signal_executions.len()
+            + debug_engine_actions.pitch_detection_executions.len();
         
         crate::common::dev_log!("[DEBUG] ✓ Engine layer successfully executed {} debug actions", total_executed);
         
@@ -491,6 +628,7 @@ impl AudioEngine {
                         frequency: config.frequency,
                         amplitude: config.volume / 100.0, // Convert percentage to 0-1 range
                         sample_rate: STANDARD_SAMPLE_RATE, // Use standard consumer audio sample rate
+                        shape: config.shape.clone(),
                     };
                     
                     worklet_manager.update_test_signal_config(audio_config);
@@ -509,6 +647,7 @@ impl AudioEngine {
                     enabled: config.enabled,
                     frequency: config.frequency,
                     volume: config.volume,
+                    shape: config.shape.clone(),
                 });
             } else {
                 return Err("[DEBUG] Audio context not available for test signal execution".to_string());
@@ -522,6 +661,50 @@ impl AudioEngine {
         Ok(())
     }
     
+    /// Execute pitch detection configurations with privileged engine access (debug builds only)
+    /// 
+    /// This method switches the algorithm of the running pitch analyzer and enables or
+    /// disables running all algorithms side by side for comparison.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pitch_detection_configs` - Pitch detection configurations to execute
+    /// * `debug_engine_actions` - Container to store executed actions
+    /// 
+    /// # Returns
+    /// 
+    /// Returns `Result<(), String>` indicating success or failure
+    #[cfg(debug_assertions)]
+    fn execute_pitch_detection_configurations(
+        &self,
+        pitch_detection_configs: &[ConfigurePitchDetection],
+        debug_engine_actions: &mut DebugEngineActions
+    ) -> Result<(), String> {
+        for config in pitch_detection_configs {
+            crate::common::dev_log!(
+                "[DEBUG] Executing privileged pitch detection configuration - algorithm: {}, compare: {}",
+                config.algorithm.name(), config.compare_algorithms
+            );
+            
+            let audio_context = self.audio_context.as_ref()
+                .ok_or_else(|| "[DEBUG] Audio context not available for pitch detection configuration".to_string())?;
+            let analyzer = audio_context.borrow().get_pitch_analyzer_clone()
+                .ok_or_else(|| "[DEBUG] Pitch analyzer not available for pitch detection configuration".to_string())?;
+            
+            let mut analyzer = analyzer.try_borrow_mut()
+                .map_err(|_| "[DEBUG] Pitch analyzer is busy".to_string())?;
+            analyzer.set_algorithm(config.algorithm)?;
+            analyzer.set_algorithm_comparison(config.compare_algorithms)?;
+            
+            debug_engine_actions.pitch_detection_executions.push(ExecutePitchDetectionConfiguration {
+                algorithm: config.algorithm,
+                compare_algorithms: config.compare_algorithms,
+            });
+        }
+        
+        Ok(())
+    }
+    
     /// Get pitch analyzer performance metrics for monitoring
     /// 
     /// Returns the latest performance metrics from the pitch analyzer, including
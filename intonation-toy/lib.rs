@@ -34,6 +34,7 @@ pub use presentation::{
 #[cfg(all(debug_assertions, test))]
 pub use presentation::{
     ConfigureTestSignal,
+    ConfigurePitchDetection,
     DebugLayerActions,
 };
 #[cfg(test)]
@@ -204,6 +205,8 @@ pub async fn start_render_loop(
                 audio_analysis: None,
                 audio_errors: Vec::new(),
                 permission_state: crate::shared_types::PermissionState::NotRequested,
+                onset_timings: Vec::new(),
+                recording_analysis: None,
             }
         };
         
@@ -219,19 +222,57 @@ pub async fn start_render_loop(
             // Only process if there are actions to handle
             let has_user_actions = !user_actions.tuning_system_changes.is_empty() ||
                                   !user_actions.root_note_adjustments.is_empty() ||
+                                  !user_actions.reference_frequency_adjustments.is_empty() ||
+                                  !user_actions.scala_tuning_imports.is_empty() ||
                                   !user_actions.scale_changes.is_empty() ||
-                                  !user_actions.root_note_audio_configurations.is_empty();
+                                  !user_actions.root_note_audio_configurations.is_empty() ||
+                                  !user_actions.polyphonic_detection_changes.is_empty() ||
+                                  !user_actions.spectrum_overlay_changes.is_empty() ||
+                                  !user_actions.stretch_tuning_changes.is_empty() ||
+                                  !user_actions.key_detection_changes.is_empty() ||
+                                  !user_actions.transposition_changes.is_empty() ||
+                                  !user_actions.in_tune_tolerance_changes.is_empty() ||
+                                  !user_actions.target_note_playbacks.is_empty() ||
+                                  !user_actions.metronome_configurations.is_empty() ||
+                                  !user_actions.intonation_pitch_changes.is_empty() ||
+                                  !user_actions.recording_analyses.is_empty();
             
             if has_user_actions {
-                trace_log!("Processing {} user actions (tuning: {}, root_note: {}, scale: {}, audio: {})", 
+                trace_log!("Processing {} user actions (tuning: {}, root_note: {}, reference: {}, scala: {}, scale: {}, \
+                    audio: {}, polyphonic: {}, spectrum overlay: {}, stretch: {}, key detection: {}, transposition: {}, \
+                    in-tune tolerance: {}, target notes: {}, metronome: {}, intonation pitch: {}, recording analysis: {})",
                     user_actions.tuning_system_changes.len() + 
                     user_actions.root_note_adjustments.len() +
+                    user_actions.reference_frequency_adjustments.len() +
+                    user_actions.scala_tuning_imports.len() +
                     user_actions.scale_changes.len() +
-                    user_actions.root_note_audio_configurations.len(),
+                    user_actions.root_note_audio_configurations.len() +
+                    user_actions.polyphonic_detection_changes.len() +
+                    user_actions.spectrum_overlay_changes.len() +
+                    user_actions.stretch_tuning_changes.len() +
+                    user_actions.key_detection_changes.len() +
+                    user_actions.transposition_changes.len() +
+                    user_actions.in_tune_tolerance_changes.len() +
+                    user_actions.target_note_playbacks.len() +
+                    user_actions.metronome_configurations.len() +
+                    user_actions.intonation_pitch_changes.len() +
+                    user_actions.recording_analyses.len(),
                     user_actions.tuning_system_changes.len(),
                     user_actions.root_note_adjustments.len(),
+                    user_actions.reference_frequency_adjustments.len(),
+                    user_actions.scala_tuning_imports.len(),
                     user_actions.scale_changes.len(),
-                    user_actions.root_note_audio_configurations.len()
+                    user_actions.root_note_audio_configurations.len(),
+                    user_actions.polyphonic_detection_changes.len(),
+                    user_actions.spectrum_overlay_changes.len(),
+                    user_actions.stretch_tuning_changes.len(),
+                    user_actions.key_detection_changes.len(),
+                    user_actions.transposition_changes.len(),
+                    user_actions.in_tune_tolerance_changes.len(),
+                    user_actions.target_note_playbacks.len(),
+                    user_actions.metronome_configurations.len(),
+                    user_actions.intonation_pitch_changes.len(),
+                    user_actions.recording_analyses.len()
                 );
                 
                 // Process and validate actions in model layer
@@ -245,19 +286,35 @@ pub async fn start_render_loop(
                 // Execute validated actions in engine layer
                 let has_model_actions = !processed_actions.actions.audio_system_configurations.is_empty() ||
                                        !processed_actions.actions.tuning_configurations.is_empty() ||
-                                       !processed_actions.actions.root_note_audio_configurations.is_empty();
+                                       !processed_actions.actions.root_note_audio_configurations.is_empty() ||
+                                       !processed_actions.actions.polyphonic_detection_configurations.is_empty() ||
+                                       !processed_actions.actions.spectrum_analysis_configurations.is_empty() ||
+                                       !processed_actions.actions.target_note_playbacks.is_empty() ||
+                                       !processed_actions.actions.metronome_configurations.is_empty() ||
+                                       !processed_actions.actions.recording_analyses.is_empty();
                 
                 if has_model_actions {
-                    trace_log!("Actions ready for execution: {} audio system, {} tuning, {} root note audio", 
+                    trace_log!("Actions ready for execution: {} audio system, {} tuning, {} root note audio, \
+                        {} polyphonic detection, {} spectrum analysis, {} target notes, {} metronome, {} recording analysis",
                         processed_actions.actions.audio_system_configurations.len(),
                         processed_actions.actions.tuning_configurations.len(),
-                        processed_actions.actions.root_note_audio_configurations.len()
+                        processed_actions.actions.root_note_audio_configurations.len(),
+                        processed_actions.actions.polyphonic_detection_configurations.len(),
+                        processed_actions.actions.spectrum_analysis_configurations.len(),
+                        processed_actions.actions.target_note_playbacks.len(),
+                        processed_actions.actions.metronome_configurations.len(),
+                        processed_actions.actions.recording_analyses.len()
                     );
                     
                     // Execute actions synchronously
                     let total_sync = processed_actions.actions.audio_system_configurations.len() + 
                                    processed_actions.actions.tuning_configurations.len() +
-                                   processed_actions.actions.root_note_audio_configurations.len();
+                                   processed_actions.actions.root_note_audio_configurations.len() +
+                                   processed_actions.actions.polyphonic_detection_configurations.len() +
+                                   processed_actions.actions.spectrum_analysis_configurations.len() +
+                                   processed_actions.actions.target_note_playbacks.len() +
+                                   processed_actions.actions.metronome_configurations.len() +
+                                   processed_actions.actions.recording_analyses.len();
                     match engine.execute_actions(processed_actions.actions) {
                         Ok(()) => {
                             if total_sync > 0 {
@@ -311,7 +368,26 @@ pub async fn start_render_loop(
                 closest_midi_note: 69,
                 cents_offset: 0.0,
                 interval_semitones: 0,
+                interval_steps: 0,
                 root_note: 53,
+                reference_frequency: crate::theory::tuning::STANDARD_REFERENCE_FREQUENCY,
+                polyphonic_notes: Vec::new(),
+                spectrum: None,
+                matched_ratio: None,
+                stretch_tuning: crate::shared_types::StretchTuning::None,
+                inharmonicity: None,
+                key_detection: crate::shared_types::KeyDetection::Off,
+                key_estimate: None,
+                transposition: crate::shared_types::Transposition::CONCERT,
+                in_tune_tolerance_cents: crate::model::DEFAULT_IN_TUNE_TOLERANCE_CENTS,
+                intonation_state: None,
+                metronome: crate::shared_types::MetronomeConfig::default(),
+                onset_timings: Vec::new(),
+                note_events: Vec::new(),
+                current_note: None,
+                vibrato: None,
+                intonation_pitch: crate::shared_types::IntonationPitch::Instantaneous,
+                recording_analysis: None,
             }
         };
         
@@ -325,19 +401,23 @@ pub async fn start_render_loop(
         #[cfg(debug_assertions)]
         {
             let (memory_usage_mb, memory_usage_percent) = web::performance::sample_memory_usage().unwrap_or((0.0, 0.0));
-            let audio_latency = if let Some(ref engine) = engine {
-                engine.get_pitch_analyzer_metrics()
-                    .map(|metrics| metrics.average_latency_ms)
-                    .unwrap_or(0.0)
-            } else {
-                0.0
-            };
+            let pitch_metrics = engine.as_ref().and_then(|engine| engine.get_pitch_analyzer_metrics());
             
-            let performance_metrics = debug::debug_panel::data_types::PerformanceMetrics {
-                fps,
-                memory_usage_mb,
-                memory_usage_percent,
-                audio_latency,
+            let performance_metrics = match pitch_metrics {
+                Some(metrics) => debug::debug_panel::data_types::PerformanceMetrics {
+                    fps,
+                    memory_usage_mb,
+                    memory_usage_percent,
+                    audio_latency: metrics.average_latency_ms,
+                    pitch_algorithm: metrics.active_algorithm,
+                    algorithm_metrics: metrics.algorithm_metrics,
+                },
+                None => debug::debug_panel::data_types::PerformanceMetrics {
+                    fps,
+                    memory_usage_mb,
+                    memory_usage_percent,
+                    ..Default::default()
+                },
             };
             if let Some(ref mut panel) = debug_panel {
                 // Collect real debug data from the engine
@@ -389,17 +469,20 @@ pub async fn start_render_loop(
                 };
                 
                 // Only process if there are debug actions to handle
-                let has_debug_actions = !debug_actions.test_signal_configurations.is_empty();
+                let has_debug_actions = !debug_actions.test_signal_configurations.is_empty() ||
+                    !debug_actions.pitch_detection_configurations.is_empty();
                 
                 if has_debug_actions {
                     trace_log!("[DEBUG] Processing {} debug actions", 
-                        debug_actions.test_signal_configurations.len()
+                        debug_actions.test_signal_configurations.len() +
+                        debug_actions.pitch_detection_configurations.len()
                     );
                     
                     // Execute debug actions synchronously
                     match _engine.execute_debug_actions_sync(debug_actions) {
                         Ok(executed_debug_actions) => {
-                            let total_debug = executed_debug_actions.test_signal_executions.len();
+                            let total_debug = executed_debug_actions.test_signal_executions.len() +
+                                executed_debug_actions.pitch_detection_executions.len();
                             if total_debug > 0 {
                                 trace_log!("[DEBUG] ✓ Executed {} debug actions", total_debug);
                             }
@@ -485,6 +568,16 @@ pub async fn start_render_loop(
             }
         );
         
+        // Clicks that the GUI did not take play the note of the nearest tuning line
+        for event in frame_input.events.iter() {
+            if let three_d::Event::MousePress { button: three_d::MouseButton::Left, position, handled: false, .. } = event
+                && let Some(ref presenter) = presenter
+                && let Ok(mut presenter_ref) = presenter.try_borrow_mut()
+            {
+                presenter_ref.on_scene_clicked(position.y);
+            }
+        }
+        
         let mut screen = frame_input.screen();
         
         // Render presentation layer (which handles its own screen clearing)
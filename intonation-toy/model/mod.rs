@@ -34,6 +34,8 @@
 //!     audio_analysis: None,
 //!     audio_errors: Vec::new(),
 //!     permission_state: crate::shared_types::PermissionState::NotRequested,
+//!     onset_timings: Vec::new(),
+//!     recording_analysis: None,
 //! };
 //! let presentation_data = model.update(timestamp, engine_data);
 //! 
@@ -56,6 +58,8 @@
 //! - ✅ Tuning system support (Equal Temperament)
 //! - ✅ User action processing and business logic validation
 //! - ✅ Three-layer action flow architecture
+//! - ✅ Note segmentation of the detected pitch into note events
+//! - ✅ Vibrato rate, extent and center pitch
 //! 
 //! ## Action Processing System
 //! 
@@ -66,20 +70,47 @@
 //! - `microphone_permission_requests` - User requests for microphone access
 //! - `tuning_system_changes` - User selections of different tuning systems
 //! - `root_note_adjustments` - User modifications to the root note
+//! - `reference_frequency_adjustments` - User modifications to the A4 reference frequency
+//! - `scala_tuning_imports` - User-defined tunings imported from Scala files
+//! - `stretch_tuning_changes` - User selections of an octave stretch curve
+//! - `spectrum_overlay_changes` - User toggles of the spectrum overlay
+//! - `key_detection_changes` - User selections of the key detection mode
+//! - `transposition_changes` - User selections of a transposing instrument
+//! - `in_tune_tolerance_changes` - User selections of the in-tune tolerance
+//! - `target_note_playbacks` - User requests to hear a scale degree of the active tuning
+//! - `metronome_configurations` - User settings of the metronome
+//! - `intonation_pitch_changes` - User selections of the pitch the intonation is judged from
+//! - `recording_analyses` - Recorded WAV files loaded for analysis
 //! 
 //! ### Processing: Business Logic Validation
 //! - `validate_microphone_permission_request_with_error()` - Ensures permission requests are appropriate
 //! - `validate_tuning_system_change_with_error()` - Validates tuning system changes
 //! - `validate_root_note_adjustment_with_error()` - Validates root note adjustments
+//! - `validate_reference_frequency_adjustment_with_error()` - Validates A4 reference frequency adjustments
+//! - `validate_scala_tuning_import_with_error()` - Parses imported Scala scale and keyboard mapping files
+//! - `validate_scale_change_with_error()` - Validates custom scale masks
+//! - `validate_stretch_tuning_change_with_error()` - Validates Railsback curve parameters
+//! - `validate_transposition_change_with_error()` - Validates the transposition range
+//! - `validate_in_tune_tolerance_change_with_error()` - Validates the in-tune tolerance range
+//! - `validate_root_note_audio_configuration_with_error()` - Validates the drone frequency, voices and envelope
+//! - `validate_target_note_playback_with_error()` - Validates the target note range, volume and duration
+//! - `validate_metronome_configuration_with_error()` - Validates the metronome tempo, meter, volume and latency
+//! - `validate_recording_analysis_with_error()` - Checks the size and header of loaded WAV files
 //! 
 //! ### Output: ModelLayerActions
 //! - `microphone_permission_requests` - Validated permission requests
 //! - `audio_system_configurations` - Validated tuning system configurations
 //! - `tuning_configurations` - Validated tuning and root note configurations
+//! - `root_note_audio_configurations` - Validated drones with the voice intervals resolved to ratios
+//! - `spectrum_analysis_configurations` - Whether the engine passes the spectrum on
+//! - `target_note_playbacks` - Validated target notes with the scale degree resolved to a frequency
+//! - `metronome_configurations` - Validated metronome settings
+//! - `recording_analyses` - WAV files for the engine to analyze
 //! 
 //! ### State Management
 //! - `apply_tuning_system_change()` - Updates internal tuning system state
 //! - `apply_root_note_change()` - Updates internal root note state
+//! - `apply_reference_frequency_change()` - Updates internal A4 reference frequency state
 //! 
 //! This system ensures that all user actions pass through business logic validation
 //! before being executed, maintaining system consistency and preventing invalid states.
@@ -94,9 +125,22 @@
 //! - Handle user configuration changes
 //! - Provide processed data to the presentation layer
 
-use crate::shared_types::{EngineUpdateResult, ModelUpdateResult, Volume, Pitch, IntonationData, TuningSystem, Scale, Error, PermissionState, MidiNote, is_valid_midi_note, semitone_in_scale};
+use crate::shared_types::{
+    EngineUpdateResult, ModelUpdateResult, Volume, Pitch, IntonationData, PolyphonicNote,
+    TuningSystem, Scale, Error, PermissionState, MidiNote, StretchTuning, KeyDetection, KeyEstimate,
+    Transposition, IntonationState, MetronomeConfig, OnsetTiming, NoteEvent, VibratoEstimate,
+    IntonationPitch, DroneInterval, DroneVoice, DroneTimbre, DroneEnvelope, TunedDroneVoice,
+    RecordingAnalysisChunk, RecordingAnalysisStatus,
+    is_valid_midi_note, semitone_in_scale,
+};
 use crate::presentation::PresentationLayerActions;
 use crate::common::warn_log;
+use crate::theory::scala::{ScalaError, ScalaScale, ScalaTuning, KeyboardMapping};
+use crate::theory::tuning::IntervalSemitones;
+use crate::theory::stretch::StretchCurve;
+use crate::theory::key_detection::KeyDetector;
+use crate::theory::note_segmentation::{NoteSegment, NoteSegmenter};
+use crate::theory::vibrato::VibratoAnalyzer;
 
 /// Validation error types for action processing
 /// 
@@ -121,6 +165,60 @@ pub(crate) enum ValidationError {
     RootNoteAlreadySet(MidiNote),
     /// Invalid frequency value
     InvalidFrequency(f32),
+    /// Reference frequency is already set to requested value
+    ReferenceFrequencyAlreadySet(f32),
+    /// Reference frequency is outside the supported range
+    ReferenceFrequencyOutOfRange(f32),
+    /// Imported Scala scale or keyboard mapping file is malformed
+    InvalidScalaFile(ScalaError),
+    /// Custom scale mask excludes the root or has bits above the octave
+    InvalidCustomScale(u16),
+    /// Polyphonic detection is already in the requested state
+    PolyphonicDetectionAlreadySet(bool),
+    /// Spectrum overlay is already in the requested state
+    SpectrumOverlayAlreadySet(bool),
+    /// Stretch tuning is already set to the requested curve
+    StretchTuningAlreadySet(StretchTuning),
+    /// Railsback curve deviation is negative, not finite or too large
+    InvalidStretchTuning(StretchTuning),
+    /// Key detection is already in the requested mode
+    KeyDetectionAlreadySet(KeyDetection),
+    /// Transposition is already set to the requested interval
+    TranspositionAlreadySet(Transposition),
+    /// Transposition is more than `MAX_TRANSPOSITION_SEMITONES` away from concert pitch
+    TranspositionOutOfRange(Transposition),
+    /// In-tune tolerance is already set to the requested value
+    InTuneToleranceAlreadySet(f32),
+    /// In-tune tolerance is outside `MIN_IN_TUNE_TOLERANCE_CENTS` to `MAX_IN_TUNE_TOLERANCE_CENTS`
+    InTuneToleranceOutOfRange(f32),
+    /// Drone voice has an invalid interval or a gain outside 0.0-1.0
+    InvalidDroneVoice(DroneVoice),
+    /// Drone has no voices
+    EmptyDrone,
+    /// Drone envelope has negative or non-finite times, or a pluck period that is not positive
+    InvalidDroneEnvelope(DroneEnvelope),
+    /// Target note degree lies outside `MIN_TARGET_NOTE_FREQUENCY` to `MAX_TARGET_NOTE_FREQUENCY`
+    TargetNoteOutOfRange(i32),
+    /// Target note volume is outside 0.0-1.0
+    InvalidTargetNoteVolume(f32),
+    /// Target note duration is not positive or longer than `MAX_TARGET_NOTE_DURATION_SECONDS`
+    InvalidTargetNoteDuration(f32),
+    /// Metronome tempo is outside `MIN_METRONOME_BPM` to `MAX_METRONOME_BPM`
+    MetronomeTempoOutOfRange(f32),
+    /// Beats per bar is zero or more than `MAX_BEATS_PER_BAR`
+    MetronomeMeterOutOfRange(u32),
+    /// Clicks per beat is zero or more than `MAX_METRONOME_SUBDIVISION`
+    MetronomeSubdivisionOutOfRange(u32),
+    /// Metronome volume is outside 0.0-1.0
+    InvalidMetronomeVolume(f32),
+    /// Latency compensation is negative or more than `MAX_LATENCY_COMPENSATION_MS`
+    LatencyCompensationOutOfRange(f32),
+    /// Intonation is already judged from the requested pitch
+    IntonationPitchAlreadySet(IntonationPitch),
+    /// Loaded recording is empty or larger than `MAX_RECORDING_FILE_BYTES`
+    RecordingFileSizeOutOfRange(usize),
+    /// Loaded recording doesn't start with a RIFF/WAVE header
+    InvalidRecordingFile,
 }
 
 /// Result of processing user actions with validation information
@@ -164,22 +262,76 @@ pub struct ConfigureAudioSystemAction {
 /// 
 /// This struct represents a tuning configuration update that has been validated
 /// by the model layer's business logic. It contains the complete tuning configuration
-/// including tuning system and root note.
+/// including tuning system, root note and A4 reference frequency.
 #[derive(Debug, Clone, PartialEq)]
 pub struct UpdateTuningConfigurationAction {
     pub tuning_system: TuningSystem,
     pub root_note: MidiNote,
+    pub reference_frequency: f32,
 }
 
 /// Validated root note audio configuration
 /// 
 /// This struct represents a root note audio configuration that has been validated
 /// by the model layer's business logic. It contains the audio generation settings
-/// including enabled state and frequency.
+/// including frequency and the drone voices, whose intervals are resolved to
+/// frequency ratios against the current tuning system.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConfigureRootNoteAudioAction {
     pub frequency: f32,
     pub volume: f32,
+    pub voices: Vec<TunedDroneVoice>,
+    pub timbre: DroneTimbre,
+    pub envelope: DroneEnvelope,
+}
+
+/// Validated target note playback
+/// 
+/// This struct represents a request to play a scale degree once that has been
+/// validated by the model layer's business logic. The degree is resolved to the
+/// frequency of its target in the active tuning, stretch included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayTargetNoteAction {
+    pub frequency: f32,
+    pub volume: f32,
+    pub duration_seconds: f32,
+    pub timbre: DroneTimbre,
+}
+
+/// Validated metronome configuration
+/// 
+/// This struct represents metronome settings that have been validated by the
+/// model layer's business logic. A disabled configuration stops the metronome.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigureMetronomeAction {
+    pub config: MetronomeConfig,
+}
+
+/// Recording to analyze
+/// 
+/// This struct represents a loaded WAV file whose size and header have been
+/// checked by the model layer. The engine decodes and analyzes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeRecordingAction {
+    pub wav_file: Vec<u8>,
+}
+
+/// Validated polyphonic detection configuration
+/// 
+/// This struct represents a request to enable or disable polyphonic detection
+/// that has been validated by the model layer's business logic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigurePolyphonicDetectionAction {
+    pub enabled: bool,
+}
+
+/// Whether the engine passes the spectrum of the input on
+/// 
+/// The spectrum is needed while the overlay shows it or while the
+/// inharmonicity of the input shapes the measured stretch curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigureSpectrumAnalysisAction {
+    pub enabled: bool,
 }
 
 /// Container for all processed model layer actions
@@ -200,6 +352,21 @@ pub struct ModelLayerActions {
     
     /// Validated root note audio configurations
     pub root_note_audio_configurations: Vec<ConfigureRootNoteAudioAction>,
+    
+    /// Validated polyphonic detection configurations
+    pub polyphonic_detection_configurations: Vec<ConfigurePolyphonicDetectionAction>,
+    
+    /// Validated spectrum analysis configurations
+    pub spectrum_analysis_configurations: Vec<ConfigureSpectrumAnalysisAction>,
+    
+    /// Validated target note playbacks
+    pub target_note_playbacks: Vec<PlayTargetNoteAction>,
+    
+    /// Validated metronome configurations
+    pub metronome_configurations: Vec<ConfigureMetronomeAction>,
+    
+    /// Recordings to analyze
+    pub recording_analyses: Vec<AnalyzeRecordingAction>,
 }
 
 impl ModelLayerActions {
@@ -212,6 +379,11 @@ impl ModelLayerActions {
             audio_system_configurations: Vec::new(),
             tuning_configurations: Vec::new(),
             root_note_audio_configurations: Vec::new(),
+            polyphonic_detection_configurations: Vec::new(),
+            spectrum_analysis_configurations: Vec::new(),
+            target_note_playbacks: Vec::new(),
+            metronome_configurations: Vec::new(),
+            recording_analyses: Vec::new(),
         }
     }
 }
@@ -245,10 +417,13 @@ impl ModelLayerActions {
 ///     audio_analysis: None,
 ///     audio_errors: Vec::new(),
 ///     permission_state: crate::shared_types::PermissionState::NotRequested,
+///     onset_timings: Vec::new(),
+///     recording_analysis: None,
 /// };
 /// 
 /// let presentation_data = model.update(0.0, engine_data);
 /// ```
+#[derive(Clone)]
 pub struct DataModel {
     // Model layer now operates without interface dependencies
     // Data flows through method parameters and return values
@@ -259,34 +434,159 @@ pub struct DataModel {
     /// Current root note for tuning calculations
     root_note: MidiNote,
     
+    /// Current A4 reference frequency in Hz
+    reference_frequency: f32,
+    
     /// Current scale for note filtering
     current_scale: Scale,
+    
+    /// Whether the engine is asked to detect several simultaneous pitches
+    polyphonic_detection: bool,
+    
+    /// Whether the spectrum overlay is shown
+    spectrum_overlay: bool,
+    
+    /// Octave stretch applied to the targets of the tuning system
+    stretch_tuning: StretchTuning,
+    
+    /// Smoothed inharmonicity coefficient measured in the input
+    inharmonicity: Option<f32>,
+    
+    /// Whether the key is detected from the pitch history
+    key_detection: KeyDetection,
+    
+    /// Pitch-class histogram of the detected pitches, filled while key detection is on
+    key_detector: KeyDetector,
+    
+    /// Timestamp of the previous update in seconds, for weighting pitches by duration
+    last_timestamp: Option<f64>,
+    
+    /// Transposition that note names are displayed in
+    transposition: Transposition,
+    
+    /// Largest offset from the closest note in cents that counts as in tune
+    in_tune_tolerance_cents: f32,
+    
+    /// Current metronome settings
+    metronome: MetronomeConfig,
+    
+    /// Most recent note starts measured against the metronome, oldest first
+    onset_history: std::collections::VecDeque<OnsetTiming>,
+    
+    /// Groups the detected pitches into notes
+    note_segmenter: NoteSegmenter,
+    
+    /// Measures vibrato in the recent detected pitches
+    vibrato_analyzer: VibratoAnalyzer,
+    
+    /// Which pitch the intonation is judged from
+    intonation_pitch: IntonationPitch,
+    
+    /// Loaded recording being interpreted while the engine analyzes it
+    recording_interpretation: Option<Box<RecordingInterpretation>>,
+}
+
+/// Interpretation of a loaded recording, filled as its analysis arrives
+#[derive(Clone)]
+struct RecordingInterpretation {
+    /// Copy of the model the recording runs through
+    model: DataModel,
+    
+    /// The result of each analyzed frame with its time in seconds
+    results: Vec<(f64, ModelUpdateResult)>,
 }
 
 /// Standard A4 = 440Hz reference frequency for Equal Temperament
 /// 
 /// This constant represents the internationally accepted standard reference frequency
-/// for Equal Temperament tuning. A4 = 440Hz serves as the baseline from which all
-/// other note frequencies are calculated in Equal Temperament.
+/// for Equal Temperament tuning. A4 = 440Hz serves as the default baseline from which
+/// all other note frequencies are calculated.
 /// 
 /// **Important**: This constant is different from the root pitch frequency returned
-/// by `get_root_pitch()`. While this constant is always 440.0Hz regardless of
-/// configuration, the root pitch varies based on the selected root note.
+/// by `get_root_pitch()`, and from the model's configured reference frequency.
+/// The configured reference starts at this value but can be changed by the user
+/// (e.g. A4 = 442Hz for orchestras or A4 = 415Hz for baroque pitch).
 /// 
 /// # Standard Reference
 /// 
 /// - Established by ISO 16:1975 and reaffirmed by various music organizations
-/// - Used as the reference point for calculating all other note frequencies
+/// - Used as the default reference point for calculating all other note frequencies
 /// - Independent of root note or tuning system configuration changes
 /// 
 /// # Usage
 /// 
 /// Use this constant when you need the absolute A4 = 440Hz reference frequency
-/// rather than the current root pitch frequency. For example:
+/// rather than the configured reference or the current root pitch frequency. For example:
 /// - Displaying standard tuning information to users
 /// - Calculating frequency ratios relative to the standard
-/// - Providing a consistent reference point across different configurations
-pub const REFERENCE_FREQUENCY: f32 = 440.0;
+/// - Initializing the configurable reference frequency
+pub const REFERENCE_FREQUENCY: f32 = crate::theory::tuning::STANDARD_REFERENCE_FREQUENCY;
+
+/// Lowest accepted A4 reference frequency (covers baroque pitch A4 = 415Hz)
+pub const MIN_REFERENCE_FREQUENCY: f32 = 400.0;
+
+/// Highest accepted A4 reference frequency (covers historical high pitch A4 = 466Hz)
+pub const MAX_REFERENCE_FREQUENCY: f32 = 480.0;
+
+/// Largest accepted Railsback curve deviation at either end of the keyboard in cents
+pub const MAX_RAILSBACK_DEVIATION_CENTS: f32 = 100.0;
+
+/// Weight of the previous inharmonicity estimate when a new measurement arrives
+pub const INHARMONICITY_SMOOTHING: f32 = 0.9;
+
+/// Time after which a detected pitch counts half as much for key detection
+pub const KEY_HISTORY_HALF_LIFE_SECONDS: f32 = 30.0;
+
+/// Clarity-weighted seconds of detected pitch needed before a key is reported
+pub const MIN_KEY_EVIDENCE_SECONDS: f32 = 4.0;
+
+/// Lowest lead of the detected key over the runner-up, in correlation, for it to be reported
+pub const MIN_KEY_CONFIDENCE: f32 = 0.05;
+
+/// Largest accepted transposition in semitones, in either direction
+pub const MAX_TRANSPOSITION_SEMITONES: i8 = 24;
+
+/// Initial largest offset from the closest note in cents that counts as in tune
+pub const DEFAULT_IN_TUNE_TOLERANCE_CENTS: f32 = 10.0;
+
+/// Narrowest accepted in-tune tolerance in cents
+pub const MIN_IN_TUNE_TOLERANCE_CENTS: f32 = 1.0;
+
+/// Widest accepted in-tune tolerance in cents, half the distance between semitones
+pub const MAX_IN_TUNE_TOLERANCE_CENTS: f32 = 50.0;
+
+/// Lowest frequency a target note is played at
+pub const MIN_TARGET_NOTE_FREQUENCY: f32 = 20.0;
+
+/// Highest frequency a target note is played at
+pub const MAX_TARGET_NOTE_FREQUENCY: f32 = 20000.0;
+
+/// Longest accepted target note duration in seconds
+pub const MAX_TARGET_NOTE_DURATION_SECONDS: f32 = 10.0;
+
+/// Slowest accepted metronome tempo in beats per minute
+pub const MIN_METRONOME_BPM: f32 = 20.0;
+
+/// Fastest accepted metronome tempo in beats per minute
+pub const MAX_METRONOME_BPM: f32 = 300.0;
+
+/// Most beats per bar the metronome accepts
+pub const MAX_BEATS_PER_BAR: u32 = 16;
+
+/// Most metronome clicks per beat
+pub const MAX_METRONOME_SUBDIVISION: u32 = 4;
+
+/// Largest accepted metronome latency compensation in milliseconds
+pub const MAX_LATENCY_COMPENSATION_MS: f32 = 500.0;
+
+/// Largest accepted recording file, about 7 minutes of 24-bit stereo audio at 48 kHz
+pub const MAX_RECORDING_FILE_BYTES: usize = 128 * 1024 * 1024;
+
+/// Number of measured note starts kept for the presentation layer
+pub const ONSET_HISTORY_LENGTH: usize = 16;
+
+/// Longest gap between updates counted as pitch duration, e.g. after the tab was hidden
+const MAX_KEY_FRAME_SECONDS: f32 = 0.1;
 
 impl DataModel {
     /// Create a new DataModel without interface dependencies
@@ -307,7 +607,23 @@ impl DataModel {
         Ok(Self {
             tuning_system: TuningSystem::EqualTemperament,
             root_note: 57, // Standard A3 root note (MIDI 57)
+            reference_frequency: REFERENCE_FREQUENCY,
             current_scale: Scale::Chromatic,
+            polyphonic_detection: false,
+            spectrum_overlay: false,
+            stretch_tuning: StretchTuning::None,
+            inharmonicity: None,
+            key_detection: KeyDetection::Off,
+            key_detector: KeyDetector::new(),
+            last_timestamp: None,
+            transposition: Transposition::CONCERT,
+            in_tune_tolerance_cents: DEFAULT_IN_TUNE_TOLERANCE_CENTS,
+            metronome: MetronomeConfig::default(),
+            onset_history: std::collections::VecDeque::with_capacity(ONSET_HISTORY_LENGTH),
+            note_segmenter: NoteSegmenter::new(),
+            vibrato_analyzer: VibratoAnalyzer::new(),
+            intonation_pitch: IntonationPitch::Instantaneous,
+            recording_interpretation: None,
         })
     }
 
@@ -342,9 +658,12 @@ impl DataModel {
     /// - Calculates deviation in cents (1/100th of a semitone)
     /// - Normalizes accuracy to 0.0-1.0 range (0.0 = perfect, 1.0 = 50+ cents off)
     /// - Returns maximum inaccuracy (1.0) when no pitch is detected
-    pub fn update(&mut self, _timestamp: f64, engine_data: EngineUpdateResult) -> ModelUpdateResult {
+    pub fn update(&mut self, timestamp: f64, engine_data: EngineUpdateResult) -> ModelUpdateResult {
+        // Interpret a loaded recording with the current settings
+        let recording_analysis = engine_data.recording_analysis.and_then(|chunk| self.interpret_recording_chunk(chunk));
+        
         // Process audio analysis from engine data
-        let (volume, pitch) = if let Some(audio_analysis) = engine_data.audio_analysis {
+        let (volume, pitch, polyphonic_pitches, spectrum) = if let Some(audio_analysis) = engine_data.audio_analysis {
             // Extract volume and pitch from audio analysis
             let volume = Volume {
                 peak_amplitude: audio_analysis.volume_level.peak_amplitude,
@@ -360,12 +679,14 @@ impl DataModel {
                 }
             };
             
-            (volume, pitch)
+            (volume, pitch, audio_analysis.polyphonic_pitches, audio_analysis.fft_data)
         } else {
             // No audio analysis available - return defaults
             (
                 Volume { peak_amplitude: -60.0, rms_amplitude: -60.0 }, // Silent levels
-                Pitch::NotDetected
+                Pitch::NotDetected,
+                Vec::new(),
+                None
             )
         };
         
@@ -409,22 +730,62 @@ impl DataModel {
             }
         };
         
+        // Measure the inharmonicity of the input only while it shapes the stretch curve
+        if self.stretch_tuning == StretchTuning::Measured
+            && let (Pitch::Detected(frequency, _), Some(spectrum)) = (&pitch, &spectrum)
+            && let Some(measured) = crate::theory::stretch::estimate_inharmonicity(spectrum, *frequency)
+        {
+            self.inharmonicity = Some(match self.inharmonicity {
+                Some(previous) => previous * INHARMONICITY_SMOOTHING + measured * (1.0 - INHARMONICITY_SMOOTHING),
+                None => measured,
+            });
+        }
+        
+        // Keep the latest note starts measured against the metronome
+        if self.metronome.enabled {
+            self.onset_history.extend(engine_data.onset_timings);
+            while self.onset_history.len() > ONSET_HISTORY_LENGTH {
+                self.onset_history.pop_front();
+            }
+        }
+        
+        // Accumulate the pitch history for key detection
+        let frame_seconds = self.last_timestamp
+            .map_or(0.0, |last_timestamp| ((timestamp - last_timestamp) as f32).clamp(0.0, MAX_KEY_FRAME_SECONDS));
+        self.last_timestamp = Some(timestamp);
+        let key_estimate = self.update_key_estimate(&pitch, frame_seconds);
+        
+        // Segment the detected pitches into notes
+        let voiced_pitch = match pitch {
+            Pitch::Detected(frequency, clarity) => Some((frequency, clarity)),
+            Pitch::NotDetected => None,
+        };
+        let note_events: Vec<NoteEvent> = self.note_segmenter
+            .process(timestamp, voiced_pitch, volume.rms_amplitude)
+            .map(|segment| self.note_event(segment))
+            .into_iter()
+            .collect();
+        let current_note = self.note_segmenter.current_note().map(|segment| self.note_event(segment));
+        
+        // Measure vibrato, whose center pitch may be judged instead of the swinging one
+        self.vibrato_analyzer.add(timestamp, voiced_pitch.map(|(frequency, _)| frequency));
+        let vibrato = self.vibrato_analyzer.estimate();
+        let judged_pitch = self.judged_pitch(&pitch, vibrato);
+        
         // Calculate accuracy based on detected pitch with full tuning context
-        let accuracy = match pitch {
-            Pitch::Detected(frequency, clarity) => {
-                // Processing detected pitch with tuning system and root note
-                
+        let detected_interval = match judged_pitch {
+            Pitch::Detected(frequency, _) => {
                 // Apply tuning-aware frequency to note conversion
-                let (closest_midi_note, accuracy_cents) = self.frequency_to_note_and_accuracy(frequency);
-                
-                // Result: Note and cents offset calculated
-                
-                IntonationData {
-                    closest_midi_note,
-                    cents_offset: accuracy_cents,
-                }
+                Some(self.frequency_to_note_and_interval(frequency))
             }
-            Pitch::NotDetected => {
+            Pitch::NotDetected => None,
+        };
+        let accuracy = match detected_interval {
+            Some((closest_midi_note, interval)) => IntonationData {
+                closest_midi_note,
+                cents_offset: interval.cents,
+            },
+            None => {
                 // No pitch detected - return default values
                 IntonationData {
                     closest_midi_note: self.root_note, // Use MidiNote directly
@@ -441,8 +802,31 @@ impl DataModel {
             Pitch::NotDetected => 0, // No interval when no pitch detected
         };
 
+        // Steps and matched ratio in the tuning system, which may not have 12 steps per octave
+        let interval_steps = detected_interval.map_or(0, |(_, interval)| interval.semitones);
+        let matched_ratio = detected_interval.and_then(|(_, interval)| interval.ratio);
+        let intonation_state = detected_interval
+            .map(|(_, interval)| IntonationState::from_cents_offset(interval.cents, self.in_tune_tolerance_cents));
+
         // Interval calculation: detected MIDI - root MIDI = interval semitones
 
+        // Place each simultaneous pitch relative to the root and the closest note
+        let root_pitch = self.get_root_pitch();
+        let polyphonic_notes = polyphonic_pitches
+            .iter()
+            .filter(|polyphonic_pitch| polyphonic_pitch.frequency > 0.0)
+            .map(|polyphonic_pitch| {
+                let (closest_midi_note, cents_offset) = self.frequency_to_note_and_accuracy(polyphonic_pitch.frequency);
+                PolyphonicNote {
+                    frequency: polyphonic_pitch.frequency,
+                    salience: polyphonic_pitch.salience,
+                    closest_midi_note,
+                    cents_offset,
+                    cents_from_root: crate::theory::tuning::cents_delta(root_pitch, polyphonic_pitch.frequency),
+                }
+            })
+            .collect();
+
         // Return processed model data with both legacy and flattened fields
         let result = ModelUpdateResult {
             volume,
@@ -456,7 +840,26 @@ impl DataModel {
             closest_midi_note: accuracy.closest_midi_note,
             cents_offset: accuracy.cents_offset,
             interval_semitones,
+            interval_steps,
             root_note: self.root_note,
+            reference_frequency: self.reference_frequency,
+            polyphonic_notes,
+            spectrum,
+            matched_ratio,
+            stretch_tuning: self.stretch_tuning,
+            inharmonicity: self.inharmonicity,
+            key_detection: self.key_detection,
+            key_estimate,
+            transposition: self.transposition,
+            in_tune_tolerance_cents: self.in_tune_tolerance_cents,
+            intonation_state,
+            metronome: self.metronome,
+            onset_timings: self.onset_history.iter().copied().collect(),
+            note_events,
+            current_note,
+            vibrato,
+            intonation_pitch: self.intonation_pitch,
+            recording_analysis,
         };
         
         result
@@ -484,6 +887,8 @@ impl DataModel {
     /// - Microphone permission requests are appropriate for the current state
     /// - Tuning system changes are valid and different from the current system
     /// - Root note adjustments are valid and result in proper frequency calculations
+    /// - Reference frequency adjustments are within the supported concert pitch range
+    /// - Imported Scala files are well-formed
     /// - All actions maintain system consistency and state integrity
     /// 
     /// # Current Implementation
@@ -534,6 +939,7 @@ impl DataModel {
                         let config = UpdateTuningConfigurationAction {
                             tuning_system: self.tuning_system.clone(),
                             root_note: midi_note,
+                            reference_frequency: self.reference_frequency,
                         };
                         
                         // Apply the state change to internal model state
@@ -549,8 +955,70 @@ impl DataModel {
                 }
         }
         
+        // Process reference frequency adjustments
+        for reference_adjustment in presentation_actions.reference_frequency_adjustments {
+            match self.validate_reference_frequency_adjustment_with_error(reference_adjustment.reference_frequency) {
+                Ok(()) => {
+                    let config = UpdateTuningConfigurationAction {
+                        tuning_system: self.tuning_system.clone(),
+                        root_note: self.root_note,
+                        reference_frequency: reference_adjustment.reference_frequency,
+                    };
+                    
+                    // Apply the state change to internal model state
+                    self.apply_reference_frequency_change(&config);
+                    
+                    model_actions.tuning_configurations.push(config);
+                }
+                Err(error) => {
+                    warn_log!("Reference frequency adjustment validation failed: {:?}", error);
+                    validation_errors.push(error);
+                }
+            }
+        }
+        
+        // Process Scala tuning imports
+        for scala_import in presentation_actions.scala_tuning_imports {
+            let registered = self.validate_scala_tuning_import_with_error(&scala_import).and_then(|(tuning, mapped_root)| {
+                let tuning_system = crate::theory::scala::register_custom_tuning(tuning).map_err(ValidationError::InvalidScalaFile)?;
+                Ok((tuning_system, mapped_root))
+            });
+            match registered {
+                Ok((tuning_system, mapped_root)) => {
+                    let config = ConfigureAudioSystemAction { tuning_system };
+                    
+                    // Apply the state change to internal model state
+                    self.apply_tuning_system_change(&config);
+                    
+                    // A keyboard mapping also pins the root note and its pitch
+                    if let Some((root_note, reference_frequency)) = mapped_root {
+                        let tuning_config = UpdateTuningConfigurationAction {
+                            tuning_system: config.tuning_system,
+                            root_note,
+                            reference_frequency,
+                        };
+                        self.apply_root_note_change(&tuning_config);
+                        self.apply_reference_frequency_change(&tuning_config);
+                        model_actions.tuning_configurations.push(tuning_config);
+                    }
+                    
+                    model_actions.audio_system_configurations.push(config);
+                }
+                Err(error) => {
+                    warn_log!("Scala tuning import validation failed: {:?}", error);
+                    validation_errors.push(error);
+                }
+            }
+        }
+        
         // Process scale changes
         for scale_change in presentation_actions.scale_changes {
+            if let Err(error) = self.validate_scale_change_with_error(&scale_change.scale) {
+                warn_log!("Scale change validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            
             // Only apply if scale is different from current
             if scale_change.scale != self.current_scale {
                 self.apply_scale_change(&scale_change);
@@ -568,6 +1036,12 @@ impl DataModel {
                     let config = ConfigureRootNoteAudioAction {
                         frequency: root_note_audio_config.frequency,
                         volume: root_note_audio_config.volume,
+                        voices: root_note_audio_config.voices.iter().map(|voice| TunedDroneVoice {
+                            ratio: self.drone_interval_ratio(voice.interval),
+                            gain: voice.gain,
+                        }).collect(),
+                        timbre: root_note_audio_config.timbre,
+                        envelope: root_note_audio_config.envelope,
                     };
                     
                     // Apply the state change to internal model state
@@ -587,6 +1061,140 @@ impl DataModel {
             }
         }
         
+        // Process polyphonic detection changes
+        for polyphonic_change in presentation_actions.polyphonic_detection_changes {
+            if polyphonic_change.enabled == self.polyphonic_detection {
+                validation_errors.push(ValidationError::PolyphonicDetectionAlreadySet(polyphonic_change.enabled));
+                continue;
+            }
+            self.polyphonic_detection = polyphonic_change.enabled;
+            model_actions.polyphonic_detection_configurations.push(ConfigurePolyphonicDetectionAction {
+                enabled: polyphonic_change.enabled,
+            });
+        }
+        
+        let spectrum_was_needed = self.spectrum_needed();
+        
+        // Process spectrum overlay changes
+        for overlay_change in presentation_actions.spectrum_overlay_changes {
+            if overlay_change.enabled == self.spectrum_overlay {
+                validation_errors.push(ValidationError::SpectrumOverlayAlreadySet(overlay_change.enabled));
+                continue;
+            }
+            self.spectrum_overlay = overlay_change.enabled;
+        }
+        
+        // Process stretch tuning changes
+        for stretch_change in presentation_actions.stretch_tuning_changes {
+            if let Err(error) = self.validate_stretch_tuning_change_with_error(&stretch_change.stretch_tuning) {
+                warn_log!("Stretch tuning change validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            // No model-layer action created since the stretch only affects note analysis
+            self.stretch_tuning = stretch_change.stretch_tuning;
+            // The measured curve starts over from the input that follows the change
+            self.inharmonicity = None;
+        }
+        
+        let spectrum_needed = self.spectrum_needed();
+        if spectrum_needed != spectrum_was_needed {
+            model_actions.spectrum_analysis_configurations.push(ConfigureSpectrumAnalysisAction {
+                enabled: spectrum_needed,
+            });
+        }
+        
+        // Process key detection changes
+        for key_detection_change in presentation_actions.key_detection_changes {
+            if key_detection_change.key_detection == self.key_detection {
+                validation_errors.push(ValidationError::KeyDetectionAlreadySet(key_detection_change.key_detection));
+                continue;
+            }
+            // Start from an empty history when detection is switched on again
+            if key_detection_change.key_detection == KeyDetection::Off {
+                self.key_detector.reset();
+            }
+            self.key_detection = key_detection_change.key_detection;
+        }
+        
+        // Process transposition changes
+        for transposition_change in presentation_actions.transposition_changes {
+            if let Err(error) = self.validate_transposition_change_with_error(&transposition_change.transposition) {
+                warn_log!("Transposition change validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            // No model-layer action created since analysis stays in concert pitch
+            self.transposition = transposition_change.transposition;
+        }
+        
+        // Process in-tune tolerance changes
+        for tolerance_change in presentation_actions.in_tune_tolerance_changes {
+            if let Err(error) = self.validate_in_tune_tolerance_change_with_error(tolerance_change.tolerance_cents) {
+                warn_log!("In-tune tolerance change validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            // No model-layer action created since the tolerance only affects the reported state
+            self.in_tune_tolerance_cents = tolerance_change.tolerance_cents;
+        }
+        
+        // Process metronome configurations
+        for metronome_configuration in presentation_actions.metronome_configurations {
+            let config = metronome_configuration.config;
+            if let Err(error) = self.validate_metronome_configuration_with_error(&config) {
+                warn_log!("Metronome configuration validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            // Timings against a stopped or moved click grid no longer apply
+            let grid_changed = !config.enabled
+                || config.bpm != self.metronome.bpm
+                || config.beats_per_bar != self.metronome.beats_per_bar
+                || config.subdivision != self.metronome.subdivision;
+            if grid_changed {
+                self.onset_history.clear();
+            }
+            self.metronome = config;
+            model_actions.metronome_configurations.push(ConfigureMetronomeAction { config });
+        }
+        
+        // Process intonation pitch changes
+        for intonation_pitch_change in presentation_actions.intonation_pitch_changes {
+            if intonation_pitch_change.intonation_pitch == self.intonation_pitch {
+                validation_errors.push(ValidationError::IntonationPitchAlreadySet(intonation_pitch_change.intonation_pitch));
+                continue;
+            }
+            // No model-layer action created since the judged pitch only affects note analysis
+            self.intonation_pitch = intonation_pitch_change.intonation_pitch;
+        }
+        
+        // Process recording analyses
+        for recording_analysis in presentation_actions.recording_analyses {
+            if let Err(error) = self.validate_recording_analysis_with_error(&recording_analysis.wav_file) {
+                warn_log!("Recording analysis validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            self.start_recording_interpretation();
+            model_actions.recording_analyses.push(AnalyzeRecordingAction { wav_file: recording_analysis.wav_file });
+        }
+        
+        // Process target note playbacks last, so they sound in a tuning changed in the same frame
+        for playback in presentation_actions.target_note_playbacks {
+            if let Err(error) = self.validate_target_note_playback_with_error(&playback) {
+                warn_log!("Target note playback validation failed: {:?}", error);
+                validation_errors.push(error);
+                continue;
+            }
+            model_actions.target_note_playbacks.push(PlayTargetNoteAction {
+                frequency: self.target_note_frequency(playback.degree),
+                volume: playback.volume,
+                duration_seconds: playback.duration_seconds,
+                timbre: playback.timbre,
+            });
+        }
+        
         ProcessedActions {
             actions: model_actions,
             validation_errors,
@@ -635,10 +1243,17 @@ impl DataModel {
     /// - Note: The closest musical note to the frequency (filtered by scale)
     /// - accuracy_cents: Deviation in cents (negative = flat, positive = sharp)
     fn frequency_to_note_and_accuracy(&self, frequency: f32) -> (MidiNote, f32) {
+        let (midi_note, interval) = self.frequency_to_note_and_interval(frequency);
+        (midi_note, interval.cents)
+    }
+
+    /// Like `frequency_to_note_and_accuracy`, returning the full interval from the
+    /// root: the steps in the tuning system, the cents and the matched just ratio
+    fn frequency_to_note_and_interval(&self, frequency: f32) -> (MidiNote, IntervalSemitones) {
         // Handle edge case: invalid or zero frequency
         if frequency <= 0.0 {
             warn_log!("[MODEL] Invalid frequency for note conversion: {}", frequency);
-            return (69, 0.0); // Return A4 (MIDI 69) as default
+            return (69, IntervalSemitones { semitones: 0, cents: 0.0, ratio: None }); // Return A4 (MIDI 69) as default
         }
         
         // Handle edge case: extremely low frequency (below human hearing ~20Hz)
@@ -657,18 +1272,31 @@ impl DataModel {
         // This is the key to tuning-aware processing
         let root_pitch = self.get_root_pitch();
         
+        // Measure against the stretched targets by removing the stretch from the input
+        let frequency = self.stretch_curve().unstretch(frequency);
+        
         // Converting frequency with tuning system and root pitch
         
         // Use the scale-aware calculation from the tuning module
+        let tuning = self.tuning_system.tuning();
         let interval_result = crate::theory::tuning::frequency_to_interval_semitones_scale_aware(
-            self.tuning_system.clone(),
+            tuning,
             root_pitch,
             frequency,
             self.current_scale,
         );
         
+        // Degrees of tunings without 12 notes per octave are not semitones,
+        // so those are named after the nearest equal tempered note
+        let semitones_from_root = if tuning.degrees_per_octave() == 12 {
+            interval_result.semitones
+        } else {
+            let degree_frequency = tuning.interval_frequency(root_pitch, interval_result.semitones);
+            (crate::theory::tuning::cents_delta(root_pitch, degree_frequency) / 100.0).round() as i32
+        };
+        
         // Calculate MIDI note from root note plus interval
-        let raw_midi_note = self.root_note as i32 + interval_result.semitones;
+        let raw_midi_note = self.root_note as i32 + semitones_from_root;
         
         // Clamp to valid MIDI range (0-127)
         let clamped_midi_note = raw_midi_note.max(0).min(127) as u8;
@@ -680,7 +1308,7 @@ impl DataModel {
             69 // Default to A4 if validation fails
         };
         
-        (final_midi_note, interval_result.cents)
+        (final_midi_note, interval_result)
     }
     
     
@@ -692,7 +1320,7 @@ impl DataModel {
     /// constant which always represents A4 = 440Hz.
     /// 
     /// # Root Pitch Calculation
-    /// - Uses A4 = 440Hz as the standard reference
+    /// - Uses the configured A4 reference frequency (440Hz by default)
     /// - Calculates other notes using Equal Temperament formula
     /// 
     /// This ensures consistent frequency mapping regardless of which tuning system
@@ -706,7 +1334,130 @@ impl DataModel {
     /// - All other frequencies are calculated relative to this root pitch
     fn get_root_pitch(&self) -> f32 {
         // Use the centralized function for consistency
-        crate::theory::tuning::midi_note_to_standard_frequency(self.root_note)
+        crate::theory::tuning::midi_note_to_frequency_with_reference(self.root_note, self.reference_frequency)
+    }
+    
+    /// Add the detected pitch to the key detection history and estimate the key
+    /// 
+    /// Each pitch is weighted by its clarity and by how long it sounded, older pitches
+    /// fade out with `KEY_HISTORY_HALF_LIFE_SECONDS`. The estimate is only returned
+    /// once there is enough evidence and the key clearly fits better than any other.
+    fn update_key_estimate(&mut self, pitch: &Pitch, frame_seconds: f32) -> Option<KeyEstimate> {
+        if self.key_detection == KeyDetection::Off {
+            return None;
+        }
+        
+        self.key_detector.decay(0.5_f32.powf(frame_seconds / KEY_HISTORY_HALF_LIFE_SECONDS));
+        if let Pitch::Detected(frequency, clarity) = *pitch {
+            self.key_detector.add(frequency, self.reference_frequency, clarity * frame_seconds);
+        }
+        
+        if self.key_detector.total_weight() < MIN_KEY_EVIDENCE_SECONDS {
+            return None;
+        }
+        self.key_detector.estimate().filter(|estimate| estimate.confidence >= MIN_KEY_CONFIDENCE)
+    }
+    
+    /// Start interpreting a loaded recording
+    /// 
+    /// The recording runs through a copy of the model with the current tuning, root,
+    /// scale and judging settings, but with an empty pitch history, so the
+    /// recording is analyzed on its own and the live state is left untouched.
+    fn start_recording_interpretation(&mut self) {
+        self.recording_interpretation = None;
+        let mut recording_model = self.clone();
+        recording_model.inharmonicity = None;
+        recording_model.key_detector = KeyDetector::new();
+        recording_model.last_timestamp = None;
+        recording_model.metronome.enabled = false;
+        recording_model.onset_history.clear();
+        recording_model.note_segmenter.reset();
+        recording_model.vibrato_analyzer.reset();
+        
+        self.recording_interpretation = Some(Box::new(RecordingInterpretation {
+            model: recording_model,
+            results: Vec::new(),
+        }));
+    }
+    
+    /// Interpret the analysis frames of a loaded recording
+    /// 
+    /// # Returns
+    /// 
+    /// The state of the analysis, or `None` if no recording is being interpreted
+    fn interpret_recording_chunk(&mut self, chunk: Result<RecordingAnalysisChunk, String>) -> Option<RecordingAnalysisStatus> {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                self.recording_interpretation = None;
+                return Some(RecordingAnalysisStatus::Failed(e));
+            }
+        };
+        let interpretation = self.recording_interpretation.as_mut()?;
+        
+        for audio_analysis in chunk.frames {
+            let time = audio_analysis.timestamp / 1000.0;
+            let engine_data = EngineUpdateResult {
+                audio_analysis: Some(audio_analysis),
+                audio_errors: Vec::new(),
+                permission_state: PermissionState::Granted,
+                onset_timings: Vec::new(),
+                recording_analysis: None,
+            };
+            let result = interpretation.model.update(time, engine_data);
+            interpretation.results.push((time, result));
+        }
+        
+        if chunk.finished {
+            let interpretation = self.recording_interpretation.take()?;
+            Some(RecordingAnalysisStatus::Finished(interpretation.results))
+        } else {
+            Some(RecordingAnalysisStatus::InProgress(chunk.progress))
+        }
+    }
+    
+    /// The pitch the intonation is judged from
+    /// 
+    /// In `IntonationPitch::VibratoCenter` mode, a detected pitch is replaced by the
+    /// center of the vibrato while there is vibrato, so the offset shows where the
+    /// note is heard rather than where the swing happens to be.
+    fn judged_pitch(&self, pitch: &Pitch, vibrato: Option<VibratoEstimate>) -> Pitch {
+        match (pitch, vibrato) {
+            (&Pitch::Detected(_, clarity), Some(vibrato)) if self.intonation_pitch == IntonationPitch::VibratoCenter => {
+                Pitch::Detected(vibrato.center_frequency, clarity)
+            }
+            _ => pitch.clone(),
+        }
+    }
+    
+    /// Place a segmented note against the closest note of the active tuning
+    /// 
+    /// The frames of a note are measured against the target of its median, so
+    /// a note close to halfway between two targets isn't split between them.
+    fn note_event(&self, segment: NoteSegment) -> NoteEvent {
+        let (closest_midi_note, cents_offset) = self.frequency_to_note_and_accuracy(segment.median_frequency);
+        NoteEvent {
+            start_time: segment.start_time,
+            end_time: segment.end_time,
+            median_frequency: segment.median_frequency,
+            closest_midi_note,
+            mean_cents_offset: cents_offset + segment.mean_cents_from_median,
+            stability_cents: segment.stability_cents,
+        }
+    }
+    
+    /// Frequency of the target of a scale degree in the active tuning
+    /// 
+    /// The target is where the tuning line of the degree is drawn, so it follows
+    /// the root, the reference frequency and the octave stretch.
+    fn target_note_frequency(&self, degree: i32) -> f32 {
+        let frequency = self.tuning_system.tuning().interval_frequency(self.get_root_pitch(), degree);
+        self.stretch_curve().stretch(frequency)
+    }
+    
+    /// Stretch curve of the current stretch tuning around the A4 reference frequency
+    fn stretch_curve(&self) -> StretchCurve {
+        StretchCurve::new(self.stretch_tuning, self.inharmonicity, self.reference_frequency)
     }
     
     /// Validate microphone permission request with detailed error reporting
@@ -741,10 +1492,15 @@ impl DataModel {
     /// - Validation of supported tuning systems
     /// - State consistency checks
     fn validate_tuning_system_change_with_error(&self, new_tuning_system: &TuningSystem) -> Result<(), ValidationError> {
-        if *new_tuning_system == self.tuning_system {
-            Err(ValidationError::TuningSystemAlreadyActive(new_tuning_system.clone()))
-        } else {
-            Ok(())
+        use crate::theory::tuning_systems::equal_division;
+        match *new_tuning_system {
+            tuning_system if tuning_system == self.tuning_system => {
+                Err(ValidationError::TuningSystemAlreadyActive(tuning_system))
+            }
+            TuningSystem::EqualDivision(divisions) if equal_division(divisions).is_none() => {
+                Err(ValidationError::UnsupportedTuningSystem(format!("{}-EDO", divisions)))
+            }
+            _ => Ok(()),
         }
     }
     
@@ -778,61 +1534,279 @@ impl DataModel {
         }
     }
     
-    /// Validate root note audio configuration request
-    /// 
-    /// Validates a root note audio configuration request by checking that the frequency
-    /// is valid and the configuration is different from the current state.
-    /// Future implementations will add:
-    /// - Frequency range validation
-    /// - Audio system compatibility checks
-    /// - Volume level validation
-    fn validate_root_note_audio_configuration_with_error(&self, config: &crate::presentation::ConfigureRootNoteAudio) -> Result<(), ValidationError> {
-        // Validate frequency is positive
-        if config.frequency <= 0.0 {
-            return Err(ValidationError::InvalidFrequency(config.frequency));
-        }
-        
-        // Allow configuration even if state is the same to ensure proper synchronization
-        Ok(())
-    }
-    
-    /// Apply tuning system change to internal state
+    /// Validate reference frequency adjustment request with detailed error reporting
     /// 
-    /// Updates the internal tuning system and root pitch frequency based on a validated
-    /// tuning system change. This method should only be called with actions that have
-    /// passed business logic validation.
+    /// Ensures that a new A4 reference frequency is a finite value within
+    /// `MIN_REFERENCE_FREQUENCY..=MAX_REFERENCE_FREQUENCY` and differs from the
+    /// current reference.
     /// 
     /// # Arguments
     /// 
-    /// * `action` - The validated tuning system configuration to apply
+    /// * `new_reference_frequency` - The requested A4 frequency in Hz
     /// 
-    /// # Current Implementation
+    /// # Returns
     /// 
-    /// Updates the internal tuning system based on a validated tuning system change.
-    /// Future implementations will add:
-    /// - State change notifications
-    /// - Logging of configuration changes
-    /// - Validation of state consistency after changes
-    fn apply_tuning_system_change(&mut self, action: &ConfigureAudioSystemAction) {
-        crate::common::dev_log!(
-            "Model layer: Tuning system changed from {:?} to {:?}",
-            self.tuning_system, action.tuning_system
-        );
-        self.tuning_system = action.tuning_system.clone();
+    /// Returns `Ok(())` if the adjustment should be processed, or a specific
+    /// `ValidationError` describing why the adjustment was rejected.
+    fn validate_reference_frequency_adjustment_with_error(&self, new_reference_frequency: f32) -> Result<(), ValidationError> {
+        if !new_reference_frequency.is_finite()
+            || !(MIN_REFERENCE_FREQUENCY..=MAX_REFERENCE_FREQUENCY).contains(&new_reference_frequency)
+        {
+            return Err(ValidationError::ReferenceFrequencyOutOfRange(new_reference_frequency));
+        }
+        
+        if (new_reference_frequency - self.reference_frequency).abs() < f32::EPSILON {
+            return Err(ValidationError::ReferenceFrequencyAlreadySet(new_reference_frequency));
+        }
+        
+        Ok(())
     }
     
-    /// Apply scale change to internal state
-    /// 
-    /// Updates the internal scale based on a validated scale change.
-    /// This method should only be called with actions that have passed business logic validation.
-    /// 
-    /// # Arguments
-    /// 
-    /// * `action` - The validated scale change to apply
-    /// 
-    /// # Current Implementation
-    /// 
-    /// Updates the internal scale based on a validated scale change.
+    /// Validate scale change request with detailed error reporting
+    /// 
+    /// Built-in scales are always valid. A custom scale must include the root
+    /// (bit 0) and may only use the 12 semitones of one octave (bits 0-11).
+    fn validate_scale_change_with_error(&self, new_scale: &Scale) -> Result<(), ValidationError> {
+        match *new_scale {
+            Scale::Custom(mask) if mask & 1 == 0 || mask > 0x0FFF => Err(ValidationError::InvalidCustomScale(mask)),
+            _ => Ok(()),
+        }
+    }
+    
+    /// Whether the spectrum of the input is used, by the overlay or the measured stretch curve
+    fn spectrum_needed(&self) -> bool {
+        self.spectrum_overlay || self.stretch_tuning == StretchTuning::Measured
+    }
+    
+    /// Validate stretch tuning change request with detailed error reporting
+    /// 
+    /// The Railsback curve deviations must be finite and between 0 and
+    /// `MAX_RAILSBACK_DEVIATION_CENTS`, the curve must differ from the current one.
+    fn validate_stretch_tuning_change_with_error(&self, new_stretch_tuning: &StretchTuning) -> Result<(), ValidationError> {
+        if let StretchTuning::Railsback { treble_cents, bass_cents } = *new_stretch_tuning {
+            let valid_range = 0.0..=MAX_RAILSBACK_DEVIATION_CENTS;
+            if !valid_range.contains(&treble_cents) || !valid_range.contains(&bass_cents) {
+                return Err(ValidationError::InvalidStretchTuning(*new_stretch_tuning));
+            }
+        }
+        
+        if *new_stretch_tuning == self.stretch_tuning {
+            return Err(ValidationError::StretchTuningAlreadySet(*new_stretch_tuning));
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate transposition change request with detailed error reporting
+    /// 
+    /// The transposition must be at most `MAX_TRANSPOSITION_SEMITONES` away from
+    /// concert pitch and differ from the current one.
+    fn validate_transposition_change_with_error(&self, new_transposition: &Transposition) -> Result<(), ValidationError> {
+        if new_transposition.semitones.unsigned_abs() > MAX_TRANSPOSITION_SEMITONES.unsigned_abs() {
+            return Err(ValidationError::TranspositionOutOfRange(*new_transposition));
+        }
+        
+        if *new_transposition == self.transposition {
+            return Err(ValidationError::TranspositionAlreadySet(*new_transposition));
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate in-tune tolerance change request with detailed error reporting
+    /// 
+    /// The tolerance must lie within `MIN_IN_TUNE_TOLERANCE_CENTS` to
+    /// `MAX_IN_TUNE_TOLERANCE_CENTS` and differ from the current one.
+    fn validate_in_tune_tolerance_change_with_error(&self, tolerance_cents: f32) -> Result<(), ValidationError> {
+        if !(MIN_IN_TUNE_TOLERANCE_CENTS..=MAX_IN_TUNE_TOLERANCE_CENTS).contains(&tolerance_cents) {
+            return Err(ValidationError::InTuneToleranceOutOfRange(tolerance_cents));
+        }
+        
+        if tolerance_cents == self.in_tune_tolerance_cents {
+            return Err(ValidationError::InTuneToleranceAlreadySet(tolerance_cents));
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate target note playback request with detailed error reporting
+    /// 
+    /// The degree must resolve to a frequency from `MIN_TARGET_NOTE_FREQUENCY` to
+    /// `MAX_TARGET_NOTE_FREQUENCY`, the volume must lie within 0.0-1.0 and the duration
+    /// must be positive and at most `MAX_TARGET_NOTE_DURATION_SECONDS`.
+    fn validate_target_note_playback_with_error(&self, playback: &crate::presentation::PlayTargetNote) -> Result<(), ValidationError> {
+        let frequency = self.target_note_frequency(playback.degree);
+        if !(MIN_TARGET_NOTE_FREQUENCY..=MAX_TARGET_NOTE_FREQUENCY).contains(&frequency) {
+            return Err(ValidationError::TargetNoteOutOfRange(playback.degree));
+        }
+        
+        if !(0.0..=1.0).contains(&playback.volume) {
+            return Err(ValidationError::InvalidTargetNoteVolume(playback.volume));
+        }
+        
+        if !(playback.duration_seconds > 0.0 && playback.duration_seconds <= MAX_TARGET_NOTE_DURATION_SECONDS) {
+            return Err(ValidationError::InvalidTargetNoteDuration(playback.duration_seconds));
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate metronome configuration with detailed error reporting
+    /// 
+    /// The tempo must lie within `MIN_METRONOME_BPM` to `MAX_METRONOME_BPM`, the bar
+    /// must have 1 to `MAX_BEATS_PER_BAR` beats, each beat 1 to `MAX_METRONOME_SUBDIVISION`
+    /// clicks, the volume must lie within 0.0-1.0 and the latency compensation within
+    /// 0 to `MAX_LATENCY_COMPENSATION_MS`.
+    fn validate_metronome_configuration_with_error(&self, config: &MetronomeConfig) -> Result<(), ValidationError> {
+        if !(MIN_METRONOME_BPM..=MAX_METRONOME_BPM).contains(&config.bpm) {
+            return Err(ValidationError::MetronomeTempoOutOfRange(config.bpm));
+        }
+        
+        if !(1..=MAX_BEATS_PER_BAR).contains(&config.beats_per_bar) {
+            return Err(ValidationError::MetronomeMeterOutOfRange(config.beats_per_bar));
+        }
+        
+        if !(1..=MAX_METRONOME_SUBDIVISION).contains(&config.subdivision) {
+            return Err(ValidationError::MetronomeSubdivisionOutOfRange(config.subdivision));
+        }
+        
+        if !(0.0..=1.0).contains(&config.volume) {
+            return Err(ValidationError::InvalidMetronomeVolume(config.volume));
+        }
+        
+        if !(0.0..=MAX_LATENCY_COMPENSATION_MS).contains(&config.latency_compensation_ms) {
+            return Err(ValidationError::LatencyCompensationOutOfRange(config.latency_compensation_ms));
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate a loaded recording before the engine decodes it
+    /// 
+    /// Only the size and the RIFF/WAVE header are checked here; the engine reports
+    /// unsupported formats and damaged files once it decodes the recording.
+    fn validate_recording_analysis_with_error(&self, wav_file: &[u8]) -> Result<(), ValidationError> {
+        if wav_file.is_empty() || wav_file.len() > MAX_RECORDING_FILE_BYTES {
+            return Err(ValidationError::RecordingFileSizeOutOfRange(wav_file.len()));
+        }
+        
+        if wav_file.len() < 12 || &wav_file[0..4] != b"RIFF" || &wav_file[8..12] != b"WAVE" {
+            return Err(ValidationError::InvalidRecordingFile);
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate Scala tuning import request with detailed error reporting
+    /// 
+    /// Parses the scale file and, when present, the keyboard mapping file. With a
+    /// keyboard mapping, the middle note becomes the root note and the A4 reference
+    /// is derived so that the root sounds at the frequency the mapping specifies.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns the imported tuning together with the mapped root note and A4
+    /// reference frequency, or a specific `ValidationError` describing why the
+    /// import was rejected.
+    fn validate_scala_tuning_import_with_error(
+        &self,
+        scala_import: &crate::presentation::ImportScalaTuning,
+    ) -> Result<(ScalaTuning, Option<(MidiNote, f32)>), ValidationError> {
+        let scale = ScalaScale::parse(&scala_import.scale_file).map_err(ValidationError::InvalidScalaFile)?;
+        
+        let Some(mapping_file) = &scala_import.keyboard_mapping_file else {
+            return Ok((ScalaTuning::new(&scale), None));
+        };
+        
+        let mapping = KeyboardMapping::parse(mapping_file).map_err(ValidationError::InvalidScalaFile)?;
+        let tuning = ScalaTuning::with_keyboard_mapping(&scale, &mapping).map_err(ValidationError::InvalidScalaFile)?;
+        let root_frequency = mapping.middle_note_frequency(&scale).map_err(ValidationError::InvalidScalaFile)?;
+        
+        let reference_frequency = root_frequency * 2.0_f32.powf((69.0 - mapping.middle_note as f32) / 12.0);
+        if !(MIN_REFERENCE_FREQUENCY..=MAX_REFERENCE_FREQUENCY).contains(&reference_frequency) {
+            return Err(ValidationError::ReferenceFrequencyOutOfRange(reference_frequency));
+        }
+        
+        Ok((tuning, Some((mapping.middle_note, reference_frequency))))
+    }
+    
+    /// Validate root note audio configuration request
+    /// 
+    /// Validates a root note audio configuration request by checking that the frequency
+    /// is valid, that the drone has at least one voice with a valid interval and gain,
+    /// and that the envelope times are usable.
+    /// Future implementations will add:
+    /// - Frequency range validation
+    /// - Audio system compatibility checks
+    /// - Volume level validation
+    fn validate_root_note_audio_configuration_with_error(&self, config: &crate::presentation::ConfigureRootNoteAudio) -> Result<(), ValidationError> {
+        // Validate frequency is positive
+        if config.frequency <= 0.0 {
+            return Err(ValidationError::InvalidFrequency(config.frequency));
+        }
+        
+        if config.voices.is_empty() {
+            return Err(ValidationError::EmptyDrone);
+        }
+        for voice in &config.voices {
+            let valid_interval = match voice.interval {
+                DroneInterval::Ratio(numerator, denominator) => numerator > 0 && denominator > 0,
+                DroneInterval::Cents(cents) => cents.is_finite() && cents.abs() <= 4800.0,
+                DroneInterval::TuningSystem(semitones) => semitones.abs() <= 48,
+            };
+            if !valid_interval || !(0.0..=1.0).contains(&voice.gain) {
+                return Err(ValidationError::InvalidDroneVoice(*voice));
+            }
+        }
+        
+        let envelope = config.envelope;
+        let valid_time = |seconds: f32| seconds.is_finite() && seconds >= 0.0;
+        let valid_period = envelope.pluck_period_seconds.is_none_or(|seconds| seconds.is_finite() && seconds > 0.0);
+        if !valid_time(envelope.attack_seconds) || !valid_time(envelope.release_seconds) || !valid_period {
+            return Err(ValidationError::InvalidDroneEnvelope(envelope));
+        }
+        
+        // Allow configuration even if state is the same to ensure proper synchronization
+        Ok(())
+    }
+    
+    /// Apply tuning system change to internal state
+    /// 
+    /// Updates the internal tuning system and root pitch frequency based on a validated
+    /// tuning system change. This method should only be called with actions that have
+    /// passed business logic validation.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `action` - The validated tuning system configuration to apply
+    /// 
+    /// # Current Implementation
+    /// 
+    /// Updates the internal tuning system based on a validated tuning system change.
+    /// Future implementations will add:
+    /// - State change notifications
+    /// - Logging of configuration changes
+    /// - Validation of state consistency after changes
+    fn apply_tuning_system_change(&mut self, action: &ConfigureAudioSystemAction) {
+        crate::common::dev_log!(
+            "Model layer: Tuning system changed from {:?} to {:?}",
+            self.tuning_system, action.tuning_system
+        );
+        self.tuning_system = action.tuning_system.clone();
+    }
+    
+    /// Apply scale change to internal state
+    /// 
+    /// Updates the internal scale based on a validated scale change.
+    /// This method should only be called with actions that have passed business logic validation.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `action` - The validated scale change to apply
+    /// 
+    /// # Current Implementation
+    /// 
+    /// Updates the internal scale based on a validated scale change.
     /// Future implementations will add:
     /// - State change notifications
     /// - Logging of configuration changes
@@ -871,6 +1845,23 @@ impl DataModel {
         self.root_note = action.root_note;
     }
     
+    /// Apply reference frequency change to internal state
+    /// 
+    /// Updates the internal A4 reference frequency based on a validated reference
+    /// frequency adjustment. This method should only be called with actions that have
+    /// passed business logic validation.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `action` - The validated tuning configuration to apply
+    fn apply_reference_frequency_change(&mut self, action: &UpdateTuningConfigurationAction) {
+        crate::common::dev_log!(
+            "Model layer: Reference frequency changed from {} Hz to {} Hz",
+            self.reference_frequency, action.reference_frequency
+        );
+        self.reference_frequency = action.reference_frequency;
+    }
+    
     /// Apply root note audio configuration change to internal state
     /// 
     /// Updates the internal root note audio enabled state based on a validated
@@ -886,6 +1877,23 @@ impl DataModel {
         );
         // Model no longer stores root note audio state - engine owns this state
     }
+    
+    /// Frequency ratio of a drone voice interval above the root
+    /// 
+    /// Tuning system intervals are looked up in the current tuning, so a major third
+    /// is 5/4 in just intonation and 2^(4/12) in equal temperament. For tunings
+    /// with more or fewer than 12 degrees the nearest degree is used.
+    fn drone_interval_ratio(&self, interval: DroneInterval) -> f32 {
+        match interval {
+            DroneInterval::Ratio(numerator, denominator) => numerator as f32 / denominator as f32,
+            DroneInterval::Cents(cents) => 2.0_f32.powf(cents / 1200.0),
+            DroneInterval::TuningSystem(semitones) => {
+                let tuning = self.tuning_system.tuning();
+                let degree = (semitones as f32 * tuning.degrees_per_octave() as f32 / 12.0).round() as i32;
+                2.0_f32.powf(tuning.interval_cents(degree) / 1200.0)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -911,7 +1919,8 @@ mod tests {
             audio_analysis: None,
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::NotRequested,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
 
         // Test that update can be called multiple times without panicking
@@ -954,6 +1963,8 @@ mod tests {
                 audio_analysis: None,
                 audio_errors: Vec::new(),
                 permission_state: crate::shared_types::PermissionState::NotRequested,
+                onset_timings: Vec::new(),
+                recording_analysis: None,
             };
 
             // Test multiple operations
@@ -989,7 +2000,8 @@ mod tests {
             audio_analysis: None,
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::NotRequested,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         // Test that update signature is correct
@@ -1009,6 +2021,7 @@ mod tests {
             volume_level: crate::shared_types::Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: crate::shared_types::Pitch::Detected(440.0, 0.95),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -1016,7 +2029,8 @@ mod tests {
             audio_analysis: Some(audio_analysis),
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::Granted,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1036,6 +2050,7 @@ mod tests {
             volume_level: crate::shared_types::Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: crate::shared_types::Pitch::Detected(260.0, 0.90),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -1043,7 +2058,8 @@ mod tests {
             audio_analysis: Some(audio_analysis),
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::Granted,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1063,6 +2079,7 @@ mod tests {
             volume_level: crate::shared_types::Volume { peak_amplitude: -60.0, rms_amplitude: -60.0 },
             pitch: crate::shared_types::Pitch::NotDetected,
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -1070,7 +2087,8 @@ mod tests {
             audio_analysis: Some(audio_analysis),
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::Granted,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1089,6 +2107,8 @@ mod tests {
             audio_analysis: None,
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::NotRequested,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1222,6 +2242,7 @@ mod tests {
             volume_level: crate::shared_types::Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: crate::shared_types::Pitch::Detected(test_frequency, 0.95),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -1229,6 +2250,8 @@ mod tests {
             audio_analysis: Some(audio_analysis.clone()),
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         // First test with root note A (default)
@@ -1338,6 +2361,689 @@ mod tests {
         }
     }
 
+    /// Test that reference frequency adjustments retune root pitch and note detection
+    #[wasm_bindgen_test]
+    fn test_reference_frequency_adjustment() {
+        let mut model = DataModel::create().unwrap();
+        assert_eq!(model.reference_frequency, REFERENCE_FREQUENCY);
+
+        let mut actions = PresentationLayerActions::new();
+        actions.reference_frequency_adjustments.push(crate::presentation::AdjustReferenceFrequency {
+            reference_frequency: 442.0,
+        });
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.validation_errors.len(), 0);
+        assert_eq!(result.actions.tuning_configurations.len(), 1);
+        assert_eq!(result.actions.tuning_configurations[0].reference_frequency, 442.0);
+        assert_eq!(model.reference_frequency, 442.0);
+
+        // A3 root is now 221Hz
+        assert!((model.get_root_pitch() - 221.0).abs() < 0.01);
+
+        // 442Hz is a perfectly tuned A4 against the new reference
+        let engine_data = EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(442.0, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        let update = model.update(0.0, engine_data);
+        assert_eq!(update.reference_frequency, 442.0);
+        assert_eq!(update.closest_midi_note, 69);
+        assert!(update.cents_offset.abs() < 0.1);
+    }
+
+    /// Test that the matched just ratio is reported for just intonation tunings
+    #[wasm_bindgen_test]
+    fn test_update_reports_matched_ratio() {
+        let mut model = DataModel::create().unwrap();
+        let harmonic_seventh = model.get_root_pitch() * 7.0 / 4.0;
+        let engine_data = || EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(harmonic_seventh, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+
+        let update = model.update(0.0, engine_data());
+        assert_eq!(update.matched_ratio, None);
+
+        model.tuning_system = TuningSystem::JustIntonation7Limit;
+        let update = model.update(0.0, engine_data());
+        assert_eq!(update.interval_semitones, 10);
+        assert_eq!(update.matched_ratio, Some(crate::shared_types::JustRatio::new(7, 4)));
+        assert!(update.cents_offset.abs() < 0.1);
+    }
+
+    /// Test the transposition is validated and reported while analysis stays in concert pitch
+    #[wasm_bindgen_test]
+    fn test_transposition_setting() {
+        let mut model = DataModel::create().unwrap();
+
+        let actions = PresentationLayerActions::builder()
+            .with_transposition_change(Transposition::B_FLAT)
+            .with_transposition_change(Transposition::B_FLAT)
+            .with_transposition_change(Transposition::new(-25))
+            .with_transposition_change(Transposition::new(i8::MIN))
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::TranspositionAlreadySet(Transposition::B_FLAT),
+            ValidationError::TranspositionOutOfRange(Transposition::new(-25)),
+            ValidationError::TranspositionOutOfRange(Transposition::new(i8::MIN)),
+        ]);
+
+        let update = model.update(0.0, EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(440.0, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        });
+        assert_eq!(update.transposition, Transposition::B_FLAT);
+        assert_eq!(update.closest_midi_note, 69);
+        assert_eq!(update.root_note, 57);
+    }
+
+    /// Test that the pitch is reported in tune within the tolerance, sharp or flat outside it
+    #[wasm_bindgen_test]
+    fn test_in_tune_tolerance() {
+        let mut model = DataModel::create().unwrap();
+        let engine_data = |frequency: f32| EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(frequency, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        // 440 Hz raised and lowered by 8 cents
+        let sharp = 440.0 * 2.0_f32.powf(8.0 / 1200.0);
+        let flat = 440.0 * 2.0_f32.powf(-8.0 / 1200.0);
+
+        let update = model.update(0.0, engine_data(sharp));
+        assert_eq!(update.in_tune_tolerance_cents, DEFAULT_IN_TUNE_TOLERANCE_CENTS);
+        assert_eq!(update.intonation_state, Some(IntonationState::InTune));
+
+        let actions = PresentationLayerActions::builder()
+            .with_in_tune_tolerance_change(5.0)
+            .with_in_tune_tolerance_change(5.0)
+            .with_in_tune_tolerance_change(0.5)
+            .with_in_tune_tolerance_change(f32::NAN)
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors.len(), 3);
+        assert_eq!(result.validation_errors[0], ValidationError::InTuneToleranceAlreadySet(5.0));
+        assert_eq!(result.validation_errors[1], ValidationError::InTuneToleranceOutOfRange(0.5));
+
+        assert_eq!(model.update(0.1, engine_data(sharp)).intonation_state, Some(IntonationState::Sharp));
+        assert_eq!(model.update(0.2, engine_data(flat)).intonation_state, Some(IntonationState::Flat));
+        assert_eq!(model.update(0.3, EngineUpdateResult {
+            audio_analysis: None,
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        }).intonation_state, None);
+    }
+
+    /// Test drone voice intervals are resolved to ratios and invalid drones are rejected
+    #[wasm_bindgen_test]
+    fn test_root_note_audio_drone_voices() {
+        use crate::presentation::ConfigureRootNoteAudio;
+        use crate::shared_types::{DronePreset, DroneVoiceTuning};
+
+        let mut model = DataModel::create().unwrap();
+        let drone = |voices: Vec<DroneVoice>, envelope: DroneEnvelope| ConfigureRootNoteAudio {
+            frequency: 261.63,
+            volume: 0.5,
+            voices,
+            timbre: DroneTimbre::Organ,
+            envelope,
+        };
+        let third = |interval: DroneInterval| DroneVoice { interval, gain: 1.0 };
+
+        let actions = PresentationLayerActions::builder()
+            .with_root_note_audio_configuration(drone(DronePreset::MajorChord.voices(DroneVoiceTuning::Just), DroneEnvelope::SUSTAINED))
+            .with_root_note_audio_configuration(drone(vec![third(DroneInterval::Cents(400.0)), third(DroneInterval::TuningSystem(4))], DroneEnvelope::SUSTAINED))
+            .with_root_note_audio_configuration(drone(Vec::new(), DroneEnvelope::SUSTAINED))
+            .with_root_note_audio_configuration(drone(vec![third(DroneInterval::Ratio(5, 0))], DroneEnvelope::SUSTAINED))
+            .with_root_note_audio_configuration(drone(vec![DroneVoice { interval: DroneInterval::Ratio(5, 4), gain: 1.5 }], DroneEnvelope::SUSTAINED))
+            .with_root_note_audio_configuration(drone(vec![third(DroneInterval::Ratio(5, 4))], DroneEnvelope { pluck_period_seconds: Some(0.0), ..DronePreset::Tanpura.envelope() }))
+            .build();
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::EmptyDrone,
+            ValidationError::InvalidDroneVoice(third(DroneInterval::Ratio(5, 0))),
+            ValidationError::InvalidDroneVoice(DroneVoice { interval: DroneInterval::Ratio(5, 4), gain: 1.5 }),
+            ValidationError::InvalidDroneEnvelope(DroneEnvelope { pluck_period_seconds: Some(0.0), ..DronePreset::Tanpura.envelope() }),
+        ]);
+        let configurations = &result.actions.root_note_audio_configurations;
+        assert_eq!(configurations.len(), 2);
+
+        // A just major third is 5/4, not the equal tempered 2^(4/12)
+        let ratios: Vec<f32> = configurations[0].voices.iter().map(|voice| voice.ratio).collect();
+        assert_eq!(ratios, vec![1.0, 1.25, 1.5]);
+        assert_eq!(configurations[0].timbre, DroneTimbre::Organ);
+
+        // The default tuning system is equal temperament
+        let equal_third = 2.0_f32.powf(4.0 / 12.0);
+        assert!((configurations[1].voices[0].ratio - equal_third).abs() < 1e-5);
+        assert!((configurations[1].voices[1].ratio - equal_third).abs() < 1e-5);
+
+        // Tuning system voices follow a tuning change queued in the same frame
+        let actions = PresentationLayerActions::builder()
+            .with_tuning_change(TuningSystem::JustIntonation)
+            .with_root_note_audio_configuration(drone(vec![third(DroneInterval::TuningSystem(4))], DroneEnvelope::SUSTAINED))
+            .build();
+        let result = model.process_user_actions(actions);
+        assert!((result.actions.root_note_audio_configurations[0].voices[0].ratio - 1.25).abs() < 1e-5);
+    }
+
+    /// Test target note degrees are resolved in the active tuning and out of range requests rejected
+    #[wasm_bindgen_test]
+    fn test_target_note_playback() {
+        let mut model = DataModel::create().unwrap();
+        let actions = PresentationLayerActions::builder()
+            .with_root_note_adjustment(60)
+            .with_tuning_change(TuningSystem::JustIntonation)
+            .with_target_note_playback(4, 0.3, 1.5)
+            .with_target_note_playback(-12, 0.3, 1.5)
+            .with_target_note_playback(200, 0.3, 1.5)
+            .with_target_note_playback(7, 1.5, 1.5)
+            .with_target_note_playback(7, 0.3, 0.0)
+            .build();
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::TargetNoteOutOfRange(200),
+            ValidationError::InvalidTargetNoteVolume(1.5),
+            ValidationError::InvalidTargetNoteDuration(0.0),
+        ]);
+        let playbacks = &result.actions.target_note_playbacks;
+        assert_eq!(playbacks.len(), 2);
+
+        // The just major third above C4 and the octave below
+        let c4 = crate::theory::tuning::midi_note_to_frequency_with_reference(60, REFERENCE_FREQUENCY);
+        assert!((playbacks[0].frequency - c4 * 1.25).abs() < 0.01);
+        assert!((playbacks[1].frequency - c4 / 2.0).abs() < 0.01);
+        assert_eq!(playbacks[0].duration_seconds, 1.5);
+    }
+
+    /// Test metronome settings are validated and note starts are kept while it runs
+    #[wasm_bindgen_test]
+    fn test_metronome_onset_timings() {
+        let mut model = DataModel::create().unwrap();
+        let config = MetronomeConfig { enabled: true, bpm: 120.0, ..MetronomeConfig::default() };
+        let actions = PresentationLayerActions::builder()
+            .with_metronome_configuration(config)
+            .with_metronome_configuration(MetronomeConfig { bpm: 400.0, ..config })
+            .with_metronome_configuration(MetronomeConfig { beats_per_bar: 0, ..config })
+            .with_metronome_configuration(MetronomeConfig { subdivision: 5, ..config })
+            .with_metronome_configuration(MetronomeConfig { volume: -0.5, ..config })
+            .with_metronome_configuration(MetronomeConfig { latency_compensation_ms: 600.0, ..config })
+            .build();
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::MetronomeTempoOutOfRange(400.0),
+            ValidationError::MetronomeMeterOutOfRange(0),
+            ValidationError::MetronomeSubdivisionOutOfRange(5),
+            ValidationError::InvalidMetronomeVolume(-0.5),
+            ValidationError::LatencyCompensationOutOfRange(600.0),
+        ]);
+        assert_eq!(result.actions.metronome_configurations, vec![ConfigureMetronomeAction { config }]);
+
+        let engine_data = |offsets: &[f32]| EngineUpdateResult {
+            audio_analysis: None,
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: offsets.iter().enumerate()
+                .map(|(beat, &offset_ms)| OnsetTiming { bar: 0, beat: beat as u32, subdivision: 0, offset_ms })
+                .collect(),
+            recording_analysis: None,
+        };
+        let update = model.update(0.0, engine_data(&[12.0, -8.0]));
+        assert_eq!(update.metronome, config);
+        let offsets: Vec<f32> = update.onset_timings.iter().map(|timing| timing.offset_ms).collect();
+        assert_eq!(offsets, vec![12.0, -8.0]);
+
+        // Only the latest note starts are kept
+        let update = model.update(0.1, engine_data(&[1.0; ONSET_HISTORY_LENGTH]));
+        assert_eq!(update.onset_timings.len(), ONSET_HISTORY_LENGTH);
+        assert!(update.onset_timings.iter().all(|timing| timing.offset_ms == 1.0));
+
+        // A new tempo starts a new history, a volume change keeps it
+        let actions = PresentationLayerActions::builder()
+            .with_metronome_configuration(MetronomeConfig { volume: 0.2, ..config })
+            .build();
+        model.process_user_actions(actions);
+        assert_eq!(model.update(0.2, engine_data(&[])).onset_timings.len(), ONSET_HISTORY_LENGTH);
+        let actions = PresentationLayerActions::builder()
+            .with_metronome_configuration(MetronomeConfig { bpm: 90.0, ..config })
+            .build();
+        model.process_user_actions(actions);
+        assert!(model.update(0.3, engine_data(&[])).onset_timings.is_empty());
+    }
+
+    /// Test loaded recordings are validated and their frames interpreted apart from the live input
+    #[wasm_bindgen_test]
+    fn test_recording_analysis() {
+        let mut model = DataModel::create().unwrap();
+        let wav_file = b"RIFF\x04\x00\x00\x00WAVE".to_vec();
+        let actions = PresentationLayerActions::builder()
+            .with_recording_analysis(&wav_file)
+            .with_recording_analysis(&[])
+            .with_recording_analysis(b"not a WAV file")
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::RecordingFileSizeOutOfRange(0),
+            ValidationError::InvalidRecordingFile,
+        ]);
+        assert_eq!(result.actions.recording_analyses, vec![AnalyzeRecordingAction { wav_file }]);
+
+        let frames: Vec<_> = (0..30)
+            .map(|i| crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: 0.3, rms_amplitude: 0.1 },
+                pitch: Pitch::Detected(440.0, 0.95),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: i as f64 * 20.0,
+            })
+            .collect();
+        let chunk_update = |model: &mut DataModel, chunk: Result<RecordingAnalysisChunk, String>| {
+            model.update(100.0, EngineUpdateResult {
+                audio_analysis: None,
+                audio_errors: Vec::new(),
+                permission_state: PermissionState::Granted,
+                onset_timings: Vec::new(),
+                recording_analysis: Some(chunk),
+            })
+        };
+        
+        let update = chunk_update(&mut model, Ok(RecordingAnalysisChunk { frames: frames[..20].to_vec(), progress: 0.6, finished: false }));
+        assert_eq!(update.recording_analysis, Some(RecordingAnalysisStatus::InProgress(0.6)));
+        let update = chunk_update(&mut model, Ok(RecordingAnalysisChunk { frames: frames[20..].to_vec(), progress: 1.0, finished: true }));
+        let Some(RecordingAnalysisStatus::Finished(results)) = update.recording_analysis else {
+            panic!("the recording should be interpreted");
+        };
+        assert_eq!(results.len(), 30);
+        assert!((results[1].0 - 0.02).abs() < 1e-9);
+        assert_eq!(results[1].1.closest_midi_note, 69);
+        assert!(results.last().unwrap().1.current_note.is_some());
+        // The recorded note doesn't sound in the live input
+        assert_eq!(update.current_note, None);
+        
+        // Chunks arriving without a loaded recording are ignored
+        let update = chunk_update(&mut model, Ok(RecordingAnalysisChunk { frames, progress: 1.0, finished: true }));
+        assert_eq!(update.recording_analysis, None);
+        
+        model.process_user_actions(PresentationLayerActions::builder().with_recording_analysis(b"RIFF\x04\x00\x00\x00WAVE").build());
+        let update = chunk_update(&mut model, Err("no data chunk".to_string()));
+        assert_eq!(update.recording_analysis, Some(RecordingAnalysisStatus::Failed("no data chunk".to_string())));
+    }
+
+    /// Test the detected pitches are segmented into note events measured in the active tuning
+    #[wasm_bindgen_test]
+    fn test_note_events() {
+        let mut model = DataModel::create().unwrap();
+        let engine_data = |pitch: Pitch| EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: 0.3, rms_amplitude: 0.1 },
+                pitch,
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        // A4 5 cents sharp for half a second, then E5 until silence, 20 updates per second
+        let sharp_a4 = 440.0 * 2.0_f32.powf(5.0 / 1200.0);
+        let frames = std::iter::repeat(Pitch::Detected(sharp_a4, 0.95)).take(10)
+            .chain(std::iter::repeat(Pitch::Detected(659.26, 0.95)).take(10))
+            .chain(std::iter::repeat(Pitch::NotDetected).take(4));
+        let mut note_events = Vec::new();
+        let mut last_update = None;
+        for (i, pitch) in frames.enumerate() {
+            let update = model.update(i as f64 * 0.05, engine_data(pitch));
+            if i == 5 {
+                let current_note = update.current_note.expect("a quarter second of A4 should be a note");
+                assert_eq!(current_note.start_time, 0.0);
+                assert!(update.note_events.is_empty());
+            }
+            note_events.extend(update.note_events.iter().copied());
+            last_update = Some(update);
+        }
+
+        assert_eq!(note_events.len(), 2);
+        assert_eq!(note_events[0].closest_midi_note, 69);
+        assert!((note_events[0].mean_cents_offset - 5.0).abs() < 0.1);
+        assert!((note_events[0].duration_seconds() - 0.45).abs() < 1e-6);
+        assert_eq!(note_events[1].closest_midi_note, 76);
+        assert!((note_events[1].start_time - 0.5).abs() < 1e-6);
+        assert_eq!(last_update.unwrap().current_note, None);
+    }
+
+    /// Test vibrato is measured and its center pitch can be judged instead of the swinging pitch
+    #[wasm_bindgen_test]
+    fn test_vibrato_center_intonation() {
+        let mut model = DataModel::create().unwrap();
+        let engine_data = |frequency: f32| EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: 0.3, rms_amplitude: 0.1 },
+                pitch: Pitch::Detected(frequency, 0.95),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        // A4 5 cents sharp with a 5.5 Hz vibrato of 40 cents, 60 updates per second
+        let sing = |model: &mut DataModel, start: f64| {
+            let mut update = None;
+            for i in 0..90 {
+                let time = start + i as f64 / 60.0;
+                let cents = 5.0 + 40.0 * (2.0 * std::f64::consts::PI * 5.5 * time).sin() as f32;
+                update = Some(model.update(time, engine_data(440.0 * 2.0_f32.powf(cents / 1200.0))));
+            }
+            update.unwrap()
+        };
+
+        let update = sing(&mut model, 0.0);
+        let vibrato = update.vibrato.expect("a steady 5.5 Hz vibrato");
+        assert!((vibrato.rate_hz - 5.5).abs() < 0.3);
+        assert!((vibrato.extent_cents - 40.0).abs() < 4.0);
+        assert_eq!(update.intonation_pitch, IntonationPitch::Instantaneous);
+
+        let actions = PresentationLayerActions::builder().with_intonation_pitch_change(IntonationPitch::VibratoCenter).build();
+        assert!(model.process_user_actions(actions).validation_errors.is_empty());
+        let update = sing(&mut model, 1.5);
+        assert_eq!(update.intonation_pitch, IntonationPitch::VibratoCenter);
+        assert_eq!(update.closest_midi_note, 69);
+        assert!((update.cents_offset - 5.0).abs() < 3.0, "center offset {}", update.cents_offset);
+        assert_eq!(update.intonation_state, Some(IntonationState::InTune));
+
+        let actions = PresentationLayerActions::builder().with_intonation_pitch_change(IntonationPitch::VibratoCenter).build();
+        assert_eq!(model.process_user_actions(actions).validation_errors, vec![ValidationError::IntonationPitchAlreadySet(IntonationPitch::VibratoCenter)]);
+    }
+
+    /// Test the key is estimated from the pitch history once there is enough evidence
+    #[wasm_bindgen_test]
+    fn test_key_detection_from_pitch_history() {
+        let mut model = DataModel::create().unwrap();
+        let engine_data = |frequency: f32| EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(frequency, 1.0),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        // G major melody G A B C D E F# G, 20 updates per second and half a second per note
+        let melody = [196.0, 220.0, 246.94, 261.63, 293.66, 329.63, 369.99, 392.0];
+        let mut timestamp = 0.0;
+        let mut play = |model: &mut DataModel| {
+            let mut update = None;
+            for frequency in melody.iter().chain(&[196.0, 293.66, 196.0]) {
+                for _ in 0..10 {
+                    timestamp += 0.05;
+                    update = Some(model.update(timestamp, engine_data(*frequency)));
+                }
+            }
+            update.unwrap()
+        };
+
+        let update = play(&mut model);
+        assert_eq!(update.key_detection, KeyDetection::Off);
+        assert_eq!(update.key_estimate, None);
+
+        let actions = PresentationLayerActions::builder().with_key_detection_change(KeyDetection::Suggest).build();
+        assert!(model.process_user_actions(actions).validation_errors.is_empty());
+        let update = play(&mut model);
+        let estimate = update.key_estimate.expect("5.5 seconds of melody should be enough evidence");
+        assert_eq!((estimate.tonic, estimate.scale), (7, Scale::Major));
+
+        let actions = PresentationLayerActions::builder().with_key_detection_change(KeyDetection::Suggest).build();
+        assert_eq!(model.process_user_actions(actions).validation_errors, vec![ValidationError::KeyDetectionAlreadySet(KeyDetection::Suggest)]);
+    }
+
+    /// Test cents offsets are measured from the stretched targets
+    #[wasm_bindgen_test]
+    fn test_stretch_tuning_shifts_targets() {
+        let mut model = DataModel::create().unwrap();
+        let stretch_tuning = StretchTuning::Railsback { treble_cents: 30.0, bass_cents: 20.0 };
+        // A piano A7 tuned on the curve: 3 octaves above A4, 30 * (3 / 3.25)² cents sharp
+        let stretched_a7 = 3520.0 * 2.0_f32.powf(crate::theory::stretch::railsback_offset_cents(3.0, 30.0, 20.0) / 1200.0);
+        let engine_data = || EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(stretched_a7, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+
+        let update = model.update(0.0, engine_data());
+        assert!(update.cents_offset > 20.0);
+
+        let actions = PresentationLayerActions::builder().with_stretch_tuning_change(stretch_tuning).build();
+        let result = model.process_user_actions(actions);
+        assert!(result.validation_errors.is_empty());
+
+        let update = model.update(0.0, engine_data());
+        assert_eq!(update.stretch_tuning, stretch_tuning);
+        assert_eq!(update.closest_midi_note, 105);
+        assert!(update.cents_offset.abs() < 0.1);
+
+        // Same curve again and out of range deviations are rejected
+        let actions = PresentationLayerActions::builder()
+            .with_stretch_tuning_change(stretch_tuning)
+            .with_stretch_tuning_change(StretchTuning::Railsback { treble_cents: -5.0, bass_cents: 20.0 })
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::StretchTuningAlreadySet(stretch_tuning),
+            ValidationError::InvalidStretchTuning(StretchTuning::Railsback { treble_cents: -5.0, bass_cents: 20.0 }),
+        ]);
+    }
+
+    /// Test the inharmonicity is only measured for the measured stretch curve, and starts over on a change
+    #[wasm_bindgen_test]
+    fn test_inharmonicity_measured_only_for_measured_stretch() {
+        let mut model = DataModel::create().unwrap();
+        // Partials of a 220 Hz string with an inharmonicity coefficient of 0.0008
+        let b = 0.0008;
+        let bin_width_hz = 5.0;
+        let mut magnitudes = vec![0.0; (8000.0 / bin_width_hz) as usize];
+        for n in 1..=12 {
+            let partial = n as f32 * 220.0 * (1.0 + b * (n * n) as f32).sqrt();
+            for (bin, magnitude) in magnitudes.iter_mut().enumerate() {
+                let distance = (bin as f32 * bin_width_hz - partial) / bin_width_hz;
+                *magnitude += (-distance * distance).exp() / n as f32;
+            }
+        }
+        let spectrum = crate::shared_types::Spectrum { magnitudes, bin_width_hz };
+        let engine_data = || EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: 0.3, rms_amplitude: 0.1 },
+                pitch: Pitch::Detected(220.0 * (1.0 + b).sqrt(), 0.9),
+                fft_data: Some(spectrum.clone()),
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+
+        assert_eq!(model.update(0.0, engine_data()).inharmonicity, None);
+
+        let actions = PresentationLayerActions::builder().with_stretch_tuning_change(StretchTuning::Measured).build();
+        model.process_user_actions(actions);
+        let measured = model.update(0.1, engine_data()).inharmonicity.expect("partials should be measured");
+        assert!((measured - b).abs() < b * 0.2, "measured {}", measured);
+
+        let actions = PresentationLayerActions::builder().with_stretch_tuning_change(StretchTuning::PIANO).build();
+        model.process_user_actions(actions);
+        assert_eq!(model.update(0.2, engine_data()).inharmonicity, None);
+    }
+
+    /// Test validation errors for invalid reference frequency adjustments
+    #[wasm_bindgen_test]
+    fn test_reference_frequency_validation_errors() {
+        let mut model = DataModel::create().unwrap();
+
+        let mut actions = PresentationLayerActions::new();
+        for reference_frequency in [440.0, 100.0, 1000.0, f32::NAN] {
+            actions.reference_frequency_adjustments.push(crate::presentation::AdjustReferenceFrequency {
+                reference_frequency,
+            });
+        }
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.actions.tuning_configurations.len(), 0);
+        assert_eq!(result.validation_errors.len(), 4);
+        assert_eq!(result.validation_errors[0], ValidationError::ReferenceFrequencyAlreadySet(440.0));
+        assert_eq!(result.validation_errors[1], ValidationError::ReferenceFrequencyOutOfRange(100.0));
+        assert_eq!(result.validation_errors[2], ValidationError::ReferenceFrequencyOutOfRange(1000.0));
+        assert!(matches!(result.validation_errors[3], ValidationError::ReferenceFrequencyOutOfRange(_)));
+        assert_eq!(model.reference_frequency, REFERENCE_FREQUENCY);
+    }
+
+    /// Test that equal divisions of the octave report steps, and unsupported divisions are rejected
+    #[wasm_bindgen_test]
+    fn test_equal_division_tuning() {
+        let mut model = DataModel::create().unwrap();
+
+        let actions = PresentationLayerActions::builder()
+            .with_tuning_change(TuningSystem::EqualDivision(31))
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors.len(), 0);
+        assert_eq!(model.tuning_system, TuningSystem::EqualDivision(31));
+
+        // A pure major third is 10 steps of 31-EDO, named after the nearest major third
+        let engine_data = EngineUpdateResult {
+            audio_analysis: Some(crate::shared_types::AudioAnalysis {
+                volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+                pitch: Pitch::Detected(model.get_root_pitch() * 5.0 / 4.0, 0.9),
+                fft_data: None,
+                polyphonic_pitches: Vec::new(),
+                timestamp: 0.0,
+            }),
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        let update = model.update(0.0, engine_data);
+        assert_eq!(update.interval_steps, 10);
+        assert_eq!(update.interval_semitones, 4);
+        assert!((update.cents_offset + 0.78).abs() < 0.01);
+
+        let actions = PresentationLayerActions::builder()
+            .with_tuning_change(TuningSystem::EqualDivision(1000))
+            .build();
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.validation_errors, vec![ValidationError::UnsupportedTuningSystem("1000-EDO".to_string())]);
+        assert_eq!(model.tuning_system, TuningSystem::EqualDivision(31));
+    }
+
+    /// Test that Scala imports switch to the imported tuning, and a keyboard mapping sets the root
+    #[wasm_bindgen_test]
+    fn test_scala_tuning_import() {
+        let mut model = DataModel::create().unwrap();
+
+        let actions = PresentationLayerActions::builder()
+            .with_scala_tuning_import(
+                include_str!("../theory/scala_files/edo_19.scl"),
+                Some(include_str!("../theory/scala_files/standard.kbm")),
+            )
+            .build();
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.validation_errors.len(), 0);
+        assert_eq!(result.actions.audio_system_configurations.len(), 1);
+        assert!(matches!(model.tuning_system, TuningSystem::Custom(_)));
+        assert_eq!(model.tuning_system.tuning().degrees_per_octave(), 19);
+        assert_eq!(result.actions.tuning_configurations.len(), 1);
+        assert_eq!(model.root_note, 60);
+        assert!((model.reference_frequency - 440.0).abs() < 0.01);
+
+        // 19-EDO degree 6 (378.9 cents) above middle C is labelled as the nearest E
+        let degree_frequency = model.tuning_system.tuning().interval_frequency(model.get_root_pitch(), 6);
+        let (midi_note, cents) = model.frequency_to_note_and_accuracy(degree_frequency);
+        assert_eq!(midi_note, 64);
+        assert!(cents.abs() < 0.01);
+    }
+
+    /// Test that malformed Scala files are rejected with typed errors
+    #[wasm_bindgen_test]
+    fn test_scala_tuning_import_errors() {
+        let mut model = DataModel::create().unwrap();
+
+        let actions = PresentationLayerActions::builder()
+            .with_scala_tuning_import(include_str!("../theory/scala_files/malformed_count.scl"), None)
+            .with_scala_tuning_import(
+                include_str!("../theory/scala_files/edo_19.scl"),
+                Some("12\n0\n127\n60\n69\n"),
+            )
+            .build();
+        let result = model.process_user_actions(actions);
+
+        assert_eq!(result.actions.audio_system_configurations.len(), 0);
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::InvalidScalaFile(ScalaError::NoteCountMismatch { expected: 5, found: 4 }),
+            ValidationError::InvalidScalaFile(ScalaError::MissingMappingField("reference frequency")),
+        ]);
+        assert_eq!(model.tuning_system, TuningSystem::EqualTemperament);
+    }
+
     /// Test edge case frequency handling
     #[wasm_bindgen_test]
     fn test_edge_case_frequency_handling() {
@@ -1368,6 +3074,8 @@ mod tests {
             audio_analysis: None,
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1406,6 +3114,123 @@ mod tests {
         assert_eq!(model.current_scale, Scale::Minor);
     }
 
+    /// Test polyphonic detection changes are validated and forwarded to the engine
+    #[wasm_bindgen_test]
+    fn test_polyphonic_detection_change_processing() {
+        let mut model = DataModel::create().unwrap();
+        
+        let mut actions = PresentationLayerActions::new();
+        actions.polyphonic_detection_changes.push(crate::presentation::ChangePolyphonicDetection { enabled: true });
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.actions.polyphonic_detection_configurations, vec![ConfigurePolyphonicDetectionAction { enabled: true }]);
+        assert!(result.validation_errors.is_empty());
+        assert!(model.polyphonic_detection);
+        
+        // Enabling again is rejected
+        let mut actions = PresentationLayerActions::new();
+        actions.polyphonic_detection_changes.push(crate::presentation::ChangePolyphonicDetection { enabled: true });
+        let result = model.process_user_actions(actions);
+        assert!(result.actions.polyphonic_detection_configurations.is_empty());
+        assert_eq!(result.validation_errors, vec![ValidationError::PolyphonicDetectionAlreadySet(true)]);
+    }
+
+    /// Test the spectrum is requested while the overlay or the measured stretch curve uses it
+    #[wasm_bindgen_test]
+    fn test_spectrum_analysis_follows_overlay_and_stretch() {
+        let mut model = DataModel::create().unwrap();
+        
+        let mut actions = PresentationLayerActions::new();
+        actions.spectrum_overlay_changes.push(crate::presentation::ChangeSpectrumOverlay { enabled: true });
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.actions.spectrum_analysis_configurations, vec![ConfigureSpectrumAnalysisAction { enabled: true }]);
+        assert!(result.validation_errors.is_empty());
+        
+        // The measured stretch curve keeps the spectrum on after the overlay is hidden
+        let mut actions = PresentationLayerActions::new();
+        actions.stretch_tuning_changes.push(crate::presentation::ChangeStretchTuning { stretch_tuning: StretchTuning::Measured });
+        actions.spectrum_overlay_changes.push(crate::presentation::ChangeSpectrumOverlay { enabled: false });
+        let result = model.process_user_actions(actions);
+        assert!(result.actions.spectrum_analysis_configurations.is_empty());
+        assert!(result.validation_errors.is_empty());
+        
+        let mut actions = PresentationLayerActions::new();
+        actions.stretch_tuning_changes.push(crate::presentation::ChangeStretchTuning { stretch_tuning: StretchTuning::None });
+        let result = model.process_user_actions(actions);
+        assert_eq!(result.actions.spectrum_analysis_configurations, vec![ConfigureSpectrumAnalysisAction { enabled: false }]);
+        
+        // Hiding a hidden overlay is rejected
+        let mut actions = PresentationLayerActions::new();
+        actions.spectrum_overlay_changes.push(crate::presentation::ChangeSpectrumOverlay { enabled: false });
+        let result = model.process_user_actions(actions);
+        assert!(result.actions.spectrum_analysis_configurations.is_empty());
+        assert_eq!(result.validation_errors, vec![ValidationError::SpectrumOverlayAlreadySet(false)]);
+    }
+
+    /// Test simultaneous pitches are placed relative to the root note
+    #[wasm_bindgen_test]
+    fn test_polyphonic_notes_relative_to_root() {
+        let mut model = DataModel::create().unwrap();
+        
+        // Root A3 = 220 Hz, a just fifth E4 and a slightly sharp C#4 above it
+        let audio_analysis = crate::shared_types::AudioAnalysis {
+            volume_level: crate::shared_types::Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
+            pitch: crate::shared_types::Pitch::Detected(220.0, 0.95),
+            fft_data: None,
+            polyphonic_pitches: vec![
+                crate::shared_types::PolyphonicPitch { frequency: 220.0, salience: 1.0 },
+                crate::shared_types::PolyphonicPitch { frequency: 277.18 * 2f32.powf(10.0 / 1200.0), salience: 0.8 },
+                crate::shared_types::PolyphonicPitch { frequency: 330.0, salience: 0.6 },
+            ],
+            timestamp: 1.0,
+        };
+        let engine_data = EngineUpdateResult {
+            audio_analysis: Some(audio_analysis),
+            audio_errors: Vec::new(),
+            permission_state: crate::shared_types::PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        };
+        
+        let result = model.update(1.0, engine_data);
+        let notes = &result.polyphonic_notes;
+        assert_eq!(notes.len(), 3);
+        assert_eq!(notes[0].closest_midi_note, 57);
+        assert!(notes[0].cents_from_root.abs() < 0.01);
+        assert_eq!(notes[1].closest_midi_note, 61);
+        assert!((notes[1].cents_offset - 10.0).abs() < 0.1);
+        assert!((notes[1].cents_from_root - 410.0).abs() < 0.1);
+        assert_eq!(notes[2].closest_midi_note, 64);
+        assert!((notes[2].cents_from_root - 701.96).abs() < 0.1);
+        assert!((notes[2].cents_offset - 1.96).abs() < 0.1);
+        assert_eq!(notes[2].salience, 0.6);
+    }
+
+    /// Test custom scale changes are validated and respected by note filtering
+    #[wasm_bindgen_test]
+    fn test_custom_scale_change_processing() {
+        let mut model = DataModel::create().unwrap();
+        
+        // Root, major third and fifth
+        let triad = Scale::Custom(0b0000_1001_0001);
+        let actions = PresentationLayerActions::builder()
+            .with_scale_change(Scale::Custom(0b0000_1001_0000)) // Missing root
+            .with_scale_change(Scale::Custom(0x1001)) // Bit above the octave
+            .with_scale_change(triad)
+            .build();
+        let result = model.process_user_actions(actions);
+        
+        assert_eq!(result.validation_errors, vec![
+            ValidationError::InvalidCustomScale(0b0000_1001_0000),
+            ValidationError::InvalidCustomScale(0x1001),
+        ]);
+        assert_eq!(model.current_scale, triad);
+        
+        // With A3 as root, D4 (a fourth above) snaps to the major third C#4
+        let (midi_note, cents) = model.frequency_to_note_and_accuracy(293.66);
+        assert_eq!(midi_note, 61);
+        assert!((cents - 100.0).abs() < 1.0);
+    }
+
     /// Test scale filtering in frequency_to_note_and_accuracy
     #[wasm_bindgen_test]
     fn test_scale_aware_note_filtering() {
@@ -1536,6 +3361,8 @@ mod tests {
             audio_analysis: None,
             audio_errors: Vec::new(),
             permission_state: crate::shared_types::PermissionState::NotRequested,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(1.0, engine_data);
@@ -1647,6 +3474,7 @@ mod tests {
             },
             pitch: crate::shared_types::Pitch::Detected(523.25, 0.88), // C5
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -1654,7 +3482,8 @@ mod tests {
             audio_analysis: Some(audio_analysis),
             audio_errors: vec![crate::shared_types::Error::ProcessingError("Test error".to_string())],
             permission_state: crate::shared_types::PermissionState::Granted,
-            root_note_audio_enabled: false,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         // Process engine data
@@ -1,12 +1,26 @@
+use std::collections::VecDeque;
 use three_d::{AmbientLight, Blend, Camera, ClearState, ColorMaterial, Context, Gm, Line, PhysicalPoint, RenderStates, RenderTarget, Srgba, Viewport, WriteMask};
 use crate::shared_types::{MidiNote, ColorScheme};
 use crate::theme::{get_current_color_scheme, rgb_to_srgba, rgb_to_srgba_with_alpha};
-use crate::app_config::{USER_PITCH_LINE_THICKNESS_MIN, USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_TRANSPARENCY_MIN, USER_PITCH_LINE_TRANSPARENCY_MAX, CLARITY_THRESHOLD};
+use crate::app_config::{
+    USER_PITCH_LINE_THICKNESS_MIN, USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_TRANSPARENCY_MIN,
+    USER_PITCH_LINE_TRANSPARENCY_MAX, CLARITY_THRESHOLD, PITCH_HISTORY_DURATION_SECONDS,
+    PITCH_HISTORY_MAX_GAP_SECONDS, SPECTRUM_OVERLAY_HEIGHT_RATIO, REGULAR_LINE_THICKNESS,
+    CENTS_GRID_LINE_THICKNESS, CENTS_GRID_ALPHA,
+};
 
 // Left margin to reserve space for note names
 const NOTE_NAME_X_OFFSET: f32 = 18.0;
 const NOTE_NAME_Y_OFFSET: f32 = 2.0;
 const NOTE_LINE_LEFT_MARGIN: f32 = 40.0;
+// Polyphonic pitch labels are placed at the right edge
+const POLYPHONIC_LABEL_RIGHT_MARGIN: f32 = 110.0;
+const POLYPHONIC_LABEL_Y_OFFSET: f32 = 4.0;
+// The matched just ratio is placed left of the polyphonic labels
+const PITCH_LABEL_RIGHT_MARGIN: f32 = 220.0;
+// Harmonic numbers are placed just above the spectrum overlay
+const HARMONIC_LABEL_X_OFFSET: f32 = 3.0;
+const HARMONIC_LABEL_Y_OFFSET: f32 = 4.0;
 
 pub fn interval_to_screen_y_position(interval: f32, viewport_height: f32) -> f32 {
     // interval of [0.5, 2.0] means [-1, +1] octaves
@@ -15,6 +29,51 @@ pub fn interval_to_screen_y_position(interval: f32, viewport_height: f32) -> f32
     y
 }
 
+/// Range of intervals from the root shown on the vertical axis, in octaves
+///
+/// The default view shows one octave below and above the root, like
+/// `interval_to_screen_y_position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchView {
+    /// Interval at the vertical center of the viewport
+    pub center: f32,
+    /// Interval from the center to the top and bottom of the view
+    pub half_range: f32,
+}
+
+impl Default for PitchView {
+    fn default() -> Self {
+        Self { center: 0.0, half_range: 1.0 }
+    }
+}
+
+impl PitchView {
+    pub fn new(center: f32, half_range: f32) -> Self {
+        Self { center, half_range }
+    }
+
+    /// Screen position of an interval in this view
+    pub fn y_position(&self, interval: f32, viewport_height: f32) -> f32 {
+        interval_to_screen_y_position((interval - self.center) / self.half_range, viewport_height)
+    }
+
+    /// Distance on screen of one octave in this view
+    pub fn pixels_per_octave(&self, viewport_height: f32) -> f32 {
+        self.y_position(self.center + 1.0, viewport_height) - self.y_position(self.center, viewport_height)
+    }
+
+    /// Lowest and highest interval in the view
+    pub fn interval_range(&self) -> (f32, f32) {
+        (self.center - self.half_range, self.center + self.half_range)
+    }
+
+    /// Move the center just enough to keep `interval` within `margin_ratio` of the half range
+    pub fn follow(&mut self, interval: f32, margin_ratio: f32) {
+        let margin = self.half_range * margin_ratio;
+        self.center = self.center.clamp(interval - margin, interval + margin);
+    }
+}
+
 /// Create a ColorMaterial with the given color and optional transparency
 fn create_color_material(color: Srgba, is_transparent: bool) -> ColorMaterial {
     ColorMaterial {
@@ -29,13 +88,118 @@ fn create_color_material(color: Srgba, is_transparent: bool) -> ColorMaterial {
     }
 }
 
+/// Thickness and alpha of a pitch line for the given clarity
+fn clarity_to_thickness_and_alpha(clarity: Option<f32>) -> (f32, f32) {
+    if let Some(clarity_value) = clarity {
+        // Map clarity from [CLARITY_THRESHOLD, 1.0] to [USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_THICKNESS_MIN]
+        let clamped_clarity = clarity_value.clamp(CLARITY_THRESHOLD, 1.0);
+        let normalized_clarity = (clamped_clarity - CLARITY_THRESHOLD) / (1.0 - CLARITY_THRESHOLD);
+        let thickness = USER_PITCH_LINE_THICKNESS_MAX + normalized_clarity * (USER_PITCH_LINE_THICKNESS_MIN - USER_PITCH_LINE_THICKNESS_MAX);
+        
+        // Map clarity to alpha using configured transparency range
+        // At CLARITY_THRESHOLD: alpha = USER_PITCH_LINE_TRANSPARENCY_MIN
+        // At 1.0 clarity: alpha = USER_PITCH_LINE_TRANSPARENCY_MAX
+        let alpha = USER_PITCH_LINE_TRANSPARENCY_MIN + normalized_clarity * (USER_PITCH_LINE_TRANSPARENCY_MAX - USER_PITCH_LINE_TRANSPARENCY_MIN);
+        (thickness, alpha)
+    } else {
+        (USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_TRANSPARENCY_MAX) // Default values when no clarity provided
+    }
+}
+
+/// A sample of the pitch history, `pitch` holds interval and clarity and is `None` during silence
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PitchHistorySample {
+    timestamp: f64,
+    pitch: Option<(f32, f32)>,
+}
+
+/// A straight piece of the pitch history trace between two consecutive detected samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchHistorySegment {
+    /// Seconds before the most recent sample at which the segment starts and ends
+    pub start_age: f32,
+    pub end_age: f32,
+    pub start_interval: f32,
+    pub end_interval: f32,
+    /// Clarity at the end of the segment
+    pub clarity: f32,
+}
+
+/// Rolling window of recent pitch samples for the scrolling pitch trace
+/// 
+/// Silence and gaps longer than `PITCH_HISTORY_MAX_GAP_SECONDS` are kept as
+/// breaks, consecutive samples are only connected when both have a pitch.
+pub struct PitchHistory {
+    samples: VecDeque<PitchHistorySample>,
+    duration_seconds: f64,
+}
+
+impl PitchHistory {
+    pub fn new(duration_seconds: f64) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            duration_seconds,
+        }
+    }
+
+    /// Add a sample and drop the samples that scrolled out of the window
+    /// 
+    /// # Arguments
+    /// 
+    /// * `timestamp` - Time of the sample in seconds
+    /// * `pitch` - Interval position and clarity, or `None` when no pitch was detected
+    pub fn push(&mut self, timestamp: f64, pitch: Option<(f32, f32)>) {
+        // Time going backwards means the timeline restarted
+        if self.samples.back().is_some_and(|last| timestamp < last.timestamp) {
+            self.samples.clear();
+        }
+        self.samples.push_back(PitchHistorySample { timestamp, pitch });
+        while self.samples.front().is_some_and(|first| timestamp - first.timestamp > self.duration_seconds) {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn duration_seconds(&self) -> f64 {
+        self.duration_seconds
+    }
+
+    /// Segments connecting consecutive detected samples, oldest first
+    pub fn segments(&self) -> Vec<PitchHistorySegment> {
+        let Some(now) = self.samples.back().map(|sample| sample.timestamp) else {
+            return Vec::new();
+        };
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .filter(|(start, end)| end.timestamp - start.timestamp <= PITCH_HISTORY_MAX_GAP_SECONDS)
+            .filter_map(|(start, end)| {
+                let (start_interval, _) = start.pitch?;
+                let (end_interval, clarity) = end.pitch?;
+                Some(PitchHistorySegment {
+                    start_age: (now - start.timestamp) as f32,
+                    end_age: (now - end.timestamp) as f32,
+                    start_interval,
+                    end_interval,
+                    clarity,
+                })
+            })
+            .collect()
+    }
+}
+
 pub struct TuningLines {
     lines: Vec<Gm<Line, ColorMaterial>>,
     midi_notes: Vec<MidiNote>,
     y_positions: Vec<f32>,
     thicknesses: Vec<f32>,
+    /// Note labels provided by the presenter, one per line
+    labels: Vec<String>,
+    /// Reused line pool for the cents grid, only the first `grid_line_count` are drawn
+    grid_lines: Vec<Gm<Line, ColorMaterial>>,
+    grid_line_count: usize,
     context: Context,
     material: ColorMaterial,
+    grid_material: ColorMaterial,
 }
 
 impl TuningLines {
@@ -47,11 +211,20 @@ impl TuningLines {
             midi_notes: Vec::new(),
             y_positions: Vec::new(),
             thicknesses: Vec::new(),
+            labels: Vec::new(),
+            grid_lines: Vec::new(),
+            grid_line_count: 0,
             context: context.clone(),
             material,
+            grid_material: Self::grid_material(color),
         }
     }
 
+    /// Faint version of the tuning line color for the cents grid
+    fn grid_material(color: Srgba) -> ColorMaterial {
+        create_color_material(Srgba::new(color.r, color.g, color.b, (CENTS_GRID_ALPHA * 255.0) as u8), true)
+    }
+
     /// Update the number of tuning lines, their positions, MIDI note numbers, and thickness
     /// The presenter calls this method with position, MIDI note, and thickness data for the active tuning system
     pub fn update_lines(&mut self, viewport: Viewport, line_data: &[(f32, MidiNote, f32)]) {
@@ -126,6 +299,27 @@ impl TuningLines {
     pub fn lines(&self) -> impl Iterator<Item = &Gm<Line, ColorMaterial>> {
         self.lines.iter()
     }
+
+    /// Place the cents grid lines, an empty `y_positions` hides the grid
+    pub fn update_cents_grid(&mut self, viewport: Viewport, y_positions: &[f32]) {
+        let width = viewport.width as f32;
+        for (index, &y) in y_positions.iter().enumerate() {
+            let start = PhysicalPoint { x: NOTE_LINE_LEFT_MARGIN, y };
+            let end = PhysicalPoint { x: width, y };
+            if let Some(line) = self.grid_lines.get_mut(index) {
+                line.geometry.set_endpoints(start, end);
+            } else {
+                let line = Line::new(&self.context, start, end, CENTS_GRID_LINE_THICKNESS);
+                self.grid_lines.push(Gm::new(line, self.grid_material.clone()));
+            }
+        }
+        self.grid_line_count = y_positions.len();
+    }
+
+    /// The cents grid lines that are drawn
+    pub fn grid_lines(&self) -> impl Iterator<Item = &Gm<Line, ColorMaterial>> {
+        self.grid_lines.iter().take(self.grid_line_count)
+    }
     
     /// Returns an iterator over the MIDI notes corresponding to each tuning line
     pub fn midi_notes(&self) -> impl Iterator<Item = MidiNote> + '_ {
@@ -137,8 +331,12 @@ impl TuningLines {
         for (i, &midi_note) in self.midi_notes.iter().enumerate() {
             let y_position = self.y_positions[i];
             
-            // Convert MIDI note to name
-            let note_name = crate::shared_types::midi_note_to_name(midi_note);
+            // Use the presenter's label, or the plain note name until labels are provided
+            let note_name = self.labels.get(i).cloned()
+                .unwrap_or_else(|| crate::shared_types::midi_note_to_name(midi_note));
+            if note_name.is_empty() {
+                continue;
+            }
             
             // Position text aligned with the line (same Y position)
             let text_y = y_position + NOTE_NAME_Y_OFFSET;
@@ -241,6 +439,28 @@ pub struct MainScene {
     current_scheme: ColorScheme,
     user_pitch_line_thickness: f32,
     user_pitch_line_alpha: f32,
+    /// Whether the user pitch line is drawn in the in-tune color
+    user_pitch_in_tune: bool,
+    /// Label text and y position of the user pitch line
+    user_pitch_label: Option<(String, f32)>,
+    pitch_history: PitchHistory,
+    /// Reused line pool for the pitch history trace, only the first `pitch_history_line_count` are drawn
+    pitch_history_lines: Vec<Gm<Line, ColorMaterial>>,
+    pitch_history_line_count: usize,
+    /// Reused line pool for simultaneous pitches, only the first `polyphonic_line_count` are drawn
+    polyphonic_lines: Vec<Gm<Line, ColorMaterial>>,
+    polyphonic_line_count: usize,
+    /// Label text, y position and alpha of each drawn polyphonic line
+    polyphonic_labels: Vec<(String, f32, f32)>,
+    /// Reused line pools for the spectrum overlay and its harmonic markers
+    spectrum_lines: Vec<Gm<Line, ColorMaterial>>,
+    spectrum_line_count: usize,
+    harmonic_marker_lines: Vec<Gm<Line, ColorMaterial>>,
+    /// Harmonic number and x position of each drawn harmonic marker
+    harmonic_marker_labels: Vec<(u32, f32)>,
+    spectrum_top: f32,
+    /// Visible interval range that pitches are positioned in
+    pitch_view: PitchView,
 }
 
 impl MainScene {
@@ -265,21 +485,39 @@ impl MainScene {
             current_scheme: scheme,
             user_pitch_line_thickness: initial_thickness,
             user_pitch_line_alpha: USER_PITCH_LINE_TRANSPARENCY_MAX,
+            user_pitch_in_tune: false,
+            user_pitch_label: None,
+            pitch_history: PitchHistory::new(PITCH_HISTORY_DURATION_SECONDS),
+            pitch_history_lines: Vec::new(),
+            pitch_history_line_count: 0,
+            polyphonic_lines: Vec::new(),
+            polyphonic_line_count: 0,
+            polyphonic_labels: Vec::new(),
+            spectrum_lines: Vec::new(),
+            spectrum_line_count: 0,
+            harmonic_marker_lines: Vec::new(),
+            harmonic_marker_labels: Vec::new(),
+            spectrum_top: 0.0,
+            pitch_view: PitchView::default(),
         })
     }
     
     pub fn update_viewport(&mut self, viewport: Viewport) {
         self.camera.set_viewport(viewport);
     }
+
+    /// Set the visible interval range for the pitch, history and polyphonic lines
+    ///
+    /// Tuning lines are positioned by the presenter, which uses the same view.
+    pub fn set_pitch_view(&mut self, pitch_view: PitchView) {
+        self.pitch_view = pitch_view;
+    }
     
     fn refresh_colors(&mut self) {
         let scheme = self.current_scheme.clone();
         
         // Recreate user pitch line with new color (it will be repositioned on next update)
-        let primary_material = create_color_material(
-            rgb_to_srgba_with_alpha(scheme.accent, self.user_pitch_line_alpha),
-            true
-        );
+        let primary_material = create_color_material(self.user_pitch_color(self.user_pitch_line_alpha), true);
         let line = Line::new(&self.context, 
             PhysicalPoint{x:NOTE_LINE_LEFT_MARGIN, y:0.0}, 
             PhysicalPoint{x:NOTE_LINE_LEFT_MARGIN, y:0.0}, 
@@ -288,6 +526,9 @@ impl MainScene {
         
         // Update tuning lines material
         self.tuning_lines.material = create_color_material(rgb_to_srgba(scheme.text), false);
+        self.tuning_lines.grid_material = TuningLines::grid_material(rgb_to_srgba(scheme.text));
+        self.tuning_lines.grid_lines.clear();
+        self.tuning_lines.grid_line_count = 0;
         
         // Clear and recreate all tuning lines with new material
         // They will be recreated with correct positions and thickness on next update_lines call
@@ -319,10 +560,19 @@ impl MainScene {
             renderable_lines.push(&self.user_pitch_line); // first in list is on top
         }
 
-        // Add all tuning lines
+        // Add the simultaneous pitches and the pitch history trace below the current pitch
+        renderable_lines.extend(self.polyphonic_lines.iter().take(self.polyphonic_line_count));
+        renderable_lines.extend(self.pitch_history_lines.iter().take(self.pitch_history_line_count));
+
+        // Add all tuning lines, with the cents grid below them
         for line in self.tuning_lines.lines() {
             renderable_lines.push(line);
         }
+        renderable_lines.extend(self.tuning_lines.grid_lines());
+
+        // Add the spectrum overlay below everything else
+        renderable_lines.extend(self.spectrum_lines.iter().take(self.spectrum_line_count));
+        renderable_lines.extend(self.harmonic_marker_lines.iter().take(self.harmonic_marker_labels.len()));
         
         // Render lines
         screen.render(
@@ -336,6 +586,26 @@ impl MainScene {
         
         // Render note labels above tuning lines
         self.tuning_lines.render_note_labels(&mut self.text_renderer);
+
+        // Render the labels of the simultaneous pitches at the right edge
+        let label_x = self.camera.viewport().width as f32 - POLYPHONIC_LABEL_RIGHT_MARGIN;
+        let label_color = scheme.primary;
+        for (label, y, alpha) in &self.polyphonic_labels {
+            self.text_renderer.queue_text(label, label_x, y + POLYPHONIC_LABEL_Y_OFFSET, 14.0, [label_color[0], label_color[1], label_color[2], *alpha]);
+        }
+
+        // Render the user pitch label next to the user pitch line
+        if let (true, Some((label, y))) = (self.pitch_detected, &self.user_pitch_label) {
+            let accent = scheme.accent;
+            let x = self.camera.viewport().width as f32 - PITCH_LABEL_RIGHT_MARGIN;
+            self.text_renderer.queue_text(label, x, y + POLYPHONIC_LABEL_Y_OFFSET, 14.0, [accent[0], accent[1], accent[2], 1.0]);
+        }
+
+        // Render the harmonic numbers above the spectrum overlay
+        let muted = scheme.muted;
+        for (harmonic, x) in &self.harmonic_marker_labels {
+            self.text_renderer.queue_text(&harmonic.to_string(), x + HARMONIC_LABEL_X_OFFSET, self.spectrum_top + HARMONIC_LABEL_Y_OFFSET, 12.0, [muted[0], muted[1], muted[2], 1.0]);
+        }
         
         // Render text models using actual Roboto font  
         let viewport = self.camera.viewport();
@@ -352,34 +622,18 @@ impl MainScene {
     pub fn update_pitch_position(&mut self, viewport: Viewport, interval: f32, pitch_detected: bool, clarity: Option<f32>) {
         self.pitch_detected = pitch_detected;
         if pitch_detected {
-            let y = interval_to_screen_y_position(interval, viewport.height as f32);
+            let y = self.pitch_view.y_position(interval, viewport.height as f32);
             let endpoints = (PhysicalPoint{x:NOTE_LINE_LEFT_MARGIN, y}, PhysicalPoint{x:viewport.width as f32, y});
             
             // Calculate thickness and alpha based on clarity
-            let (new_thickness, new_alpha) = if let Some(clarity_value) = clarity {
-                // Map clarity from [CLARITY_THRESHOLD, 1.0] to [USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_THICKNESS_MIN]
-                let clamped_clarity = clarity_value.clamp(CLARITY_THRESHOLD, 1.0);
-                let normalized_clarity = (clamped_clarity - CLARITY_THRESHOLD) / (1.0 - CLARITY_THRESHOLD);
-                let thickness = USER_PITCH_LINE_THICKNESS_MAX + normalized_clarity * (USER_PITCH_LINE_THICKNESS_MIN - USER_PITCH_LINE_THICKNESS_MAX);
-                
-                // Map clarity to alpha using configured transparency range
-                // At CLARITY_THRESHOLD: alpha = USER_PITCH_LINE_TRANSPARENCY_MIN
-                // At 1.0 clarity: alpha = USER_PITCH_LINE_TRANSPARENCY_MAX
-                let alpha = USER_PITCH_LINE_TRANSPARENCY_MIN + normalized_clarity * (USER_PITCH_LINE_TRANSPARENCY_MAX - USER_PITCH_LINE_TRANSPARENCY_MIN);
-                (thickness, alpha)
-            } else {
-                (USER_PITCH_LINE_THICKNESS_MAX, USER_PITCH_LINE_TRANSPARENCY_MAX) // Default values when no clarity provided
-            };
+            let (new_thickness, new_alpha) = clarity_to_thickness_and_alpha(clarity);
             
             // Check if thickness or alpha changed - if so, recreate the line
             let thickness_changed = (new_thickness - self.user_pitch_line_thickness).abs() > f32::EPSILON;
             let alpha_changed = (new_alpha - self.user_pitch_line_alpha).abs() > f32::EPSILON;
             
             if thickness_changed || alpha_changed {
-                let primary_material = create_color_material(
-                    rgb_to_srgba_with_alpha(self.current_scheme.accent, new_alpha),
-                    true
-                );
+                let primary_material = create_color_material(self.user_pitch_color(new_alpha), true);
                 let line = Line::new(&self.context, endpoints.0, endpoints.1, new_thickness);
                 self.user_pitch_line = Gm::new(line, primary_material);
                 self.user_pitch_line_thickness = new_thickness;
@@ -391,6 +645,166 @@ impl MainScene {
         }
     }
     
+    /// Draw the user pitch line in the in-tune color while the pitch is within tolerance
+    pub fn set_pitch_in_tune(&mut self, in_tune: bool) {
+        if in_tune != self.user_pitch_in_tune {
+            self.user_pitch_in_tune = in_tune;
+            self.user_pitch_line.material.color = self.user_pitch_color(self.user_pitch_line_alpha);
+        }
+    }
+
+    /// Place the cents grid around the tuning lines, see `TuningLines::update_cents_grid`
+    pub fn update_cents_grid(&mut self, viewport: Viewport, y_positions: &[f32]) {
+        self.tuning_lines.update_cents_grid(viewport, y_positions);
+    }
+
+    fn user_pitch_color(&self, alpha: f32) -> Srgba {
+        let color = if self.user_pitch_in_tune { self.current_scheme.in_tune } else { self.current_scheme.accent };
+        rgb_to_srgba_with_alpha(color, alpha)
+    }
+
+    /// Label the user pitch line, e.g. with the matched just ratio
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Interval position as passed to `update_pitch_position`
+    /// * `label` - Text to draw, `None` hides the label
+    pub fn update_pitch_label(&mut self, viewport: Viewport, interval: f32, label: Option<String>) {
+        let y = self.pitch_view.y_position(interval, viewport.height as f32);
+        self.user_pitch_label = label.map(|label| (label, y));
+    }
+
+    /// Add a sample to the pitch history trace
+    /// 
+    /// # Arguments
+    /// 
+    /// * `timestamp` - Time of the sample in seconds
+    /// * `interval` - Interval position as passed to `update_pitch_position`, ignored when no pitch is detected
+    /// * `pitch_detected` - Whether a pitch was detected, silence is drawn as a break
+    /// * `clarity` - Clarity of the detected pitch
+    pub fn push_pitch_history(&mut self, timestamp: f64, interval: f32, pitch_detected: bool, clarity: Option<f32>) {
+        let pitch = pitch_detected.then(|| (interval, clarity.unwrap_or(1.0)));
+        self.pitch_history.push(timestamp, pitch);
+    }
+
+    /// Position the pitch history trace, scrolling from the right edge (now) to the note line margin
+    pub fn update_pitch_history(&mut self, viewport: Viewport) {
+        let width = viewport.width as f32;
+        let height = viewport.height as f32;
+        let pixels_per_second = (width - NOTE_LINE_LEFT_MARGIN) / self.pitch_history.duration_seconds() as f32;
+        let segments = self.pitch_history.segments();
+
+        for (index, segment) in segments.iter().enumerate() {
+            let start = PhysicalPoint {
+                x: width - segment.start_age * pixels_per_second,
+                y: self.pitch_view.y_position(segment.start_interval, height),
+            };
+            let end = PhysicalPoint {
+                x: width - segment.end_age * pixels_per_second,
+                y: self.pitch_view.y_position(segment.end_interval, height),
+            };
+            let (thickness, alpha) = clarity_to_thickness_and_alpha(Some(segment.clarity));
+            let color = rgb_to_srgba_with_alpha(self.current_scheme.accent, alpha);
+
+            if let Some(line) = self.pitch_history_lines.get_mut(index) {
+                line.geometry.set_endpoints(start, end);
+                line.geometry.set_thickness(thickness);
+                line.material.color = color;
+            } else {
+                let line = Line::new(&self.context, start, end, thickness);
+                self.pitch_history_lines.push(Gm::new(line, create_color_material(color, true)));
+            }
+        }
+        self.pitch_history_line_count = segments.len();
+    }
+
+    /// Position one line per simultaneously detected pitch
+    ///
+    /// MainScene doesn't know about music theory - the presenter provides the labels
+    ///
+    /// # Arguments
+    ///
+    /// * `pitches` - Interval position, salience (0.0 - 1.0) and label of each pitch
+    pub fn update_polyphonic_pitches(&mut self, viewport: Viewport, pitches: &[(f32, f32, String)]) {
+        let width = viewport.width as f32;
+        let height = viewport.height as f32;
+        self.polyphonic_labels.clear();
+
+        for (index, (interval, salience, label)) in pitches.iter().enumerate() {
+            let y = self.pitch_view.y_position(*interval, height);
+            let start = PhysicalPoint { x: NOTE_LINE_LEFT_MARGIN, y };
+            let end = PhysicalPoint { x: width, y };
+            let alpha = 0.3 + 0.6 * salience.clamp(0.0, 1.0);
+            let color = rgb_to_srgba_with_alpha(self.current_scheme.primary, alpha);
+
+            if let Some(line) = self.polyphonic_lines.get_mut(index) {
+                line.geometry.set_endpoints(start, end);
+                line.material.color = color;
+            } else {
+                let line = Line::new(&self.context, start, end, USER_PITCH_LINE_THICKNESS_MIN);
+                self.polyphonic_lines.push(Gm::new(line, create_color_material(color, true)));
+            }
+            self.polyphonic_labels.push((label.clone(), y, alpha));
+        }
+        self.polyphonic_line_count = pitches.len();
+    }
+
+    /// Draw the spectrum overlay along the bottom of the viewport
+    ///
+    /// The presenter maps frequencies to positions; an empty `levels` hides the overlay.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - Level (0.0 - 1.0) of evenly spaced columns from the left margin to the right edge
+    /// * `harmonic_markers` - Harmonic number and horizontal position (0.0 - 1.0) of each marker
+    pub fn update_spectrum(&mut self, viewport: Viewport, levels: &[f32], harmonic_markers: &[(u32, f32)]) {
+        let width = viewport.width as f32 - NOTE_LINE_LEFT_MARGIN;
+        let overlay_height = viewport.height as f32 * SPECTRUM_OVERLAY_HEIGHT_RATIO;
+        self.spectrum_top = overlay_height;
+        self.harmonic_marker_labels.clear();
+        if levels.is_empty() {
+            self.spectrum_line_count = 0;
+            return;
+        }
+
+        let column_x = |column: usize| NOTE_LINE_LEFT_MARGIN + width * column as f32 / (levels.len() - 1).max(1) as f32;
+        let points: Vec<PhysicalPoint> = levels
+            .iter()
+            .enumerate()
+            .map(|(column, level)| PhysicalPoint { x: column_x(column), y: level * overlay_height })
+            .collect();
+        let spectrum_color = rgb_to_srgba_with_alpha(self.current_scheme.secondary, 0.8);
+        for (index, segment) in points.windows(2).enumerate() {
+            if let Some(line) = self.spectrum_lines.get_mut(index) {
+                line.geometry.set_endpoints(segment[0], segment[1]);
+                line.material.color = spectrum_color;
+            } else {
+                let line = Line::new(&self.context, segment[0], segment[1], REGULAR_LINE_THICKNESS * 2.0);
+                self.spectrum_lines.push(Gm::new(line, create_color_material(spectrum_color, true)));
+            }
+        }
+        self.spectrum_line_count = points.len() - 1;
+
+        let marker_color = rgb_to_srgba_with_alpha(self.current_scheme.accent, 0.5);
+        for (index, &(harmonic, position)) in harmonic_markers.iter().enumerate() {
+            let x = NOTE_LINE_LEFT_MARGIN + width * position;
+            let (bottom, top) = (PhysicalPoint { x, y: 0.0 }, PhysicalPoint { x, y: overlay_height });
+            if let Some(line) = self.harmonic_marker_lines.get_mut(index) {
+                line.geometry.set_endpoints(bottom, top);
+                line.material.color = marker_color;
+            } else {
+                let line = Line::new(&self.context, bottom, top, REGULAR_LINE_THICKNESS);
+                self.harmonic_marker_lines.push(Gm::new(line, create_color_material(marker_color, true)));
+            }
+            self.harmonic_marker_labels.push((harmonic, x));
+        }
+    }
+
+    /// Set the labels of the tuning lines, in the order of the line data passed to `update_tuning_lines`
+    pub fn update_tuning_line_labels(&mut self, labels: Vec<String>) {
+        self.tuning_lines.labels = labels;
+    }
+
     /// Update tuning lines with position, MIDI note, and thickness data provided by the presenter
     /// MainScene doesn't know about music theory - it just positions lines where told
     pub fn update_tuning_lines(&mut self, viewport: Viewport, line_data: &[(f32, MidiNote, f32)]) {
@@ -666,6 +1080,161 @@ mod tests {
         assert_eq!(scene.tuning_lines.lines().count(), 2, "Tuning lines should persist through state changes");
     }
 
+    #[wasm_bindgen_test]
+    fn test_pitch_history_breaks_on_silence() {
+        let mut history = PitchHistory::new(5.0);
+        history.push(0.0, Some((0.0, 0.9)));
+        history.push(0.1, Some((0.1, 0.8)));
+        history.push(0.2, None);
+        history.push(0.3, Some((0.2, 0.9)));
+        history.push(0.4, Some((0.3, 0.95)));
+
+        let segments = history.segments();
+        assert_eq!(segments.len(), 2, "Silence should not be bridged");
+        assert!((segments[0].start_age - 0.4).abs() < 1e-6);
+        assert!((segments[0].end_age - 0.3).abs() < 1e-6);
+        assert_eq!(segments[0].end_interval, 0.1);
+        assert_eq!(segments[0].clarity, 0.8);
+        assert!(segments[1].end_age.abs() < 1e-6);
+        assert_eq!(segments[1].clarity, 0.95);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_pitch_history_breaks_on_long_gaps_and_scrolls() {
+        let mut history = PitchHistory::new(1.0);
+        history.push(0.0, Some((0.0, 0.9)));
+        history.push(0.1, Some((0.0, 0.9)));
+        history.push(0.1 + PITCH_HISTORY_MAX_GAP_SECONDS + 0.1, Some((0.0, 0.9)));
+        assert_eq!(history.segments().len(), 1, "Long gaps should not be bridged");
+
+        // Samples older than the window scroll out
+        history.push(2.0, Some((0.0, 0.9)));
+        assert!(history.segments().is_empty());
+        history.push(2.1, Some((0.0, 0.9)));
+        assert_eq!(history.segments().len(), 1);
+
+        // A timestamp going backwards restarts the history
+        history.push(0.5, Some((0.0, 0.9)));
+        assert!(history.segments().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_update_pitch_history_reuses_lines() {
+        let context = create_test_context();
+        let viewport = create_test_viewport();
+        
+        let mut scene = MainScene::new(&context, viewport).unwrap();
+        for i in 0..4 {
+            scene.push_pitch_history(i as f64 * 0.1, 0.5, true, Some(0.9));
+        }
+        scene.update_pitch_history(viewport);
+        assert_eq!(scene.pitch_history_line_count, 3);
+
+        scene.push_pitch_history(0.4, 0.0, false, None);
+        scene.update_pitch_history(viewport);
+        assert_eq!(scene.pitch_history_line_count, 3, "Silence adds no segment");
+        assert_eq!(scene.pitch_history_lines.len(), 3);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_update_polyphonic_pitches_reuses_lines() {
+        let context = create_test_context();
+        let viewport = create_test_viewport();
+
+        let mut scene = MainScene::new(&context, viewport).unwrap();
+        let chord = vec![
+            (0.0, 1.0, "A3 +0¢".to_string()),
+            (4.0 / 12.0, 0.6, "C#4 +386¢".to_string()),
+            (7.0 / 12.0, 0.4, "E4 +702¢".to_string()),
+        ];
+        scene.update_polyphonic_pitches(viewport, &chord);
+        assert_eq!(scene.polyphonic_line_count, 3);
+        assert_eq!(scene.polyphonic_labels[2].0, "E4 +702¢");
+        assert!(scene.polyphonic_labels[0].2 > scene.polyphonic_labels[2].2, "More salient pitches are more opaque");
+
+        scene.update_polyphonic_pitches(viewport, &chord[..1]);
+        assert_eq!(scene.polyphonic_line_count, 1);
+        assert_eq!(scene.polyphonic_labels.len(), 1);
+        assert_eq!(scene.polyphonic_lines.len(), 3);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_update_pitch_label() {
+        let context = create_test_context();
+        let viewport = create_test_viewport();
+
+        let mut scene = MainScene::new(&context, viewport).unwrap();
+        scene.update_pitch_label(viewport, 10.0 / 12.0, Some("7/4, +3¢".to_string()));
+        let (label, y) = scene.user_pitch_label.clone().unwrap();
+        assert_eq!(label, "7/4, +3¢");
+        assert_eq!(y, interval_to_screen_y_position(10.0 / 12.0, viewport.height as f32));
+
+        scene.update_pitch_label(viewport, 0.0, None);
+        assert!(scene.user_pitch_label.is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_pitch_in_tune_color() {
+        let context = create_test_context();
+        let viewport = create_test_viewport();
+
+        let mut scene = MainScene::new(&context, viewport).unwrap();
+        let scheme = scene.current_scheme.clone();
+        scene.update_pitch_position(viewport, 0.0, true, Some(0.9));
+        scene.set_pitch_in_tune(true);
+        assert_eq!(scene.user_pitch_line.material.color, rgb_to_srgba_with_alpha(scheme.in_tune, scene.user_pitch_line_alpha));
+
+        // The color is kept when the line is recreated for another clarity
+        scene.update_pitch_position(viewport, 0.0, true, Some(0.5));
+        assert_eq!(scene.user_pitch_line.material.color, rgb_to_srgba_with_alpha(scheme.in_tune, scene.user_pitch_line_alpha));
+
+        scene.set_pitch_in_tune(false);
+        assert_eq!(scene.user_pitch_line.material.color, rgb_to_srgba_with_alpha(scheme.accent, scene.user_pitch_line_alpha));
+
+        scene.update_cents_grid(viewport, &[290.0, 310.0]);
+        assert_eq!(scene.tuning_lines.grid_lines().count(), 2);
+        scene.update_cents_grid(viewport, &[]);
+        assert_eq!(scene.tuning_lines.grid_lines().count(), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_pitch_view() {
+        let height = 600.0;
+        let view = PitchView::default();
+        assert_eq!(view.y_position(0.5, height), interval_to_screen_y_position(0.5, height));
+
+        // A fifth above the root fills the upper half of a ±1 semitone view centered a fifth up
+        let fifth = 7.0 / 12.0;
+        let mut view = PitchView::new(fifth, 1.0 / 12.0);
+        assert_eq!(view.y_position(fifth, height), 300.0);
+        assert!((view.y_position(fifth + 1.0 / 12.0, height) - interval_to_screen_y_position(1.0, height)).abs() < 1e-3);
+        assert_eq!(view.interval_range(), (fifth - 1.0 / 12.0, fifth + 1.0 / 12.0));
+
+        // Following leaves the view alone until the pitch leaves the margin, then keeps it at the margin
+        view.follow(fifth + 0.5 / 12.0, 0.7);
+        assert_eq!(view.center, fifth);
+        view.follow(1.0, 0.7);
+        assert!((view.center - (1.0 - 0.7 / 12.0)).abs() < 1e-6);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_update_spectrum_reuses_lines() {
+        let context = create_test_context();
+        let viewport = create_test_viewport();
+
+        let mut scene = MainScene::new(&context, viewport).unwrap();
+        scene.update_spectrum(viewport, &[0.0, 0.5, 1.0, 0.5], &[(1, 0.2), (2, 0.4)]);
+        assert_eq!(scene.spectrum_line_count, 3);
+        assert_eq!(scene.harmonic_marker_labels.len(), 2);
+        assert_eq!(scene.harmonic_marker_labels[1].0, 2);
+
+        // Hiding the overlay keeps the lines for reuse
+        scene.update_spectrum(viewport, &[], &[]);
+        assert_eq!(scene.spectrum_line_count, 0);
+        assert!(scene.harmonic_marker_labels.is_empty());
+        assert_eq!(scene.spectrum_lines.len(), 3);
+    }
+
     #[wasm_bindgen_test]
     fn test_thickness_calculation_high_clarity() {
         let context = create_test_context();
@@ -60,7 +60,7 @@
 
 
 mod main_scene;
-pub use main_scene::{MainScene, TuningLines};
+pub use main_scene::{MainScene, PitchView, TuningLines};
 
 mod startup_scene;
 pub use startup_scene::StartupScene;
@@ -68,10 +68,16 @@ pub use startup_scene::StartupScene;
 mod smoothing;
 pub use smoothing::EmaSmoother;
 
+mod session_recorder;
+pub use session_recorder::{SessionRecorder, RecordedFrame, MAX_RECORDED_FRAMES};
+
 use std::rc::Rc;
 use std::cell::RefCell;
 use three_d::{RenderTarget, Context, Viewport};
-use crate::shared_types::{ModelUpdateResult, TuningSystem, Scale, MidiNote, Pitch, PermissionState};
+use crate::shared_types::{ModelUpdateResult, TuningSystem, Scale, MidiNote, Pitch, PermissionState, NoteNamingSystem, StretchTuning, KeyDetection, Transposition, IntonationState, DronePreset, DroneVoiceTuning, DroneTimbre, DroneVoice, DroneEnvelope, MetronomeConfig, IntonationPitch, RecordingAnalysisStatus};
+use crate::theory::stretch::StretchCurve;
+#[cfg(debug_assertions)]
+use crate::shared_types::{PitchDetectionAlgorithm, TestSignalShape};
 
 #[cfg(target_arch = "wasm32")]
 use crate::web::main_scene_ui::{setup_main_scene_ui, cleanup_main_scene_ui, setup_event_listeners};
@@ -125,6 +131,39 @@ impl AdjustRootNote {
     }
 }
 
+/// Request to change the A4 reference frequency
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjustReferenceFrequency {
+    pub reference_frequency: f32,
+}
+
+#[cfg(test)]
+impl AdjustReferenceFrequency {
+    pub fn new(reference_frequency: f32) -> Self {
+        Self { reference_frequency }
+    }
+}
+
+/// Request to import a user-defined tuning from Scala files
+/// 
+/// Contains the text of a `.scl` scale file and optionally of a `.kbm`
+/// keyboard mapping file. Parsing happens in the model layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportScalaTuning {
+    pub scale_file: String,
+    pub keyboard_mapping_file: Option<String>,
+}
+
+#[cfg(test)]
+impl ImportScalaTuning {
+    pub fn new(scale_file: &str, keyboard_mapping_file: Option<&str>) -> Self {
+        Self {
+            scale_file: scale_file.to_string(),
+            keyboard_mapping_file: keyboard_mapping_file.map(str::to_string),
+        }
+    }
+}
+
 /// Action for changing the active scale
 /// 
 /// This action represents a user request to change the musical scale used
@@ -141,6 +180,151 @@ impl ScaleChangeAction {
     }
 }
 
+/// Request to enable or disable detection of several simultaneous pitches
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangePolyphonicDetection {
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+impl ChangePolyphonicDetection {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+/// Request to show or hide the spectrum overlay
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeSpectrumOverlay {
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+impl ChangeSpectrumOverlay {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+/// Request to change the octave stretch applied to the tuning system
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeStretchTuning {
+    pub stretch_tuning: StretchTuning,
+}
+
+#[cfg(test)]
+impl ChangeStretchTuning {
+    pub fn new(stretch_tuning: StretchTuning) -> Self {
+        Self { stretch_tuning }
+    }
+}
+
+/// Request to change the key detection mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeKeyDetection {
+    pub key_detection: KeyDetection,
+}
+
+#[cfg(test)]
+impl ChangeKeyDetection {
+    pub fn new(key_detection: KeyDetection) -> Self {
+        Self { key_detection }
+    }
+}
+
+/// Request to display note names for a transposing instrument
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeTransposition {
+    pub transposition: Transposition,
+}
+
+#[cfg(test)]
+impl ChangeTransposition {
+    pub fn new(transposition: Transposition) -> Self {
+        Self { transposition }
+    }
+}
+
+/// Request to change how far from the closest note a pitch still counts as in tune
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeInTuneTolerance {
+    pub tolerance_cents: f32,
+}
+
+#[cfg(test)]
+impl ChangeInTuneTolerance {
+    pub fn new(tolerance_cents: f32) -> Self {
+        Self { tolerance_cents }
+    }
+}
+
+/// Request to play a scale degree of the active tuning once
+/// 
+/// The degree counts steps of the tuning system from the root, so the model can
+/// resolve it to the exact target frequency, including any octave stretch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayTargetNote {
+    pub degree: i32,
+    pub volume: f32,
+    pub duration_seconds: f32,
+    pub timbre: DroneTimbre,
+}
+
+#[cfg(test)]
+impl PlayTargetNote {
+    pub fn new(degree: i32, volume: f32, duration_seconds: f32) -> Self {
+        Self { degree, volume, duration_seconds, timbre: DroneTimbre::Sine }
+    }
+}
+
+/// Request to start, reconfigure or stop the metronome
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigureMetronome {
+    pub config: MetronomeConfig,
+}
+
+#[cfg(test)]
+impl ConfigureMetronome {
+    pub fn new(config: MetronomeConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// Request to change which pitch the intonation is judged from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeIntonationPitch {
+    pub intonation_pitch: IntonationPitch,
+}
+
+#[cfg(test)]
+impl ChangeIntonationPitch {
+    pub fn new(intonation_pitch: IntonationPitch) -> Self {
+        Self { intonation_pitch }
+    }
+}
+
+/// Request to analyze a recorded WAV file into the session recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeRecording {
+    pub wav_file: Vec<u8>,
+}
+
+#[cfg(test)]
+impl AnalyzeRecording {
+    pub fn new(wav_file: &[u8]) -> Self {
+        Self { wav_file: wav_file.to_vec() }
+    }
+}
+
+/// Root note and scale changes that would move to the detected key
+/// 
+/// `None` fields already match the detected key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeySuggestion {
+    pub root_note: Option<MidiNote>,
+    pub scale: Option<Scale>,
+}
+
 // Debug action structs (only available in debug builds)
 #[cfg(debug_assertions)]
 #[derive(Debug, Clone, PartialEq)]
@@ -148,12 +332,28 @@ pub struct ConfigureTestSignal {
     pub enabled: bool,
     pub frequency: f32,
     pub volume: f32,
+    pub shape: TestSignalShape,
 }
 
 #[cfg(all(debug_assertions, test))]
 impl ConfigureTestSignal {
-    pub fn new(enabled: bool, frequency: f32, volume: f32) -> Self {
-        Self { enabled, frequency, volume }
+    pub fn new(enabled: bool, frequency: f32, volume: f32, shape: TestSignalShape) -> Self {
+        Self { enabled, frequency, volume, shape }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigurePitchDetection {
+    pub algorithm: PitchDetectionAlgorithm,
+    /// Run every algorithm on the same input and collect per-algorithm metrics
+    pub compare_algorithms: bool,
+}
+
+#[cfg(all(debug_assertions, test))]
+impl ConfigurePitchDetection {
+    pub fn new(algorithm: PitchDetectionAlgorithm, compare_algorithms: bool) -> Self {
+        Self { algorithm, compare_algorithms }
     }
 }
 
@@ -161,12 +361,22 @@ impl ConfigureTestSignal {
 pub struct ConfigureRootNoteAudio {
     pub frequency: f32,
     pub volume: f32,
+    /// Voices of the drone, the model resolves their intervals to frequency ratios
+    pub voices: Vec<DroneVoice>,
+    pub timbre: DroneTimbre,
+    pub envelope: DroneEnvelope,
 }
 
 #[cfg(all(debug_assertions, test))]
 impl ConfigureRootNoteAudio {
     pub fn new(frequency: f32, volume: f32) -> Self {
-        Self { frequency, volume }
+        Self {
+            frequency,
+            volume,
+            voices: DronePreset::Root.voices(DroneVoiceTuning::Just),
+            timbre: DroneTimbre::Sine,
+            envelope: DroneEnvelope::SUSTAINED,
+        }
     }
 }
 
@@ -179,8 +389,20 @@ impl ConfigureRootNoteAudio {
 pub struct PresentationLayerActions {
     pub tuning_system_changes: Vec<ChangeTuningSystem>,
     pub root_note_adjustments: Vec<AdjustRootNote>,
+    pub reference_frequency_adjustments: Vec<AdjustReferenceFrequency>,
+    pub scala_tuning_imports: Vec<ImportScalaTuning>,
     pub scale_changes: Vec<ScaleChangeAction>,
     pub root_note_audio_configurations: Vec<ConfigureRootNoteAudio>,
+    pub polyphonic_detection_changes: Vec<ChangePolyphonicDetection>,
+    pub spectrum_overlay_changes: Vec<ChangeSpectrumOverlay>,
+    pub stretch_tuning_changes: Vec<ChangeStretchTuning>,
+    pub key_detection_changes: Vec<ChangeKeyDetection>,
+    pub transposition_changes: Vec<ChangeTransposition>,
+    pub in_tune_tolerance_changes: Vec<ChangeInTuneTolerance>,
+    pub target_note_playbacks: Vec<PlayTargetNote>,
+    pub metronome_configurations: Vec<ConfigureMetronome>,
+    pub intonation_pitch_changes: Vec<ChangeIntonationPitch>,
+    pub recording_analyses: Vec<AnalyzeRecording>,
 }
 
 impl PresentationLayerActions {
@@ -189,8 +411,20 @@ impl PresentationLayerActions {
         Self {
             tuning_system_changes: Vec::new(),
             root_note_adjustments: Vec::new(),
+            reference_frequency_adjustments: Vec::new(),
+            scala_tuning_imports: Vec::new(),
             scale_changes: Vec::new(),
             root_note_audio_configurations: Vec::new(),
+            polyphonic_detection_changes: Vec::new(),
+            spectrum_overlay_changes: Vec::new(),
+            stretch_tuning_changes: Vec::new(),
+            key_detection_changes: Vec::new(),
+            transposition_changes: Vec::new(),
+            in_tune_tolerance_changes: Vec::new(),
+            target_note_playbacks: Vec::new(),
+            metronome_configurations: Vec::new(),
+            intonation_pitch_changes: Vec::new(),
+            recording_analyses: Vec::new(),
         }
     }
 }
@@ -207,8 +441,20 @@ impl PresentationLayerActions {
 pub struct PresentationLayerActionsBuilder {
     tuning_system_changes: Vec<ChangeTuningSystem>,
     root_note_adjustments: Vec<AdjustRootNote>,
+    reference_frequency_adjustments: Vec<AdjustReferenceFrequency>,
+    scala_tuning_imports: Vec<ImportScalaTuning>,
     scale_changes: Vec<ScaleChangeAction>,
     root_note_audio_configurations: Vec<ConfigureRootNoteAudio>,
+    polyphonic_detection_changes: Vec<ChangePolyphonicDetection>,
+    spectrum_overlay_changes: Vec<ChangeSpectrumOverlay>,
+    stretch_tuning_changes: Vec<ChangeStretchTuning>,
+    key_detection_changes: Vec<ChangeKeyDetection>,
+    transposition_changes: Vec<ChangeTransposition>,
+    in_tune_tolerance_changes: Vec<ChangeInTuneTolerance>,
+    target_note_playbacks: Vec<PlayTargetNote>,
+    metronome_configurations: Vec<ConfigureMetronome>,
+    intonation_pitch_changes: Vec<ChangeIntonationPitch>,
+    recording_analyses: Vec<AnalyzeRecording>,
 }
 
 #[cfg(test)]
@@ -217,8 +463,20 @@ impl PresentationLayerActionsBuilder {
         Self {
             tuning_system_changes: Vec::new(),
             root_note_adjustments: Vec::new(),
+            reference_frequency_adjustments: Vec::new(),
+            scala_tuning_imports: Vec::new(),
             scale_changes: Vec::new(),
             root_note_audio_configurations: Vec::new(),
+            polyphonic_detection_changes: Vec::new(),
+            spectrum_overlay_changes: Vec::new(),
+            stretch_tuning_changes: Vec::new(),
+            key_detection_changes: Vec::new(),
+            transposition_changes: Vec::new(),
+            in_tune_tolerance_changes: Vec::new(),
+            target_note_playbacks: Vec::new(),
+            metronome_configurations: Vec::new(),
+            intonation_pitch_changes: Vec::new(),
+            recording_analyses: Vec::new(),
         }
     }
     
@@ -232,17 +490,94 @@ impl PresentationLayerActionsBuilder {
         self
     }
     
+    pub fn with_reference_frequency_adjustment(mut self, reference_frequency: f32) -> Self {
+        self.reference_frequency_adjustments.push(AdjustReferenceFrequency::new(reference_frequency));
+        self
+    }
+    
+    pub fn with_scala_tuning_import(mut self, scale_file: &str, keyboard_mapping_file: Option<&str>) -> Self {
+        self.scala_tuning_imports.push(ImportScalaTuning::new(scale_file, keyboard_mapping_file));
+        self
+    }
+    
     pub fn with_scale_change(mut self, scale: Scale) -> Self {
         self.scale_changes.push(ScaleChangeAction::new(scale));
         self
     }
     
+    pub fn with_polyphonic_detection_change(mut self, enabled: bool) -> Self {
+        self.polyphonic_detection_changes.push(ChangePolyphonicDetection::new(enabled));
+        self
+    }
+    
+    pub fn with_spectrum_overlay_change(mut self, enabled: bool) -> Self {
+        self.spectrum_overlay_changes.push(ChangeSpectrumOverlay::new(enabled));
+        self
+    }
+    
+    pub fn with_stretch_tuning_change(mut self, stretch_tuning: StretchTuning) -> Self {
+        self.stretch_tuning_changes.push(ChangeStretchTuning::new(stretch_tuning));
+        self
+    }
+    
+    pub fn with_key_detection_change(mut self, key_detection: KeyDetection) -> Self {
+        self.key_detection_changes.push(ChangeKeyDetection::new(key_detection));
+        self
+    }
+    
+    pub fn with_transposition_change(mut self, transposition: Transposition) -> Self {
+        self.transposition_changes.push(ChangeTransposition::new(transposition));
+        self
+    }
+    
+    pub fn with_in_tune_tolerance_change(mut self, tolerance_cents: f32) -> Self {
+        self.in_tune_tolerance_changes.push(ChangeInTuneTolerance::new(tolerance_cents));
+        self
+    }
+    
+    pub fn with_root_note_audio_configuration(mut self, configuration: ConfigureRootNoteAudio) -> Self {
+        self.root_note_audio_configurations.push(configuration);
+        self
+    }
+    
+    pub fn with_target_note_playback(mut self, degree: i32, volume: f32, duration_seconds: f32) -> Self {
+        self.target_note_playbacks.push(PlayTargetNote::new(degree, volume, duration_seconds));
+        self
+    }
+    
+    pub fn with_metronome_configuration(mut self, config: MetronomeConfig) -> Self {
+        self.metronome_configurations.push(ConfigureMetronome::new(config));
+        self
+    }
+    
+    pub fn with_intonation_pitch_change(mut self, intonation_pitch: IntonationPitch) -> Self {
+        self.intonation_pitch_changes.push(ChangeIntonationPitch::new(intonation_pitch));
+        self
+    }
+    
+    pub fn with_recording_analysis(mut self, wav_file: &[u8]) -> Self {
+        self.recording_analyses.push(AnalyzeRecording::new(wav_file));
+        self
+    }
+    
     pub fn build(self) -> PresentationLayerActions {
         PresentationLayerActions {
             tuning_system_changes: self.tuning_system_changes,
             root_note_adjustments: self.root_note_adjustments,
+            reference_frequency_adjustments: self.reference_frequency_adjustments,
+            scala_tuning_imports: self.scala_tuning_imports,
             scale_changes: self.scale_changes,
             root_note_audio_configurations: self.root_note_audio_configurations,
+            polyphonic_detection_changes: self.polyphonic_detection_changes,
+            spectrum_overlay_changes: self.spectrum_overlay_changes,
+            stretch_tuning_changes: self.stretch_tuning_changes,
+            key_detection_changes: self.key_detection_changes,
+            transposition_changes: self.transposition_changes,
+            in_tune_tolerance_changes: self.in_tune_tolerance_changes,
+            target_note_playbacks: self.target_note_playbacks,
+            metronome_configurations: self.metronome_configurations,
+            intonation_pitch_changes: self.intonation_pitch_changes,
+            recording_analyses: self.recording_analyses,
         }
     }
 }
@@ -256,6 +591,7 @@ impl PresentationLayerActionsBuilder {
 #[derive(Debug, Clone, PartialEq)]
 pub struct DebugLayerActions {
     pub test_signal_configurations: Vec<ConfigureTestSignal>,
+    pub pitch_detection_configurations: Vec<ConfigurePitchDetection>,
 }
 
 #[cfg(debug_assertions)]
@@ -264,6 +600,7 @@ impl DebugLayerActions {
     pub(crate) fn new() -> Self {
         Self {
             test_signal_configurations: Vec::new(),
+            pitch_detection_configurations: Vec::new(),
         }
     }
 }
@@ -279,6 +616,7 @@ impl DebugLayerActions {
 #[cfg(all(debug_assertions, test))]
 pub struct DebugLayerActionsBuilder {
     test_signal_configurations: Vec<ConfigureTestSignal>,
+    pitch_detection_configurations: Vec<ConfigurePitchDetection>,
 }
 
 #[cfg(all(debug_assertions, test))]
@@ -286,17 +624,24 @@ impl DebugLayerActionsBuilder {
     pub fn new() -> Self {
         Self {
             test_signal_configurations: Vec::new(),
+            pitch_detection_configurations: Vec::new(),
         }
     }
     
-    pub fn with_test_signal(mut self, enabled: bool, frequency: f32, volume: f32) -> Self {
-        self.test_signal_configurations.push(ConfigureTestSignal::new(enabled, frequency, volume));
+    pub fn with_test_signal(mut self, enabled: bool, frequency: f32, volume: f32, shape: TestSignalShape) -> Self {
+        self.test_signal_configurations.push(ConfigureTestSignal::new(enabled, frequency, volume, shape));
+        self
+    }
+    
+    pub fn with_pitch_detection(mut self, algorithm: PitchDetectionAlgorithm, compare_algorithms: bool) -> Self {
+        self.pitch_detection_configurations.push(ConfigurePitchDetection::new(algorithm, compare_algorithms));
         self
     }
     
     pub fn build(self) -> DebugLayerActions {
         DebugLayerActions {
             test_signal_configurations: self.test_signal_configurations,
+            pitch_detection_configurations: self.pitch_detection_configurations,
         }
     }
 }
@@ -358,9 +703,53 @@ pub struct Presenter {
     /// Processed interval position for rendering
     interval_position: f32,
     
+    /// A4 reference frequency used for root note audio and pitch positioning
+    /// 
+    /// Synchronized from the model on every `process_data()` call.
+    reference_frequency: f32,
+    
     /// EMA smoother for interval position smoothing
     pub ema_smoother: EmaSmoother,
     
+    /// Records model results for export when a session recording is in progress
+    session_recorder: SessionRecorder,
+    
+    /// Whether the spectrum overlay is drawn in the main scene
+    spectrum_overlay_enabled: bool,
+    
+    /// Whether cents offsets are marked around the tuning lines
+    cents_grid_enabled: bool,
+    
+    /// How note names are displayed in the scene and the sidebar
+    note_naming_system: NoteNamingSystem,
+    
+    /// Changes that would move to the key detected by the model, if it differs from the current key
+    key_suggestion: Option<KeySuggestion>,
+    
+    /// Tonic and scale of the detected key last applied in auto mode
+    applied_key: Option<(u8, Scale)>,
+    
+    /// Visible interval range of the main scene
+    pitch_view: PitchView,
+    
+    /// Index into `PITCH_VIEW_ZOOM_LEVELS` of the current half range
+    pitch_view_zoom_level: usize,
+    
+    /// Whether the view pans to keep the detected pitch in sight
+    pitch_view_follow: bool,
+    
+    /// Voicing of the root note drone
+    drone_preset: DronePreset,
+    
+    /// How the voices of the drone preset are tuned
+    drone_voice_tuning: DroneVoiceTuning,
+    
+    /// Sound of the drone voices
+    drone_timbre: DroneTimbre,
+    
+    /// Screen position and scale degree of every tuning line drawn in the last frame
+    tuning_line_targets: Vec<(f32, i32)>,
+    
     /// Tracks whether the main scene UI is currently active
     /// Used to manage HTML UI lifecycle during scene transitions
     #[cfg(target_arch = "wasm32")]
@@ -407,7 +796,21 @@ impl Presenter {
             #[cfg(debug_assertions)]
             pending_debug_actions: DebugLayerActions::new(),
             interval_position: 0.0,
+            reference_frequency: crate::theory::tuning::STANDARD_REFERENCE_FREQUENCY,
             ema_smoother: EmaSmoother::new(0.1),
+            session_recorder: SessionRecorder::new(),
+            spectrum_overlay_enabled: false,
+            cents_grid_enabled: false,
+            note_naming_system: NoteNamingSystem::default(),
+            key_suggestion: None,
+            applied_key: None,
+            pitch_view: PitchView::new(0.0, crate::app_config::PITCH_VIEW_ZOOM_LEVELS[crate::app_config::DEFAULT_PITCH_VIEW_ZOOM_LEVEL]),
+            pitch_view_zoom_level: crate::app_config::DEFAULT_PITCH_VIEW_ZOOM_LEVEL,
+            pitch_view_follow: false,
+            drone_preset: DronePreset::default(),
+            drone_voice_tuning: DroneVoiceTuning::default(),
+            drone_timbre: DroneTimbre::default(),
+            tuning_line_targets: Vec::new(),
             #[cfg(target_arch = "wasm32")]
             main_scene_ui_active: true, // UI is now active from the start
             #[cfg(target_arch = "wasm32")]
@@ -451,13 +854,74 @@ impl Presenter {
         let tuning_line_data = if matches!(self.scene, Scene::Main(_)) {
             Self::get_tuning_line_positions(
                 model_data.root_note,
+                model_data.reference_frequency,
                 model_data.tuning_system,
                 model_data.scale,
+                StretchCurve::new(model_data.stretch_tuning, model_data.inharmonicity, model_data.reference_frequency),
+                self.pitch_view,
                 viewport
             )
         } else {
             Vec::new()
         };
+        self.tuning_line_targets = Self::tuning_line_degrees(model_data.tuning_system, model_data.scale, self.pitch_view)
+            .into_iter()
+            .zip(&tuning_line_data)
+            .map(|(degree, &(y, _, _))| (y, degree))
+            .collect();
+
+        // Spell note names for the root and scale in the selected naming system, in written pitch
+        let note_name = |midi_note: MidiNote| crate::theory::note_naming::written_note_name(
+            midi_note,
+            model_data.root_note,
+            model_data.scale,
+            self.note_naming_system,
+            model_data.transposition,
+        );
+        let tuning_line_labels = Self::tuning_line_labels(
+            model_data.tuning_system,
+            model_data.scale,
+            self.pitch_view,
+            &tuning_line_data,
+            note_name,
+        );
+
+        // Position and label every simultaneously detected pitch relative to the root
+        let root_frequency = Self::midi_note_to_frequency(model_data.root_note, model_data.reference_frequency);
+        let polyphonic_pitches: Vec<(f32, f32, String)> = model_data.polyphonic_notes.iter()
+            .map(|note| (
+                (note.frequency / root_frequency).log2(),
+                note.salience,
+                format!("{} {:+.0}¢", note_name(note.closest_midi_note), note.cents_from_root),
+            ))
+            .collect();
+
+        // Label the pitch with the just ratio it was matched to, e.g. "7/4, +3¢"
+        let pitch_label = model_data.matched_ratio
+            .filter(|_| pitch_detected)
+            .map(|ratio| ratio.label_with_cents(model_data.cents_offset));
+
+        // Cents offsets around every tuning line
+        let cents_grid = if self.cents_grid_enabled {
+            Self::cents_grid_positions(&tuning_line_data, self.pitch_view, viewport)
+        } else {
+            Vec::new()
+        };
+        let pitch_in_tune = model_data.intonation_state == Some(IntonationState::InTune);
+
+        // Spectrum on a log-frequency axis with markers at the harmonics of the detected pitch
+        let pitch_view = self.pitch_view;
+        let (spectrum_levels, harmonic_markers) = match (&model_data.spectrum, self.spectrum_overlay_enabled) {
+            (Some(spectrum), true) => {
+                let columns = (viewport.width as f32 / crate::app_config::SPECTRUM_COLUMN_WIDTH_PX) as usize;
+                let harmonic_markers = match model_data.pitch {
+                    Pitch::Detected(frequency, _) => Self::harmonic_marker_positions(frequency),
+                    Pitch::NotDetected => Vec::new(),
+                };
+                (Self::spectrum_overlay_levels(spectrum, columns), harmonic_markers)
+            }
+            _ => (Vec::new(), Vec::new()),
+        };
         
         match &mut self.scene {
             Scene::Startup(_) => {
@@ -465,11 +929,19 @@ impl Presenter {
             }
             Scene::Main(main_scene) => {
                 main_scene.update_viewport(viewport);
+                main_scene.set_pitch_view(pitch_view);
                 
                 // Update tuning lines - MainScene doesn't know about music theory
                 main_scene.update_tuning_lines(viewport, &tuning_line_data);
+                main_scene.update_tuning_line_labels(tuning_line_labels);
+                main_scene.update_cents_grid(viewport, &cents_grid);
                 
+                main_scene.set_pitch_in_tune(pitch_in_tune);
                 main_scene.update_pitch_position(viewport, interval_position, pitch_detected, clarity);
+                main_scene.update_pitch_label(viewport, interval_position, pitch_label);
+                main_scene.update_pitch_history(viewport);
+                main_scene.update_polyphonic_pitches(viewport, &polyphonic_pitches);
+                main_scene.update_spectrum(viewport, &spectrum_levels, &harmonic_markers);
             }
         }
     }
@@ -493,8 +965,15 @@ impl Presenter {
     /// 3. Processes accuracy metrics for tuning feedback
     /// 4. Manages error states and user feedback
     /// 5. Updates permission status display
-    /// 6. Prepares data for next render cycle
-    pub fn process_data(&mut self, _timestamp: f64, model_data: ModelUpdateResult) {
+    /// 6. Records the frame when a session recording is in progress, or loads an analyzed recording
+    /// 7. Prepares data for next render cycle
+    pub fn process_data(&mut self, timestamp: f64, model_data: ModelUpdateResult) {
+        // Capture the frame if a session recording is in progress
+        self.session_recorder.record(timestamp, &model_data);
+        if let Some(RecordingAnalysisStatus::Finished(results)) = &model_data.recording_analysis {
+            self.session_recorder.load(results);
+        }
+        
         // Process volume data for visualization
         self.process_volume_data(&model_data.volume);
         
@@ -513,6 +992,20 @@ impl Presenter {
         // Update tuning system display
         self.process_tuning_system(&model_data.tuning_system);
         
+        // Keep the reference frequency in sync with the model
+        self.reference_frequency = model_data.reference_frequency;
+        
+        // Follow the detected key, or keep it as a suggestion for the user to apply
+        self.key_suggestion = Self::key_suggestion(&model_data);
+        let detected_key = model_data.key_estimate.map(|estimate| (estimate.tonic, estimate.scale));
+        if model_data.key_detection != KeyDetection::AutoApply {
+            self.applied_key = None;
+        } else if detected_key != self.applied_key {
+            // Only a newly detected key is applied, the changes are not queued again every frame
+            self.on_key_suggestion_accepted();
+            self.applied_key = detected_key;
+        }
+        
         // Sync HTML UI with updated state
         self.sync_html_ui(&model_data);
         
@@ -531,6 +1024,21 @@ impl Presenter {
                 self.ema_smoother.reset();
             }
         }
+        
+        // Pan along with the detected pitch when following it
+        if self.pitch_view_follow && matches!(model_data.pitch, Pitch::Detected(_, _)) {
+            self.pitch_view.follow(self.interval_position, crate::app_config::PITCH_VIEW_FOLLOW_MARGIN_RATIO);
+            self.clamp_pitch_view_center();
+        }
+        
+        // Extend the scrolling pitch trace with the smoothed position
+        if let Scene::Main(main_scene) = &mut self.scene {
+            let (pitch_detected, clarity) = match model_data.pitch {
+                Pitch::Detected(_, clarity) => (true, Some(clarity)),
+                Pitch::NotDetected => (false, None),
+            };
+            main_scene.push_pitch_history(timestamp, self.interval_position, pitch_detected, clarity);
+        }
     }
 
     /// Retrieve and clear all pending user actions
@@ -564,28 +1072,294 @@ impl Presenter {
         self.pending_user_actions.tuning_system_changes.push(ChangeTuningSystem { tuning_system: tuning_system.clone() });
     }
 
-    /// Handle user request to adjust the root note
+    /// Handle user request to adjust the root note
+    /// 
+    /// This method should be called by UI components when the user selects
+    /// a different root note from a control or input field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `root_note` - The new root note selected by the user
+    pub fn on_root_note_adjusted(&mut self, root_note: MidiNote) {
+        self.pending_user_actions.root_note_adjustments.push(AdjustRootNote { root_note });
+    }
+
+    /// Handle user request to change the A4 reference frequency
+    /// 
+    /// This method should be called by UI components when the user changes
+    /// the concert pitch (e.g. A4=442Hz or A4=415Hz). The reference is also
+    /// used immediately for root note audio configured in the same frame, so
+    /// the drone follows the new reference without waiting for the model.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reference_frequency` - The new A4 frequency in Hz
+    pub fn on_reference_frequency_adjusted(&mut self, reference_frequency: f32) {
+        self.pending_user_actions.reference_frequency_adjustments.push(AdjustReferenceFrequency { reference_frequency });
+        self.reference_frequency = reference_frequency;
+    }
+
+    /// Handle user request to import a tuning from Scala files
+    /// 
+    /// This method should be called by UI components once the contents of a
+    /// `.scl` file, and optionally a `.kbm` file, have been read. The model
+    /// validates the files and switches to the imported tuning.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale_file` - Contents of the `.scl` scale file
+    /// * `keyboard_mapping_file` - Contents of the `.kbm` keyboard mapping file, if any
+    pub fn on_scala_tuning_imported(&mut self, scale_file: String, keyboard_mapping_file: Option<String>) {
+        self.pending_user_actions.scala_tuning_imports.push(ImportScalaTuning { scale_file, keyboard_mapping_file });
+    }
+
+    /// Handle a recorded WAV file the user loaded for analysis
+    /// 
+    /// This method should be called by UI components once the contents of the
+    /// file have been read. A session recording in progress is stopped. The engine
+    /// analyzes the recording like live input, and the results replace the session
+    /// recording, ready to be exported.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `wav_file` - Contents of the `.wav` file
+    pub fn on_recording_file_loaded(&mut self, wav_file: Vec<u8>) {
+        self.session_recorder.stop();
+        self.pending_user_actions.recording_analyses.push(AnalyzeRecording { wav_file });
+    }
+
+    /// Handle scale change action
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - The new scale to set
+    pub fn on_scale_changed(&mut self, scale: Scale) {
+        // Queue scale change action for collection by model layer
+        self.pending_user_actions.scale_changes.push(ScaleChangeAction { scale: scale.clone() });
+    }
+
+
+    /// Handle user request to change how note names are displayed
+    /// 
+    /// Naming is a display setting, so it takes effect on the next frame
+    /// without going through the model layer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `note_naming_system` - The naming system for note labels
+    pub fn on_note_naming_system_changed(&mut self, note_naming_system: NoteNamingSystem) {
+        self.note_naming_system = note_naming_system;
+    }
+
+    /// The naming system used for note labels
+    pub fn note_naming_system(&self) -> NoteNamingSystem {
+        self.note_naming_system
+    }
+
+    /// Handle user request to enable or disable polyphonic detection
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enabled` - Whether several simultaneous pitches should be detected
+    pub fn on_polyphonic_detection_changed(&mut self, enabled: bool) {
+        self.pending_user_actions.polyphonic_detection_changes.push(ChangePolyphonicDetection { enabled });
+    }
+
+    /// Handle user request to start, reconfigure or stop the metronome
+    /// 
+    /// # Arguments
+    /// 
+    /// * `config` - Tempo, meter, accent, subdivision, volume and latency compensation
+    pub fn on_metronome_configured(&mut self, config: MetronomeConfig) {
+        self.pending_user_actions.metronome_configurations.push(ConfigureMetronome { config });
+    }
+
+    /// Handle user request to change which pitch the intonation is judged from
+    /// 
+    /// # Arguments
+    /// 
+    /// * `intonation_pitch` - The detected pitch, or the center pitch while there is vibrato
+    pub fn on_intonation_pitch_changed(&mut self, intonation_pitch: IntonationPitch) {
+        self.pending_user_actions.intonation_pitch_changes.push(ChangeIntonationPitch { intonation_pitch });
+    }
+
+    /// Handle user request to change the key detection mode
+    /// 
+    /// # Arguments
+    /// 
+    /// * `key_detection` - Whether the detected key is suggested or applied automatically
+    pub fn on_key_detection_changed(&mut self, key_detection: KeyDetection) {
+        self.pending_user_actions.key_detection_changes.push(ChangeKeyDetection { key_detection });
+    }
+
+    /// Handle user request to apply the detected key
+    /// 
+    /// Queues the root note and scale changes of the current suggestion, which the
+    /// model validates like any other root note and scale change.
+    pub fn on_key_suggestion_accepted(&mut self) {
+        let Some(suggestion) = self.key_suggestion.take() else {
+            return;
+        };
+        if let Some(root_note) = suggestion.root_note {
+            self.pending_user_actions.root_note_adjustments.push(AdjustRootNote { root_note });
+        }
+        if let Some(scale) = suggestion.scale {
+            self.pending_user_actions.scale_changes.push(ScaleChangeAction { scale });
+        }
+    }
+
+    /// Changes that would move from the current root note and scale to the detected key
+    /// 
+    /// The root note keeps its octave as far as possible, it moves by at most a tritone.
+    pub fn key_suggestion(model_data: &ModelUpdateResult) -> Option<KeySuggestion> {
+        let estimate = model_data.key_estimate?;
+        let root_note = estimate.nearest_root_note(model_data.root_note);
+        let suggestion = KeySuggestion {
+            root_note: (root_note != model_data.root_note).then_some(root_note),
+            scale: (estimate.scale != model_data.scale).then_some(estimate.scale),
+        };
+        (suggestion.root_note.is_some() || suggestion.scale.is_some()).then_some(suggestion)
+    }
+
+    /// Handle user request to display note names for a transposing instrument
+    /// 
+    /// # Arguments
+    /// 
+    /// * `transposition` - Semitones from concert pitch up to the written pitch
+    pub fn on_transposition_changed(&mut self, transposition: Transposition) {
+        self.pending_user_actions.transposition_changes.push(ChangeTransposition { transposition });
+    }
+
+    /// Handle user request to change how far from the closest note a pitch still counts as in tune
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tolerance_cents` - Largest offset from the closest note in either direction
+    pub fn on_in_tune_tolerance_changed(&mut self, tolerance_cents: f32) {
+        self.pending_user_actions.in_tune_tolerance_changes.push(ChangeInTuneTolerance { tolerance_cents });
+    }
+
+    /// Handle user request to change the octave stretch
+    /// 
+    /// # Arguments
+    /// 
+    /// * `stretch_tuning` - The stretch curve the targets of the tuning system follow
+    pub fn on_stretch_tuning_changed(&mut self, stretch_tuning: StretchTuning) {
+        self.pending_user_actions.stretch_tuning_changes.push(ChangeStretchTuning { stretch_tuning });
+    }
+
+    /// Handle user request to zoom the pitch range in or out
+    /// 
+    /// Steps through `PITCH_VIEW_ZOOM_LEVELS`, from ±1 semitone to several
+    /// octaves, keeping the center of the view in place.
+    /// 
+    /// # Returns
+    /// 
+    /// The view after zooming
+    pub fn on_pitch_view_zoomed(&mut self, zoom_in: bool) -> PitchView {
+        let levels = crate::app_config::PITCH_VIEW_ZOOM_LEVELS.len();
+        self.pitch_view_zoom_level = if zoom_in {
+            self.pitch_view_zoom_level.saturating_sub(1)
+        } else {
+            (self.pitch_view_zoom_level + 1).min(levels - 1)
+        };
+        self.pitch_view.half_range = crate::app_config::PITCH_VIEW_ZOOM_LEVELS[self.pitch_view_zoom_level];
+        self.pitch_view
+    }
+
+    /// Handle user request to pan the pitch range up or down
+    /// 
+    /// Panning by hand stops following the detected pitch.
+    /// 
+    /// # Returns
+    /// 
+    /// The view after panning
+    pub fn on_pitch_view_panned(&mut self, up: bool) -> PitchView {
+        let step = self.pitch_view.half_range * crate::app_config::PITCH_VIEW_PAN_STEP_RATIO;
+        self.pitch_view.center += if up { step } else { -step };
+        self.clamp_pitch_view_center();
+        self.pitch_view_follow = false;
+        self.pitch_view
+    }
+
+    /// Handle user request to start or stop following the detected pitch
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the view follows the pitch after the toggle
+    pub fn on_pitch_view_follow_toggled(&mut self) -> bool {
+        self.pitch_view_follow = !self.pitch_view_follow;
+        self.pitch_view_follow
+    }
+
+    /// Handle user request to return to one octave below and above the root
+    /// 
+    /// # Returns
+    /// 
+    /// The view after the reset
+    pub fn on_pitch_view_reset(&mut self) -> PitchView {
+        self.pitch_view_zoom_level = crate::app_config::DEFAULT_PITCH_VIEW_ZOOM_LEVEL;
+        self.pitch_view = PitchView::new(0.0, crate::app_config::PITCH_VIEW_ZOOM_LEVELS[self.pitch_view_zoom_level]);
+        self.pitch_view
+    }
+
+    /// The visible interval range of the main scene
+    pub fn pitch_view(&self) -> PitchView {
+        self.pitch_view
+    }
+
+    /// Whether the view follows the detected pitch
+    pub fn pitch_view_follow(&self) -> bool {
+        self.pitch_view_follow
+    }
+
+    fn clamp_pitch_view_center(&mut self) {
+        let max_center = crate::app_config::PITCH_VIEW_MAX_CENTER_OCTAVES;
+        self.pitch_view.center = self.pitch_view.center.clamp(-max_center, max_center);
+    }
+
+    /// Handle user request to start or stop a session recording
     /// 
-    /// This method should be called by UI components when the user selects
-    /// a different root note from a control or input field.
+    /// Starting a recording discards the previous one. Stopping keeps the
+    /// recorded frames available for export until the next recording starts.
     /// 
-    /// # Arguments
+    /// # Returns
     /// 
-    /// * `root_note` - The new root note selected by the user
-    pub fn on_root_note_adjusted(&mut self, root_note: MidiNote) {
-        self.pending_user_actions.root_note_adjustments.push(AdjustRootNote { root_note });
+    /// `true` if a recording is in progress after the toggle
+    pub fn on_session_recording_toggled(&mut self) -> bool {
+        if self.session_recorder.is_recording() {
+            self.session_recorder.stop();
+        } else {
+            self.session_recorder.start();
+        }
+        self.session_recorder.is_recording()
     }
 
-    /// Handle scale change action
+    /// The session recorder, for exporting recorded frames
+    pub fn session_recorder(&self) -> &SessionRecorder {
+        &self.session_recorder
+    }
+
+    /// Handle user request to show or hide the spectrum overlay
     /// 
-    /// # Arguments
+    /// # Returns
     /// 
-    /// * `scale` - The new scale to set
-    pub fn on_scale_changed(&mut self, scale: Scale) {
-        // Queue scale change action for collection by model layer
-        self.pending_user_actions.scale_changes.push(ScaleChangeAction { scale: scale.clone() });
+    /// `true` if the overlay is shown after the toggle
+    pub fn on_spectrum_overlay_toggled(&mut self) -> bool {
+        self.spectrum_overlay_enabled = !self.spectrum_overlay_enabled;
+        self.pending_user_actions.spectrum_overlay_changes.push(ChangeSpectrumOverlay { enabled: self.spectrum_overlay_enabled });
+        self.spectrum_overlay_enabled
     }
 
+    /// Handle user request to show or hide the cents grid around the tuning lines
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the grid is shown after the toggle
+    pub fn on_cents_grid_toggled(&mut self) -> bool {
+        self.cents_grid_enabled = !self.cents_grid_enabled;
+        self.cents_grid_enabled
+    }
 
     /// Retrieve and clear all pending debug actions (debug builds only)
     /// 
@@ -617,12 +1391,31 @@ impl Presenter {
     /// * `enabled` - Whether test signal generation should be enabled
     /// * `frequency` - The frequency of the test signal in Hz
     /// * `volume` - The volume of the test signal (0-100)
+    /// * `shape` - Waveform, vibrato, glide and noise of the test signal
     #[cfg(debug_assertions)]
-    pub fn on_test_signal_configured(&mut self, enabled: bool, frequency: f32, volume: f32) {
+    pub fn on_test_signal_configured(&mut self, enabled: bool, frequency: f32, volume: f32, shape: TestSignalShape) {
         self.pending_debug_actions.test_signal_configurations.push(ConfigureTestSignal {
             enabled,
             frequency,
             volume,
+            shape,
+        });
+    }
+
+    /// Handle debug request to configure pitch detection (debug builds only)
+    /// 
+    /// This method should be called by debug UI components to select the pitch
+    /// detection algorithm and to compare the algorithms on the same input.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `algorithm` - The algorithm whose result is reported to the model
+    /// * `compare_algorithms` - Whether all algorithms should analyze every window
+    #[cfg(debug_assertions)]
+    pub fn on_pitch_detection_configured(&mut self, algorithm: PitchDetectionAlgorithm, compare_algorithms: bool) {
+        self.pending_debug_actions.pitch_detection_configurations.push(ConfigurePitchDetection {
+            algorithm,
+            compare_algorithms,
         });
     }
 
@@ -643,11 +1436,8 @@ impl Presenter {
         
         crate::common::dev_log!("PRESENTER: Root note audio configured - root_note: {}, volume: {}", 
                                 root_note, volume);
-        let frequency = Self::midi_note_to_frequency(root_note);
-        self.pending_user_actions.root_note_audio_configurations.push(ConfigureRootNoteAudio {
-            frequency,
-            volume,
-        });
+        let configuration = self.root_note_audio_configuration(root_note, volume);
+        self.pending_user_actions.root_note_audio_configurations.push(configuration);
         crate::common::dev_log!("PRESENTER: Added action to pending_user_actions, total actions: {}", self.pending_user_actions.root_note_audio_configurations.len());
     }
     
@@ -669,14 +1459,83 @@ impl Presenter {
         crate::common::dev_log!("PRESENTER: Root note audio configured - root_note: {}, volume: {}", 
                                 root_note, volume);
         
-        let frequency = Self::midi_note_to_frequency(root_note);
-        self.pending_user_actions.root_note_audio_configurations.push(ConfigureRootNoteAudio {
-            frequency,
-            volume,
-        });
+        let configuration = self.root_note_audio_configuration(root_note, volume);
+        self.pending_user_actions.root_note_audio_configurations.push(configuration);
         crate::common::dev_log!("PRESENTER: Added action to pending_user_actions with volume control");
     }
 
+    /// Handle user selection of the drone voicing, voice tuning and timbre
+    /// 
+    /// The selection applies to the next root note audio configuration, so UI
+    /// components should call `on_root_note_audio_configured()` afterwards to
+    /// hear the change.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `preset` - Voicing of the drone, a plucked tanpura cycle or a sustained chord
+    /// * `voice_tuning` - Whether the voices are just, equal tempered or follow the tuning system
+    /// * `timbre` - Sound of the drone voices
+    pub fn on_drone_configured(&mut self, preset: DronePreset, voice_tuning: DroneVoiceTuning, timbre: DroneTimbre) {
+        self.drone_preset = preset;
+        self.drone_voice_tuning = voice_tuning;
+        self.drone_timbre = timbre;
+    }
+
+    /// Handle user request to hear a scale degree of the active tuning
+    /// 
+    /// The note is played once with the drone timbre, at `TARGET_NOTE_VOLUME`
+    /// for `TARGET_NOTE_DURATION_SECONDS`, independent of the drone volume.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `degree` - Steps of the tuning system above (or below) the root
+    pub fn on_target_note_requested(&mut self, degree: i32) {
+        self.pending_user_actions.target_note_playbacks.push(PlayTargetNote {
+            degree,
+            volume: crate::app_config::TARGET_NOTE_VOLUME,
+            duration_seconds: crate::app_config::TARGET_NOTE_DURATION_SECONDS,
+            timbre: self.drone_timbre,
+        });
+    }
+
+    /// Handle a click on the main scene
+    /// 
+    /// A click close to a tuning line plays its note, see `on_target_note_requested()`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `y` - Vertical position in physical pixels from the bottom of the viewport
+    /// 
+    /// # Returns
+    /// 
+    /// The scale degree that is played, `None` if no tuning line is close to the click
+    pub fn on_scene_clicked(&mut self, y: f32) -> Option<i32> {
+        let degree = Self::tuning_line_at(&self.tuning_line_targets, y)?;
+        self.on_target_note_requested(degree);
+        Some(degree)
+    }
+
+    /// Scale degree of the tuning line closest to `y`, if within `TUNING_LINE_CLICK_TOLERANCE_PX`
+    fn tuning_line_at(tuning_line_targets: &[(f32, i32)], y: f32) -> Option<i32> {
+        tuning_line_targets
+            .iter()
+            .map(|&(line_y, degree)| ((line_y - y).abs(), degree))
+            .filter(|&(distance, _)| distance <= crate::app_config::TUNING_LINE_CLICK_TOLERANCE_PX)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, degree)| degree)
+    }
+
+    /// Root note audio configuration for the current drone settings
+    fn root_note_audio_configuration(&self, root_note: MidiNote, volume: f32) -> ConfigureRootNoteAudio {
+        ConfigureRootNoteAudio {
+            frequency: Self::midi_note_to_frequency(root_note, self.reference_frequency),
+            volume,
+            voices: self.drone_preset.voices(self.drone_voice_tuning),
+            timbre: self.drone_timbre,
+            envelope: self.drone_preset.envelope(),
+        }
+    }
+
 
     /// Render the presentation layer to the screen
     /// 
@@ -860,14 +1719,9 @@ impl Presenter {
     /// * `tuning_system` - Current tuning system from the model layer
     fn process_tuning_system(&mut self, tuning_system: &crate::shared_types::TuningSystem) {
         // Process tuning system without caching
-        match tuning_system {
-            crate::shared_types::TuningSystem::EqualTemperament => {
-                // Update UI to show Equal Temperament tuning
-            }
-            crate::shared_types::TuningSystem::JustIntonation => {
-                // Update UI to show Just Intonation tuning
-            }
-        }
+        let _tuning_name = tuning_system.tuning().name();
+        
+        // Future: Update UI to show the active tuning system by name
     }
     
     /// Calculate interval position directly from frequency and root note
@@ -898,8 +1752,8 @@ impl Presenter {
     fn calculate_interval_position_from_frequency(&self, pitch: &Pitch, root_note: MidiNote) -> f32 {
         match pitch {
             Pitch::Detected(frequency, _clarity) => {
-                // Calculate root note frequency using standard A4=440Hz reference
-                let root_frequency = Self::midi_note_to_frequency(root_note);
+                // Calculate root note frequency using the configured A4 reference
+                let root_frequency = Self::midi_note_to_frequency(root_note, self.reference_frequency);
                 
                 // Calculate interval position using log2 of frequency ratio
                 // This maps frequency ratios directly to position values:
@@ -914,11 +1768,12 @@ impl Presenter {
     
     /// Convert MIDI note number to frequency in Hz
     /// 
-    /// Uses the standard equal temperament formula with A4 (MIDI note 69) = 440Hz
+    /// Uses the standard equal temperament formula with A4 (MIDI note 69) as the reference
     /// 
     /// # Arguments
     /// 
     /// * `midi_note` - The MIDI note number (0-127)
+    /// * `reference_frequency` - The frequency of A4 in Hz
     /// 
     /// # Returns
     /// 
@@ -926,95 +1781,171 @@ impl Presenter {
     /// 
     /// # Formula
     /// 
-    /// `frequency = 440.0 * 2^((midi_note - 69) / 12)`
+    /// `frequency = reference_frequency * 2^((midi_note - 69) / 12)`
     /// 
     /// This formula is based on:
-    /// - A4 (MIDI note 69) is the reference pitch at 440Hz
+    /// - A4 (MIDI note 69) is the reference pitch (440Hz by default)
     /// - Each semitone up multiplies frequency by 2^(1/12)
     /// - Each octave up doubles the frequency
-    fn midi_note_to_frequency(midi_note: MidiNote) -> f32 {
-        crate::theory::tuning::midi_note_to_standard_frequency(midi_note)
+    fn midi_note_to_frequency(midi_note: MidiNote, reference_frequency: f32) -> f32 {
+        crate::theory::tuning::midi_note_to_frequency_with_reference(midi_note, reference_frequency)
     }
 
     
+    /// Degrees of the active tuning system that get a tuning line, in drawing order
+    /// 
+    /// Lines cover the interval range of the pitch view: the root (0) and the
+    /// degrees above it in ascending order, then the degrees below it. Only degrees
+    /// in the scale are included, see `theory::tuning::degree_in_scale`.
+    fn tuning_line_degrees(tuning_system: TuningSystem, scale: Scale, pitch_view: PitchView) -> Vec<i32> {
+        let tuning = tuning_system.tuning();
+        let degrees = tuning.degrees_per_octave() as i32;
+        let (lowest, highest) = pitch_view.interval_range();
+        let period_octaves = tuning.period_cents() / 1200.0;
+        let first_period = (lowest / period_octaves).floor() as i32;
+        let last_period = (highest / period_octaves).ceil() as i32;
+        // Allow for rounding so lines at the edges of the view are kept
+        let in_view = |degree: i32| {
+            let interval = tuning.interval_cents(degree) / 1200.0;
+            interval >= lowest - 1e-4 && interval <= highest + 1e-4
+        };
+        let mut line_degrees: Vec<i32> = (first_period * degrees..=last_period * degrees)
+            .filter(|&degree| in_view(degree))
+            .filter(|&degree| crate::theory::tuning::degree_in_scale(tuning, scale, degree))
+            .collect();
+        line_degrees.sort_by_key(|&degree| (degree < 0, degree));
+        line_degrees
+    }
+
     /// Get tuning line positions for the active tuning system
     /// Returns only the positions for intervals that are relevant to the current tuning system
+    /// 
+    /// Lines cover the interval range of the pitch view. Degrees of tunings without
+    /// 12 notes per period are assigned the MIDI note of the nearest semitone.
+    /// Lines are drawn at the targets of the stretch curve.
     pub fn get_tuning_line_positions(
         root_note: MidiNote,
+        reference_frequency: f32,
         tuning_system: TuningSystem,
         scale: Scale,
+        stretch_curve: StretchCurve,
+        pitch_view: PitchView,
         viewport: Viewport
     ) -> Vec<(f32, MidiNote, f32)> {
-        let root_frequency = crate::theory::tuning::midi_note_to_standard_frequency(root_note);
-        
-        // Helper function to determine line thickness based on semitone offset
-        let get_thickness = |semitone: i32| -> f32 {
-            // Octave lines (multiples of 12 semitones) get configurable thickness, others get regular thickness
-            if semitone % 12 == 0 {
+        let root_frequency = crate::theory::tuning::midi_note_to_frequency_with_reference(root_note, reference_frequency);
+        let tuning = tuning_system.tuning();
+        let degrees = tuning.degrees_per_octave() as i32;
+        
+        // Helper function to determine line thickness based on degree offset
+        let get_thickness = |degree: i32| -> f32 {
+            // Period lines (octaves in most tunings) get configurable thickness, others get regular thickness
+            if degree % degrees == 0 {
                 crate::app_config::OCTAVE_LINE_THICKNESS
             } else {
                 crate::app_config::REGULAR_LINE_THICKNESS
             }
         };
         
-        // Show intervals from -12 to +12 semitones including root (0)
-        let mut line_data = Vec::new();
-        
-        // Add center line (root note, 0 semitones)
-        if crate::shared_types::semitone_in_scale(scale, 0) {
-            // Root frequency stays at interval 0.0 (log2(1) = 0)
-            let interval = 0.0;
-            let y_position = crate::presentation::main_scene::interval_to_screen_y_position(
-                interval,
-                viewport.height as f32,
-            );
-            let thickness = get_thickness(0);
-            line_data.push((y_position, root_note, thickness));
-        }
-        
-        // Add intervals above root: +1 to +12 semitones
-        for semitone in 1..=12 {
-            // Only show intervals that are in the current scale
-            if crate::shared_types::semitone_in_scale(scale, semitone) {
-                let frequency = crate::theory::tuning::interval_frequency(
-                    tuning_system,
-                    root_frequency,
-                    semitone,
-                );
-                let interval = (frequency / root_frequency).log2();
-                let y_position = crate::presentation::main_scene::interval_to_screen_y_position(
-                    interval,
-                    viewport.height as f32,
-                );
-                let midi_note = (root_note as i32 + semitone).clamp(0, 127) as MidiNote;
-                let thickness = get_thickness(semitone);
-                line_data.push((y_position, midi_note, thickness));
-            }
-        }
-        
-        // Add intervals below root: -12 to -1 semitones
-        for semitone in -12..=-1 {
-            // Only show intervals that are in the current scale
-            if crate::shared_types::semitone_in_scale(scale, semitone) {
-                let frequency = crate::theory::tuning::interval_frequency(
-                    tuning_system,
-                    root_frequency,
-                    semitone,
-                );
+        Self::tuning_line_degrees(tuning_system, scale, pitch_view)
+            .into_iter()
+            .map(|degree| {
+                let frequency = tuning.interval_frequency(root_frequency, degree);
                 let interval = (frequency / root_frequency).log2();
-                let y_position = crate::presentation::main_scene::interval_to_screen_y_position(
-                    interval,
-                    viewport.height as f32,
-                );
-                let midi_note = (root_note as i32 + semitone).clamp(0, 127) as MidiNote;
-                let thickness = get_thickness(semitone);
-                line_data.push((y_position, midi_note, thickness));
-            }
-        }
-        
-        line_data
+                let stretched_interval = (stretch_curve.stretch(frequency) / root_frequency).log2();
+                let y_position = pitch_view.y_position(stretched_interval, viewport.height as f32);
+                let semitones = crate::theory::tuning::degree_to_semitone(tuning, degree)
+                    .unwrap_or((interval * 12.0).round() as i32);
+                let midi_note = (root_note as i32 + semitones).clamp(0, 127) as MidiNote;
+                (y_position, midi_note, get_thickness(degree))
+            })
+            .collect()
+    }
+
+    /// Labels of the tuning lines returned by `get_tuning_line_positions`
+    /// 
+    /// In tunings without 12 notes per period only the degrees that stand in for a
+    /// semitone are labelled, the others would crowd the labels with repeated names.
+    fn tuning_line_labels(
+        tuning_system: TuningSystem,
+        scale: Scale,
+        pitch_view: PitchView,
+        line_data: &[(f32, MidiNote, f32)],
+        note_name: impl Fn(MidiNote) -> String,
+    ) -> Vec<String> {
+        let tuning = tuning_system.tuning();
+        Self::tuning_line_degrees(tuning_system, scale, pitch_view)
+            .into_iter()
+            .zip(line_data)
+            .map(|(degree, &(_, midi_note, _))| match crate::theory::tuning::degree_to_semitone(tuning, degree) {
+                Some(_) => note_name(midi_note),
+                None => String::new(),
+            })
+            .collect()
+    }
+    
+    /// Positions of the cents grid lines around the tuning lines
+    /// 
+    /// Every offset in `CENTS_GRID_OFFSETS` is marked above and below each tuning
+    /// line, leaving out offsets that would be closer than `CENTS_GRID_MIN_SPACING_PX`
+    /// to their line in the current view.
+    pub fn cents_grid_positions(line_data: &[(f32, MidiNote, f32)], pitch_view: PitchView, viewport: Viewport) -> Vec<f32> {
+        let pixels_per_cent = pitch_view.pixels_per_octave(viewport.height as f32) / 1200.0;
+        crate::app_config::CENTS_GRID_OFFSETS
+            .iter()
+            .map(|cents| cents * pixels_per_cent)
+            .filter(|&offset| offset >= crate::app_config::CENTS_GRID_MIN_SPACING_PX)
+            .flat_map(|offset| line_data.iter().flat_map(move |&(y, _, _)| [y - offset, y + offset]))
+            .collect()
     }
     
+    /// Position of a frequency on the spectrum overlay's log-frequency axis
+    /// 
+    /// 0.0 is `SPECTRUM_MIN_FREQUENCY_HZ` and 1.0 is `SPECTRUM_MAX_FREQUENCY_HZ`.
+    fn frequency_to_spectrum_position(frequency: f32) -> f32 {
+        use crate::app_config::{SPECTRUM_MAX_FREQUENCY_HZ, SPECTRUM_MIN_FREQUENCY_HZ};
+        (frequency / SPECTRUM_MIN_FREQUENCY_HZ).ln() / (SPECTRUM_MAX_FREQUENCY_HZ / SPECTRUM_MIN_FREQUENCY_HZ).ln()
+    }
+
+    /// Downsample a spectrum to evenly spaced columns on a log-frequency axis
+    /// 
+    /// Each column shows the strongest bin within its frequency range, or the
+    /// interpolated magnitude where the bins are wider than the column. Levels are
+    /// mapped from `SPECTRUM_FLOOR_DB` to 0 dB (full scale) onto 0.0 - 1.0.
+    pub fn spectrum_overlay_levels(spectrum: &crate::shared_types::Spectrum, columns: usize) -> Vec<f32> {
+        use crate::app_config::{SPECTRUM_FLOOR_DB, SPECTRUM_MAX_FREQUENCY_HZ, SPECTRUM_MIN_FREQUENCY_HZ};
+        let ratio = SPECTRUM_MAX_FREQUENCY_HZ / SPECTRUM_MIN_FREQUENCY_HZ;
+        let column_frequency = |position: f32| SPECTRUM_MIN_FREQUENCY_HZ * ratio.powf(position / columns as f32);
+
+        (0..columns)
+            .map(|column| {
+                let low = column_frequency(column as f32);
+                let high = column_frequency(column as f32 + 1.0);
+                let first_bin = (low / spectrum.bin_width_hz).ceil() as usize;
+                let last_bin = ((high / spectrum.bin_width_hz).floor() as usize).min(spectrum.magnitudes.len().saturating_sub(1));
+                let magnitude = if first_bin <= last_bin {
+                    spectrum.magnitudes[first_bin..=last_bin].iter().copied().fold(0.0, f32::max)
+                } else {
+                    spectrum.magnitude_at(column_frequency(column as f32 + 0.5))
+                };
+                let level_db = 20.0 * magnitude.max(1e-10).log10();
+                ((level_db - SPECTRUM_FLOOR_DB) / -SPECTRUM_FLOOR_DB).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+
+    /// Harmonic numbers and overlay positions of the harmonics of a fundamental
+    /// 
+    /// Only harmonics within the overlay's frequency range are returned, up to
+    /// `SPECTRUM_MAX_HARMONICS`.
+    pub fn harmonic_marker_positions(fundamental: f32) -> Vec<(u32, f32)> {
+        if fundamental <= 0.0 {
+            return Vec::new();
+        }
+        (1..=crate::app_config::SPECTRUM_MAX_HARMONICS)
+            .map(|harmonic| (harmonic, Self::frequency_to_spectrum_position(fundamental * harmonic as f32)))
+            .filter(|&(_, position)| (0.0..=1.0).contains(&position))
+            .collect()
+    }
 
     /// Convert MIDI note to frequency using specified tuning system and root note.
     /// 
@@ -1027,6 +1958,8 @@ impl Presenter {
     /// * `root_note` - The root note for calculating intervals
     /// * `tuning_system` - The tuning system to use (Equal Temperament or Just Intonation)
     /// 
+    /// The root note frequency is derived from the presenter's current A4 reference.
+    /// 
     /// # Returns
     /// 
     /// The frequency in Hz according to the specified tuning system
@@ -1046,22 +1979,27 @@ impl Presenter {
         root_note: MidiNote,
         tuning_system: TuningSystem,
     ) -> f32 {
-        let root_frequency = crate::theory::tuning::midi_note_to_standard_frequency(root_note);
+        let root_frequency = crate::theory::tuning::midi_note_to_frequency_with_reference(root_note, self.reference_frequency);
         let interval_semitones = (midi_note as i32) - (root_note as i32);
-        crate::theory::tuning::interval_frequency(tuning_system, root_frequency, interval_semitones)
+        let tuning = tuning_system.tuning();
+        if tuning.degrees_per_octave() != 12 {
+            // MIDI notes are semitones, which are not degrees of this tuning
+            return root_frequency * 2.0_f32.powf(interval_semitones as f32 / 12.0);
+        }
+        tuning.interval_frequency(root_frequency, interval_semitones)
     }
 
 
     /// Synchronize HTML UI with specified presenter state
     #[cfg(all(target_arch = "wasm32", debug_assertions))]
     fn sync_html_ui(&self, model_data: &ModelUpdateResult) {
-        crate::web::main_scene_ui::sync_ui_with_presenter_state(model_data);
+        crate::web::main_scene_ui::sync_ui_with_presenter_state(model_data, self.note_naming_system);
     }
     
     /// Synchronize HTML UI with specified presenter state (non-debug version)
     #[cfg(all(target_arch = "wasm32", not(debug_assertions)))]
     fn sync_html_ui(&self, model_data: &ModelUpdateResult) {
-        crate::web::main_scene_ui::sync_ui_with_presenter_state(model_data);
+        crate::web::main_scene_ui::sync_ui_with_presenter_state(model_data, self.note_naming_system);
     }
 
     /// No-op version for non-WASM targets
@@ -1101,6 +2039,8 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
     use three_d::*;
+    #[cfg(debug_assertions)]
+    use crate::shared_types::TestWaveform;
     
     /// Create test model data for testing purposes
     fn create_test_model_data() -> ModelUpdateResult {
@@ -1118,8 +2058,26 @@ mod tests {
             closest_midi_note: 69,
             cents_offset: 0.0,
             interval_semitones: 0,
+            interval_steps: 0,
             root_note: 53,
-            root_note_audio_enabled: false,
+            reference_frequency: 440.0,
+            polyphonic_notes: Vec::new(),
+            spectrum: None,
+            matched_ratio: None,
+            stretch_tuning: crate::shared_types::StretchTuning::None,
+            inharmonicity: None,
+            key_detection: crate::shared_types::KeyDetection::Off,
+            key_estimate: None,
+            transposition: crate::shared_types::Transposition::CONCERT,
+            in_tune_tolerance_cents: 10.0,
+            intonation_state: None,
+            metronome: crate::shared_types::MetronomeConfig::default(),
+            onset_timings: Vec::new(),
+            note_events: Vec::new(),
+            current_note: None,
+            vibrato: None,
+            intonation_pitch: crate::shared_types::IntonationPitch::Instantaneous,
+            recording_analysis: None,
         }
     }
 
@@ -1432,6 +2390,31 @@ mod tests {
         let debug_actions = presenter.get_debug_actions();
         
         assert!(debug_actions.test_signal_configurations.is_empty());
+        assert!(debug_actions.pitch_detection_configurations.is_empty());
+    }
+
+    #[cfg(debug_assertions)]
+    #[wasm_bindgen_test]
+    fn test_pitch_detection_configuration_collection() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        presenter.on_pitch_detection_configured(PitchDetectionAlgorithm::ProbabilisticYin, true);
+
+        let debug_actions = presenter.get_debug_actions();
+        assert_eq!(
+            debug_actions.pitch_detection_configurations,
+            vec![ConfigurePitchDetection::new(PitchDetectionAlgorithm::ProbabilisticYin, true)]
+        );
+        assert_eq!(
+            debug_actions,
+            DebugLayerActions::builder()
+                .with_pitch_detection(PitchDetectionAlgorithm::ProbabilisticYin, true)
+                .build()
+        );
+
+        let debug_actions2 = presenter.get_debug_actions();
+        assert!(debug_actions2.pitch_detection_configurations.is_empty());
     }
 
     #[cfg(debug_assertions)]
@@ -1442,13 +2425,15 @@ mod tests {
             .expect("Presenter creation should succeed");
 
         // Configure test signal
-        presenter.on_test_signal_configured(true, 440.0, 50.0);
+        let shape = TestSignalShape { waveform: TestWaveform::Sawtooth, vibrato_rate_hz: 5.0, vibrato_depth_cents: 20.0, ..Default::default() };
+        presenter.on_test_signal_configured(true, 440.0, 50.0, shape.clone());
         
         let debug_actions = presenter.get_debug_actions();
         assert_eq!(debug_actions.test_signal_configurations.len(), 1);
         assert_eq!(debug_actions.test_signal_configurations[0].enabled, true);
         assert_eq!(debug_actions.test_signal_configurations[0].frequency, 440.0);
         assert_eq!(debug_actions.test_signal_configurations[0].volume, 20.0);
+        assert_eq!(debug_actions.test_signal_configurations[0].shape, shape);
         
         // After getting actions, they should be cleared
         let debug_actions2 = presenter.get_debug_actions();
@@ -1463,8 +2448,8 @@ mod tests {
             .expect("Presenter creation should succeed");
 
         // Trigger multiple debug actions
-        presenter.on_test_signal_configured(true, 880.0, 75.0);
-        presenter.on_test_signal_configured(false, 220.0, 25.0); // Second test signal config
+        presenter.on_test_signal_configured(true, 880.0, 75.0, TestSignalShape::default());
+        presenter.on_test_signal_configured(false, 220.0, 25.0, TestSignalShape::default()); // Second test signal config
         
         let debug_actions = presenter.get_debug_actions();
         
@@ -1501,8 +2486,8 @@ mod tests {
     #[wasm_bindgen_test]
     fn test_debug_action_struct_creation() {
         
-        let test_signal1 = ConfigureTestSignal { enabled: true, frequency: 440.0, volume: 20.0 };
-        let test_signal2 = ConfigureTestSignal { enabled: true, frequency: 440.0, volume: 20.0 };
+        let test_signal1 = ConfigureTestSignal { enabled: true, frequency: 440.0, volume: 20.0, shape: TestSignalShape::default() };
+        let test_signal2 = ConfigureTestSignal { enabled: true, frequency: 440.0, volume: 20.0, shape: TestSignalShape::default() };
         assert_eq!(test_signal1, test_signal2);
     }
     
@@ -1556,11 +2541,11 @@ mod tests {
     #[wasm_bindgen_test]
     fn test_midi_note_to_frequency() {
         // Test known MIDI note frequencies
-        assert!((Presenter::midi_note_to_frequency(69) - 440.0).abs() < 0.001, "A4 (MIDI 69) should be 440Hz");
-        assert!((Presenter::midi_note_to_frequency(60) - 261.626).abs() < 0.01, "C4 (MIDI 60) should be ~261.626Hz");
-        assert!((Presenter::midi_note_to_frequency(57) - 220.0).abs() < 0.001, "A3 (MIDI 57) should be 220Hz");
-        assert!((Presenter::midi_note_to_frequency(81) - 880.0).abs() < 0.001, "A5 (MIDI 81) should be 880Hz");
-        assert!((Presenter::midi_note_to_frequency(53) - 174.614).abs() < 0.01, "F3 (MIDI 53) should be ~174.614Hz");
+        assert!((Presenter::midi_note_to_frequency(69, 440.0) - 440.0).abs() < 0.001, "A4 (MIDI 69) should be 440Hz");
+        assert!((Presenter::midi_note_to_frequency(60, 440.0) - 261.626).abs() < 0.01, "C4 (MIDI 60) should be ~261.626Hz");
+        assert!((Presenter::midi_note_to_frequency(57, 440.0) - 220.0).abs() < 0.001, "A3 (MIDI 57) should be 220Hz");
+        assert!((Presenter::midi_note_to_frequency(81, 440.0) - 880.0).abs() < 0.001, "A5 (MIDI 81) should be 880Hz");
+        assert!((Presenter::midi_note_to_frequency(53, 440.0) - 174.614).abs() < 0.01, "F3 (MIDI 53) should be ~174.614Hz");
     }
 
     /// Test that Presenter creation succeeds
@@ -1595,6 +2580,105 @@ mod tests {
         assert!(actions2.scale_changes.is_empty());
     }
 
+    /// Test reference frequency action collection and root note audio retuning
+    #[wasm_bindgen_test]
+    fn test_reference_frequency_adjustment_collection() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        presenter.on_reference_frequency_adjusted(442.0);
+        presenter.on_root_note_audio_configured(true, 69, 0.5);
+
+        let actions = presenter.get_user_actions();
+        assert_eq!(actions.reference_frequency_adjustments.len(), 1);
+        assert_eq!(actions.reference_frequency_adjustments[0].reference_frequency, 442.0);
+
+        // Root note audio queued in the same frame uses the new reference
+        assert_eq!(actions.root_note_audio_configurations.len(), 1);
+        assert!((actions.root_note_audio_configurations[0].frequency - 442.0).abs() < 0.001);
+
+        let actions2 = presenter.get_user_actions();
+        assert!(actions2.reference_frequency_adjustments.is_empty());
+    }
+
+    /// Test that clicks close to a tuning line request its scale degree
+    #[wasm_bindgen_test]
+    fn test_target_note_playback() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        presenter.tuning_line_targets = vec![(100.0, -12), (300.0, 0), (320.0, 1), (500.0, 12)];
+        assert_eq!(presenter.on_scene_clicked(312.0), Some(1));
+        assert_eq!(presenter.on_scene_clicked(296.0), Some(0));
+        assert_eq!(presenter.on_scene_clicked(200.0), None);
+        presenter.on_target_note_requested(-12);
+
+        let actions = presenter.get_user_actions();
+        let degrees: Vec<i32> = actions.target_note_playbacks.iter().map(|playback| playback.degree).collect();
+        assert_eq!(degrees, vec![1, 0, -12]);
+        assert_eq!(actions.target_note_playbacks[0].duration_seconds, crate::app_config::TARGET_NOTE_DURATION_SECONDS);
+    }
+
+    /// Test that metronome settings are forwarded as user actions
+    #[wasm_bindgen_test]
+    fn test_metronome_configuration() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        let config = MetronomeConfig { enabled: true, bpm: 72.0, beats_per_bar: 3, subdivision: 2, ..MetronomeConfig::default() };
+        presenter.on_metronome_configured(config);
+        presenter.on_metronome_configured(MetronomeConfig { enabled: false, ..config });
+
+        let actions = presenter.get_user_actions();
+        assert_eq!(actions.metronome_configurations, vec![
+            ConfigureMetronome::new(config),
+            ConfigureMetronome::new(MetronomeConfig { enabled: false, ..config }),
+        ]);
+        assert!(presenter.get_user_actions().metronome_configurations.is_empty());
+    }
+
+    /// Test that a loaded recording is queued for analysis and its results replace the session recording
+    #[wasm_bindgen_test]
+    fn test_recording_analysis() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        presenter.on_recording_file_loaded(b"RIFF".to_vec());
+        assert_eq!(presenter.get_user_actions().recording_analyses, vec![AnalyzeRecording::new(b"RIFF")]);
+
+        let mut model_data = create_test_model_data();
+        model_data.recording_analysis = Some(RecordingAnalysisStatus::InProgress(0.5));
+        presenter.process_data(5.0, model_data.clone());
+        assert!(presenter.session_recorder().frames().is_empty());
+
+        model_data.recording_analysis = Some(RecordingAnalysisStatus::Finished(vec![(0.1, create_test_model_data()), (0.2, create_test_model_data())]));
+        presenter.process_data(5.0, model_data);
+        assert!(!presenter.session_recorder().is_recording());
+        assert_eq!(presenter.session_recorder().frames().len(), 2);
+        assert!((presenter.session_recorder().frames()[0].time_ms - 100.0).abs() < 1e-9);
+    }
+
+    /// Test that the drone selection shapes the next root note audio configuration
+    #[wasm_bindgen_test]
+    fn test_drone_configuration() {
+        let mut presenter = Presenter::create()
+            .expect("Presenter creation should succeed");
+
+        presenter.on_root_note_audio_configured(true, 60, 0.5);
+        presenter.on_drone_configured(DronePreset::MajorChord, DroneVoiceTuning::Just, DroneTimbre::Organ);
+        presenter.on_root_note_audio_configured(true, 60, 0.5);
+
+        let actions = presenter.get_user_actions();
+        assert_eq!(actions.root_note_audio_configurations.len(), 2);
+        assert_eq!(actions.root_note_audio_configurations[0].voices.len(), 1);
+        assert_eq!(actions.root_note_audio_configurations[0].timbre, DroneTimbre::Sine);
+
+        let chord = &actions.root_note_audio_configurations[1];
+        assert_eq!(chord.voices, DronePreset::MajorChord.voices(DroneVoiceTuning::Just));
+        assert_eq!(chord.timbre, DroneTimbre::Organ);
+        assert_eq!(chord.envelope, DroneEnvelope::SUSTAINED);
+    }
+
     /// Test scale-aware tuning line filtering with different scales
     #[wasm_bindgen_test]
     fn test_scale_aware_tuning_line_filtering() {
@@ -1609,25 +2693,25 @@ mod tests {
         };
 
         // Test with Major scale - should have fewer lines than chromatic
-        let major_positions = Presenter::get_tuning_line_positions(57, crate::shared_types::TuningSystem::EqualTemperament, Scale::Major, viewport);
+        let major_positions = Presenter::get_tuning_line_positions(57, 440.0, crate::shared_types::TuningSystem::EqualTemperament, Scale::Major, StretchCurve::none(440.0), PitchView::default(), viewport);
         
         // Test with Chromatic scale - should have all semitones
-        let chromatic_positions = Presenter::get_tuning_line_positions(57, crate::shared_types::TuningSystem::EqualTemperament, Scale::Chromatic, viewport);
+        let chromatic_positions = Presenter::get_tuning_line_positions(57, 440.0, crate::shared_types::TuningSystem::EqualTemperament, Scale::Chromatic, StretchCurve::none(440.0), PitchView::default(), viewport);
         
         // Chromatic should have more positions than Major
         assert!(chromatic_positions.len() > major_positions.len());
         
         // Test with Minor scale
-        let minor_positions = Presenter::get_tuning_line_positions(57, crate::shared_types::TuningSystem::EqualTemperament, Scale::Minor, viewport);
+        let minor_positions = Presenter::get_tuning_line_positions(57, 440.0, crate::shared_types::TuningSystem::EqualTemperament, Scale::Minor, StretchCurve::none(440.0), PitchView::default(), viewport);
         
         // Major and Minor should have the same number of positions (both are 7-note scales)
         assert_eq!(major_positions.len(), minor_positions.len());
         
         // Test with MajorPentatonic scale - should have 5 notes
-        let major_penta_positions = Presenter::get_tuning_line_positions(57, crate::shared_types::TuningSystem::EqualTemperament, Scale::MajorPentatonic, viewport);
+        let major_penta_positions = Presenter::get_tuning_line_positions(57, 440.0, crate::shared_types::TuningSystem::EqualTemperament, Scale::MajorPentatonic, StretchCurve::none(440.0), PitchView::default(), viewport);
         
         // Test with MinorPentatonic scale - should have 5 notes
-        let minor_penta_positions = Presenter::get_tuning_line_positions(57, crate::shared_types::TuningSystem::EqualTemperament, Scale::MinorPentatonic, viewport);
+        let minor_penta_positions = Presenter::get_tuning_line_positions(57, 440.0, crate::shared_types::TuningSystem::EqualTemperament, Scale::MinorPentatonic, StretchCurve::none(440.0), PitchView::default(), viewport);
         
         // Both pentatonic scales should have the same number of positions (5 notes each)
         assert_eq!(major_penta_positions.len(), minor_penta_positions.len());
@@ -1637,6 +2721,231 @@ mod tests {
         assert!(minor_penta_positions.len() < minor_positions.len());
     }
 
+    /// Test that imported tunings without 12 notes per octave show every degree of the scale
+    #[wasm_bindgen_test]
+    fn test_tuning_lines_for_non_twelve_tone_tuning() {
+        let viewport = three_d::Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+
+        let scale = crate::theory::scala::ScalaScale::parse(include_str!("../theory/scala_files/edo_19.scl")).unwrap();
+        let tuning_system = crate::theory::scala::register_custom_tuning(crate::theory::scala::ScalaTuning::new(&scale)).unwrap();
+
+        // Chromatic shows all 19 degrees per octave
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, tuning_system, Scale::Chromatic, StretchCurve::none(440.0), PitchView::default(), viewport);
+        assert_eq!(positions.len(), 2 * 19 + 1);
+
+        let octave_lines = positions.iter()
+            .filter(|(_, _, thickness)| *thickness == crate::app_config::OCTAVE_LINE_THICKNESS)
+            .count();
+        assert_eq!(octave_lines, 3);
+
+        // Degree 11 (694.7 cents) is labelled as the nearest equal tempered fifth
+        assert_eq!(positions[11].1, 57 + 7);
+        assert_eq!(positions[19].1, 57 + 12);
+
+        // Major shows the degrees closest to the semitones of the scale
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, tuning_system, Scale::Major, StretchCurve::none(440.0), PitchView::default(), viewport);
+        assert_eq!(positions.len(), 2 * 7 + 1);
+    }
+
+    /// Test that n-EDO tuning lines only label the degrees that stand in for a semitone
+    #[wasm_bindgen_test]
+    fn test_tuning_line_labels_for_equal_divisions() {
+        let viewport = three_d::Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+        let tuning_system = TuningSystem::EqualDivision(24);
+
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, tuning_system, Scale::Chromatic, StretchCurve::none(440.0), PitchView::default(), viewport);
+        assert_eq!(positions.len(), 2 * 24 + 1);
+        let labels = Presenter::tuning_line_labels(tuning_system, Scale::Chromatic, PitchView::default(), &positions, crate::shared_types::midi_note_to_name);
+        assert_eq!(labels.len(), positions.len());
+        assert_eq!(labels[0], "A3");
+        assert_eq!(labels[1], "");
+        assert_eq!(labels[2], "Bb3");
+        assert_eq!(labels.iter().filter(|label| !label.is_empty()).count(), 2 * 12 + 1);
+
+        // 12-tone tunings label every line
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, TuningSystem::JustIntonation, Scale::Major, StretchCurve::none(440.0), PitchView::default(), viewport);
+        let labels = Presenter::tuning_line_labels(TuningSystem::JustIntonation, Scale::Major, PitchView::default(), &positions, crate::shared_types::midi_note_to_name);
+        assert!(labels.iter().all(|label| !label.is_empty()));
+    }
+
+    /// Test that the detected key is suggested, and applied through user actions in auto mode
+    #[wasm_bindgen_test]
+    fn test_key_suggestion() {
+        let mut model = crate::model::DataModel::create().unwrap();
+        let mut model_data = model.update(0.0, crate::shared_types::EngineUpdateResult {
+            audio_analysis: None,
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        });
+        assert_eq!(Presenter::key_suggestion(&model_data), None);
+
+        // E minor detected with root A3 and a chromatic scale, the root moves down to E3
+        model_data.key_detection = KeyDetection::Suggest;
+        model_data.key_estimate = Some(crate::shared_types::KeyEstimate { tonic: 4, scale: Scale::Minor, correlation: 0.9, confidence: 0.2 });
+        assert_eq!(Presenter::key_suggestion(&model_data), Some(KeySuggestion { root_note: Some(52), scale: Some(Scale::Minor) }));
+
+        let mut presenter = Presenter::create().expect("Presenter creation should succeed");
+        presenter.process_data(0.0, model_data.clone());
+        assert!(presenter.get_user_actions().root_note_adjustments.is_empty(), "Suggestions wait for the user");
+        presenter.on_key_suggestion_accepted();
+        let actions = presenter.get_user_actions();
+        assert_eq!(actions.root_note_adjustments, vec![AdjustRootNote { root_note: 52 }]);
+        assert_eq!(actions.scale_changes, vec![ScaleChangeAction { scale: Scale::Minor }]);
+
+        // Auto mode queues the changes right away, only the ones that differ
+        model_data.key_detection = KeyDetection::AutoApply;
+        model_data.root_note = 52;
+        presenter.process_data(0.0, model_data.clone());
+        let actions = presenter.get_user_actions();
+        assert!(actions.root_note_adjustments.is_empty());
+        assert_eq!(actions.scale_changes, vec![ScaleChangeAction { scale: Scale::Minor }]);
+
+        // The same key is applied once, until a different key is detected
+        presenter.process_data(0.1, model_data.clone());
+        assert!(presenter.get_user_actions().scale_changes.is_empty());
+        model_data.key_estimate = Some(crate::shared_types::KeyEstimate { tonic: 7, scale: Scale::Major, correlation: 0.9, confidence: 0.2 });
+        presenter.process_data(0.2, model_data);
+        let actions = presenter.get_user_actions();
+        assert_eq!(actions.root_note_adjustments, vec![AdjustRootNote { root_note: 55 }]);
+        assert_eq!(actions.scale_changes, vec![ScaleChangeAction { scale: Scale::Major }]);
+    }
+
+    /// Test that tuning lines follow the stretch curve, which leaves A4 in place
+    #[wasm_bindgen_test]
+    fn test_tuning_lines_follow_stretch_curve() {
+        let viewport = three_d::Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+        let stretch_curve = StretchCurve::new(StretchTuning::PIANO, None, 440.0);
+
+        let plain = Presenter::get_tuning_line_positions(57, 440.0, TuningSystem::EqualTemperament, Scale::Chromatic, StretchCurve::none(440.0), PitchView::default(), viewport);
+        let stretched = Presenter::get_tuning_line_positions(57, 440.0, TuningSystem::EqualTemperament, Scale::Chromatic, stretch_curve, PitchView::default(), viewport);
+        assert_eq!(plain.len(), stretched.len());
+
+        // Lines run from the root up an octave, then from an octave below up to the root
+        // A2 is tuned flat, A4 is the unstretched reference
+        assert_eq!((stretched[12].1, stretched[13].1), (69, 45));
+        assert!(stretched[13].0 < plain[13].0);
+        assert!((stretched[12].0 - plain[12].0).abs() < 1e-3);
+    }
+
+    /// Test that tuning lines are generated for the visible range of a zoomed or panned view
+    #[wasm_bindgen_test]
+    fn test_tuning_lines_cover_pitch_view() {
+        let viewport = three_d::Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+
+        // ±1 semitone shows the root and its neighbours, the root in the middle
+        let view = PitchView::new(0.0, 1.0 / 12.0);
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, TuningSystem::EqualTemperament, Scale::Chromatic, StretchCurve::none(440.0), view, viewport);
+        let notes: Vec<MidiNote> = positions.iter().map(|&(_, midi_note, _)| midi_note).collect();
+        assert_eq!(notes, vec![57, 58, 56]);
+        assert_eq!(positions[0].0, 300.0);
+
+        // One octave below to three octaves above the root
+        let view = PitchView::new(1.0, 2.0);
+        let positions = Presenter::get_tuning_line_positions(57, 440.0, TuningSystem::EqualTemperament, Scale::Chromatic, StretchCurve::none(440.0), view, viewport);
+        assert_eq!(positions.len(), 4 * 12 + 1);
+        assert_eq!(positions.iter().map(|&(_, midi_note, _)| midi_note).max(), Some(57 + 36));
+        let labels = Presenter::tuning_line_labels(TuningSystem::EqualTemperament, Scale::Chromatic, view, &positions, crate::shared_types::midi_note_to_name);
+        assert_eq!(labels.len(), positions.len());
+    }
+
+    /// Test that the cents grid marks only the offsets that are far enough apart to see
+    #[wasm_bindgen_test]
+    fn test_cents_grid_positions() {
+        let viewport = three_d::Viewport {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+        let line_data = [(300.0, 57, 1.0), (400.0, 58, 1.0)];
+
+        // ±1 octave is 285 px per octave, only 25 cents is more than 4 px from the line
+        let positions = Presenter::cents_grid_positions(&line_data, PitchView::default(), viewport);
+        assert_eq!(positions.len(), 2 * 2);
+        let offset = 25.0 * 285.0 / 1200.0;
+        assert!((positions[0] - (300.0 - offset)).abs() < 1e-3);
+        assert!((positions[1] - (300.0 + offset)).abs() < 1e-3);
+
+        // ±1 semitone shows every offset
+        let positions = Presenter::cents_grid_positions(&line_data, PitchView::new(0.0, 1.0 / 12.0), viewport);
+        assert_eq!(positions.len(), 3 * 2 * 2);
+
+        let mut presenter = Presenter::create().expect("Presenter creation should succeed");
+        assert!(presenter.on_cents_grid_toggled());
+        assert!(!presenter.on_cents_grid_toggled());
+    }
+
+    /// Test zooming, panning and following the pitch
+    #[wasm_bindgen_test]
+    fn test_pitch_view_controls() {
+        let mut presenter = Presenter::create().expect("Presenter creation should succeed");
+        assert_eq!(presenter.pitch_view(), PitchView::default());
+
+        // Zooming stops at the narrowest and widest levels
+        for _ in 0..10 {
+            presenter.on_pitch_view_zoomed(true);
+        }
+        assert_eq!(presenter.pitch_view().half_range, 1.0 / 12.0);
+        for _ in 0..10 {
+            presenter.on_pitch_view_zoomed(false);
+        }
+        assert_eq!(presenter.pitch_view().half_range, 3.0);
+
+        // Panning by hand stops following
+        assert!(presenter.on_pitch_view_follow_toggled());
+        let view = presenter.on_pitch_view_panned(true);
+        assert_eq!(view.center, 1.5);
+        assert!(!presenter.pitch_view_follow());
+        for _ in 0..10 {
+            presenter.on_pitch_view_panned(false);
+        }
+        assert_eq!(presenter.pitch_view().center, -crate::app_config::PITCH_VIEW_MAX_CENTER_OCTAVES);
+
+        // Following moves the view towards a pitch two octaves above the root
+        presenter.on_pitch_view_reset();
+        presenter.on_pitch_view_zoomed(true);
+        presenter.on_pitch_view_follow_toggled();
+        let mut model = crate::model::DataModel::create().unwrap();
+        let mut model_data = model.update(0.0, crate::shared_types::EngineUpdateResult {
+            audio_analysis: None,
+            audio_errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
+        });
+        model_data.root_note = 57;
+        model_data.pitch = Pitch::Detected(880.0, 0.9);
+        for _ in 0..100 {
+            presenter.process_data(0.0, model_data.clone());
+        }
+        let (lowest, highest) = presenter.pitch_view().interval_range();
+        assert!(lowest < 2.0 && 2.0 < highest);
+
+        assert_eq!(presenter.on_pitch_view_reset(), PitchView::default());
+    }
+
     /// Test UI synchronization includes scale parameter
     #[wasm_bindgen_test]
     fn test_ui_synchronization_with_scale() {
@@ -1829,5 +3138,59 @@ mod tests {
         let is_detected = matches!(test_data.pitch, Pitch::Detected(_, _));
         assert!(!is_detected, "NotDetected should remain not detected");
     }
-    
+
+    #[wasm_bindgen_test]
+    fn test_spectrum_overlay_levels() {
+        // Bins every 10 Hz, full scale at 440 Hz, -40 dB at 1000 Hz, silence elsewhere
+        let mut magnitudes = vec![0.0; 1001];
+        magnitudes[44] = 1.0;
+        magnitudes[100] = 0.01;
+        let spectrum = crate::shared_types::Spectrum { magnitudes, bin_width_hz: 10.0 };
+
+        let columns = 200;
+        let levels = Presenter::spectrum_overlay_levels(&spectrum, columns);
+        assert_eq!(levels.len(), columns);
+        let column_of = |frequency: f32| (Presenter::frequency_to_spectrum_position(frequency) * columns as f32) as usize;
+        assert_eq!(levels[column_of(440.0)], 1.0);
+        assert!((levels[column_of(1000.0)] - 0.5).abs() < 1e-4);
+        assert_eq!(levels[column_of(3000.0)], 0.0);
+        assert!(levels.iter().all(|level| (0.0..=1.0).contains(level)));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_harmonic_marker_positions() {
+        let markers = Presenter::harmonic_marker_positions(crate::app_config::SPECTRUM_MIN_FREQUENCY_HZ);
+        assert_eq!(markers.len(), crate::app_config::SPECTRUM_MAX_HARMONICS as usize);
+        assert_eq!(markers[0], (1, 0.0));
+        assert!(markers.windows(2).all(|pair| pair[1].1 > pair[0].1));
+
+        // Only harmonics below the top of the overlay are marked
+        let markers = Presenter::harmonic_marker_positions(3000.0);
+        assert_eq!(markers.iter().map(|&(harmonic, _)| harmonic).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(Presenter::harmonic_marker_positions(0.0).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_spectrum_overlay_toggle() {
+        let mut presenter = Presenter::create().unwrap();
+        assert!(presenter.on_spectrum_overlay_toggled());
+        assert!(!presenter.on_spectrum_overlay_toggled());
+        // The model is told, so the engine only passes the spectrum on while it is shown
+        let expected = PresentationLayerActionsBuilder::new()
+            .with_spectrum_overlay_change(true)
+            .with_spectrum_overlay_change(false)
+            .build();
+        assert_eq!(presenter.get_user_actions(), expected);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_note_naming_system_change() {
+        let mut presenter = Presenter::create().unwrap();
+        assert_eq!(presenter.note_naming_system(), NoteNamingSystem::Letter);
+
+        presenter.on_note_naming_system_changed(NoteNamingSystem::German);
+        assert_eq!(presenter.note_naming_system(), NoteNamingSystem::German);
+        // Naming is a display setting and queues no action for the model
+        assert_eq!(presenter.get_user_actions(), PresentationLayerActions::new());
+    }
 }
@@ -0,0 +1,374 @@
+//! Session Recording Module
+//!
+//! This module records the stream of `ModelUpdateResult` frames produced by the
+//! model layer so that a practice session can be exported and analyzed later,
+//! for example by a teacher or in a notebook.
+//!
+//! ## Export Formats
+//!
+//! - CSV: one row per frame with a header row, empty cells where no pitch was detected
+//! - JSON: an object with session metadata, a `frames` array with `null` where no pitch was
+//!   detected, and a `notes` array with the notes that ended during the recording
+//!
+//! Times are exported in milliseconds relative to the first recorded frame.
+
+use crate::shared_types::{midi_note_to_name, MidiNote, ModelUpdateResult, NoteEvent, Pitch, TuningSystem};
+
+/// Maximum number of frames kept in one recording, about 30 minutes at 60 fps
+pub const MAX_RECORDED_FRAMES: usize = 108_000;
+
+/// Snapshot of one model update
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    /// Time since the first recorded frame in milliseconds
+    pub time_ms: f64,
+    /// Detected frequency in Hz and its clarity, `None` when no pitch was detected
+    pub pitch: Option<(f32, f32)>,
+    pub closest_midi_note: MidiNote,
+    pub cents_offset: f32,
+    pub interval_semitones: i32,
+    pub root_note: MidiNote,
+    pub tuning_system: TuningSystem,
+    pub reference_frequency: f32,
+    pub rms_amplitude: f32,
+}
+
+/// Records model results with timestamps and exports them as CSV or JSON
+#[derive(Debug, Default)]
+pub struct SessionRecorder {
+    recording: bool,
+    start_timestamp: Option<f64>,
+    frames: Vec<RecordedFrame>,
+    /// Notes that ended during the recording, with times relative to the first recorded frame
+    notes: Vec<NoteEvent>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new recording, discarding any previously recorded frames
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.start_timestamp = None;
+        self.frames.clear();
+        self.notes.clear();
+    }
+
+    /// Stop recording, keeping the recorded frames for export
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    pub fn notes(&self) -> &[NoteEvent] {
+        &self.notes
+    }
+
+    /// Duration between the first and the last recorded frame in seconds
+    pub fn duration_seconds(&self) -> f64 {
+        self.frames.last().map_or(0.0, |frame| frame.time_ms / 1000.0)
+    }
+
+    /// Record a model result if a recording is in progress
+    ///
+    /// Frames beyond `MAX_RECORDED_FRAMES` are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - The timestamp of the model update in seconds
+    /// * `model_data` - The model result to record
+    pub fn record(&mut self, timestamp: f64, model_data: &ModelUpdateResult) {
+        if !self.recording || self.frames.len() >= MAX_RECORDED_FRAMES {
+            return;
+        }
+
+        let start_timestamp = *self.start_timestamp.get_or_insert(timestamp);
+        self.frames.push(RecordedFrame {
+            time_ms: (timestamp - start_timestamp) * 1000.0,
+            pitch: match model_data.pitch {
+                Pitch::Detected(frequency, clarity) => Some((frequency, clarity)),
+                Pitch::NotDetected => None,
+            },
+            closest_midi_note: model_data.closest_midi_note,
+            cents_offset: model_data.cents_offset,
+            interval_semitones: model_data.interval_semitones,
+            root_note: model_data.root_note,
+            tuning_system: model_data.tuning_system,
+            reference_frequency: model_data.reference_frequency,
+            rms_amplitude: model_data.volume.rms_amplitude,
+        });
+        self.notes.extend(model_data.note_events.iter().map(|note| NoteEvent {
+            start_time: note.start_time - start_timestamp,
+            end_time: note.end_time - start_timestamp,
+            ..*note
+        }));
+    }
+
+    /// Replace the recording with the results of an analyzed recording
+    ///
+    /// Frame and note times count from the start of the analyzed recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - Model results with their times in seconds from the start of the recording
+    pub fn load(&mut self, results: &[(f64, ModelUpdateResult)]) {
+        self.start();
+        self.start_timestamp = Some(0.0);
+        for (time, model_data) in results {
+            self.record(*time, model_data);
+        }
+        self.stop();
+    }
+
+    /// Export the recorded frames as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "time_ms,frequency_hz,clarity,closest_note,closest_midi_note,cents_offset,interval_semitones,root_note,tuning_system,reference_frequency_hz,rms_amplitude\n",
+        );
+        for frame in &self.frames {
+            let (frequency, clarity, note, midi_note, cents_offset, interval) = match frame.pitch {
+                Some((frequency, clarity)) => (
+                    format!("{:.3}", frequency),
+                    format!("{:.3}", clarity),
+                    midi_note_to_name(frame.closest_midi_note),
+                    frame.closest_midi_note.to_string(),
+                    format!("{:.2}", frame.cents_offset),
+                    frame.interval_semitones.to_string(),
+                ),
+                None => Default::default(),
+            };
+            csv.push_str(&format!(
+                "{:.1},{},{},{},{},{},{},{},{},{:.2},{:.6}\n",
+                frame.time_ms,
+                frequency,
+                clarity,
+                note,
+                midi_note,
+                cents_offset,
+                interval,
+                midi_note_to_name(frame.root_note),
+                csv_field(frame.tuning_system.tuning().name()),
+                frame.reference_frequency,
+                frame.rms_amplitude,
+            ));
+        }
+        csv
+    }
+
+    /// Export the recorded frames as a JSON document
+    pub fn to_json(&self) -> String {
+        let frames: Vec<serde_json::Value> = self
+            .frames
+            .iter()
+            .map(|frame| {
+                let detected = frame.pitch.is_some();
+                serde_json::json!({
+                    "time_ms": frame.time_ms,
+                    "frequency_hz": frame.pitch.map(|(frequency, _)| frequency),
+                    "clarity": frame.pitch.map(|(_, clarity)| clarity),
+                    "closest_note": detected.then(|| midi_note_to_name(frame.closest_midi_note)),
+                    "closest_midi_note": detected.then_some(frame.closest_midi_note),
+                    "cents_offset": detected.then_some(frame.cents_offset),
+                    "interval_semitones": detected.then_some(frame.interval_semitones),
+                    "root_note": midi_note_to_name(frame.root_note),
+                    "tuning_system": frame.tuning_system.tuning().name(),
+                    "reference_frequency_hz": frame.reference_frequency,
+                    "rms_amplitude": frame.rms_amplitude,
+                })
+            })
+            .collect();
+
+        let notes: Vec<serde_json::Value> = self
+            .notes
+            .iter()
+            .map(|note| {
+                serde_json::json!({
+                    "start_ms": note.start_time * 1000.0,
+                    "end_ms": note.end_time * 1000.0,
+                    "median_frequency_hz": note.median_frequency,
+                    "closest_note": midi_note_to_name(note.closest_midi_note),
+                    "closest_midi_note": note.closest_midi_note,
+                    "mean_cents_offset": note.mean_cents_offset,
+                    "stability_cents": note.stability_cents,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "duration_seconds": self.duration_seconds(),
+            "frame_count": self.frames.len(),
+            "frames": frames,
+            "notes": notes,
+        })
+        .to_string()
+    }
+}
+
+/// Quote a CSV field if it contains characters that would break the row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_types::{IntonationData, KeyDetection, PermissionState, Scale, StretchTuning, Transposition, Volume};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn model_data(pitch: Pitch, closest_midi_note: MidiNote, cents_offset: f32) -> ModelUpdateResult {
+        ModelUpdateResult {
+            volume: Volume { peak_amplitude: 0.5, rms_amplitude: 0.25 },
+            pitch,
+            accuracy: IntonationData { closest_midi_note, cents_offset },
+            tuning_system: TuningSystem::EqualTemperament,
+            scale: Scale::Chromatic,
+            errors: Vec::new(),
+            permission_state: PermissionState::Granted,
+            closest_midi_note,
+            cents_offset,
+            interval_semitones: closest_midi_note as i32 - 57,
+            interval_steps: closest_midi_note as i32 - 57,
+            root_note: 57,
+            reference_frequency: 440.0,
+            polyphonic_notes: Vec::new(),
+            spectrum: None,
+            matched_ratio: None,
+            stretch_tuning: StretchTuning::None,
+            inharmonicity: None,
+            key_detection: KeyDetection::Off,
+            key_estimate: None,
+            transposition: Transposition::CONCERT,
+            in_tune_tolerance_cents: 10.0,
+            intonation_state: None,
+            metronome: crate::shared_types::MetronomeConfig::default(),
+            onset_timings: Vec::new(),
+            note_events: Vec::new(),
+            current_note: None,
+            vibrato: None,
+            intonation_pitch: crate::shared_types::IntonationPitch::Instantaneous,
+            recording_analysis: None,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_records_only_while_recording() {
+        let mut recorder = SessionRecorder::new();
+        recorder.record(1.0, &model_data(Pitch::Detected(440.0, 0.9), 69, 0.0));
+        assert!(recorder.frames().is_empty());
+
+        recorder.start();
+        recorder.record(2.0, &model_data(Pitch::Detected(440.0, 0.9), 69, 0.0));
+        recorder.record(2.5, &model_data(Pitch::NotDetected, 0, 0.0));
+        recorder.stop();
+        recorder.record(3.0, &model_data(Pitch::Detected(440.0, 0.9), 69, 0.0));
+
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.frames().len(), 2);
+        assert_eq!(recorder.frames()[0].time_ms, 0.0);
+        assert_eq!(recorder.frames()[1].time_ms, 500.0);
+        assert_eq!(recorder.frames()[1].pitch, None);
+        assert_eq!(recorder.duration_seconds(), 0.5);
+
+        // Starting again discards the previous recording
+        recorder.start();
+        assert!(recorder.frames().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_csv_export() {
+        let mut recorder = SessionRecorder::new();
+        recorder.start();
+        recorder.record(10.0, &model_data(Pitch::Detected(442.0, 0.95), 69, 7.85));
+        recorder.record(10.1, &model_data(Pitch::NotDetected, 0, 0.0));
+
+        let csv = recorder.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("time_ms,frequency_hz,clarity,closest_note"));
+        assert_eq!(lines[1], "0.0,442.000,0.950,A4,69,7.85,12,A3,Equal Temperament,440.00,0.250000");
+        assert_eq!(lines[2], "100.0,,,,,,,A3,Equal Temperament,440.00,0.250000");
+        assert!(lines.iter().all(|line| line.split(',').count() == 11));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_json_export() {
+        let mut recorder = SessionRecorder::new();
+        recorder.start();
+        recorder.record(0.0, &model_data(Pitch::Detected(261.63, 0.8), 60, -0.1));
+        recorder.record(0.02, &model_data(Pitch::NotDetected, 0, 0.0));
+
+        let json: serde_json::Value = serde_json::from_str(&recorder.to_json()).unwrap();
+        assert_eq!(json["frame_count"], 2);
+        let frames = json["frames"].as_array().unwrap();
+        assert_eq!(frames[0]["closest_note"], "C4");
+        assert_eq!(frames[0]["interval_semitones"], 3);
+        assert!((frames[0]["frequency_hz"].as_f64().unwrap() - 261.63).abs() < 1e-3);
+        assert!(frames[1]["frequency_hz"].is_null());
+        assert!(frames[1]["closest_note"].is_null());
+        assert_eq!(frames[1]["root_note"], "A3");
+        assert!((frames[1]["time_ms"].as_f64().unwrap() - 20.0).abs() < 1e-9);
+        assert!(json["notes"].as_array().unwrap().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_records_ended_notes() {
+        let mut recorder = SessionRecorder::new();
+        recorder.start();
+        recorder.record(5.0, &model_data(Pitch::Detected(440.0, 0.9), 69, 0.0));
+        let mut update = model_data(Pitch::NotDetected, 0, 0.0);
+        update.note_events.push(NoteEvent {
+            start_time: 5.0,
+            end_time: 5.5,
+            median_frequency: 441.0,
+            closest_midi_note: 69,
+            mean_cents_offset: 3.5,
+            stability_cents: 2.0,
+        });
+        recorder.record(5.6, &update);
+
+        assert_eq!(recorder.notes().len(), 1);
+        assert_eq!(recorder.notes()[0].start_time, 0.0);
+        let json: serde_json::Value = serde_json::from_str(&recorder.to_json()).unwrap();
+        let note = &json["notes"][0];
+        assert_eq!(note["closest_note"], "A4");
+        assert_eq!(note["end_ms"], 500.0);
+        assert_eq!(note["mean_cents_offset"], 3.5);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_load_analyzed_recording() {
+        let mut recorder = SessionRecorder::new();
+        recorder.start();
+        recorder.record(1.0, &model_data(Pitch::Detected(440.0, 0.9), 69, 0.0));
+        recorder.load(&[
+            (0.25, model_data(Pitch::Detected(261.63, 0.8), 60, -0.1)),
+            (0.5, model_data(Pitch::NotDetected, 0, 0.0)),
+        ]);
+
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.frames().len(), 2);
+        assert_eq!(recorder.frames()[0].time_ms, 250.0);
+        assert_eq!(recorder.frames()[0].closest_midi_note, 60);
+        assert_eq!(recorder.frames()[1].time_ms, 500.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("Werckmeister III"), "Werckmeister III");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -133,7 +133,8 @@ pub struct ColorScheme {
     pub muted: [f32; 3],
     pub border: [f32; 3],
     pub error: [f32; 3],
-    
+    /// Pitch line color while the pitch is within the in-tune tolerance
+    pub in_tune: [f32; 3],
 }
 
 impl Default for ColorScheme {
@@ -154,6 +155,7 @@ impl ColorScheme {
             muted:      [0.64, 0.68, 0.75],  // #A3ADBf
             border:     [0.28, 0.28, 0.32],  // #474852 (Subtle outline for panels/inputs)
             error:      [0.90, 0.35, 0.38],  // #E65A60 (Desaturated red for errors)
+            in_tune:    [0.64, 0.75, 0.55],  // #A3BE8C (Green for an in-tune pitch)
         }
     }
     
@@ -168,6 +170,7 @@ impl ColorScheme {
             muted: [0.6, 0.6, 0.6],
             border:     [0.28, 0.28, 0.32],  // #474852 (Subtle outline for panels/inputs)
             error:      [0.90, 0.35, 0.38],  // #E65A60 (Desaturated red for errors)
+            in_tune:    [0.64, 0.75, 0.55],  // #A3BE8C (Green for an in-tune pitch)
         }
     }
     
@@ -182,6 +185,7 @@ impl ColorScheme {
             muted: [0.5, 0.4, 0.3],
             border:     [0.28, 0.28, 0.32],  // #474852 (Subtle outline for panels/inputs)
             error:      [0.90, 0.35, 0.38],  // #E65A60 (Desaturated red for errors)
+            in_tune:    [0.64, 0.75, 0.55],  // #A3BE8C (Green for an in-tune pitch)
         }
     }
     
@@ -196,14 +200,39 @@ impl ColorScheme {
             muted: [0.6, 0.4, 0.4],
             border:     [0.28, 0.28, 0.32],  // #474852 (Subtle outline for panels/inputs)
             error:      [0.90, 0.35, 0.38],  // #E65A60 (Desaturated red for errors)
+            in_tune:    [0.64, 0.75, 0.55],  // #A3BE8C (Green for an in-tune pitch)
         }
     }
 }
 
+/// Identifies a built-in or user-defined tuning system
+/// 
+/// This is the key the layers pass around and store, not the tuning itself. The
+/// pitch of each scale degree is defined by the `theory::tuning_systems::Tuning`
+/// implementation it is looked up to, see `TuningSystem::tuning()`. Variants with a
+/// number are keys into a registry: `EqualDivision` into the supported equal
+/// divisions, `Custom` into the tunings imported from Scala files (see `theory::scala`).
+/// Keeping a small `Copy` key lets the model, the presenter and the session
+/// recordings compare and remember tuning systems without sharing trait objects.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TuningSystem {
     EqualTemperament,
+    /// 5-limit Just Intonation
     JustIntonation,
+    /// 7-limit Just Intonation, adds septimal intervals such as 7/4 and 7/6
+    JustIntonation7Limit,
+    /// 11-limit Just Intonation, adds undecimal intervals such as 11/8
+    JustIntonation11Limit,
+    Pythagorean,
+    QuarterCommaMeantone,
+    WerckmeisterIII,
+    KirnbergerIII,
+    Vallotti,
+    Young,
+    /// Equal division of the octave into the given number of steps (n-EDO)
+    EqualDivision(u16),
+    /// Imported tuning, identified by its id in the custom tuning registry
+    Custom(u16),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -213,9 +242,65 @@ pub enum Scale {
     Minor,
     MajorPentatonic,
     MinorPentatonic,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Locrian,
+    HarmonicMinor,
+    MelodicMinor,
+    WholeTone,
+    Blues,
+    OctatonicWholeHalf,
+    OctatonicHalfWhole,
+    /// User-defined scale, bit n is set when the semitone n above the root is included
+    Custom(u16),
 }
 
 impl Scale {
+    /// Every built-in scale, in the order they are listed in the user interface
+    pub const ALL: [Scale; 16] = [
+        Scale::Chromatic,
+        Scale::Major,
+        Scale::Minor,
+        Scale::MajorPentatonic,
+        Scale::MinorPentatonic,
+        Scale::Dorian,
+        Scale::Phrygian,
+        Scale::Lydian,
+        Scale::Mixolydian,
+        Scale::Locrian,
+        Scale::HarmonicMinor,
+        Scale::MelodicMinor,
+        Scale::WholeTone,
+        Scale::Blues,
+        Scale::OctatonicWholeHalf,
+        Scale::OctatonicHalfWhole,
+    ];
+
+    /// Human readable name, used in the user interface
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scale::Chromatic => "Chromatic",
+            Scale::Major => "Major",
+            Scale::Minor => "Minor",
+            Scale::MajorPentatonic => "Major Pentatonic",
+            Scale::MinorPentatonic => "Minor Pentatonic",
+            Scale::Dorian => "Dorian",
+            Scale::Phrygian => "Phrygian",
+            Scale::Lydian => "Lydian",
+            Scale::Mixolydian => "Mixolydian",
+            Scale::Locrian => "Locrian",
+            Scale::HarmonicMinor => "Harmonic Minor",
+            Scale::MelodicMinor => "Melodic Minor",
+            Scale::WholeTone => "Whole Tone",
+            Scale::Blues => "Blues",
+            Scale::OctatonicWholeHalf => "Octatonic (Whole-Half)",
+            Scale::OctatonicHalfWhole => "Octatonic (Half-Whole)",
+            Scale::Custom(_) => "Custom",
+        }
+    }
+
     /// Returns a boolean array indicating which semitones (0-11) from the root are included in the scale.
     /// Index 0 represents the root note (always true), index 1 represents +1 semitone from root, etc.
     pub fn pattern(&self) -> [bool; 12] {
@@ -230,8 +315,52 @@ impl Scale {
             Scale::MajorPentatonic => [true, false, true, false, true, false, false, true, false, true, false, false],
             // Minor Pentatonic scale: Root + m3-W-W-m3-W (semitones: 0,3,5,7,10)
             Scale::MinorPentatonic => [true, false, false, true, false, true, false, true, false, false, true, false],
+            // Dorian mode: Root + W-H-W-W-W-H-W (semitones: 0,2,3,5,7,9,10)
+            Scale::Dorian => [true, false, true, true, false, true, false, true, false, true, true, false],
+            // Phrygian mode: Root + H-W-W-W-H-W-W (semitones: 0,1,3,5,7,8,10)
+            Scale::Phrygian => [true, true, false, true, false, true, false, true, true, false, true, false],
+            // Lydian mode: Root + W-W-W-H-W-W-H (semitones: 0,2,4,6,7,9,11)
+            Scale::Lydian => [true, false, true, false, true, false, true, true, false, true, false, true],
+            // Mixolydian mode: Root + W-W-H-W-W-H-W (semitones: 0,2,4,5,7,9,10)
+            Scale::Mixolydian => [true, false, true, false, true, true, false, true, false, true, true, false],
+            // Locrian mode: Root + H-W-W-H-W-W-W (semitones: 0,1,3,5,6,8,10)
+            Scale::Locrian => [true, true, false, true, false, true, true, false, true, false, true, false],
+            // Harmonic Minor scale: Root + W-H-W-W-H-A2-H (semitones: 0,2,3,5,7,8,11)
+            Scale::HarmonicMinor => [true, false, true, true, false, true, false, true, true, false, false, true],
+            // Melodic Minor scale (ascending): Root + W-H-W-W-W-W-H (semitones: 0,2,3,5,7,9,11)
+            Scale::MelodicMinor => [true, false, true, true, false, true, false, true, false, true, false, true],
+            // Whole Tone scale: Root + W-W-W-W-W-W (semitones: 0,2,4,6,8,10)
+            Scale::WholeTone => [true, false, true, false, true, false, true, false, true, false, true, false],
+            // Blues scale: Minor Pentatonic plus the flat fifth (semitones: 0,3,5,6,7,10)
+            Scale::Blues => [true, false, false, true, false, true, true, true, false, false, true, false],
+            // Octatonic scale starting with a whole step (semitones: 0,2,3,5,6,8,9,11)
+            Scale::OctatonicWholeHalf => [true, false, true, true, false, true, true, false, true, true, false, true],
+            // Octatonic scale starting with a half step (semitones: 0,1,3,4,6,7,9,10)
+            Scale::OctatonicHalfWhole => [true, true, false, true, true, false, true, true, false, true, true, false],
+            // Custom scale: bits of the mask, with the root always included
+            Scale::Custom(mask) => std::array::from_fn(|semitone| semitone == 0 || mask & (1 << semitone) != 0),
         }
     }
+
+    /// The scale as a bit mask, bit n is set when the semitone n above the root is included
+    pub fn mask(&self) -> u16 {
+        self.pattern()
+            .iter()
+            .enumerate()
+            .filter(|(_, included)| **included)
+            .fold(0, |mask, (semitone, _)| mask | (1 << semitone))
+    }
+
+    /// Scale for a bit mask, preferring a built-in scale with the same semitones
+    ///
+    /// The root is always included, so bit 0 is set on the resulting mask.
+    pub fn from_mask(mask: u16) -> Scale {
+        let mask = (mask & 0x0FFF) | 1;
+        Scale::ALL
+            .into_iter()
+            .find(|scale| scale.mask() == mask)
+            .unwrap_or(Scale::Custom(mask))
+    }
 }
 
 /// Check if a semitone offset from the root is included in the given scale.
@@ -250,17 +379,620 @@ pub struct IntonationData {
     pub cents_offset: f32, // Distance in cents from the closest note (negative = flat, positive = sharp)
 }
 
+/// A just frequency ratio such as 7/4, reduced to lowest terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JustRatio {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl JustRatio {
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Self { numerator, denominator }
+    }
+
+    pub fn value(self) -> f32 {
+        self.numerator as f32 / self.denominator as f32
+    }
+
+    /// Size of the interval in cents
+    pub fn cents(self) -> f32 {
+        1200.0 * self.value().log2()
+    }
+
+    /// The ratio with a deviation in cents for display, e.g. "7/4, +3¢"
+    pub fn label_with_cents(self, cents: f32) -> String {
+        format!("{}, {:+.0}¢", self, cents)
+    }
+}
+
+impl std::fmt::Display for JustRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pitch {
     Detected(f32, f32), // frequency, clarity
     NotDetected,
 }
 
+/// Pitch detection algorithm used by the engine's pitch detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PitchDetectionAlgorithm {
+    McLeod,
+    Yin,
+    /// Probabilistic YIN with candidate tracking across frames
+    ProbabilisticYin,
+    Autocorrelation,
+}
+
+impl PitchDetectionAlgorithm {
+    pub const ALL: [PitchDetectionAlgorithm; 4] = [
+        PitchDetectionAlgorithm::McLeod,
+        PitchDetectionAlgorithm::Yin,
+        PitchDetectionAlgorithm::ProbabilisticYin,
+        PitchDetectionAlgorithm::Autocorrelation,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PitchDetectionAlgorithm::McLeod => "McLeod",
+            PitchDetectionAlgorithm::Yin => "YIN",
+            PitchDetectionAlgorithm::ProbabilisticYin => "pYIN",
+            PitchDetectionAlgorithm::Autocorrelation => "Autocorrelation",
+        }
+    }
+}
+
+/// Waveform of the debug test signal
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TestWaveform {
+    #[default]
+    Sine,
+    Sawtooth,
+    Square,
+    /// Relative amplitudes of the harmonics, starting at the fundamental
+    Harmonics(Vec<f32>),
+}
+
+impl TestWaveform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TestWaveform::Sine => "Sine",
+            TestWaveform::Sawtooth => "Sawtooth",
+            TestWaveform::Square => "Square",
+            TestWaveform::Harmonics(_) => "Harmonics",
+        }
+    }
+}
+
+/// Exponential glide of the test signal from its frequency to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glide {
+    pub to_frequency: f32,
+    pub seconds: f32,
+}
+
+/// Everything about the debug test signal apart from its frequency and level
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TestSignalShape {
+    pub waveform: TestWaveform,
+    /// Vibrato rate in Hz, no vibrato at 0.0
+    pub vibrato_rate_hz: f32,
+    /// Vibrato depth in cents either side of the frequency
+    pub vibrato_depth_cents: f32,
+    /// Glide that starts whenever the signal is configured
+    pub glide: Option<Glide>,
+    /// Ratio of signal to added white noise in dB, `None` for no noise
+    pub snr_db: Option<f32>,
+}
+
+/// Interval of a drone voice above the root, negative intervals are below it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DroneInterval {
+    /// Exact frequency ratio, e.g. 5/4 for a just major third
+    Ratio(u32, u32),
+    /// Size in cents, e.g. 400.0 for an equal tempered major third
+    Cents(f32),
+    /// The interval of this many semitones as tuned by the current tuning system
+    TuningSystem(i32),
+}
+
+/// How the voices of a drone preset are tuned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DroneVoiceTuning {
+    /// 5-limit just ratios, so thirds and fifths do not beat against the root
+    #[default]
+    Just,
+    Equal,
+    /// Follow the current tuning system
+    TuningSystem,
+}
+
+impl DroneVoiceTuning {
+    pub const ALL: [DroneVoiceTuning; 3] = [DroneVoiceTuning::Just, DroneVoiceTuning::Equal, DroneVoiceTuning::TuningSystem];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DroneVoiceTuning::Just => "Just",
+            DroneVoiceTuning::Equal => "Equal",
+            DroneVoiceTuning::TuningSystem => "Tuning System",
+        }
+    }
+
+    /// Interval of `semitones` above the root in this tuning
+    pub fn interval(&self, semitones: i32) -> DroneInterval {
+        // 5-limit just ratios of the semitones within one octave
+        const JUST_RATIOS: [(u32, u32); 12] = [
+            (1, 1), (16, 15), (9, 8), (6, 5), (5, 4), (4, 3), (45, 32), (3, 2), (8, 5), (5, 3), (9, 5), (15, 8),
+        ];
+        match self {
+            DroneVoiceTuning::Just => {
+                let (numerator, denominator) = JUST_RATIOS[semitones.rem_euclid(12) as usize];
+                let octaves = semitones.div_euclid(12);
+                if octaves >= 0 {
+                    DroneInterval::Ratio(numerator << octaves, denominator)
+                } else {
+                    DroneInterval::Ratio(numerator, denominator << -octaves)
+                }
+            }
+            DroneVoiceTuning::Equal => DroneInterval::Cents(semitones as f32 * 100.0),
+            DroneVoiceTuning::TuningSystem => DroneInterval::TuningSystem(semitones),
+        }
+    }
+}
+
+/// Sound of the drone voices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DroneTimbre {
+    #[default]
+    Sine,
+    /// A few soft harmonics, like a flue organ pipe
+    Organ,
+    /// Strong odd harmonics, like a clarinet
+    Reed,
+    /// Many slowly decaying harmonics, like the buzzing bridge of a tanpura
+    Tanpura,
+}
+
+impl DroneTimbre {
+    pub const ALL: [DroneTimbre; 4] = [DroneTimbre::Sine, DroneTimbre::Organ, DroneTimbre::Reed, DroneTimbre::Tanpura];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DroneTimbre::Sine => "Sine",
+            DroneTimbre::Organ => "Organ",
+            DroneTimbre::Reed => "Reed",
+            DroneTimbre::Tanpura => "Tanpura",
+        }
+    }
+}
+
+/// Volume envelope of the drone voices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DroneEnvelope {
+    pub attack_seconds: f32,
+    /// Fade out time of a sustained voice, or the time a plucked voice takes to decay by 60 dB
+    pub release_seconds: f32,
+    /// Time between two plucks of each voice, `None` for sustained voices
+    ///
+    /// The voices are plucked one after another, evenly spread over the period.
+    pub pluck_period_seconds: Option<f32>,
+}
+
+impl DroneEnvelope {
+    pub const SUSTAINED: DroneEnvelope = DroneEnvelope { attack_seconds: 0.05, release_seconds: 0.05, pluck_period_seconds: None };
+}
+
+impl Default for DroneEnvelope {
+    fn default() -> Self {
+        Self::SUSTAINED
+    }
+}
+
+/// One voice of the drone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DroneVoice {
+    pub interval: DroneInterval,
+    /// Level relative to the other voices (0.0-1.0)
+    pub gain: f32,
+}
+
+/// A drone voice with its interval resolved to a frequency ratio by the model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TunedDroneVoice {
+    /// Frequency relative to the root frequency
+    pub ratio: f32,
+    /// Level relative to the other voices (0.0-1.0)
+    pub gain: f32,
+}
+
+impl TunedDroneVoice {
+    pub const ROOT: TunedDroneVoice = TunedDroneVoice { ratio: 1.0, gain: 1.0 };
+}
+
+/// Voicings of the drone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DronePreset {
+    #[default]
+    Root,
+    RootAndFifth,
+    MajorChord,
+    MinorChord,
+    /// Plucked cycle of the fifth of the lower octave, the root twice and the lower octave, like a tanpura
+    Tanpura,
+}
+
+impl DronePreset {
+    pub const ALL: [DronePreset; 5] = [
+        DronePreset::Root,
+        DronePreset::RootAndFifth,
+        DronePreset::MajorChord,
+        DronePreset::MinorChord,
+        DronePreset::Tanpura,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DronePreset::Root => "Root",
+            DronePreset::RootAndFifth => "Root + Fifth",
+            DronePreset::MajorChord => "Major Chord",
+            DronePreset::MinorChord => "Minor Chord",
+            DronePreset::Tanpura => "Tanpura",
+        }
+    }
+
+    /// Semitones of the voices above the root, in the order a tanpura plucks them
+    fn semitones(&self) -> &'static [i32] {
+        match self {
+            DronePreset::Root => &[0],
+            DronePreset::RootAndFifth => &[0, 7],
+            DronePreset::MajorChord => &[0, 4, 7],
+            DronePreset::MinorChord => &[0, 3, 7],
+            DronePreset::Tanpura => &[-5, 0, 0, -12],
+        }
+    }
+
+    pub fn voices(&self, tuning: DroneVoiceTuning) -> Vec<DroneVoice> {
+        self.semitones()
+            .iter()
+            .map(|&semitones| DroneVoice { interval: tuning.interval(semitones), gain: 1.0 })
+            .collect()
+    }
+
+    /// Sustained, apart from the plucked tanpura cycle
+    pub fn envelope(&self) -> DroneEnvelope {
+        match self {
+            DronePreset::Tanpura => DroneEnvelope { attack_seconds: 0.01, release_seconds: 4.0, pluck_period_seconds: Some(3.2) },
+            _ => DroneEnvelope::SUSTAINED,
+        }
+    }
+}
+
+/// Settings of the metronome
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetronomeConfig {
+    pub enabled: bool,
+    /// Beats per minute
+    pub bpm: f32,
+    pub beats_per_bar: u32,
+    /// Whether the first beat of each bar has its own, louder click
+    pub accent: bool,
+    /// Clicks per beat, 1 for clicks on the beats only
+    pub subdivision: u32,
+    /// Click volume (0.0-1.0)
+    pub volume: f32,
+    /// Delay from the speakers to the analyzed input that is subtracted from measured note starts
+    pub latency_compensation_ms: f32,
+}
+
+impl Default for MetronomeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bpm: 100.0,
+            beats_per_bar: 4,
+            accent: true,
+            subdivision: 1,
+            volume: 0.5,
+            latency_compensation_ms: 0.0,
+        }
+    }
+}
+
+/// A detected note start measured against the nearest metronome click
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnsetTiming {
+    /// Bar of the click, counted from 0 when the metronome started
+    pub bar: u32,
+    /// Beat of the click within the bar, 0 for the downbeat
+    pub beat: u32,
+    /// Click within the beat, 0 for the beat itself
+    pub subdivision: u32,
+    /// Time from the click to the note start in milliseconds, negative when early
+    pub offset_ms: f32,
+}
+
+/// A sung or played note found by segmenting the detected pitch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoteEvent {
+    /// Timestamp of the first frame of the note in seconds
+    pub start_time: f64,
+    /// Timestamp of the last frame of the note in seconds, the latest frame while it still sounds
+    pub end_time: f64,
+    /// Median of the detected frequencies in Hz
+    pub median_frequency: f32,
+    /// Closest note to the median frequency in the active tuning
+    pub closest_midi_note: MidiNote,
+    /// Mean offset from the closest note in cents
+    pub mean_cents_offset: f32,
+    /// Standard deviation of the pitch in cents, 0.0 for a perfectly steady note
+    pub stability_cents: f32,
+}
+
+impl NoteEvent {
+    pub fn duration_seconds(&self) -> f64 {
+        self.end_time - self.start_time
+    }
+}
+
+/// Vibrato measured in the recent pitch history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VibratoEstimate {
+    /// Vibrato cycles per second
+    pub rate_hz: f32,
+    /// Swing above and below the center pitch in cents
+    pub extent_cents: f32,
+    /// Perceived center pitch in Hz, the mean pitch over whole vibrato cycles
+    pub center_frequency: f32,
+}
+
+/// Which pitch the intonation is judged from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IntonationPitch {
+    /// The pitch detected in each frame
+    #[default]
+    Instantaneous,
+    /// The center pitch of the vibrato while there is vibrato, the detected pitch otherwise
+    VibratoCenter,
+}
+
+/// How note names are displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NoteNamingSystem {
+    /// English letter names, e.g. Eb4
+    #[default]
+    Letter,
+    /// Fixed-do solfège, e.g. Mib4
+    FixedDo,
+    /// Movable-do solfège relative to the root, e.g. Me
+    MovableDo,
+    /// German letter names with H for B and B for Bb, e.g. Es4
+    German,
+    /// Nashville numbers relative to the root, e.g. b3
+    Nashville,
+}
+
+impl NoteNamingSystem {
+    pub const ALL: [NoteNamingSystem; 5] = [
+        NoteNamingSystem::Letter,
+        NoteNamingSystem::FixedDo,
+        NoteNamingSystem::MovableDo,
+        NoteNamingSystem::German,
+        NoteNamingSystem::Nashville,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteNamingSystem::Letter => "Letter Names",
+            NoteNamingSystem::FixedDo => "Fixed Do",
+            NoteNamingSystem::MovableDo => "Movable Do",
+            NoteNamingSystem::German => "German",
+            NoteNamingSystem::Nashville => "Nashville Numbers",
+        }
+    }
+
+    /// Whether names are relative to the root instead of absolute pitches
+    ///
+    /// Relative names carry no octave number.
+    pub fn is_relative(&self) -> bool {
+        matches!(self, NoteNamingSystem::MovableDo | NoteNamingSystem::Nashville)
+    }
+}
+
+/// Octave stretch applied to the target frequencies of the tuning system
+///
+/// Strings with stiffness, such as piano strings, have partials that run sharp
+/// of the harmonic series, so their octaves are tuned wider than 2:1.
+/// See `theory::stretch` for the curves.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StretchTuning {
+    /// Pure 2:1 octaves
+    #[default]
+    None,
+    /// Railsback curve, with the deviation in cents at C8 (sharp) and at A0 (flat)
+    Railsback { treble_cents: f32, bass_cents: f32 },
+    /// Octaves widened by the inharmonicity measured in the input spectrum
+    Measured,
+}
+
+impl StretchTuning {
+    /// Typical stretch of a well tuned upright piano
+    pub const PIANO: StretchTuning = StretchTuning::Railsback { treble_cents: 30.0, bass_cents: 20.0 };
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StretchTuning::None => "None",
+            StretchTuning::Railsback { .. } => "Railsback Curve",
+            StretchTuning::Measured => "Measured Inharmonicity",
+        }
+    }
+}
+
+/// Transposition of the instrument whose written pitch is displayed
+///
+/// The interval in semitones from the sounding (concert) pitch up to the written
+/// pitch: a B♭ clarinet sounds a major second below written, so it is 2.
+/// Analysis always happens in concert pitch, only displayed names are transposed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Transposition {
+    pub semitones: i8,
+}
+
+impl Transposition {
+    pub const CONCERT: Transposition = Transposition { semitones: 0 };
+    /// B♭ clarinet, B♭ trumpet and soprano saxophone
+    pub const B_FLAT: Transposition = Transposition { semitones: 2 };
+    /// E♭ alto saxophone
+    pub const E_FLAT_ALTO: Transposition = Transposition { semitones: 9 };
+    /// F horn and English horn
+    pub const F: Transposition = Transposition { semitones: 7 };
+    /// B♭ tenor saxophone and bass clarinet
+    pub const B_FLAT_TENOR: Transposition = Transposition { semitones: 14 };
+    /// E♭ baritone saxophone
+    pub const E_FLAT_BARITONE: Transposition = Transposition { semitones: 21 };
+
+    pub const PRESETS: [Transposition; 6] = [
+        Transposition::CONCERT,
+        Transposition::B_FLAT,
+        Transposition::E_FLAT_ALTO,
+        Transposition::F,
+        Transposition::B_FLAT_TENOR,
+        Transposition::E_FLAT_BARITONE,
+    ];
+
+    pub const fn new(semitones: i8) -> Self {
+        Self { semitones }
+    }
+
+    pub fn name(&self) -> String {
+        match *self {
+            Transposition::CONCERT => "Concert Pitch (C)".to_string(),
+            Transposition::B_FLAT => "B♭ Instrument".to_string(),
+            Transposition::E_FLAT_ALTO => "E♭ Alto".to_string(),
+            Transposition::F => "F Instrument".to_string(),
+            Transposition::B_FLAT_TENOR => "B♭ Tenor".to_string(),
+            Transposition::E_FLAT_BARITONE => "E♭ Baritone".to_string(),
+            Transposition { semitones } => format!("{:+} Semitones", semitones),
+        }
+    }
+
+    /// Written note for a sounding note, clamped to the MIDI range
+    pub fn written(&self, concert_note: MidiNote) -> MidiNote {
+        (concert_note as i32 + self.semitones as i32).clamp(0, 127) as MidiNote
+    }
+}
+
+/// Whether the detected pitch is within the in-tune tolerance of its closest note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntonationState {
+    InTune,
+    Sharp,
+    Flat,
+}
+
+impl IntonationState {
+    /// State of a pitch `cents_offset` away from its closest note
+    ///
+    /// Offsets of exactly `tolerance_cents` still count as in tune.
+    pub fn from_cents_offset(cents_offset: f32, tolerance_cents: f32) -> Self {
+        if cents_offset.abs() <= tolerance_cents {
+            IntonationState::InTune
+        } else if cents_offset > 0.0 {
+            IntonationState::Sharp
+        } else {
+            IntonationState::Flat
+        }
+    }
+}
+
+/// Whether the key is detected from the pitch history, and what happens with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyDetection {
+    #[default]
+    Off,
+    /// Show the detected key so the user can apply it
+    Suggest,
+    /// Change the root note and scale to the detected key
+    AutoApply,
+}
+
+/// Key estimated from the pitch history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyEstimate {
+    /// Pitch class of the tonic, C = 0
+    pub tonic: u8,
+    /// `Scale::Major` or `Scale::Minor`
+    pub scale: Scale,
+    /// Correlation of the pitch history with the key profile (-1.0-1.0)
+    pub correlation: f32,
+    /// How much better the key fits than the runner-up, in correlation
+    pub confidence: f32,
+}
+
+impl KeyEstimate {
+    /// The MIDI note with the tonic's pitch class nearest to `root_note`
+    pub fn nearest_root_note(&self, root_note: MidiNote) -> MidiNote {
+        let offset = (self.tonic as i32 - root_note as i32).rem_euclid(12);
+        let offset = if offset > 6 { offset - 12 } else { offset };
+        (root_note as i32 + offset).clamp(0, 127) as MidiNote
+    }
+}
+
+/// One of several simultaneous pitches found by polyphonic detection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolyphonicPitch {
+    pub frequency: f32,
+    /// Harmonic strength relative to the strongest simultaneous pitch (0.0-1.0)
+    pub salience: f32,
+}
+
+/// A simultaneous pitch placed relative to the root note in the active tuning
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolyphonicNote {
+    pub frequency: f32,
+    pub salience: f32,
+    pub closest_midi_note: MidiNote,
+    /// Distance in cents from the closest note (negative = flat, positive = sharp)
+    pub cents_offset: f32,
+    /// Distance in cents from the root note
+    pub cents_from_root: f32,
+}
+
+/// Windowed magnitude spectrum of the latest analysis block
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    /// Magnitude per bin from 0 Hz up to the Nyquist frequency, a full scale sine reads 1.0
+    pub magnitudes: Vec<f32>,
+    /// Distance between two bins in Hz
+    pub bin_width_hz: f32,
+}
+
+impl Spectrum {
+    /// Magnitude at a frequency, linearly interpolated between the neighboring bins
+    pub fn magnitude_at(&self, frequency: f32) -> f32 {
+        let position = frequency / self.bin_width_hz;
+        if position.is_nan() || position < 0.0 {
+            return 0.0;
+        }
+        let index = position as usize;
+        match (self.magnitudes.get(index), self.magnitudes.get(index + 1)) {
+            (Some(&lower), Some(&upper)) => lower + (upper - lower) * position.fract(),
+            (Some(&lower), None) if position.fract() == 0.0 => lower,
+            _ => 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AudioAnalysis {
     pub volume_level: Volume,
     pub pitch: Pitch,
-    pub fft_data: Option<Vec<f32>>, // roadmap
+    pub fft_data: Option<Spectrum>,
+    /// Simultaneous pitches, empty unless polyphonic detection is enabled
+    pub polyphonic_pitches: Vec<PolyphonicPitch>,
     pub timestamp: f64,
 }
 
@@ -336,6 +1068,35 @@ pub struct EngineUpdateResult {
     pub audio_analysis: Option<AudioAnalysis>,
     pub audio_errors: Vec<Error>,
     pub permission_state: PermissionState,
+    /// Note starts measured against the metronome since the previous update, empty while the metronome is off
+    pub onset_timings: Vec<OnsetTiming>,
+    /// Windows of a loaded recording analyzed in this update, `None` while no recording is being analyzed
+    pub recording_analysis: Option<Result<RecordingAnalysisChunk, String>>,
+}
+
+/// Part of the analysis of a loaded recording
+///
+/// Recordings are analyzed a few windows per update, so the render loop keeps
+/// running while a long recording is analyzed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingAnalysisChunk {
+    /// One entry per analysis window, timestamped in milliseconds from the start of the recording
+    pub frames: Vec<AudioAnalysis>,
+    /// Fraction of the recording analyzed so far, from 0.0 to 1.0
+    pub progress: f32,
+    /// Whether this chunk ends the recording
+    pub finished: bool,
+}
+
+/// State of the analysis of a loaded recording
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordingAnalysisStatus {
+    /// Fraction of the recording analyzed so far, from 0.0 to 1.0
+    InProgress(f32),
+    /// Results with their times in seconds from the start of the recording
+    Finished(Vec<(f64, ModelUpdateResult)>),
+    /// The recording could not be decoded or analyzed
+    Failed(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -351,7 +1112,44 @@ pub struct ModelUpdateResult {
     pub closest_midi_note: MidiNote,
     pub cents_offset: f32,
     pub interval_semitones: i32,
+    /// Interval from the root in steps of the tuning system, equal to `interval_semitones` in 12-tone tunings
+    pub interval_steps: i32,
     pub root_note: MidiNote,
+    /// Frequency of A4 in Hz that all note frequencies are derived from
+    pub reference_frequency: f32,
+    /// Simultaneous pitches, empty unless polyphonic detection is enabled
+    pub polyphonic_notes: Vec<PolyphonicNote>,
+    /// Magnitude spectrum of the latest analysis block
+    pub spectrum: Option<Spectrum>,
+    /// Just ratio the detected pitch was matched to, `None` for tunings that are not built from just ratios
+    pub matched_ratio: Option<JustRatio>,
+    /// Octave stretch that the targets of `cents_offset` include
+    pub stretch_tuning: StretchTuning,
+    /// Inharmonicity coefficient B measured in the input, `None` until enough partials were found
+    pub inharmonicity: Option<f32>,
+    pub key_detection: KeyDetection,
+    /// Key detected from the pitch history, `None` while detection is off or the evidence is too weak
+    pub key_estimate: Option<KeyEstimate>,
+    /// Transposition that note names are displayed in, all other fields are in concert pitch
+    pub transposition: Transposition,
+    /// Largest `cents_offset` in either direction that counts as in tune
+    pub in_tune_tolerance_cents: f32,
+    /// Whether the detected pitch is in tune, sharp or flat, `None` while no pitch is detected
+    pub intonation_state: Option<IntonationState>,
+    pub metronome: MetronomeConfig,
+    /// Most recent note starts measured against the metronome, oldest first
+    pub onset_timings: Vec<OnsetTiming>,
+    /// Notes that ended with this update
+    pub note_events: Vec<NoteEvent>,
+    /// The note that is sounding, `None` between notes
+    pub current_note: Option<NoteEvent>,
+    /// Vibrato in the recent pitch history, `None` without vibrato
+    pub vibrato: Option<VibratoEstimate>,
+    /// Which pitch `cents_offset`, `closest_midi_note` and `intonation_state` are judged from
+    pub intonation_pitch: IntonationPitch,
+    /// Progress or outcome of the analysis of a loaded recording, `None` unless
+    /// a recording was analyzed in this update
+    pub recording_analysis: Option<RecordingAnalysisStatus>,
 }
 
 /// Converts a semitone interval to a musical interval name.
@@ -466,6 +1264,19 @@ pub fn interval_name_from_semitones(semitones: i32) -> String {
     }
 }
 
+/// Converts an interval in steps of a tuning to a name, e.g. "11\19 (Perfect Fifth)"
+/// 
+/// Tunings with 12 steps per octave use the plain interval name. Other tunings
+/// show the step count over the steps per octave, followed by the name of the
+/// closest 12-tone interval `semitones`.
+pub fn interval_name_from_steps(steps: i32, steps_per_octave: usize, semitones: i32) -> String {
+    if steps_per_octave == 12 {
+        interval_name_from_semitones(steps)
+    } else {
+        format!("{}\\{} ({})", steps, steps_per_octave, interval_name_from_semitones(semitones))
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -478,6 +1289,7 @@ mod tests {
             volume_level: Volume { peak_amplitude: 0.5, rms_amplitude: 0.3 },
             pitch: Pitch::Detected(440.0, 0.9),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 123.456,
         };
 
@@ -487,6 +1299,8 @@ mod tests {
             audio_analysis: Some(test_analysis.clone()),
             audio_errors: test_errors.clone(),
             permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
 
         assert_eq!(update_result.audio_analysis, Some(test_analysis));
@@ -513,7 +1327,26 @@ mod tests {
             closest_midi_note: 69,
             cents_offset: -10.0,
             interval_semitones: 0,
+            interval_steps: 0,
             root_note: 53,
+            reference_frequency: 440.0,
+            polyphonic_notes: Vec::new(),
+            spectrum: None,
+            matched_ratio: None,
+            stretch_tuning: StretchTuning::None,
+            inharmonicity: None,
+            key_detection: KeyDetection::Off,
+            key_estimate: None,
+            transposition: Transposition::CONCERT,
+            in_tune_tolerance_cents: 10.0,
+            intonation_state: None,
+            metronome: crate::shared_types::MetronomeConfig::default(),
+            onset_timings: Vec::new(),
+            note_events: Vec::new(),
+            current_note: None,
+            vibrato: None,
+            intonation_pitch: crate::shared_types::IntonationPitch::Instantaneous,
+            recording_analysis: None,
         };
 
         assert_eq!(update_result.volume, test_volume);
@@ -526,6 +1359,7 @@ mod tests {
         assert_eq!(update_result.cents_offset, -10.0);
         assert_eq!(update_result.interval_semitones, 0);
         assert_eq!(update_result.root_note, 53);
+        assert_eq!(update_result.reference_frequency, 440.0);
     }
 
     #[wasm_bindgen_test]
@@ -586,7 +1420,8 @@ mod tests {
         let analysis = AudioAnalysis {
             volume_level: Volume { peak_amplitude: 0.8, rms_amplitude: 0.6 },
             pitch: Pitch::Detected(440.0, 0.9),
-            fft_data: Some(vec![0.1, 0.2, 0.3]),
+            fft_data: Some(Spectrum { magnitudes: vec![0.1, 0.2, 0.3], bin_width_hz: 10.0 }),
+            polyphonic_pitches: Vec::new(),
             timestamp: 123.456,
         };
         
@@ -596,6 +1431,16 @@ mod tests {
         assert!(analysis.fft_data.is_some());
     }
 
+    #[wasm_bindgen_test]
+    fn test_spectrum_magnitude_at() {
+        let spectrum = Spectrum { magnitudes: vec![0.0, 1.0, 0.5], bin_width_hz: 10.0 };
+        assert_eq!(spectrum.magnitude_at(10.0), 1.0);
+        assert_eq!(spectrum.magnitude_at(15.0), 0.75);
+        assert_eq!(spectrum.magnitude_at(20.0), 0.5);
+        assert_eq!(spectrum.magnitude_at(25.0), 0.0);
+        assert_eq!(spectrum.magnitude_at(-1.0), 0.0);
+    }
+
     #[wasm_bindgen_test]
     fn test_midi_note_validation() {
         assert!(is_valid_midi_note(0));
@@ -655,6 +1500,13 @@ mod tests {
         assert_eq!(interval_name_from_semitones(-24), "2 Octaves (descending)");
     }
 
+    #[wasm_bindgen_test]
+    fn test_interval_name_from_steps() {
+        assert_eq!(interval_name_from_steps(7, 12, 7), "Perfect Fifth");
+        assert_eq!(interval_name_from_steps(11, 19, 7), "11\\19 (Perfect Fifth)");
+        assert_eq!(interval_name_from_steps(-10, 31, -4), "-10\\31 (Major Third (descending))");
+    }
+
     #[wasm_bindgen_test]
     fn test_scale_patterns() {
         // Test Chromatic scale pattern (all semitones including root)
@@ -678,6 +1530,50 @@ mod tests {
         assert_eq!(minor_pent, [true, false, false, true, false, true, false, true, false, false, true, false]);
     }
 
+    #[wasm_bindgen_test]
+    fn test_extended_scale_patterns() {
+        // Every built-in scale includes the root and has the expected number of notes
+        let expected_note_counts = [12, 7, 7, 5, 5, 7, 7, 7, 7, 7, 7, 7, 6, 6, 8, 8];
+        for (scale, expected) in Scale::ALL.into_iter().zip(expected_note_counts) {
+            let pattern = scale.pattern();
+            assert!(pattern[0], "{} should include the root", scale.name());
+            assert_eq!(pattern.iter().filter(|included| **included).count(), expected, "{}", scale.name());
+        }
+
+        // Modes of the major scale are rotations of it
+        let major = Scale::Major.pattern();
+        for (mode, start) in [(Scale::Dorian, 2), (Scale::Phrygian, 4), (Scale::Lydian, 5), (Scale::Mixolydian, 7), (Scale::Minor, 9), (Scale::Locrian, 11)] {
+            let rotated: [bool; 12] = std::array::from_fn(|semitone| major[(semitone + start) % 12]);
+            assert_eq!(mode.pattern(), rotated, "{}", mode.name());
+        }
+
+        // Built-in scales are distinct
+        for (index, scale) in Scale::ALL.iter().enumerate() {
+            assert!(Scale::ALL[index + 1..].iter().all(|other| other.mask() != scale.mask()), "{}", scale.name());
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_custom_scale_mask() {
+        // Root, major third and fifth
+        let triad = Scale::from_mask(0b0000_1001_0001);
+        assert_eq!(triad, Scale::Custom(0b0000_1001_0001));
+        assert!(semitone_in_scale(triad, 0));
+        assert!(semitone_in_scale(triad, 4));
+        assert!(semitone_in_scale(triad, 7));
+        assert!(!semitone_in_scale(triad, 5));
+        assert!(semitone_in_scale(triad, -8));
+        assert!(semitone_in_scale(triad, 19));
+
+        // The root is always included and bits above the octave are ignored
+        assert_eq!(Scale::from_mask(0b1111_0000_1001_0000), Scale::Custom(0b0000_1001_0001));
+        assert!(Scale::Custom(0).pattern()[0]);
+
+        // Masks of built-in scales resolve to the built-in scale
+        assert_eq!(Scale::from_mask(Scale::Dorian.mask()), Scale::Dorian);
+        assert_eq!(Scale::from_mask(0x0FFF), Scale::Chromatic);
+    }
+
     #[wasm_bindgen_test]
     fn test_semitone_in_scale() {
         // Test root is always in scale
@@ -811,5 +1707,24 @@ mod tests {
         assert_eq!(midi_note_to_name(117), "A8");  // A8
     }
 
+    #[test]
+    fn test_drone_preset_voices() {
+        assert_eq!(DroneVoiceTuning::Just.interval(4), DroneInterval::Ratio(5, 4));
+        assert_eq!(DroneVoiceTuning::Just.interval(19), DroneInterval::Ratio(6, 2));
+        assert_eq!(DroneVoiceTuning::Just.interval(-5), DroneInterval::Ratio(3, 4));
+        assert_eq!(DroneVoiceTuning::Equal.interval(-12), DroneInterval::Cents(-1200.0));
+        assert_eq!(DroneVoiceTuning::TuningSystem.interval(7), DroneInterval::TuningSystem(7));
+
+        let major = DronePreset::MajorChord.voices(DroneVoiceTuning::Just);
+        let intervals: Vec<DroneInterval> = major.iter().map(|voice| voice.interval).collect();
+        assert_eq!(intervals, vec![DroneInterval::Ratio(1, 1), DroneInterval::Ratio(5, 4), DroneInterval::Ratio(3, 2)]);
+        assert_eq!(DronePreset::Root.envelope(), DroneEnvelope::SUSTAINED);
+
+        let tanpura = DronePreset::Tanpura.voices(DroneVoiceTuning::Just);
+        assert_eq!(tanpura.len(), 4);
+        assert_eq!(tanpura[3].interval, DroneInterval::Ratio(1, 2));
+        assert!(DronePreset::Tanpura.envelope().pluck_period_seconds.is_some());
+    }
+
 
 }
\ No newline at end of file
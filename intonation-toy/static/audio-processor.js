@@ -455,7 +455,8 @@ class AudioWorkletMessageProtocol {
                     timestamp: timestamp,
                     sequenceNumber: options.chunkCounter || 0,
                     bufferId: options.bufferId || 0,
-                    bufferPoolStats: options.bufferPoolStats || null
+                    bufferPoolStats: options.bufferPoolStats || null,
+                    startFrame: options.startFrame ?? null
                 },
                 buffer: buffer
             }
@@ -651,6 +652,7 @@ class PitchDetectionProcessor extends AudioWorkletProcessor {
         this.currentBufferArray = null;
         this.currentBufferId = 0; // Track buffer ID for ping-pong pattern
         this.writePosition = 0;
+        this.bufferStartFrame = 0; // AudioContext frame of the first sample in the current buffer
         
         // Timeout configuration for low-latency sending
         this.bufferTimeout = 100; // 100ms timeout for partial buffers (allows natural buffer filling)
@@ -782,7 +784,8 @@ class PitchDetectionProcessor extends AudioWorkletProcessor {
                     sampleCount: metadata.sampleCount,
                     chunkCounter: metadata.chunkCounter,
                     bufferId: this.currentBufferId,
-                    bufferPoolStats: bufferPoolStats
+                    bufferPoolStats: bufferPoolStats,
+                    startFrame: this.bufferStartFrame
                 });
                 
                 // Send buffer with transferable
@@ -1066,6 +1069,11 @@ class PitchDetectionProcessor extends AudioWorkletProcessor {
                 const remainingSpace = this.batchSize - this.writePosition;
                 const samplesToWrite = Math.min(this.chunkSize, remainingSpace);
                 
+                // Remember where in context time this buffer begins (used for onset timing)
+                if (this.writePosition === 0) {
+                    this.bufferStartFrame = currentFrame;
+                }
+                
                 // Write samples to the current position
                 this.currentBufferArray.set(processedAudio.subarray(0, samplesToWrite), this.writePosition);
                 this.writePosition += samplesToWrite;
@@ -1089,6 +1097,7 @@ class PitchDetectionProcessor extends AudioWorkletProcessor {
                                 0
                             );
                             this.writePosition = remainingSamples;
+                            this.bufferStartFrame = currentFrame + samplesToWrite;
                         }
                     }
                 }
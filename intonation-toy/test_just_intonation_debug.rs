@@ -17,6 +17,7 @@ fn main() {
         volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
         pitch: Pitch::Detected(test_frequency, 0.95),
         fft_data: None,
+        polyphonic_pitches: Vec::new(),
         timestamp: 1.0,
     };
     
@@ -24,6 +25,8 @@ fn main() {
         audio_analysis: Some(audio_analysis),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Test with Equal Temperament first
@@ -63,6 +66,7 @@ fn main() {
             volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: Pitch::Detected(freq, 0.95),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         };
         
@@ -70,6 +74,8 @@ fn main() {
             audio_analysis: Some(test_audio),
             audio_errors: Vec::new(),
             permission_state: PermissionState::Granted,
+            onset_timings: Vec::new(),
+            recording_analysis: None,
         };
         
         let result = model.update(3.0, test_engine_data);
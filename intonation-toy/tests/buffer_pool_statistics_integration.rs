@@ -70,6 +70,7 @@ impl MockAudioWorkletMessageGenerator {
             sequence_number: Some(message_id),
             buffer_id: Some(1),
             buffer_pool_stats: Some(stats),
+            start_frame: None,
         };
         
         FromWorkletEnvelope {
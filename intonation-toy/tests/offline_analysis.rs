@@ -0,0 +1,135 @@
+//! Integration tests for the offline analysis of recordings
+//!
+//! WAV decoding and offline analysis don't depend on the browser, so unlike the
+//! other integration tests these run natively:
+//!
+//! ```sh
+//! cargo test -p intonation-toy --test offline_analysis
+//! ```
+
+use intonation_toy::engine::audio::offline_analysis::{
+    analyze_recording, analyze_wav, AnalysisTimeline, OfflineAnalysisConfig, RecordingAnalysis,
+};
+
+const SAMPLE_RATE: u32 = 44100;
+
+fn tone(frequency: f32, seconds: f32, amplitude: f32) -> Vec<f32> {
+    let len = (seconds * SAMPLE_RATE as f32) as usize;
+    (0..len)
+        .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / SAMPLE_RATE as f32).sin())
+        .collect()
+}
+
+/// Encode samples as a 16-bit PCM mono WAV file
+fn pcm16_wav(samples: &[f32]) -> Vec<u8> {
+    let data: Vec<u8> = samples.iter().flat_map(|s| ((s * 32767.0) as i16).to_le_bytes()).collect();
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&data);
+    bytes
+}
+
+/// A3 for one second, half a second of silence, then E4 for one second
+fn two_notes() -> Vec<f32> {
+    let mut samples = tone(220.0, 1.0, 0.5);
+    samples.extend(vec![0.0; SAMPLE_RATE as usize / 2]);
+    samples.extend(tone(329.63, 1.0, 0.5));
+    samples
+}
+
+#[test]
+fn test_analyze_wav_timeline() {
+    let timeline = analyze_wav(&pcm16_wav(&two_notes()), &OfflineAnalysisConfig::default()).unwrap();
+    assert_eq!(timeline.sample_rate, SAMPLE_RATE);
+    assert!((timeline.duration_seconds - 2.5).abs() < 1e-6);
+    assert!(!timeline.frames.is_empty());
+    assert!(timeline.frames.windows(2).all(|w| w[1].time_seconds > w[0].time_seconds));
+    assert!(timeline.frames.last().unwrap().time_seconds <= timeline.duration_seconds);
+
+    let first = timeline.frame_at(0.5).unwrap().pitch.as_ref().expect("A3 should be detected");
+    assert!((first.frequency - 220.0).abs() < 2.0);
+    assert!((first.timestamp - timeline.frame_at(0.5).unwrap().time_seconds * 1000.0).abs() < 1e-6);
+
+    let silence = timeline.frame_at(1.25).unwrap();
+    assert!(silence.pitch.is_none());
+    assert!(silence.volume.rms_amplitude < 1e-3);
+
+    let second = timeline.frame_at(2.0).unwrap().pitch.as_ref().expect("E4 should be detected");
+    assert!((second.frequency - 329.63).abs() < 3.0);
+    assert!(timeline.frame_at(2.0).unwrap().volume.peak_amplitude > 0.45);
+
+    let ratio = timeline.voiced_ratio();
+    assert!(ratio > 0.5 && ratio < 1.0, "voiced ratio {}", ratio);
+}
+
+#[test]
+fn test_analysis_in_steps_matches_the_whole_recording() {
+    let samples = two_notes();
+    let config = OfflineAnalysisConfig::default();
+    let whole = analyze_recording(&samples, SAMPLE_RATE, &config).unwrap();
+
+    let mut analysis = RecordingAnalysis::from_wav(pcm16_wav(&samples), &config).unwrap();
+    assert_eq!(analysis.sample_rate(), SAMPLE_RATE);
+    assert!((analysis.duration_seconds() - 2.5).abs() < 1e-6);
+
+    let mut frames = Vec::new();
+    let mut progress = analysis.progress();
+    assert_eq!(progress, 0.0);
+    while !analysis.is_finished() {
+        let step = analysis.analyze_windows(3).unwrap();
+        assert!(!step.is_empty() && step.len() <= 3);
+        assert!(analysis.progress() > progress);
+        progress = analysis.progress();
+        frames.extend(step);
+    }
+    assert_eq!(progress, 1.0);
+    assert!(analysis.analyze_windows(3).unwrap().is_empty());
+
+    assert_eq!(frames.len(), whole.frames.len());
+    for (stepped_frame, whole_frame) in frames.iter().zip(&whole.frames) {
+        assert_eq!(stepped_frame.time_seconds, whole_frame.time_seconds);
+        match (&stepped_frame.pitch, &whole_frame.pitch) {
+            // The WAV file holds the samples at 16 bits
+            (Some(stepped), Some(whole)) => assert!((stepped.frequency - whole.frequency).abs() < 0.5),
+            (None, None) => {}
+            pitches => panic!("at {}s: {:?}", stepped_frame.time_seconds, pitches),
+        }
+    }
+}
+
+#[test]
+fn test_analyze_short_recording_is_padded() {
+    let samples = tone(440.0, 0.05, 0.5);
+    let timeline = analyze_recording(&samples, SAMPLE_RATE, &OfflineAnalysisConfig::default()).unwrap();
+    assert_eq!(timeline.frames.len(), 1);
+
+    let analysis = RecordingAnalysis::from_samples(Vec::new(), SAMPLE_RATE, &OfflineAnalysisConfig::default()).unwrap();
+    assert!(!analysis.is_finished());
+}
+
+#[test]
+fn test_analyze_wav_rejects_invalid_input() {
+    let error = analyze_wav(b"RIFF", &OfflineAnalysisConfig::default()).unwrap_err();
+    assert!(error.contains("not a RIFF/WAVE file"), "{}", error);
+
+    let samples = tone(440.0, 0.5, 0.5);
+    for overlap_factor in [f32::NAN, f32::INFINITY, -0.1, 0.95] {
+        let config = OfflineAnalysisConfig { overlap_factor, ..OfflineAnalysisConfig::default() };
+        assert!(analyze_recording(&samples, SAMPLE_RATE, &config).is_err(), "overlap factor {}", overlap_factor);
+    }
+
+    let empty = AnalysisTimeline { sample_rate: SAMPLE_RATE, duration_seconds: 0.0, frames: Vec::new() };
+    assert_eq!(empty.voiced_ratio(), 0.0);
+    assert!(empty.frame_at(0.0).is_none());
+}
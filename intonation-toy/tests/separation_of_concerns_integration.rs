@@ -66,6 +66,7 @@ fn test_model_processes_frequency_with_tuning_context() {
         volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
         pitch: Pitch::Detected(440.0, 0.95),
         fft_data: None,
+        polyphonic_pitches: Vec::new(),
         timestamp: 1.0,
     };
     
@@ -73,6 +74,8 @@ fn test_model_processes_frequency_with_tuning_context() {
         audio_analysis: Some(audio_analysis),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Process with default tuning (A root, Equal Temperament)
@@ -166,6 +169,7 @@ async fn test_tuning_changes_affect_only_model() {
         volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
         pitch: Pitch::Detected(test_frequency, 0.95),
         fft_data: None,
+        polyphonic_pitches: Vec::new(),
         timestamp: 1.0,
     };
     
@@ -174,6 +178,8 @@ async fn test_tuning_changes_affect_only_model() {
         audio_analysis: Some(audio_analysis),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Get initial model result with A root
@@ -214,10 +220,13 @@ fn test_layer_separation_boundaries() {
             volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: Pitch::Detected(440.0, 0.95), // Only raw frequency
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         }),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Engine result has no accuracy or note fields - verified by type system
@@ -239,7 +248,26 @@ fn test_layer_separation_boundaries() {
         closest_midi_note: 69,
         cents_offset: 1.0,
         interval_semitones: 0,
+        interval_steps: 0,
         root_note: 69,
+        reference_frequency: 440.0,
+        polyphonic_notes: Vec::new(),
+        spectrum: None,
+        matched_ratio: None,
+        stretch_tuning: pitch_toy::shared_types::StretchTuning::None,
+        inharmonicity: None,
+        key_detection: pitch_toy::shared_types::KeyDetection::Off,
+        key_estimate: None,
+        transposition: pitch_toy::shared_types::Transposition::CONCERT,
+        in_tune_tolerance_cents: 10.0,
+        intonation_state: None,
+        metronome: pitch_toy::shared_types::MetronomeConfig::default(),
+        onset_timings: Vec::new(),
+        note_events: Vec::new(),
+        current_note: None,
+        vibrato: None,
+        intonation_pitch: crate::shared_types::IntonationPitch::Instantaneous,
+        recording_analysis: None,
     };
     
     // Model result has musical fields - verified by type system
@@ -264,6 +292,8 @@ fn test_error_propagation_through_layers() {
             pitch_toy::shared_types::Error::MicrophonePermissionDenied,
         ],
         permission_state: PermissionState::Denied,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Process through model
@@ -300,10 +330,13 @@ fn test_volume_data_flow() {
             },
             pitch: Pitch::NotDetected,
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         }),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Process through model
@@ -327,10 +360,13 @@ fn test_sequential_tuning_context_changes() {
             volume_level: Volume { peak_amplitude: -10.0, rms_amplitude: -15.0 },
             pitch: Pitch::Detected(c5_frequency, 0.92),
             fft_data: None,
+            polyphonic_pitches: Vec::new(),
             timestamp: 1.0,
         }),
         audio_errors: Vec::new(),
         permission_state: PermissionState::Granted,
+        onset_timings: Vec::new(),
+        recording_analysis: None,
     };
     
     // Test with different root notes in sequence
@@ -11,11 +11,18 @@ pub fn initialize_theme(theme: Theme) {
 }
 
 pub fn get_current_theme() -> Theme {
-    *CURRENT_THEME
-        .get()
-        .expect("Theme not initialized")
-        .lock()
-        .unwrap()
+    *current_theme().lock().unwrap()
+}
+
+#[cfg(not(test))]
+fn current_theme() -> &'static Mutex<Theme> {
+    CURRENT_THEME.get().expect("Theme not initialized")
+}
+
+// Tests build presenters and scenes without going through app startup
+#[cfg(test)]
+fn current_theme() -> &'static Mutex<Theme> {
+    CURRENT_THEME.get_or_init(|| Mutex::new(crate::app_config::DEFAULT_THEME))
 }
 
 pub fn get_current_color_scheme() -> ColorScheme {
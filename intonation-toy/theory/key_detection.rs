@@ -0,0 +1,161 @@
+//! Key detection from a pitch-class histogram
+//!
+//! Detected pitches are accumulated into a histogram of the 12 pitch classes,
+//! weighted by how long and how clearly they sounded. The histogram is then
+//! correlated with the Krumhansl-Kessler key profiles rotated to each of the 12
+//! tonics, and the best matching major or minor key is the estimate.
+//!
+//! Only major and minor keys are matched, not the other `Scale` variants. The
+//! modes, pentatonic and blues scales share their notes with a major or minor
+//! key, and profiles for them fit a melody almost as well as that key does, which
+//! leaves too little confidence to tell any key apart. Melodies in those scales
+//! are reported as the major or minor key that fits best. Chromatic, whole tone
+//! and octatonic scales have no tonic to detect.
+//!
+//! Older evidence fades out with `decay()`, so the estimate follows modulations.
+
+use crate::shared_types::{KeyEstimate, Scale};
+
+/// Krumhansl-Kessler probe tone ratings for a major key, starting at the tonic
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+
+/// Krumhansl-Kessler probe tone ratings for a minor key, starting at the tonic
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Scales the profiles stand for, in the order they are tried; only major and minor, see above
+const PROFILES: [(Scale, &[f32; 12]); 2] = [(Scale::Major, &MAJOR_PROFILE), (Scale::Minor, &MINOR_PROFILE)];
+
+/// Weighted pitch-class histogram of the detected pitches
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyDetector {
+    /// Accumulated weight per pitch class, C = 0
+    histogram: [f32; 12],
+}
+
+impl KeyDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a detected pitch with the given weight, usually clarity times duration
+    ///
+    /// The pitch class is the nearest equal tempered note relative to A4 at
+    /// `reference_frequency`, so the histogram does not depend on the tuning system.
+    pub fn add(&mut self, frequency: f32, reference_frequency: f32, weight: f32) {
+        if frequency <= 0.0 || weight <= 0.0 {
+            return;
+        }
+        let midi_note = (69.0 + 12.0 * (frequency / reference_frequency).log2()).round() as i32;
+        self.histogram[midi_note.rem_euclid(12) as usize] += weight;
+    }
+
+    /// Scale all accumulated weights by `factor` (0.0-1.0)
+    pub fn decay(&mut self, factor: f32) {
+        for weight in &mut self.histogram {
+            *weight *= factor;
+        }
+    }
+
+    /// Sum of the accumulated weights
+    pub fn total_weight(&self) -> f32 {
+        self.histogram.iter().sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.histogram = [0.0; 12];
+    }
+
+    /// Best matching key, `None` while no pitch was added
+    ///
+    /// The confidence is the difference between the correlation of the best and
+    /// the runner-up key, which is small for ambiguous input such as a single
+    /// repeated note or relative major and minor keys that fit about equally.
+    pub fn estimate(&self) -> Option<KeyEstimate> {
+        if self.total_weight() <= 0.0 {
+            return None;
+        }
+
+        let mut candidates: Vec<KeyEstimate> = PROFILES
+            .iter()
+            .flat_map(|&(scale, profile)| (0..12u8).map(move |tonic| {
+                let rotated: [f32; 12] = std::array::from_fn(|pitch_class| profile[(pitch_class + 12 - tonic as usize) % 12]);
+                (tonic, scale, rotated)
+            }))
+            .map(|(tonic, scale, rotated)| KeyEstimate {
+                tonic,
+                scale,
+                correlation: pearson_correlation(&self.histogram, &rotated),
+                confidence: 0.0,
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
+
+        let runner_up_correlation = candidates[1].correlation;
+        let best = candidates[0];
+        Some(KeyEstimate { confidence: best.correlation - runner_up_correlation, ..best })
+    }
+}
+
+/// Pearson correlation coefficient of two series, 0.0 if either is constant
+fn pearson_correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a) * (x - mean_a);
+        variance_b += (y - mean_b) * (y - mean_b);
+    }
+    if variance_a <= 0.0 || variance_b <= 0.0 {
+        return 0.0;
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frequency of a MIDI note with A4 = 440 Hz
+    fn frequency(midi_note: i32) -> f32 {
+        440.0 * 2.0_f32.powf((midi_note - 69) as f32 / 12.0)
+    }
+
+    #[test]
+    fn test_estimate_major_key() {
+        let mut detector = KeyDetector::new();
+        assert_eq!(detector.estimate(), None);
+
+        // D major melody, tonic and dominant held longest
+        for (midi_note, seconds) in [(62, 2.0), (64, 0.5), (66, 1.0), (67, 0.5), (69, 1.5), (71, 0.5), (73, 0.5), (74, 1.0)] {
+            detector.add(frequency(midi_note), 440.0, seconds);
+        }
+        let estimate = detector.estimate().unwrap();
+        assert_eq!((estimate.tonic, estimate.scale), (2, Scale::Major));
+        assert!(estimate.confidence > 0.0);
+    }
+
+    #[test]
+    fn test_estimate_minor_key() {
+        let mut detector = KeyDetector::new();
+        // A harmonic minor line, the raised seventh points to A minor rather than C major
+        for (midi_note, seconds) in [(57, 2.0), (59, 0.5), (60, 1.0), (62, 0.5), (64, 1.5), (65, 0.5), (68, 1.0), (69, 1.0)] {
+            detector.add(frequency(midi_note), 440.0, seconds);
+        }
+        let estimate = detector.estimate().unwrap();
+        assert_eq!((estimate.tonic, estimate.scale), (9, Scale::Minor));
+    }
+
+    #[test]
+    fn test_decay_and_reference_frequency() {
+        let mut detector = KeyDetector::new();
+        // A4 = 415 Hz puts the same pitch classes a semitone lower in frequency
+        detector.add(415.0, 415.0, 1.0);
+        assert_eq!(detector.histogram[9], 1.0);
+
+        detector.decay(0.5);
+        assert_eq!(detector.total_weight(), 0.5);
+        detector.reset();
+        assert_eq!(detector.estimate(), None);
+    }
+}
@@ -1 +1,8 @@
-pub mod tuning;
\ No newline at end of file
+pub mod tuning;
+pub mod tuning_systems;
+pub mod scala;
+pub mod note_naming;
+pub mod stretch;
+pub mod key_detection;
+pub mod note_segmentation;
+pub mod vibrato;
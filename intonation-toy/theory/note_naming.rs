@@ -0,0 +1,304 @@
+//! Key-aware note spelling and note naming systems
+//!
+//! A MIDI note only identifies a pitch class and octave, so the same note can be
+//! spelled D#4 or Eb4. The spelling is chosen from the root note and scale:
+//!
+//! - The root is spelled as in the key signature of the scale's parent major key,
+//!   e.g. a minor scale on pitch class 3 is spelled D#, its relative major is F#.
+//! - Seven-note scales give every degree its own letter, so the raised seventh of
+//!   harmonic minor on D is C#, never Db.
+//! - Any other note is spelled as a degree of the major scale on the root:
+//!   b2, b3, #4, b6 and b7.
+//!
+//! The spelled note is then written in one of the `NoteNamingSystem`s. Accidentals
+//! are written as `#` and `b` to match `midi_note_to_name`.
+
+use crate::shared_types::{MidiNote, NoteNamingSystem, Scale, Transposition};
+
+/// Letter names, indexed by letter (C = 0 ... B = 6)
+const LETTERS: [&str; 7] = ["C", "D", "E", "F", "G", "A", "B"];
+
+/// Fixed-do syllables, indexed by letter
+const FIXED_DO_SYLLABLES: [&str; 7] = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"];
+
+/// Semitones of the natural notes above C, indexed by letter
+const NATURAL_SEMITONES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// Semitones of the major scale degrees above the root, indexed by degree (1 = 0 ... 7 = 6)
+const MAJOR_SEMITONES: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// Scale degree (0-based) and alteration of each semitone above the root for notes outside the scale
+const CHROMATIC_DEGREES: [(i32, i32); 12] = [
+    (0, 0), (1, -1), (1, 0), (2, -1), (2, 0), (3, 0),
+    (3, 1), (4, 0), (5, -1), (5, 0), (6, -1), (6, 0),
+];
+
+/// Sharps (positive) or flats (negative) in the major key on each pitch class
+const MAJOR_KEY_SIGNATURES: [i32; 12] = [0, -5, 2, -3, 4, -1, 6, 1, -4, 3, -2, 5];
+
+/// Movable-do syllables per degree for a lowered, natural and raised degree
+const MOVABLE_DO_SYLLABLES: [[&str; 3]; 7] = [
+    ["De", "Do", "Di"],
+    ["Ra", "Re", "Ri"],
+    ["Me", "Mi", "Mi#"],
+    ["Fab", "Fa", "Fi"],
+    ["Se", "Sol", "Si"],
+    ["Le", "La", "Li"],
+    ["Te", "Ti", "Ti#"],
+];
+
+/// A note spelled with a letter, an accidental and an octave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpelledNote {
+    /// Letter index, C = 0 ... B = 6
+    pub letter: usize,
+    /// Sharps (positive) or flats (negative)
+    pub accidental: i32,
+    /// Scientific pitch notation octave, which follows the letter: B#3 and C4 sound the same
+    pub octave: i32,
+}
+
+impl SpelledNote {
+    /// Spell a MIDI note with the given letter
+    fn with_letter(midi_note: MidiNote, letter: usize) -> Self {
+        let semitones = midi_note as i32;
+        let accidental = (semitones - NATURAL_SEMITONES[letter] + 6).rem_euclid(12) - 6;
+        let octave = (semitones - accidental - NATURAL_SEMITONES[letter]).div_euclid(12) - 1;
+        Self { letter, accidental, octave }
+    }
+}
+
+/// Spell a MIDI note for the given root note and scale
+pub fn spell_note(midi_note: MidiNote, root_note: MidiNote, scale: Scale) -> SpelledNote {
+    let tonic = spell_root(root_note, scale);
+    let semitones_above_root = (midi_note as i32 - root_note as i32).rem_euclid(12);
+
+    // Seven-note scales use every letter once
+    if let Some(degree) = heptatonic_degree(scale, tonic, semitones_above_root) {
+        return SpelledNote::with_letter(midi_note, (tonic.letter + degree) % 7);
+    }
+
+    let (degree, _) = CHROMATIC_DEGREES[semitones_above_root as usize];
+    SpelledNote::with_letter(midi_note, (tonic.letter + degree as usize) % 7)
+}
+
+/// Name of a MIDI note for the given root note and scale
+///
+/// Absolute naming systems include the octave number, relative ones do not.
+pub fn note_name(midi_note: MidiNote, root_note: MidiNote, scale: Scale, system: NoteNamingSystem) -> String {
+    let note = spell_note(midi_note, root_note, scale);
+    match system {
+        NoteNamingSystem::Letter => {
+            format!("{}{}{}", LETTERS[note.letter], accidental_suffix(note.accidental), note.octave)
+        }
+        NoteNamingSystem::FixedDo => {
+            format!("{}{}{}", FIXED_DO_SYLLABLES[note.letter], accidental_suffix(note.accidental), note.octave)
+        }
+        NoteNamingSystem::German => format!("{}{}", german_name(note), note.octave),
+        NoteNamingSystem::MovableDo => {
+            let (degree, alteration) = degree_relative_to_root(note, midi_note, root_note, scale);
+            match alteration {
+                -1..=1 => MOVABLE_DO_SYLLABLES[degree][(alteration + 1) as usize].to_string(),
+                _ => format!("{}{}", MOVABLE_DO_SYLLABLES[degree][1], accidental_suffix(alteration)),
+            }
+        }
+        NoteNamingSystem::Nashville => {
+            let (degree, alteration) = degree_relative_to_root(note, midi_note, root_note, scale);
+            format!("{}{}", accidental_suffix(alteration), degree + 1)
+        }
+    }
+}
+
+/// Name of a sounding MIDI note in the written pitch of a transposing instrument
+///
+/// Both the note and the root are transposed, so the note is spelled in the
+/// written key, e.g. concert E♭ major is written F major for a B♭ clarinet.
+pub fn written_note_name(
+    midi_note: MidiNote,
+    root_note: MidiNote,
+    scale: Scale,
+    system: NoteNamingSystem,
+    transposition: Transposition,
+) -> String {
+    note_name(transposition.written(midi_note), transposition.written(root_note), scale, system)
+}
+
+/// Spelling of the root in the key signature of the scale's parent major key
+fn spell_root(root_note: MidiNote, scale: Scale) -> SpelledNote {
+    let parent_major = (root_note as i32 + parent_major_offset(scale)).rem_euclid(12);
+    let sharps = MAJOR_KEY_SIGNATURES[parent_major as usize];
+
+    // The parent major tonic is `sharps` fifths from C, each fifth is four letters up
+    let parent_letter = (sharps * 4).rem_euclid(7) as usize;
+    let semitones_to_root = (root_note as i32 - parent_major).rem_euclid(12);
+    let letters_to_root = MAJOR_SEMITONES
+        .iter()
+        .position(|&semitones| semitones == semitones_to_root)
+        .unwrap_or(0);
+    SpelledNote::with_letter(root_note, (parent_letter + letters_to_root) % 7)
+}
+
+/// Semitones from the root up to the tonic of the major key whose signature the scale uses
+fn parent_major_offset(scale: Scale) -> i32 {
+    match scale {
+        Scale::Major | Scale::MajorPentatonic | Scale::Chromatic | Scale::WholeTone
+        | Scale::OctatonicWholeHalf | Scale::OctatonicHalfWhole => 0,
+        Scale::Minor | Scale::MinorPentatonic | Scale::HarmonicMinor | Scale::MelodicMinor | Scale::Blues => 3,
+        Scale::Dorian => 10,
+        Scale::Phrygian => 8,
+        Scale::Lydian => 7,
+        Scale::Mixolydian => 5,
+        Scale::Locrian => 1,
+        Scale::Custom(_) => {
+            // Minor third without a major third reads as a minor key
+            let pattern = scale.pattern();
+            if pattern[3] && !pattern[4] { 3 } else { 0 }
+        }
+    }
+}
+
+/// Degree (0-based) of a note in a seven-note scale
+///
+/// Returns `None` for scales without seven notes, notes outside the scale, and
+/// scales whose degrees cannot all be spelled with at most a double accidental.
+fn heptatonic_degree(scale: Scale, tonic: SpelledNote, semitones_above_root: i32) -> Option<usize> {
+    let degrees: Vec<i32> = (0..12).filter(|&semitone| scale.pattern()[semitone as usize]).collect();
+    if degrees.len() != 7 {
+        return None;
+    }
+
+    let tonic_semitones = NATURAL_SEMITONES[tonic.letter] + tonic.accidental;
+    let spellable = degrees.iter().enumerate().all(|(degree, semitones)| {
+        let letter = (tonic.letter + degree) % 7;
+        let accidental = (tonic_semitones + semitones - NATURAL_SEMITONES[letter] + 6).rem_euclid(12) - 6;
+        accidental.abs() <= 2
+    });
+    if !spellable {
+        return None;
+    }
+    degrees.iter().position(|&semitones| semitones == semitones_above_root)
+}
+
+/// Major scale degree (0-based) of a spelled note relative to the root, with its alteration
+fn degree_relative_to_root(note: SpelledNote, midi_note: MidiNote, root_note: MidiNote, scale: Scale) -> (usize, i32) {
+    let tonic = spell_root(root_note, scale);
+    let degree = (note.letter + 7 - tonic.letter) % 7;
+    let semitones_above_root = (midi_note as i32 - root_note as i32).rem_euclid(12);
+    let alteration = (semitones_above_root - MAJOR_SEMITONES[degree] + 6).rem_euclid(12) - 6;
+    (degree, alteration)
+}
+
+fn accidental_suffix(accidental: i32) -> String {
+    if accidental >= 0 {
+        "#".repeat(accidental as usize)
+    } else {
+        "b".repeat(accidental.unsigned_abs() as usize)
+    }
+}
+
+/// German note name without octave: H for B, B for Bb, "is" for sharps and "es" for flats
+fn german_name(note: SpelledNote) -> String {
+    let letter = LETTERS[note.letter];
+    match (letter, note.accidental) {
+        ("B", 0) => "H".to_string(),
+        ("B", -1) => "B".to_string(),
+        ("B", accidental) if accidental < 0 => format!("H{}", "es".repeat(accidental.unsigned_abs() as usize)),
+        // Es and As drop the doubled vowel
+        ("E" | "A", accidental) if accidental < 0 => format!("{}s{}", letter, "es".repeat(accidental.unsigned_abs() as usize - 1)),
+        (letter, accidental) if accidental < 0 => format!("{}{}", letter, "es".repeat(accidental.unsigned_abs() as usize)),
+        ("B", accidental) => format!("H{}", "is".repeat(accidental as usize)),
+        (letter, accidental) => format!("{}{}", letter, "is".repeat(accidental as usize)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(root_note: MidiNote, scale: Scale, system: NoteNamingSystem) -> Vec<String> {
+        (0..12).map(|semitone| note_name(root_note + semitone, root_note, scale, system)).collect()
+    }
+
+    #[test]
+    fn test_root_spelling_follows_key_signature() {
+        let root = |root_note, scale| note_name(root_note, root_note, scale, NoteNamingSystem::Letter);
+        assert_eq!(root(61, Scale::Major), "Db4");
+        assert_eq!(root(61, Scale::Minor), "C#4");
+        assert_eq!(root(63, Scale::Major), "Eb4");
+        assert_eq!(root(63, Scale::Minor), "D#4");
+        assert_eq!(root(66, Scale::Major), "F#4");
+        assert_eq!(root(68, Scale::Minor), "G#4");
+        assert_eq!(root(70, Scale::Minor), "Bb4");
+        assert_eq!(root(70, Scale::Dorian), "Bb4");
+    }
+
+    #[test]
+    fn test_heptatonic_scales_use_every_letter() {
+        let in_scale = |root_note: MidiNote, scale: Scale| -> Vec<String> {
+            (0..12)
+                .filter(|&semitone| scale.pattern()[semitone as usize])
+                .map(|semitone| note_name(root_note + semitone, root_note, scale, NoteNamingSystem::Letter))
+                .collect()
+        };
+        assert_eq!(in_scale(63, Scale::Major), ["Eb4", "F4", "G4", "Ab4", "Bb4", "C5", "D5"]);
+        assert_eq!(in_scale(62, Scale::HarmonicMinor), ["D4", "E4", "F4", "G4", "A4", "Bb4", "C#5"]);
+        assert_eq!(in_scale(68, Scale::HarmonicMinor), ["G#4", "A#4", "B4", "C#5", "D#5", "E5", "F##5"]);
+        assert_eq!(in_scale(66, Scale::Major), ["F#4", "G#4", "A#4", "B4", "C#5", "D#5", "E#5"]);
+    }
+
+    #[test]
+    fn test_chromatic_notes_and_octaves() {
+        assert_eq!(
+            names(57, Scale::Chromatic, NoteNamingSystem::Letter),
+            ["A3", "Bb3", "B3", "C4", "C#4", "D4", "D#4", "E4", "F4", "F#4", "G4", "G#4"]
+        );
+        // The octave number follows the letter
+        assert_eq!(note_name(59, 66, Scale::Major, NoteNamingSystem::Letter), "B3");
+        assert_eq!(note_name(59, 61, Scale::Minor, NoteNamingSystem::Letter), "B3");
+        assert_eq!(note_name(59, 68, Scale::HarmonicMinor, NoteNamingSystem::Letter), "B3");
+        assert_eq!(note_name(60, 66, Scale::Major, NoteNamingSystem::Letter), "B#3");
+        assert_eq!(note_name(59, 63, Scale::Minor, NoteNamingSystem::Letter), "B3");
+        assert_eq!(note_name(71, 61, Scale::Major, NoteNamingSystem::Letter), "Cb5");
+    }
+
+    #[test]
+    fn test_fixed_do_and_german() {
+        assert_eq!(note_name(63, 63, Scale::Major, NoteNamingSystem::FixedDo), "Mib4");
+        assert_eq!(note_name(67, 60, Scale::Major, NoteNamingSystem::FixedDo), "Sol4");
+        assert_eq!(
+            names(58, Scale::Major, NoteNamingSystem::German),
+            ["B3", "Ces4", "C4", "Des4", "D4", "Es4", "E4", "F4", "Ges4", "G4", "As4", "A4"]
+        );
+        assert_eq!(note_name(66, 66, Scale::Major, NoteNamingSystem::German), "Fis4");
+        assert_eq!(note_name(71, 66, Scale::Major, NoteNamingSystem::German), "H4");
+        assert_eq!(note_name(71, 61, Scale::Major, NoteNamingSystem::German), "Ces5");
+    }
+
+    #[test]
+    fn test_written_note_names() {
+        // Concert Eb major is F major for a Bb clarinet and C major for an Eb alto saxophone
+        assert_eq!(written_note_name(63, 63, Scale::Major, NoteNamingSystem::Letter, Transposition::B_FLAT), "F4");
+        assert_eq!(written_note_name(70, 63, Scale::Major, NoteNamingSystem::Letter, Transposition::B_FLAT), "C5");
+        assert_eq!(written_note_name(63, 63, Scale::Major, NoteNamingSystem::Letter, Transposition::E_FLAT_ALTO), "C5");
+        // Concert Bb in E major is written in B major for an F horn, where F is spelled E#
+        assert_eq!(written_note_name(58, 64, Scale::Major, NoteNamingSystem::Letter, Transposition::F), "E#4");
+        assert_eq!(written_note_name(69, 57, Scale::Major, NoteNamingSystem::Letter, Transposition::CONCERT), "A4");
+    }
+
+    #[test]
+    fn test_relative_naming_systems() {
+        assert_eq!(
+            names(62, Scale::Chromatic, NoteNamingSystem::MovableDo),
+            ["Do", "Ra", "Re", "Me", "Mi", "Fa", "Fi", "Sol", "Le", "La", "Te", "Ti"]
+        );
+        assert_eq!(
+            names(62, Scale::Chromatic, NoteNamingSystem::Nashville),
+            ["1", "b2", "2", "b3", "3", "4", "#4", "5", "b6", "6", "b7", "7"]
+        );
+        // Harmonic minor's raised seventh is Ti, its minor third Me
+        assert_eq!(note_name(73, 62, Scale::HarmonicMinor, NoteNamingSystem::MovableDo), "Ti");
+        assert_eq!(note_name(65, 62, Scale::HarmonicMinor, NoteNamingSystem::MovableDo), "Me");
+        assert_eq!(note_name(68, 62, Scale::Lydian, NoteNamingSystem::Nashville), "#4");
+        assert_eq!(note_name(50, 62, Scale::Major, NoteNamingSystem::Nashville), "1");
+    }
+}
@@ -0,0 +1,244 @@
+//! Note segmentation of the frame-by-frame pitch
+//!
+//! Frames count as voiced when a pitch was detected with enough clarity and the
+//! input is loud enough. Consecutive voiced frames at about the same pitch form a
+//! note. A note ends when the input stays unvoiced for longer than a short
+//! dropout, or when the pitch moves away from the median of the note and stays
+//! there long enough to be the next note rather than a detection glitch.
+//!
+//! Notes shorter than `MIN_NOTE_SECONDS` are dropped.
+
+/// Lowest clarity of a frame that can be part of a note
+pub const MIN_NOTE_CLARITY: f32 = 0.8;
+
+/// Lowest RMS amplitude (0.0-1.0) of a frame that can be part of a note
+pub const MIN_NOTE_RMS_AMPLITUDE: f32 = 0.01;
+
+/// Distance from the median pitch of a note in cents beyond which a frame doesn't belong to it
+pub const NOTE_CHANGE_CENTS: f32 = 70.0;
+
+/// Shortest duration of a note in seconds
+pub const MIN_NOTE_SECONDS: f64 = 0.1;
+
+/// Longest run of unvoiced input in seconds that is bridged within a note
+pub const MAX_DROPOUT_SECONDS: f64 = 0.08;
+
+/// Summary of a segmented note
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoteSegment {
+    /// Time of the first frame of the note in seconds
+    pub start_time: f64,
+    /// Time of the last frame of the note in seconds
+    pub end_time: f64,
+    /// Median of the frame frequencies in Hz
+    pub median_frequency: f32,
+    /// Mean distance of the frames from the median frequency in cents
+    pub mean_cents_from_median: f32,
+    /// Standard deviation of the frame pitches in cents, 0.0 for a perfectly steady note
+    pub stability_cents: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct NoteFrame {
+    time: f64,
+    frequency: f32,
+}
+
+/// Groups voiced frames into notes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteSegmenter {
+    /// Frames of the sounding note, which may still be too short to count
+    frames: Vec<NoteFrame>,
+    /// Consecutive frames away from the pitch of the note, possibly the start of the next one
+    deviating: Vec<NoteFrame>,
+}
+
+impl NoteSegmenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one analysis frame and return the note it ended, if any
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time of the frame in seconds
+    /// * `pitch` - Detected frequency in Hz and its clarity, `None` when no pitch was detected
+    /// * `rms_amplitude` - RMS level of the input (0.0-1.0)
+    pub fn process(&mut self, time: f64, pitch: Option<(f32, f32)>, rms_amplitude: f32) -> Option<NoteSegment> {
+        let voiced = pitch.filter(|&(frequency, clarity)| {
+            frequency > 0.0 && clarity >= MIN_NOTE_CLARITY && rms_amplitude >= MIN_NOTE_RMS_AMPLITUDE
+        });
+        let last_voiced_time = self.deviating.last().or(self.frames.last()).map(|frame| frame.time);
+        let dropout_exceeded = last_voiced_time.is_some_and(|last_time| time - last_time > MAX_DROPOUT_SECONDS);
+
+        let Some((frequency, _)) = voiced else {
+            return if dropout_exceeded { self.finish() } else { None };
+        };
+        let frame = NoteFrame { time, frequency };
+
+        if dropout_exceeded {
+            let ended = self.finish();
+            self.frames.push(frame);
+            return ended;
+        }
+
+        if self.frames.is_empty() || cents(frequency, median_frequency(&self.frames)).abs() <= NOTE_CHANGE_CENTS {
+            // Back at the pitch of the note, so the deviating frames were a glitch
+            self.frames.push(frame);
+            self.deviating.clear();
+            return None;
+        }
+
+        if self.deviating.first().is_some_and(|first| cents(frequency, first.frequency).abs() > NOTE_CHANGE_CENTS) {
+            self.deviating.clear();
+        }
+        self.deviating.push(frame);
+        if time - self.deviating[0].time < MIN_NOTE_SECONDS {
+            return None;
+        }
+
+        let next_note = std::mem::take(&mut self.deviating);
+        let ended = self.finish();
+        self.frames = next_note;
+        ended
+    }
+
+    /// The note that is sounding, `None` between notes or while it is too short to count
+    pub fn current_note(&self) -> Option<NoteSegment> {
+        summarize(&self.frames)
+    }
+
+    pub fn reset(&mut self) {
+        self.frames.clear();
+        self.deviating.clear();
+    }
+
+    fn finish(&mut self) -> Option<NoteSegment> {
+        self.deviating.clear();
+        let frames = std::mem::take(&mut self.frames);
+        summarize(&frames)
+    }
+}
+
+fn summarize(frames: &[NoteFrame]) -> Option<NoteSegment> {
+    let (first, last) = (frames.first()?, frames.last()?);
+    if last.time - first.time < MIN_NOTE_SECONDS {
+        return None;
+    }
+
+    let median = median_frequency(frames);
+    let deviations: Vec<f32> = frames.iter().map(|frame| cents(frame.frequency, median)).collect();
+    let mean = deviations.iter().sum::<f32>() / deviations.len() as f32;
+    let variance = deviations.iter().map(|deviation| (deviation - mean) * (deviation - mean)).sum::<f32>() / deviations.len() as f32;
+
+    Some(NoteSegment {
+        start_time: first.time,
+        end_time: last.time,
+        median_frequency: median,
+        mean_cents_from_median: mean,
+        stability_cents: variance.sqrt(),
+    })
+}
+
+fn median_frequency(frames: &[NoteFrame]) -> f32 {
+    let mut frequencies: Vec<f32> = frames.iter().map(|frame| frame.frequency).collect();
+    frequencies.sort_by(f32::total_cmp);
+    let middle = frequencies.len() / 2;
+    if frequencies.len().is_multiple_of(2) {
+        (frequencies[middle - 1] + frequencies[middle]) / 2.0
+    } else {
+        frequencies[middle]
+    }
+}
+
+/// Distance from `reference` to `frequency` in cents
+fn cents(frequency: f32, reference: f32) -> f32 {
+    1200.0 * (frequency / reference).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_SECONDS: f64 = 1.0 / 60.0;
+
+    /// Feed `seconds` of frames starting at `start`, collecting the ended notes
+    fn feed(segmenter: &mut NoteSegmenter, start: f64, seconds: f64, pitch: Option<(f32, f32)>, rms_amplitude: f32) -> Vec<NoteSegment> {
+        let frames = (seconds / FRAME_SECONDS).round() as usize;
+        (0..frames)
+            .filter_map(|i| segmenter.process(start + i as f64 * FRAME_SECONDS, pitch, rms_amplitude))
+            .collect()
+    }
+
+    #[test]
+    fn test_note_ends_after_silence() {
+        let mut segmenter = NoteSegmenter::new();
+        assert!(feed(&mut segmenter, 0.0, 0.5, Some((440.0, 0.95)), 0.1).is_empty());
+        let current = segmenter.current_note().unwrap();
+        assert_eq!(current.start_time, 0.0);
+        assert_eq!(current.median_frequency, 440.0);
+
+        let notes = feed(&mut segmenter, 0.5, 0.3, None, 0.0);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].start_time, 0.0);
+        assert!((notes[0].end_time - (0.5 - FRAME_SECONDS)).abs() < 1e-9);
+        assert_eq!(notes[0].stability_cents, 0.0);
+        assert_eq!(segmenter.current_note(), None);
+    }
+
+    #[test]
+    fn test_short_dropouts_and_glitches_are_bridged() {
+        let mut segmenter = NoteSegmenter::new();
+        let mut notes = feed(&mut segmenter, 0.0, 0.3, Some((440.0, 0.95)), 0.1);
+        // Two quiet frames and a single octave error stay within the note
+        notes.extend(feed(&mut segmenter, 0.3, 2.0 * FRAME_SECONDS, Some((440.0, 0.95)), 0.001));
+        notes.extend(feed(&mut segmenter, 0.3 + 2.0 * FRAME_SECONDS, FRAME_SECONDS, Some((880.0, 0.95)), 0.1));
+        notes.extend(feed(&mut segmenter, 0.35, 0.3, Some((440.0, 0.95)), 0.1));
+        assert!(notes.is_empty());
+        assert_eq!(segmenter.current_note().unwrap().start_time, 0.0);
+    }
+
+    #[test]
+    fn test_pitch_change_starts_next_note() {
+        let mut segmenter = NoteSegmenter::new();
+        let mut notes = feed(&mut segmenter, 0.0, 0.5, Some((440.0, 0.95)), 0.1);
+        notes.extend(feed(&mut segmenter, 0.5, 0.5, Some((493.88, 0.95)), 0.1));
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].median_frequency, 440.0);
+        assert!(notes[0].end_time < 0.5);
+
+        // The next note starts at the first frame of the new pitch
+        let current = segmenter.current_note().unwrap();
+        assert!((current.start_time - 0.5).abs() < 1e-9);
+        assert_eq!(current.median_frequency, 493.88);
+    }
+
+    #[test]
+    fn test_unclear_quiet_and_short_input_is_not_a_note() {
+        let mut segmenter = NoteSegmenter::new();
+        let mut notes = feed(&mut segmenter, 0.0, 0.5, Some((440.0, 0.5)), 0.1);
+        notes.extend(feed(&mut segmenter, 1.0, 0.5, Some((440.0, 0.95)), 0.001));
+        notes.extend(feed(&mut segmenter, 2.0, 0.05, Some((440.0, 0.95)), 0.1));
+        notes.extend(feed(&mut segmenter, 2.05, 0.5, None, 0.0));
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn test_mean_and_stability() {
+        let mut segmenter = NoteSegmenter::new();
+        let sharp = 440.0 * 2.0_f32.powf(10.0 / 1200.0);
+        for i in 0..20 {
+            let frequency = if i % 2 == 0 { 440.0 } else { sharp };
+            segmenter.process(i as f64 * FRAME_SECONDS, Some((frequency, 0.9)), 0.1);
+        }
+        let note = segmenter.current_note().unwrap();
+        // Even count, so the median lies halfway between the two pitches
+        assert!((cents(note.median_frequency, 440.0) - 5.0).abs() < 0.1);
+        assert!(note.mean_cents_from_median.abs() < 0.1);
+        assert!((note.stability_cents - 5.0).abs() < 0.1);
+
+        segmenter.reset();
+        assert_eq!(segmenter.current_note(), None);
+    }
+}
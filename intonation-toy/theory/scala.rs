@@ -0,0 +1,644 @@
+//! Scala scale (`.scl`) and keyboard mapping (`.kbm`) file support
+//!
+//! Scala files are the de facto exchange format for microtonal tunings. A `.scl`
+//! file lists the pitches of one period of a scale, and an optional `.kbm` file
+//! maps keys to scale degrees and pins one key to a reference frequency.
+//!
+//! Parsing follows the format description at
+//! <https://www.huygens-fokker.org/scala/scl_format.html>:
+//! - Lines starting with `!` are comments
+//! - Pitches containing a `.` are cents, anything else is a ratio (`3/2` or `2`)
+//! - The implicit first degree is 1/1 and the last listed pitch is the period
+//!
+//! Imported tunings are registered with `register_custom_tuning()` and are then
+//! addressed through `TuningSystem::Custom`, like any built-in tuning.
+//!
+//! Scales are limited to `MAX_SCALA_NOTES` notes with an average step of at least
+//! `MIN_AVERAGE_STEP_CENTS`, which keeps the number of tuning lines in view bounded.
+
+use std::sync::{Mutex, OnceLock};
+use crate::shared_types::{MidiNote, TuningSystem};
+use super::tuning_systems::Tuning;
+
+/// Largest note count of a scale and largest size of a keyboard mapping
+pub const MAX_SCALA_NOTES: usize = 128;
+
+/// Smallest average step between the notes of a period in cents
+///
+/// A period must be at least `MIN_AVERAGE_STEP_CENTS` times the note count, so
+/// there are no more than 240 notes per octave.
+pub const MIN_AVERAGE_STEP_CENTS: f32 = 5.0;
+
+/// Longest tuning name taken from the description of a scale, in characters
+const MAX_NAME_CHARS: usize = 80;
+
+/// Errors for malformed Scala files
+///
+/// Line numbers are 1-based and refer to the line in the original file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScalaError {
+    /// The scale file has no description line
+    MissingDescription,
+    /// The scale file ends before the note count
+    MissingNoteCount,
+    /// The note count is not a non-negative integer
+    InvalidNoteCount { line: usize, text: String },
+    /// The note count or the keyboard mapping size is above `MAX_SCALA_NOTES`
+    TooManyNotes { line: usize, count: usize },
+    /// A pitch line is neither cents nor a positive ratio
+    InvalidPitch { line: usize, text: String },
+    /// The number of pitch lines differs from the declared note count
+    NoteCountMismatch { expected: usize, found: usize },
+    /// The scale declares zero notes, so it has no period
+    EmptyScale,
+    /// The period (last pitch, or the formal octave of a mapping) is not above the root
+    NonPositivePeriod(f32),
+    /// The period is smaller than `MIN_AVERAGE_STEP_CENTS` per note
+    PeriodTooSmall { period_cents: f32, note_count: usize },
+    /// The keyboard mapping file ends before a required header field
+    MissingMappingField(&'static str),
+    /// A keyboard mapping header field has an invalid value
+    InvalidMappingField { line: usize, field: &'static str, text: String },
+    /// A keyboard mapping entry is neither a scale degree nor `x`
+    InvalidMappingEntry { line: usize, text: String },
+    /// The keyboard mapping lists more entries than its declared size
+    MappingSizeMismatch { expected: usize, found: usize },
+    /// The middle note of the keyboard mapping is not mapped to a scale degree
+    UnmappedMiddleNote,
+    /// The reference note of the keyboard mapping is not mapped to a scale degree
+    UnmappedReferenceNote,
+    /// `MAX_CUSTOM_TUNINGS` different tunings have already been imported
+    TooManyCustomTunings,
+}
+
+impl std::fmt::Display for ScalaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScalaError::MissingDescription => write!(f, "Missing description line"),
+            ScalaError::MissingNoteCount => write!(f, "Missing note count"),
+            ScalaError::InvalidNoteCount { line, text } => write!(f, "Invalid note count on line {}: '{}'", line, text),
+            ScalaError::TooManyNotes { line, count } => write!(f, "Count of {} on line {} is above the maximum of {}", count, line, MAX_SCALA_NOTES),
+            ScalaError::InvalidPitch { line, text } => write!(f, "Invalid pitch on line {}: '{}'", line, text),
+            ScalaError::NoteCountMismatch { expected, found } => write!(f, "Expected {} pitches, found {}", expected, found),
+            ScalaError::EmptyScale => write!(f, "Scale has no notes"),
+            ScalaError::NonPositivePeriod(cents) => write!(f, "Period of {} cents is not above the root", cents),
+            ScalaError::PeriodTooSmall { period_cents, note_count } => {
+                write!(f, "Period of {} cents is too small for {} notes", period_cents, note_count)
+            }
+            ScalaError::MissingMappingField(field) => write!(f, "Missing keyboard mapping field: {}", field),
+            ScalaError::InvalidMappingField { line, field, text } => write!(f, "Invalid {} on line {}: '{}'", field, line, text),
+            ScalaError::InvalidMappingEntry { line, text } => write!(f, "Invalid mapping entry on line {}: '{}'", line, text),
+            ScalaError::MappingSizeMismatch { expected, found } => write!(f, "Expected at most {} mapping entries, found {}", expected, found),
+            ScalaError::UnmappedMiddleNote => write!(f, "Middle note is not mapped to a scale degree"),
+            ScalaError::UnmappedReferenceNote => write!(f, "Reference note is not mapped to a scale degree"),
+            ScalaError::TooManyCustomTunings => write!(f, "No more than {} tunings can be imported", MAX_CUSTOM_TUNINGS),
+        }
+    }
+}
+
+impl std::error::Error for ScalaError {}
+
+/// Check that a period is above the root and leaves room for its notes
+fn check_period(period_cents: f32, note_count: usize) -> Result<(), ScalaError> {
+    if period_cents <= 0.0 {
+        return Err(ScalaError::NonPositivePeriod(period_cents));
+    }
+    if period_cents < note_count as f32 * MIN_AVERAGE_STEP_CENTS {
+        return Err(ScalaError::PeriodTooSmall { period_cents, note_count });
+    }
+    Ok(())
+}
+
+/// Non-comment lines of a Scala file together with their 1-based line numbers
+fn content_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.starts_with('!'))
+}
+
+/// First whitespace separated token of a line, trailing text is a free-form comment
+fn first_token(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or("")
+}
+
+/// Parse a pitch token as cents (contains a `.`) or as a ratio (`n/d` or `n`)
+fn parse_pitch(token: &str) -> Option<f32> {
+    if token.contains('.') {
+        return token.parse::<f32>().ok().filter(|cents| cents.is_finite());
+    }
+
+    let (numerator, denominator) = match token.split_once('/') {
+        Some((numerator, denominator)) => (numerator, denominator),
+        None => (token, "1"),
+    };
+    let numerator: u64 = numerator.parse().ok()?;
+    let denominator: u64 = denominator.parse().ok()?;
+    if numerator == 0 || denominator == 0 {
+        return None;
+    }
+    Some(1200.0 * (numerator as f64 / denominator as f64).log2() as f32)
+}
+
+/// A scale parsed from a `.scl` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalaScale {
+    pub description: String,
+    /// Pitches of degrees 1..=n in cents above the root, the last one is the period
+    pub pitches: Vec<f32>,
+}
+
+impl ScalaScale {
+    /// Parse the contents of a `.scl` file
+    pub fn parse(text: &str) -> Result<Self, ScalaError> {
+        let mut lines = content_lines(text);
+
+        // The description may legitimately be empty, so blank lines are only skipped afterwards
+        let (_, description) = lines.next().ok_or(ScalaError::MissingDescription)?;
+        let mut lines = lines.filter(|(_, line)| !line.trim().is_empty());
+
+        let (count_line, count_text) = lines.next().ok_or(ScalaError::MissingNoteCount)?;
+        let note_count: usize = first_token(count_text).parse().map_err(|_| ScalaError::InvalidNoteCount {
+            line: count_line,
+            text: count_text.trim().to_string(),
+        })?;
+
+        if note_count > MAX_SCALA_NOTES {
+            return Err(ScalaError::TooManyNotes { line: count_line, count: note_count });
+        }
+
+        let mut pitches = Vec::new();
+        for (line, pitch_text) in lines {
+            let cents = parse_pitch(first_token(pitch_text)).ok_or_else(|| ScalaError::InvalidPitch {
+                line,
+                text: pitch_text.trim().to_string(),
+            })?;
+            pitches.push(cents);
+        }
+
+        if pitches.len() != note_count {
+            return Err(ScalaError::NoteCountMismatch { expected: note_count, found: pitches.len() });
+        }
+        let period = *pitches.last().ok_or(ScalaError::EmptyScale)?;
+        check_period(period, note_count)?;
+
+        Ok(Self {
+            description: description.trim().to_string(),
+            pitches,
+        })
+    }
+
+    /// Number of notes per period
+    pub fn note_count(&self) -> usize {
+        self.pitches.len()
+    }
+
+    /// Size of the period in cents (1200.0 for octave-repeating scales)
+    pub fn period_cents(&self) -> f32 {
+        self.pitches[self.pitches.len() - 1]
+    }
+
+    /// Cents above the root of a scale degree, continuing into higher and lower periods
+    pub fn degree_cents(&self, degree: i32) -> f32 {
+        let note_count = self.note_count() as i32;
+        let periods = degree.div_euclid(note_count);
+        let index = degree.rem_euclid(note_count) as usize;
+        let cents_in_period = if index == 0 { 0.0 } else { self.pitches[index - 1] };
+        cents_in_period + periods as f32 * self.period_cents()
+    }
+}
+
+/// A keyboard mapping parsed from a `.kbm` file
+///
+/// The first and last note describe the retuned key range of a synthesizer and
+/// are kept for completeness only, the tuning lines follow the visible range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardMapping {
+    pub first_note: MidiNote,
+    pub last_note: MidiNote,
+    /// Key that the first mapping entry applies to, becomes the root note
+    pub middle_note: MidiNote,
+    pub reference_note: MidiNote,
+    pub reference_frequency: f32,
+    /// Scale degree that is repeated every `mapping.len()` keys
+    pub octave_degree: usize,
+    /// Scale degree per key, starting at the middle note (`None` for unmapped keys)
+    ///
+    /// An empty mapping is the linear mapping, where every key is the next scale degree.
+    pub mapping: Vec<Option<usize>>,
+}
+
+impl KeyboardMapping {
+    /// Parse the contents of a `.kbm` file
+    pub fn parse(text: &str) -> Result<Self, ScalaError> {
+        let mut lines = content_lines(text).filter(|(_, line)| !line.trim().is_empty());
+
+        let mut next_field = |field: &'static str| -> Result<(usize, &str), ScalaError> {
+            lines.next()
+                .map(|(line, text)| (line, first_token(text)))
+                .ok_or(ScalaError::MissingMappingField(field))
+        };
+        fn invalid(line: usize, field: &'static str, text: &str) -> ScalaError {
+            ScalaError::InvalidMappingField { line, field, text: text.to_string() }
+        }
+        fn midi_note(line: usize, field: &'static str, text: &str) -> Result<MidiNote, ScalaError> {
+            text.parse::<MidiNote>().ok()
+                .filter(|note| crate::shared_types::is_valid_midi_note(*note))
+                .ok_or_else(|| invalid(line, field, text))
+        }
+
+        let (line, text) = next_field("map size")?;
+        let map_size: usize = text.parse().map_err(|_| invalid(line, "map size", text))?;
+        if map_size > MAX_SCALA_NOTES {
+            return Err(ScalaError::TooManyNotes { line, count: map_size });
+        }
+        let (line, text) = next_field("first note")?;
+        let first_note = midi_note(line, "first note", text)?;
+        let (line, text) = next_field("last note")?;
+        let last_note = midi_note(line, "last note", text)?;
+        if first_note > last_note {
+            return Err(invalid(line, "last note", text));
+        }
+        let (line, text) = next_field("middle note")?;
+        let middle_note = midi_note(line, "middle note", text)?;
+        let (line, text) = next_field("reference note")?;
+        let reference_note = midi_note(line, "reference note", text)?;
+        let (line, text) = next_field("reference frequency")?;
+        let reference_frequency: f32 = text.parse().ok()
+            .filter(|frequency: &f32| frequency.is_finite() && *frequency > 0.0)
+            .ok_or_else(|| invalid(line, "reference frequency", text))?;
+        let (line, text) = next_field("octave degree")?;
+        let octave_degree: usize = text.parse().map_err(|_| invalid(line, "octave degree", text))?;
+
+        let mut mapping = Vec::new();
+        for (line, entry_text) in lines {
+            let entry = first_token(entry_text);
+            if entry.eq_ignore_ascii_case("x") {
+                mapping.push(None);
+            } else {
+                let degree = entry.parse::<usize>().map_err(|_| ScalaError::InvalidMappingEntry {
+                    line,
+                    text: entry_text.trim().to_string(),
+                })?;
+                mapping.push(Some(degree));
+            }
+        }
+
+        if mapping.len() > map_size {
+            return Err(ScalaError::MappingSizeMismatch { expected: map_size, found: mapping.len() });
+        }
+        // Keys without an entry are unmapped
+        mapping.resize(map_size, None);
+
+        Ok(Self {
+            first_note,
+            last_note,
+            middle_note,
+            reference_note,
+            reference_frequency,
+            octave_degree,
+            mapping,
+        })
+    }
+
+    /// Cents above the middle note of the key `offset` keys away from it
+    fn key_cents(&self, scale: &ScalaScale, offset: i32) -> Option<f32> {
+        if self.mapping.is_empty() {
+            return Some(scale.degree_cents(offset));
+        }
+
+        let map_size = self.mapping.len() as i32;
+        let repeats = offset.div_euclid(map_size);
+        let degree = self.mapping[offset.rem_euclid(map_size) as usize]?;
+        let middle_degree = self.mapping[0]?;
+        let formal_octave = scale.degree_cents(self.octave_degree as i32);
+        Some(scale.degree_cents(degree as i32) - scale.degree_cents(middle_degree as i32) + repeats as f32 * formal_octave)
+    }
+
+    /// Frequency of the middle note, derived from the reference note and frequency
+    pub fn middle_note_frequency(&self, scale: &ScalaScale) -> Result<f32, ScalaError> {
+        if self.mapping.first().is_some_and(|entry| entry.is_none()) {
+            return Err(ScalaError::UnmappedMiddleNote);
+        }
+        let offset = self.reference_note as i32 - self.middle_note as i32;
+        let cents = self.key_cents(scale, offset).ok_or(ScalaError::UnmappedReferenceNote)?;
+        Ok(self.reference_frequency / 2.0_f32.powf(cents / 1200.0))
+    }
+}
+
+/// A user-defined tuning imported from Scala files
+///
+/// Degree 0 is the root and the pattern repeats every `period_cents`, which is
+/// not necessarily an octave. With a keyboard mapping, the degrees are the
+/// mapped keys of one mapping pattern, so unmapped keys produce no tuning lines.
+///
+/// Scala files may list pitches out of order or beyond the period, and mappings
+/// may map keys to lower degrees than the middle note. The degrees are therefore
+/// brought into the first period and sorted, as the interval search expects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalaTuning {
+    name: String,
+    /// Cents above the root per degree, starting with 0.0 for the root
+    degree_cents: Vec<f32>,
+    period_cents: f32,
+}
+
+impl ScalaTuning {
+    /// Tuning with one degree per scale note
+    pub fn new(scale: &ScalaScale) -> Self {
+        let period_cents = scale.period_cents();
+        let degree_cents = (0..scale.note_count() as i32).map(|degree| scale.degree_cents(degree));
+        Self {
+            name: Self::name_for(scale),
+            degree_cents: sorted_degrees(degree_cents, period_cents),
+            period_cents,
+        }
+    }
+
+    /// Tuning with one degree per mapped key of a keyboard mapping
+    pub fn with_keyboard_mapping(scale: &ScalaScale, keyboard_mapping: &KeyboardMapping) -> Result<Self, ScalaError> {
+        if keyboard_mapping.mapping.is_empty() {
+            return Ok(Self::new(scale));
+        }
+        if keyboard_mapping.mapping[0].is_none() {
+            return Err(ScalaError::UnmappedMiddleNote);
+        }
+
+        let period_cents = keyboard_mapping.key_cents(scale, keyboard_mapping.mapping.len() as i32)
+            .ok_or(ScalaError::UnmappedMiddleNote)?;
+        check_period(period_cents, keyboard_mapping.mapping.len())?;
+
+        let degree_cents = (0..keyboard_mapping.mapping.len() as i32)
+            .filter_map(|key| keyboard_mapping.key_cents(scale, key));
+        Ok(Self {
+            name: Self::name_for(scale),
+            degree_cents: sorted_degrees(degree_cents, period_cents),
+            period_cents,
+        })
+    }
+
+    fn name_for(scale: &ScalaScale) -> String {
+        if scale.description.is_empty() {
+            "Scala Tuning".to_string()
+        } else {
+            scale.description.chars().take(MAX_NAME_CHARS).collect()
+        }
+    }
+}
+
+/// Degrees brought into the first period, in ascending order without duplicates
+///
+/// Pitches at a whole number of periods fall onto the root.
+fn sorted_degrees(degree_cents: impl Iterator<Item = f32>, period_cents: f32) -> Vec<f32> {
+    let mut degrees: Vec<f32> = degree_cents.map(|cents| cents.rem_euclid(period_cents)).collect();
+    degrees.sort_by(f32::total_cmp);
+    degrees.dedup_by(|a, b| (*a - *b).abs() < 1e-3);
+    // A pitch just below a whole period rounds onto it, it is the next root
+    if degrees.last().is_some_and(|&last| period_cents - last < 1e-3) {
+        degrees.pop();
+    }
+    degrees
+}
+
+impl Tuning for ScalaTuning {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn degrees_per_octave(&self) -> usize {
+        self.degree_cents.len()
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        self.degree_cents[degree]
+    }
+
+    fn period_cents(&self) -> f32 {
+        self.period_cents
+    }
+}
+
+/// Largest number of distinct imported tunings, one per id of `TuningSystem::Custom`
+pub const MAX_CUSTOM_TUNINGS: usize = 32;
+
+/// Imported tunings, indexed by the id in `TuningSystem::Custom`
+///
+/// The registry owns a fixed number of slots. A slot is filled once and never
+/// replaced, so its tuning can be handed out as `&'static dyn Tuning` like the
+/// built-in tunings, and the registry never grows beyond `MAX_CUSTOM_TUNINGS`
+/// tunings of at most `MAX_SCALA_NOTES` degrees.
+static CUSTOM_TUNINGS: [OnceLock<ScalaTuning>; MAX_CUSTOM_TUNINGS] = [const { OnceLock::new() }; MAX_CUSTOM_TUNINGS];
+
+/// Held while registering, so two registrations don't pick the same slot
+static REGISTRATION: Mutex<()> = Mutex::new(());
+
+/// Register an imported tuning and return the tuning system that refers to it
+///
+/// A tuning identical to one that is already registered gets the existing id, so
+/// importing the same files again doesn't use up another slot.
+pub fn register_custom_tuning(tuning: ScalaTuning) -> Result<TuningSystem, ScalaError> {
+    let _registration = REGISTRATION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for (id, slot) in CUSTOM_TUNINGS.iter().enumerate() {
+        match slot.get() {
+            Some(registered) if *registered == tuning => return Ok(TuningSystem::Custom(id as u16)),
+            Some(_) => continue,
+            None => {
+                let _ = slot.set(tuning);
+                return Ok(TuningSystem::Custom(id as u16));
+            }
+        }
+    }
+    Err(ScalaError::TooManyCustomTunings)
+}
+
+/// Look up a registered tuning by id
+pub fn custom_tuning(id: u16) -> Option<&'static ScalaTuning> {
+    CUSTOM_TUNINGS.get(id as usize)?.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEANTONE_SCL: &str = include_str!("scala_files/meantone_quarter_comma.scl");
+    const JUST_SCL: &str = include_str!("scala_files/just_12.scl");
+    const EDO_19_SCL: &str = include_str!("scala_files/edo_19.scl");
+    const BOHLEN_PIERCE_SCL: &str = include_str!("scala_files/bohlen_pierce.scl");
+    const STANDARD_KBM: &str = include_str!("scala_files/standard.kbm");
+    const WHITE_KEYS_KBM: &str = include_str!("scala_files/white_keys.kbm");
+    const MAJOR_SCL: &str = include_str!("scala_files/major_7.scl");
+
+    #[test]
+    fn test_parse_cents_scale() {
+        let scale = ScalaScale::parse(MEANTONE_SCL).unwrap();
+        assert_eq!(scale.description, "1/4-comma meantone, Eb to G#");
+        assert_eq!(scale.note_count(), 12);
+        assert!((scale.pitches[3] - 386.314).abs() < 0.001);
+        assert_eq!(scale.period_cents(), 1200.0);
+    }
+
+    #[test]
+    fn test_parse_ratio_scale_matches_builtin_just_intonation() {
+        let scale = ScalaScale::parse(JUST_SCL).unwrap();
+        let tuning = ScalaTuning::new(&scale);
+        let builtin = TuningSystem::JustIntonation.tuning();
+        assert_eq!(tuning.degrees_per_octave(), 12);
+        for degree in 0..12 {
+            assert!((tuning.degree_cents(degree) - builtin.degree_cents(degree)).abs() < 0.01, "degree {}", degree);
+        }
+    }
+
+    #[test]
+    fn test_non_twelve_tone_scales() {
+        let edo_19 = ScalaTuning::new(&ScalaScale::parse(EDO_19_SCL).unwrap());
+        assert_eq!(edo_19.degrees_per_octave(), 19);
+        assert!((edo_19.interval_frequency(220.0, 19) - 440.0).abs() < 0.01);
+        assert!((edo_19.degree_cents(11) - 694.737).abs() < 0.01);
+
+        // Bohlen-Pierce repeats at the tritave (3:1) instead of the octave
+        let bohlen_pierce = ScalaTuning::new(&ScalaScale::parse(BOHLEN_PIERCE_SCL).unwrap());
+        assert_eq!(bohlen_pierce.degrees_per_octave(), 13);
+        assert!((bohlen_pierce.interval_frequency(100.0, 13) - 300.0).abs() < 0.01);
+        assert!((bohlen_pierce.interval_frequency(100.0, -13) - 100.0 / 3.0).abs() < 0.01);
+        let interval = bohlen_pierce.frequency_to_interval(100.0, 300.0 * 7.0 / 5.0);
+        assert_eq!(interval.semitones, 13 + 4);
+        assert!(interval.cents.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_keyboard_mapping() {
+        let mapping = KeyboardMapping::parse(STANDARD_KBM).unwrap();
+        assert_eq!(mapping.middle_note, 60);
+        assert_eq!(mapping.reference_note, 69);
+        assert_eq!(mapping.reference_frequency, 440.0);
+        assert_eq!(mapping.octave_degree, 12);
+        assert_eq!(mapping.mapping.len(), 12);
+        assert!(mapping.mapping.iter().enumerate().all(|(key, degree)| *degree == Some(key)));
+
+        // Middle C in Just Intonation is a pure major sixth below A4
+        let scale = ScalaScale::parse(JUST_SCL).unwrap();
+        let middle = mapping.middle_note_frequency(&scale).unwrap();
+        assert!((middle - 440.0 * 3.0 / 5.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_keyboard_mapping_with_unmapped_keys() {
+        let scale = ScalaScale::parse(MAJOR_SCL).unwrap();
+        let mapping = KeyboardMapping::parse(WHITE_KEYS_KBM).unwrap();
+        assert_eq!(mapping.mapping.iter().filter(|entry| entry.is_none()).count(), 5);
+
+        let tuning = ScalaTuning::with_keyboard_mapping(&scale, &mapping).unwrap();
+        assert_eq!(tuning.degrees_per_octave(), 7);
+        assert_eq!(tuning.period_cents(), 1200.0);
+        assert!((tuning.degree_cents(4) - 701.955).abs() < 0.01);
+
+        // The reference A is the sixth degree, a 5/3 above the middle C
+        let middle = mapping.middle_note_frequency(&scale).unwrap();
+        assert!((middle - 440.0 * 3.0 / 5.0).abs() < 0.01);
+
+        // Reference on an unmapped black key
+        let mut unmapped_reference = mapping.clone();
+        unmapped_reference.reference_note = 61;
+        assert_eq!(unmapped_reference.middle_note_frequency(&scale), Err(ScalaError::UnmappedReferenceNote));
+    }
+
+    #[test]
+    fn test_malformed_scale_files() {
+        assert_eq!(
+            ScalaScale::parse(include_str!("scala_files/malformed_count.scl")),
+            Err(ScalaError::NoteCountMismatch { expected: 5, found: 4 })
+        );
+        assert_eq!(
+            ScalaScale::parse(include_str!("scala_files/malformed_pitch.scl")),
+            Err(ScalaError::InvalidPitch { line: 6, text: "3/0 division by zero".to_string() })
+        );
+        assert_eq!(ScalaScale::parse("! only a comment\n"), Err(ScalaError::MissingDescription));
+        assert_eq!(ScalaScale::parse("description\n"), Err(ScalaError::MissingNoteCount));
+        assert_eq!(
+            ScalaScale::parse("description\n twelve\n"),
+            Err(ScalaError::InvalidNoteCount { line: 2, text: "twelve".to_string() })
+        );
+        assert_eq!(ScalaScale::parse("description\n0\n"), Err(ScalaError::EmptyScale));
+        assert_eq!(ScalaScale::parse("description\n1\n-100.0\n"), Err(ScalaError::NonPositivePeriod(-100.0)));
+        assert!(matches!(ScalaScale::parse("description\n1\n-3/2\n"), Err(ScalaError::InvalidPitch { line: 3, .. })));
+    }
+
+    #[test]
+    fn test_scale_size_limits() {
+        // Huge declared counts are rejected before any pitch is read
+        assert_eq!(
+            ScalaScale::parse("description\n4000000000\n1200.0\n"),
+            Err(ScalaError::TooManyNotes { line: 2, count: 4_000_000_000 })
+        );
+        assert!(matches!(
+            ScalaScale::parse("description\n18446744073709551615\n1200.0\n"),
+            Err(ScalaError::TooManyNotes { line: 2, .. } | ScalaError::InvalidNoteCount { line: 2, .. })
+        ));
+        assert_eq!(
+            KeyboardMapping::parse("4000000000\n0\n127\n60\n69\n440.0\n12\n"),
+            Err(ScalaError::TooManyNotes { line: 1, count: 4_000_000_000 })
+        );
+
+        assert_eq!(
+            ScalaScale::parse("description\n2\n0.5\n1.0\n"),
+            Err(ScalaError::PeriodTooSmall { period_cents: 1.0, note_count: 2 })
+        );
+        // A single note with a small period, like Carlos Alpha, is fine
+        assert!(ScalaScale::parse("Carlos Alpha\n1\n78.0\n").is_ok());
+    }
+
+    #[test]
+    fn test_degrees_are_sorted_within_the_period() {
+        let scale = ScalaScale::parse("unsorted\n3\n700.0\n400.0\n1200.0\n").unwrap();
+        let tuning = ScalaTuning::new(&scale);
+        assert_eq!(tuning.degree_cents, vec![0.0, 400.0, 700.0]);
+
+        // The middle key plays degree 2, so the next key maps below it
+        let mapping = KeyboardMapping::parse("3\n0\n127\n60\n60\n440.0\n3\n2\n0\n1\n").unwrap();
+        let tuning = ScalaTuning::with_keyboard_mapping(&scale, &mapping).unwrap();
+        assert_eq!(tuning.period_cents(), 1200.0);
+        assert_eq!(tuning.degree_cents, vec![0.0, 300.0, 800.0]);
+        let interval = tuning.frequency_to_interval(100.0, 100.0 * 2.0_f32.powf(310.0 / 1200.0));
+        assert_eq!(interval.semitones, 1);
+        assert!((interval.cents - 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_malformed_keyboard_mappings() {
+        assert_eq!(
+            KeyboardMapping::parse("12\n0\n127\n60\n69\n"),
+            Err(ScalaError::MissingMappingField("reference frequency"))
+        );
+        assert_eq!(
+            KeyboardMapping::parse("12\n0\n127\n60\n200\n440.0\n12\n"),
+            Err(ScalaError::InvalidMappingField { line: 5, field: "reference note", text: "200".to_string() })
+        );
+        assert_eq!(
+            KeyboardMapping::parse("2\n0\n127\n60\n69\n440.0\n2\n0\n1\n2\n"),
+            Err(ScalaError::MappingSizeMismatch { expected: 2, found: 3 })
+        );
+        assert_eq!(
+            KeyboardMapping::parse("2\n0\n127\n60\n69\n440.0\n2\n0\ny\n"),
+            Err(ScalaError::InvalidMappingEntry { line: 9, text: "y".to_string() })
+        );
+
+        let scale = ScalaScale::parse(MAJOR_SCL).unwrap();
+        let unmapped_middle = KeyboardMapping::parse("2\n0\n127\n60\n60\n440.0\n7\nx\n1\n").unwrap();
+        assert_eq!(ScalaTuning::with_keyboard_mapping(&scale, &unmapped_middle), Err(ScalaError::UnmappedMiddleNote));
+        assert_eq!(unmapped_middle.middle_note_frequency(&scale), Err(ScalaError::UnmappedMiddleNote));
+    }
+
+    #[test]
+    fn test_register_custom_tuning() {
+        let scale = ScalaScale::parse(EDO_19_SCL).unwrap();
+        let tuning_system = register_custom_tuning(ScalaTuning::new(&scale)).unwrap();
+        let TuningSystem::Custom(id) = tuning_system else {
+            panic!("expected a custom tuning system, got {:?}", tuning_system);
+        };
+        assert_eq!(custom_tuning(id).unwrap().degrees_per_octave(), 19);
+        assert_eq!(tuning_system.tuning().name(), "19-tone equal temperament");
+
+        // Importing the same tuning again reuses its entry
+        assert_eq!(register_custom_tuning(ScalaTuning::new(&scale)), Ok(tuning_system));
+    }
+}
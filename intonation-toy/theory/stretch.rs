@@ -0,0 +1,258 @@
+//! Stretched tuning for inharmonic instruments
+//!
+//! The partials of a stiff string run sharp of the harmonic series: partial `n` of
+//! a string with fundamental `f0` sounds at `n * f0 * sqrt(1 + B * n²)`, where `B`
+//! is the inharmonicity coefficient of the string. Tuners match the partials of
+//! neighbouring notes, so tuned octaves end up wider than 2:1 and the deviation from
+//! the tuning system grows towards both ends of the keyboard.
+//!
+//! Two curves are supported:
+//!
+//! - The Railsback curve, parametrized by the deviation at the top (C8) and at the
+//!   bottom (A0) of the piano keyboard
+//! - A constant stretch per octave derived from a measured inharmonicity coefficient,
+//!   see `estimate_inharmonicity()`
+//!
+//! Offsets are measured from A4, which is never stretched.
+
+use crate::shared_types::{Spectrum, StretchTuning};
+
+/// Octaves from A4 up to C8, where the Railsback treble deviation is reached
+const RAILSBACK_TREBLE_OCTAVES: f32 = 39.0 / 12.0;
+
+/// Octaves from A4 down to A0, where the Railsback bass deviation is reached
+const RAILSBACK_BASS_OCTAVES: f32 = 4.0;
+
+/// Highest partial searched for when estimating inharmonicity
+const MAX_PARTIAL: usize = 16;
+
+/// Fewest partials needed above the fundamental for an inharmonicity estimate
+const MIN_MEASURED_PARTIALS: usize = 3;
+
+/// Partials weaker than this fraction of the strongest partial are ignored
+const MIN_PARTIAL_RELATIVE_MAGNITUDE: f32 = 0.02;
+
+/// Highest inharmonicity coefficient accepted from a measurement
+///
+/// The shortest piano strings reach about 0.01, larger values are misidentified partials.
+pub const MAX_INHARMONICITY: f32 = 0.02;
+
+/// Number of fixed-point iterations used to invert the stretch curve
+const UNSTRETCH_ITERATIONS: usize = 4;
+
+/// Deviation of the Railsback curve in cents at the given number of octaves from A4
+///
+/// The curve rises quadratically to `treble_cents` at C8 and falls quadratically
+/// to `-bass_cents` at A0, which approximates the averaged curve measured by
+/// Railsback on well tuned pianos.
+pub fn railsback_offset_cents(octaves_from_a4: f32, treble_cents: f32, bass_cents: f32) -> f32 {
+    if octaves_from_a4 >= 0.0 {
+        treble_cents * (octaves_from_a4 / RAILSBACK_TREBLE_OCTAVES).powi(2)
+    } else {
+        -bass_cents * (octaves_from_a4 / RAILSBACK_BASS_OCTAVES).powi(2)
+    }
+}
+
+/// Width in cents that an octave gains on a string with inharmonicity coefficient `b`
+///
+/// Tuning the octave to beat-free on the second partial of the lower note sets the
+/// upper fundamental to `2 * f0 * sqrt((1 + 4B) / (1 + B))`.
+pub fn inharmonic_octave_stretch_cents(b: f32) -> f32 {
+    600.0 * ((1.0 + 4.0 * b) / (1.0 + b)).log2()
+}
+
+/// Stretch curve placed around the A4 reference frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StretchCurve {
+    pub stretch_tuning: StretchTuning,
+    /// Measured inharmonicity coefficient, only used by `StretchTuning::Measured`
+    pub inharmonicity: Option<f32>,
+    pub reference_frequency: f32,
+}
+
+impl StretchCurve {
+    pub fn new(stretch_tuning: StretchTuning, inharmonicity: Option<f32>, reference_frequency: f32) -> Self {
+        Self { stretch_tuning, inharmonicity, reference_frequency }
+    }
+
+    /// Pure octaves around the given A4 reference frequency
+    pub fn none(reference_frequency: f32) -> Self {
+        Self::new(StretchTuning::None, None, reference_frequency)
+    }
+
+    /// Deviation in cents of the stretched target from the unstretched frequency
+    ///
+    /// `StretchTuning::Measured` applies no stretch until an inharmonicity was measured.
+    pub fn offset_cents(&self, frequency: f32) -> f32 {
+        if frequency <= 0.0 {
+            return 0.0;
+        }
+        let octaves_from_a4 = (frequency / self.reference_frequency).log2();
+        match self.stretch_tuning {
+            StretchTuning::None => 0.0,
+            StretchTuning::Railsback { treble_cents, bass_cents } => {
+                railsback_offset_cents(octaves_from_a4, treble_cents, bass_cents)
+            }
+            StretchTuning::Measured => self.inharmonicity
+                .map_or(0.0, |b| inharmonic_octave_stretch_cents(b) * octaves_from_a4),
+        }
+    }
+
+    /// Stretched target of an unstretched frequency
+    pub fn stretch(&self, frequency: f32) -> f32 {
+        frequency * 2.0_f32.powf(self.offset_cents(frequency) / 1200.0)
+    }
+
+    /// Unstretched frequency of a stretched target, the inverse of `stretch()`
+    pub fn unstretch(&self, stretched_frequency: f32) -> f32 {
+        let mut frequency = stretched_frequency;
+        for _ in 0..UNSTRETCH_ITERATIONS {
+            frequency = stretched_frequency * 2.0_f32.powf(-self.offset_cents(frequency) / 1200.0);
+        }
+        frequency
+    }
+}
+
+/// Estimate the inharmonicity coefficient B of a tone from its spectrum
+///
+/// Searches the partials of `fundamental` one by one, each around the frequency
+/// predicted by the estimate from the partials found so far, and refines the peak
+/// positions by parabolic interpolation. B is then fitted by least squares to
+/// `(f_n / (n * f_1))² - 1 = B' * (n² - 1)` with `B' = B / (1 + B)`.
+///
+/// Returns `None` when fewer than `MIN_MEASURED_PARTIALS` partials were found or
+/// the fit is out of the plausible range.
+pub fn estimate_inharmonicity(spectrum: &Spectrum, fundamental: f32) -> Option<f32> {
+    if fundamental <= 0.0 || spectrum.bin_width_hz <= 0.0 {
+        return None;
+    }
+
+    let nyquist = spectrum.bin_width_hz * spectrum.magnitudes.len() as f32;
+    let strongest = spectrum.magnitudes.iter().cloned().fold(0.0_f32, f32::max);
+    if strongest <= 0.0 {
+        return None;
+    }
+    // Stay clear of the neighbouring partials when searching around a prediction
+    let half_window = (2.0 * spectrum.bin_width_hz).min(0.3 * fundamental);
+
+    let mut weighted_sum = 0.0;
+    let mut weight_total = 0.0;
+    let mut measured_partials = 0;
+    for n in 2..=MAX_PARTIAL {
+        let n_squared_minus_one = (n * n - 1) as f32;
+        let estimate = if weight_total > 0.0 { weighted_sum / weight_total } else { 0.0 };
+        let predicted = n as f32 * fundamental * (1.0 + estimate * n_squared_minus_one).sqrt();
+        if predicted + half_window >= nyquist {
+            break;
+        }
+
+        let Some(partial) = find_peak(spectrum, predicted - half_window, predicted + half_window) else {
+            continue;
+        };
+        if partial.1 < strongest * MIN_PARTIAL_RELATIVE_MAGNITUDE {
+            continue;
+        }
+
+        let ratio = partial.0 / (n as f32 * fundamental);
+        weighted_sum += (ratio * ratio - 1.0) * n_squared_minus_one;
+        weight_total += n_squared_minus_one * n_squared_minus_one;
+        measured_partials += 1;
+    }
+
+    if measured_partials < MIN_MEASURED_PARTIALS {
+        return None;
+    }
+
+    let b_prime = (weighted_sum / weight_total).max(0.0);
+    let b = b_prime / (1.0 - b_prime);
+    (b <= MAX_INHARMONICITY).then_some(b)
+}
+
+/// Interpolated frequency and magnitude of the strongest local maximum between two frequencies
+fn find_peak(spectrum: &Spectrum, low: f32, high: f32) -> Option<(f32, f32)> {
+    let first_bin = ((low / spectrum.bin_width_hz).ceil() as usize).max(1);
+    let last_bin = ((high / spectrum.bin_width_hz).floor() as usize).min(spectrum.magnitudes.len().saturating_sub(2));
+    let magnitudes = &spectrum.magnitudes;
+
+    let peak_bin = (first_bin..=last_bin)
+        .filter(|&bin| magnitudes[bin] >= magnitudes[bin - 1] && magnitudes[bin] >= magnitudes[bin + 1])
+        .max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b]))?;
+
+    let (left, center, right) = (magnitudes[peak_bin - 1], magnitudes[peak_bin], magnitudes[peak_bin + 1]);
+    let curvature = left - 2.0 * center + right;
+    let offset = if curvature < 0.0 { 0.5 * (left - right) / curvature } else { 0.0 };
+    let magnitude = center - 0.25 * (left - right) * offset;
+    Some(((peak_bin as f32 + offset) * spectrum.bin_width_hz, magnitude))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spectrum of a tone with inharmonic partials of falling strength
+    fn inharmonic_spectrum(fundamental: f32, b: f32, bin_width_hz: f32) -> Spectrum {
+        let mut magnitudes = vec![0.0; (8000.0 / bin_width_hz) as usize];
+        for n in 1..=12 {
+            let partial = n as f32 * fundamental * (1.0 + b * (n * n) as f32).sqrt();
+            let amplitude = 1.0 / n as f32;
+            for (bin, magnitude) in magnitudes.iter_mut().enumerate() {
+                let distance = (bin as f32 * bin_width_hz - partial) / bin_width_hz;
+                *magnitude += amplitude * (-distance * distance).exp();
+            }
+        }
+        Spectrum { magnitudes, bin_width_hz }
+    }
+
+    #[test]
+    fn test_railsback_offset() {
+        assert_eq!(railsback_offset_cents(0.0, 30.0, 20.0), 0.0);
+        assert!((railsback_offset_cents(RAILSBACK_TREBLE_OCTAVES, 30.0, 20.0) - 30.0).abs() < 1e-4);
+        assert!((railsback_offset_cents(-4.0, 30.0, 20.0) + 20.0).abs() < 1e-4);
+        // Deviation grows faster towards the ends of the keyboard
+        let one_octave_up = railsback_offset_cents(1.0, 30.0, 20.0);
+        let two_octaves_up = railsback_offset_cents(2.0, 30.0, 20.0);
+        assert!(one_octave_up > 0.0 && two_octaves_up > 2.0 * one_octave_up);
+    }
+
+    #[test]
+    fn test_inharmonic_octave_stretch() {
+        assert_eq!(inharmonic_octave_stretch_cents(0.0), 0.0);
+        // Typical mid-range piano string, roughly 1 cent per octave
+        let stretch = inharmonic_octave_stretch_cents(0.0004);
+        assert!(stretch > 0.9 && stretch < 1.2, "stretch was {}", stretch);
+    }
+
+    #[test]
+    fn test_stretch_curve_round_trip() {
+        let curve = StretchCurve::new(StretchTuning::PIANO, None, 440.0);
+        assert_eq!(curve.stretch(440.0), 440.0);
+        assert!(curve.stretch(3520.0) > 3520.0);
+        assert!(curve.stretch(55.0) < 55.0);
+        for frequency in [30.0, 110.0, 440.0, 1000.0, 4000.0] {
+            assert!((curve.unstretch(curve.stretch(frequency)) - frequency).abs() < frequency * 1e-5);
+        }
+
+        // Measured stretch is flat until an inharmonicity is known
+        let measured = StretchCurve::new(StretchTuning::Measured, None, 440.0);
+        assert_eq!(measured.offset_cents(880.0), 0.0);
+        let measured = StretchCurve::new(StretchTuning::Measured, Some(0.0004), 440.0);
+        assert!((measured.offset_cents(880.0) - inharmonic_octave_stretch_cents(0.0004)).abs() < 1e-4);
+        assert!((StretchCurve::none(440.0).stretch(1000.0) - 1000.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_estimate_inharmonicity() {
+        let b = 0.0008;
+        let spectrum = inharmonic_spectrum(220.0, b, 5.0);
+        let fundamental = 220.0 * (1.0 + b).sqrt();
+        let estimate = estimate_inharmonicity(&spectrum, fundamental).expect("partials should be found");
+        assert!((estimate - b).abs() < b * 0.2, "estimate was {}", estimate);
+
+        let harmonic = inharmonic_spectrum(220.0, 0.0, 5.0);
+        let estimate = estimate_inharmonicity(&harmonic, 220.0).expect("partials should be found");
+        assert!(estimate < 1e-5, "estimate was {}", estimate);
+
+        let silence = Spectrum { magnitudes: vec![0.0; 1024], bin_width_hz: 5.0 };
+        assert_eq!(estimate_inharmonicity(&silence, 220.0), None);
+    }
+}
@@ -1,58 +1,46 @@
-use crate::shared_types::{MidiNote, TuningSystem, Scale, semitone_in_scale};
+use crate::shared_types::{JustRatio, MidiNote, Scale, semitone_in_scale};
+use super::tuning_systems::Tuning;
 
 /// Represents an interval as a base semitone with cents deviation
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct IntervalSemitones {
     pub semitones: i32,
     pub cents: f32,
+    /// Just ratio the cents are measured from, `None` for tunings without just ratios
+    pub ratio: Option<JustRatio>,
 }
 
-/// Just Intonation frequency ratios for the 12-tone chromatic scale
-/// 
-/// These ratios represent the harmonic relationships between notes in Just Intonation.
-/// Index corresponds to semitones from the root (0-11).
-const JUST_INTONATION_RATIOS: [(i32, f32); 12] = [
-    (0, 1.0),           // Unison
-    (1, 16.0 / 15.0),   // Minor second
-    (2, 9.0 / 8.0),     // Major second
-    (3, 6.0 / 5.0),     // Minor third
-    (4, 5.0 / 4.0),     // Major third
-    (5, 4.0 / 3.0),     // Perfect fourth
-    (6, 45.0 / 32.0),   // Tritone
-    (7, 3.0 / 2.0),     // Perfect fifth
-    (8, 8.0 / 5.0),     // Minor sixth
-    (9, 5.0 / 3.0),     // Major sixth
-    (10, 9.0 / 5.0),    // Minor seventh
-    (11, 15.0 / 8.0),   // Major seventh
-];
-
-/// Get the Just Intonation ratio for a given semitone interval
-fn get_just_intonation_ratio(semitone: i32) -> f32 {
-    let semitone_in_octave = semitone.rem_euclid(12) as usize;
-    JUST_INTONATION_RATIOS[semitone_in_octave].1
+impl IntervalSemitones {
+    /// Matched ratio and deviation for display, e.g. "7/4, +3¢"
+    pub fn ratio_label(&self) -> Option<String> {
+        self.ratio.map(|ratio| ratio.label_with_cents(self.cents))
+    }
 }
 
+/// Frequency of the note `interval_semitones` degrees away from the root in the given tuning
 pub fn interval_frequency(
-    tuning_system: TuningSystem,
+    tuning: &dyn Tuning,
     root_frequency_hz: f32,
     interval_semitones: i32,
 ) -> f32 {
-    match tuning_system {
-        TuningSystem::EqualTemperament => {
-            root_frequency_hz * 2.0_f32.powf(interval_semitones as f32 / 12.0)
-        }
-        TuningSystem::JustIntonation => {
-            let octaves = interval_semitones.div_euclid(12);
-            let ratio = get_just_intonation_ratio(interval_semitones);
-            root_frequency_hz * ratio * 2.0_f32.powi(octaves)
-        }
-    }
+    tuning.interval_frequency(root_frequency_hz, interval_semitones)
 }
 
+/// Concert pitch for A4 used by "Standard Tuning"
+pub const STANDARD_REFERENCE_FREQUENCY: f32 = 440.0;
+
 /// We refer to Equal Temperament A4=440 as "Standard Tuning"
 /// and the frequencies of the notes as "standard frequencies"
 pub fn midi_note_to_standard_frequency(midi_note: MidiNote) -> f32 {
-    440.0 * 2.0_f32.powf((midi_note as f32 - 69.0) / 12.0)
+    midi_note_to_frequency_with_reference(midi_note, STANDARD_REFERENCE_FREQUENCY)
+}
+
+/// Equal Temperament frequency of a MIDI note with A4 tuned to `reference_frequency_hz`
+/// 
+/// Use this instead of `midi_note_to_standard_frequency` when the A4 reference
+/// is configurable, e.g. A4=415 for baroque pitch or A4=442 for orchestras.
+pub fn midi_note_to_frequency_with_reference(midi_note: MidiNote, reference_frequency_hz: f32) -> f32 {
+    reference_frequency_hz * 2.0_f32.powf((midi_note as f32 - 69.0) / 12.0)
 }
 
 /// Convert a frequency to its interval relative to a root frequency
@@ -60,52 +48,15 @@ pub fn midi_note_to_standard_frequency(midi_note: MidiNote) -> f32 {
 /// Returns the base semitone interval and cents deviation separately.
 /// This handles cases where cents deviation may exceed ±100 cents.
 /// 
-/// For Equal Temperament: Base semitone is rounded to nearest, cents show deviation
-/// For Just Intonation: Base semitone is closest just interval, cents show deviation from that
+/// The base semitone is the closest scale degree of the tuning, and cents show
+/// the deviation from that degree. For Equal Temperament this is the nearest
+/// semitone, for Just Intonation the closest just interval.
 pub fn frequency_to_interval_semitones(
-    tuning_system: TuningSystem,
+    tuning: &dyn Tuning,
     root_frequency_hz: f32,
     target_frequency_hz: f32,
 ) -> IntervalSemitones {
-    match tuning_system {
-        TuningSystem::EqualTemperament => {
-            let total_cents = cents_delta(root_frequency_hz, target_frequency_hz);
-            let base_semitones = (total_cents / 100.0).round() as i32;
-            let base_freq = root_frequency_hz * 2.0_f32.powf(base_semitones as f32 / 12.0);
-            let cents_deviation = cents_delta(base_freq, target_frequency_hz);
-            
-            IntervalSemitones {
-                semitones: base_semitones,
-                cents: cents_deviation,
-            }
-        }
-        TuningSystem::JustIntonation => {
-            let ratio = target_frequency_hz / root_frequency_hz;
-            let octaves = ratio.log2().floor() as i32;
-            let ratio_in_octave = ratio / 2.0_f32.powf(octaves as f32);
-            
-            let (closest_semitone, closest_ratio) = JUST_INTONATION_RATIOS
-                .iter()
-                .min_by(|(_, r1), (_, r2)| {
-                    let target_ratio_freq = root_frequency_hz * ratio_in_octave;
-                    let just_freq1 = root_frequency_hz * r1;
-                    let just_freq2 = root_frequency_hz * r2;
-                    let cents_diff1 = cents_delta(just_freq1, target_ratio_freq).abs();
-                    let cents_diff2 = cents_delta(just_freq2, target_ratio_freq).abs();
-                    cents_diff1.partial_cmp(&cents_diff2).unwrap()
-                })
-                .unwrap();
-            
-            let base_semitones = octaves * 12 + *closest_semitone;
-            let just_intonation_freq = root_frequency_hz * closest_ratio * 2.0_f32.powf(octaves as f32);
-            let cents_deviation = cents_delta(just_intonation_freq, target_frequency_hz);
-            
-            IntervalSemitones {
-                semitones: base_semitones,
-                cents: cents_deviation,
-            }
-        }
-    }
+    tuning.frequency_to_interval(root_frequency_hz, target_frequency_hz)
 }
 
 /// Calculate the difference between two frequencies in cents
@@ -127,6 +78,33 @@ pub fn cents_delta(frequency1_hz: f32, frequency2_hz: f32) -> f32 {
     1200.0 * (frequency2_hz / frequency1_hz).log2()
 }
 
+/// The semitone a degree of the tuning stands in for
+///
+/// In 12-tone tunings every degree is its own semitone. In other tunings a degree
+/// stands in for the semitone it is closest to, unless a neighbouring degree is
+/// even closer to that semitone, so that every semitone has at most one degree.
+/// For example in 24-EDO the quarter tones stand in for no semitone.
+pub fn degree_to_semitone(tuning: &dyn Tuning, degree: i32) -> Option<i32> {
+    if tuning.degrees_per_octave() == 12 {
+        return Some(degree);
+    }
+    let semitone = (tuning.interval_cents(degree) / 100.0).round() as i32;
+    let distance = |interval: i32| (tuning.interval_cents(interval) - semitone as f32 * 100.0).abs();
+    (distance(degree) < distance(degree - 1) && distance(degree) <= distance(degree + 1)).then_some(semitone)
+}
+
+/// Whether a degree of the tuning belongs to the scale
+///
+/// Scales are defined on 12 semitones. In other tunings a degree belongs to the
+/// scale when it stands in for one of the scale's semitones, see `degree_to_semitone`.
+/// Scales do not apply to tunings whose period is not an octave.
+pub fn degree_in_scale(tuning: &dyn Tuning, scale: Scale, degree: i32) -> bool {
+    if scale == Scale::Chromatic || tuning.period_cents() != 1200.0 {
+        return true;
+    }
+    degree_to_semitone(tuning, degree).is_some_and(|semitone| semitone_in_scale(scale, semitone))
+}
+
 /// Find the closest scale note to a given semitone interval
 /// 
 /// This function searches for the nearest scale member when the candidate semitone
@@ -167,8 +145,10 @@ fn find_closest_scale_note(candidate_semitone: i32, scale: Scale) -> i32 {
 /// This implementation directly finds the closest scale note by frequency distance
 /// rather than first rounding to chromatic semitones, which prevents issues where
 /// non-scale chromatic notes are closer in semitone count but further in frequency.
+/// 
+/// In tunings without 12 degrees per period the scale is applied with `degree_in_scale`.
 pub fn frequency_to_interval_semitones_scale_aware(
-    tuning_system: TuningSystem,
+    tuning: &dyn Tuning,
     root_frequency_hz: f32,
     target_frequency_hz: f32,
     scale: Scale,
@@ -176,53 +156,34 @@ pub fn frequency_to_interval_semitones_scale_aware(
     // For chromatic scale, use the standard algorithm since all notes are in scale
     if scale == Scale::Chromatic {
         return frequency_to_interval_semitones(
-            tuning_system,
+            tuning,
             root_frequency_hz,
             target_frequency_hz,
         );
     }
     
     // For non-chromatic scales, find the closest scale note by frequency distance
-    let mut closest_semitone = 0;
-    let mut smallest_cents_distance = f32::INFINITY;
+    let mut closest = IntervalSemitones { semitones: 0, cents: f32::INFINITY, ratio: None };
     
-    // Search across a reasonable range of octaves (±4 octaves = ±48 semitones)
+    // Search across a reasonable range of octaves (±4 octaves = ±48 semitones in 12-tone tunings)
     // This covers the typical range of musical instruments and human voice
-    for semitone in -48..=48 {
+    let search_range = 4 * tuning.degrees_per_octave() as i32;
+    for semitone in -search_range..=search_range {
         // Skip notes not in the scale
-        if !semitone_in_scale(scale, semitone) {
+        if !degree_in_scale(tuning, scale, semitone) {
             continue;
         }
         
-        // Calculate the frequency for this scale note
-        let scale_note_frequency = interval_frequency(
-            tuning_system.clone(),
-            root_frequency_hz,
-            semitone,
-        );
-        
-        // Calculate cents distance to target frequency
-        let cents_distance = cents_delta(scale_note_frequency, target_frequency_hz).abs();
+        // Deviation from this scale note, or from its closest just ratio
+        let deviation = tuning.interval_deviation(root_frequency_hz, semitone, target_frequency_hz);
         
         // Update if this is the closest scale note found so far
-        if cents_distance < smallest_cents_distance {
-            smallest_cents_distance = cents_distance;
-            closest_semitone = semitone;
+        if deviation.cents.abs() < closest.cents.abs() {
+            closest = deviation;
         }
     }
     
-    // Calculate the final cents offset relative to the closest scale note
-    let scale_note_frequency = interval_frequency(
-        tuning_system,
-        root_frequency_hz,
-        closest_semitone,
-    );
-    let cents_offset = cents_delta(scale_note_frequency, target_frequency_hz);
-    
-    IntervalSemitones {
-        semitones: closest_semitone,
-        cents: cents_offset,
-    }
+    closest
 }
 
 /// Scale-aware interval frequency calculation
@@ -231,18 +192,28 @@ pub fn frequency_to_interval_semitones_scale_aware(
 /// This is useful for getting the "target" frequency that a scale-aware
 /// intonation system would expect for a given interval.
 pub fn interval_frequency_scale_aware(
-    tuning_system: TuningSystem,
+    tuning: &dyn Tuning,
     root_frequency_hz: f32,
     interval_semitones: i32,
     scale: Scale,
 ) -> f32 {
+    let degrees = tuning.degrees_per_octave() as i32;
+    if degrees != 12 {
+        // Search outward for the closest scale degree, favoring upward for ties
+        let scale_degree = (0..=degrees)
+            .flat_map(|distance| [interval_semitones + distance, interval_semitones - distance])
+            .find(|&degree| degree_in_scale(tuning, scale, degree))
+            .unwrap_or(interval_semitones);
+        return interval_frequency(tuning, root_frequency_hz, scale_degree);
+    }
     let scale_semitone = find_closest_scale_note(interval_semitones, scale);
-    interval_frequency(tuning_system, root_frequency_hz, scale_semitone)
+    interval_frequency(tuning, root_frequency_hz, scale_semitone)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared_types::TuningSystem;
 
     #[test]
     fn test_equal_temperament_matches_existing_formula() {
@@ -250,7 +221,7 @@ mod tests {
         
         for offset in -12..=12 {
             let expected = 440.0 * 2.0_f32.powf(offset as f32 / 12.0);
-            let actual = interval_frequency(TuningSystem::EqualTemperament, root_freq, offset);
+            let actual = interval_frequency(TuningSystem::EqualTemperament.tuning(), root_freq, offset);
             assert!((expected - actual).abs() < 0.001);
         }
         
@@ -261,20 +232,34 @@ mod tests {
         assert!((freq_a4 - 440.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_midi_note_to_frequency_with_reference() {
+        assert!((midi_note_to_frequency_with_reference(69, 415.0) - 415.0).abs() < 0.001);
+        assert!((midi_note_to_frequency_with_reference(57, 442.0) - 221.0).abs() < 0.001);
+        assert!((midi_note_to_frequency_with_reference(81, 432.0) - 864.0).abs() < 0.001);
+
+        // The standard reference must give the same result as the standard function
+        for midi_note in [0, 21, 60, 69, 108, 127] {
+            let standard = midi_note_to_standard_frequency(midi_note);
+            let with_reference = midi_note_to_frequency_with_reference(midi_note, STANDARD_REFERENCE_FREQUENCY);
+            assert!((standard - with_reference).abs() < 0.001);
+        }
+    }
+
     #[test]
     fn test_just_intonation_ratios() {
         let root_freq = 440.0;
         
-        let perfect_fifth = interval_frequency(TuningSystem::JustIntonation, root_freq, 7);
+        let perfect_fifth = interval_frequency(TuningSystem::JustIntonation.tuning(), root_freq, 7);
         assert!((perfect_fifth - root_freq * 3.0 / 2.0).abs() < 0.001);
         
-        let major_third = interval_frequency(TuningSystem::JustIntonation, root_freq, 4);
+        let major_third = interval_frequency(TuningSystem::JustIntonation.tuning(), root_freq, 4);
         assert!((major_third - root_freq * 5.0 / 4.0).abs() < 0.001);
         
-        let octave = interval_frequency(TuningSystem::JustIntonation, root_freq, 12);
+        let octave = interval_frequency(TuningSystem::JustIntonation.tuning(), root_freq, 12);
         assert!((octave - root_freq * 2.0).abs() < 0.001);
         
-        let octave_down = interval_frequency(TuningSystem::JustIntonation, root_freq, -12);
+        let octave_down = interval_frequency(TuningSystem::JustIntonation.tuning(), root_freq, -12);
         assert!((octave_down - root_freq / 2.0).abs() < 0.001);
     }
 
@@ -283,7 +268,7 @@ mod tests {
         let root_freq = 440.0;
         
         let interval = frequency_to_interval_semitones(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             root_freq * 2.0,
         );
@@ -291,7 +276,7 @@ mod tests {
         assert!(interval.cents.abs() < 0.001);
         
         let interval = frequency_to_interval_semitones(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             root_freq * 2.0_f32.powf(7.0 / 12.0),
         );
@@ -304,7 +289,7 @@ mod tests {
         let root_freq = 440.0;
         
         let interval = frequency_to_interval_semitones(
-            TuningSystem::JustIntonation,
+            TuningSystem::JustIntonation.tuning(),
             root_freq,
             root_freq * 3.0 / 2.0,
         );
@@ -312,7 +297,7 @@ mod tests {
         assert!(interval.cents.abs() < 0.001);
         
         let interval = frequency_to_interval_semitones(
-            TuningSystem::JustIntonation,
+            TuningSystem::JustIntonation.tuning(),
             root_freq,
             root_freq * 5.0 / 4.0,
         );
@@ -320,6 +305,29 @@ mod tests {
         assert!(interval.cents.abs() < 0.001);
     }
 
+    #[test]
+    fn test_frequency_to_interval_matches_original_behavior() {
+        let root_freq = 220.0;
+        let cents_above = |cents: f32| root_freq * 2.0_f32.powf(cents / 1200.0);
+        let cases = [
+            // 12-TET rounds to the nearest semitone, including across the octave
+            (TuningSystem::EqualTemperament, cents_above(1190.0), 12, -10.0),
+            (TuningSystem::EqualTemperament, 330.0, 7, 1.955),
+            (TuningSystem::EqualTemperament, cents_above(-10.0), 0, -10.0),
+            // Just intonation only searches the degrees of the target's own octave
+            (TuningSystem::JustIntonation, cents_above(1190.0), 11, 101.73),
+            (TuningSystem::JustIntonation, 330.0, 7, 0.0),
+            (TuningSystem::JustIntonation, cents_above(-10.0), -1, 101.73),
+            (TuningSystem::JustIntonation, cents_above(350.0), 3, 34.36),
+        ];
+
+        for (tuning_system, target, semitones, cents) in cases {
+            let interval = frequency_to_interval_semitones(tuning_system.tuning(), root_freq, target);
+            assert_eq!(interval.semitones, semitones, "{:?} at {}Hz", tuning_system, target);
+            assert!((interval.cents - cents).abs() < 0.01, "{:?} at {}Hz: {} cents", tuning_system, target, interval.cents);
+        }
+    }
+
     #[test]
     fn test_cents_delta() {
         let base_freq = 440.0;
@@ -354,9 +362,9 @@ mod tests {
         
         // Test notes not in Major scale - should round to nearest scale note
         assert_eq!(find_closest_scale_note(1, Scale::Major), 2);   // Minor 2nd -> Major 2nd (up)
-        assert_eq!(find_closest_scale_note(3, Scale::Major), 2);   // Minor 3rd -> Major 2nd (down)
+        assert_eq!(find_closest_scale_note(3, Scale::Major), 4);   // Minor 3rd -> Major 3rd (tie, up)
         assert_eq!(find_closest_scale_note(6, Scale::Major), 7);   // Tritone -> Perfect 5th (up)
-        assert_eq!(find_closest_scale_note(8, Scale::Major), 7);   // Minor 6th -> Perfect 5th (down)
+        assert_eq!(find_closest_scale_note(8, Scale::Major), 9);   // Minor 6th -> Major 6th (tie, up)
         assert_eq!(find_closest_scale_note(10, Scale::Major), 11); // Minor 7th -> Major 7th (up)
         
         // Test chromatic scale - all notes should remain unchanged
@@ -377,10 +385,10 @@ mod tests {
         
         // Test notes not in MajorPentatonic scale
         assert_eq!(find_closest_scale_note(1, Scale::MajorPentatonic), 2);   // Db -> D (up)
-        assert_eq!(find_closest_scale_note(3, Scale::MajorPentatonic), 2);   // Eb -> D (down)
+        assert_eq!(find_closest_scale_note(3, Scale::MajorPentatonic), 4);   // Eb -> E (tie, up)
         assert_eq!(find_closest_scale_note(5, Scale::MajorPentatonic), 4);   // F -> E (down)
         assert_eq!(find_closest_scale_note(6, Scale::MajorPentatonic), 7);   // Gb -> G (up)
-        assert_eq!(find_closest_scale_note(8, Scale::MajorPentatonic), 7);   // Ab -> G (down)
+        assert_eq!(find_closest_scale_note(8, Scale::MajorPentatonic), 9);   // Ab -> A (tie, up)
         assert_eq!(find_closest_scale_note(10, Scale::MajorPentatonic), 9);  // Bb -> A (down)
         assert_eq!(find_closest_scale_note(11, Scale::MajorPentatonic), 12); // B -> C octave (up)
         
@@ -394,15 +402,15 @@ mod tests {
         // Test notes not in MinorPentatonic scale
         assert_eq!(find_closest_scale_note(1, Scale::MinorPentatonic), 0);   // Db -> C (down)
         assert_eq!(find_closest_scale_note(2, Scale::MinorPentatonic), 3);   // D -> Eb (up)
-        assert_eq!(find_closest_scale_note(4, Scale::MinorPentatonic), 3);   // E -> Eb (down)
-        assert_eq!(find_closest_scale_note(6, Scale::MinorPentatonic), 5);   // Gb -> F (down)
+        assert_eq!(find_closest_scale_note(4, Scale::MinorPentatonic), 5);   // E -> F (tie, up)
+        assert_eq!(find_closest_scale_note(6, Scale::MinorPentatonic), 7);   // Gb -> G (tie, up)
         assert_eq!(find_closest_scale_note(8, Scale::MinorPentatonic), 7);   // Ab -> G (down)
         assert_eq!(find_closest_scale_note(9, Scale::MinorPentatonic), 10);  // A -> Bb (up)
-        assert_eq!(find_closest_scale_note(11, Scale::MinorPentatonic), 10); // B -> Bb (down)
+        assert_eq!(find_closest_scale_note(11, Scale::MinorPentatonic), 12); // B -> C octave (tie, up)
         
         // Test pentatonic octave handling
         assert_eq!(find_closest_scale_note(13, Scale::MajorPentatonic), 14); // Octave + Db -> Octave + D
-        assert_eq!(find_closest_scale_note(-11, Scale::MinorPentatonic), -10); // -B -> -Bb
+        assert_eq!(find_closest_scale_note(-11, Scale::MinorPentatonic), -12); // Db an octave down -> C an octave down
     }
 
     #[test]
@@ -413,7 +421,7 @@ mod tests {
         // A# (1 semitone) should map to B (2 semitones) in Major scale
         let a_sharp_freq = root_freq * 2.0_f32.powf(1.0 / 12.0);
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             a_sharp_freq,
             Scale::Major,
@@ -427,7 +435,7 @@ mod tests {
         // Test frequency already in scale (B natural)
         let b_freq = root_freq * 2.0_f32.powf(2.0 / 12.0);
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             b_freq,
             Scale::Major,
@@ -437,13 +445,13 @@ mod tests {
         
         // Test Chromatic scale behavior (should be identical to non-scale-aware)
         let interval_chromatic = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             a_sharp_freq,
             Scale::Chromatic,
         );
         let interval_raw = frequency_to_interval_semitones(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             a_sharp_freq,
         );
@@ -454,7 +462,7 @@ mod tests {
         // C# (3 semitones from A) should map to D (4 semitones) in A Major Pentatonic
         let c_sharp_freq = root_freq * 2.0_f32.powf(3.0 / 12.0);
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             c_sharp_freq,
             Scale::MajorPentatonic,
@@ -466,7 +474,7 @@ mod tests {
         // B (2 semitones from A) should map to C (3 semitones) in A Minor Pentatonic
         let b_freq = root_freq * 2.0_f32.powf(2.0 / 12.0);
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             b_freq,
             Scale::MinorPentatonic,
@@ -477,7 +485,7 @@ mod tests {
         // Test frequency that's already in pentatonic scale
         let e_freq = root_freq * 2.0_f32.powf(7.0 / 12.0); // E (Perfect 5th from A)
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             e_freq,
             Scale::MajorPentatonic,
@@ -493,7 +501,7 @@ mod tests {
         // Test Just Intonation with Major scale
         // Use a frequency that would naturally map to a non-scale note
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::JustIntonation,
+            TuningSystem::JustIntonation.tuning(),
             root_freq,
             root_freq * 16.0 / 15.0, // Minor 2nd in JI
             Scale::Major,
@@ -504,13 +512,97 @@ mod tests {
         assert!(interval.cents < 0.0);
     }
 
+    #[test]
+    fn test_frequency_to_interval_semitones_reports_matched_ratio() {
+        let root_freq = 220.0;
+        let harmonic_seventh = root_freq * 7.0 / 4.0 * 2.0_f32.powf(3.0 / 1200.0);
+
+        let interval = frequency_to_interval_semitones(
+            TuningSystem::JustIntonation7Limit.tuning(),
+            root_freq,
+            harmonic_seventh,
+        );
+        assert_eq!(interval.semitones, 10);
+        assert_eq!(interval.ratio, Some(JustRatio::new(7, 4)));
+        assert_eq!(interval.ratio_label().as_deref(), Some("7/4, +3¢"));
+
+        // Mixolydian contains the minor seventh, so the scale-aware search matches it too
+        let interval = frequency_to_interval_semitones_scale_aware(
+            TuningSystem::JustIntonation7Limit.tuning(),
+            root_freq,
+            harmonic_seventh,
+            Scale::Mixolydian,
+        );
+        assert_eq!(interval.semitones, 10);
+        assert_eq!(interval.ratio, Some(JustRatio::new(7, 4)));
+        assert!((interval.cents - 3.0).abs() < 0.01);
+
+        // A subminor third outside the scale is placed on the closest scale note
+        let interval = frequency_to_interval_semitones_scale_aware(
+            TuningSystem::JustIntonation7Limit.tuning(),
+            root_freq,
+            root_freq * 7.0 / 6.0,
+            Scale::MajorPentatonic,
+        );
+        assert_eq!(interval.semitones, 2);
+        assert_eq!(interval.ratio, Some(JustRatio::new(8, 7)));
+
+        let interval = frequency_to_interval_semitones(TuningSystem::EqualTemperament.tuning(), root_freq, 330.0);
+        assert_eq!(interval.ratio, None);
+        assert_eq!(interval.ratio_label(), None);
+    }
+
+    #[test]
+    fn test_degree_to_semitone_equal_divisions() {
+        // 12-tone tunings map every degree to itself
+        let just = TuningSystem::JustIntonation.tuning();
+        assert!((-24..=24).all(|degree| degree_to_semitone(just, degree) == Some(degree)));
+
+        // 24-EDO: even steps are semitones, quarter tones stand in for none
+        let edo_24 = TuningSystem::EqualDivision(24).tuning();
+        assert_eq!(degree_to_semitone(edo_24, 14), Some(7));
+        assert_eq!(degree_to_semitone(edo_24, 15), None);
+        assert_eq!(degree_to_semitone(edo_24, -2), Some(-1));
+
+        // Every semitone has exactly one degree in 19-, 31- and 53-EDO
+        for divisions in [19, 31, 53] {
+            let tuning = TuningSystem::EqualDivision(divisions).tuning();
+            let semitones: Vec<i32> = (0..divisions as i32).filter_map(|degree| degree_to_semitone(tuning, degree)).collect();
+            assert_eq!(semitones, (0..12).collect::<Vec<_>>(), "{}-EDO", divisions);
+        }
+        let edo_19 = TuningSystem::EqualDivision(19).tuning();
+        assert_eq!(degree_to_semitone(edo_19, 19 + 11), Some(12 + 7));
+    }
+
+    #[test]
+    fn test_scale_aware_equal_divisions() {
+        let root_freq = 220.0;
+        let edo_31 = TuningSystem::EqualDivision(31).tuning();
+
+        let major_degrees: Vec<i32> = (0..31).filter(|&degree| degree_in_scale(edo_31, Scale::Major, degree)).collect();
+        assert_eq!(major_degrees, vec![0, 5, 10, 13, 18, 23, 28]);
+        assert!((0..31).all(|degree| degree_in_scale(edo_31, Scale::Chromatic, degree)));
+
+        // A pure major third is closest to the 10-step major third
+        let interval = frequency_to_interval_semitones_scale_aware(edo_31, root_freq, root_freq * 5.0 / 4.0, Scale::Major);
+        assert_eq!(interval.semitones, 10);
+
+        // A subminor third (7 steps) is outside the scale and moves to the major second
+        let subminor_third = edo_31.interval_frequency(root_freq, 7);
+        let interval = frequency_to_interval_semitones_scale_aware(edo_31, root_freq, subminor_third, Scale::Major);
+        assert_eq!(interval.semitones, 5);
+
+        let freq = interval_frequency_scale_aware(edo_31, root_freq, 8, Scale::Major);
+        assert!((freq - edo_31.interval_frequency(root_freq, 10)).abs() < 0.001);
+    }
+
     #[test]
     fn test_interval_frequency_scale_aware() {
         let root_freq = 440.0;
         
         // Test that asking for a non-scale interval returns the closest scale note frequency
         let freq = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             1, // Minor 2nd (not in Major scale)
             Scale::Major,
@@ -518,7 +610,7 @@ mod tests {
         
         // Should return frequency for Major 2nd (2 semitones)
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             2,
         );
@@ -526,14 +618,14 @@ mod tests {
         
         // Test that scale notes remain unchanged
         let freq = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             4, // Major 3rd (in Major scale)
             Scale::Major,
         );
         
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             4,
         );
@@ -541,46 +633,46 @@ mod tests {
         
         // Test Chromatic scale (should be identical to non-scale-aware)
         let freq_chromatic = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             6, // Tritone
             Scale::Chromatic,
         );
         
         let freq_raw = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             6,
         );
         assert!((freq_chromatic - freq_raw).abs() < 0.001);
         
         // Test MajorPentatonic scale
-        // Request Minor 3rd (3 semitones), should get Major 2nd (2 semitones) as closest
+        // Request Minor 3rd (3 semitones), Major 2nd and Major 3rd are equally close and the upper one wins
         let freq_pentatonic = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             3, // Minor 3rd (not in Major Pentatonic)
             Scale::MajorPentatonic,
         );
         
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
-            2, // Major 2nd (closest in Major Pentatonic)
+            4, // Major 3rd (closest in Major Pentatonic, ties go up)
         );
         assert!((freq_pentatonic - expected_freq).abs() < 0.001);
         
         // Test MinorPentatonic scale
         // Request Major 2nd (2 semitones), should get Minor 3rd (3 semitones) as closest
         let freq_minor_penta = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             2, // Major 2nd (not in Minor Pentatonic)
             Scale::MinorPentatonic,
         );
         
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             3, // Minor 3rd (closest in Minor Pentatonic)
         );
@@ -588,14 +680,14 @@ mod tests {
         
         // Test that pentatonic scale notes remain unchanged
         let freq = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             7, // Perfect 5th (in both Major and Minor Pentatonic)
             Scale::MajorPentatonic,
         );
         
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             7,
         );
@@ -611,7 +703,7 @@ mod tests {
         
         // Test 224.4Hz - should map to A3 with positive cents
         let interval_224_4 = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             224.4,
             Scale::Major, // Non-chromatic scale
@@ -623,7 +715,7 @@ mod tests {
         // Test 226.6Hz - this was the problematic case
         // It should map to A3 (+51.4 cents), NOT B3 (-148.8 cents)
         let interval_226_6 = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             226.6,
             Scale::Major,
@@ -633,22 +725,22 @@ mod tests {
         assert!(interval_226_6.cents > 50.0 && interval_226_6.cents < 55.0); // Should be ~51.4 cents
         
         // Verify that this is indeed closer to A3 than to B3
-        let a3_freq = interval_frequency(TuningSystem::EqualTemperament, root_freq, 0);
-        let b3_freq = interval_frequency(TuningSystem::EqualTemperament, root_freq, 2);
+        let a3_freq = interval_frequency(TuningSystem::EqualTemperament.tuning(), root_freq, 0);
+        let b3_freq = interval_frequency(TuningSystem::EqualTemperament.tuning(), root_freq, 2);
         let cents_to_a3 = cents_delta(a3_freq, 226.6).abs();
         let cents_to_b3 = cents_delta(b3_freq, 226.6).abs();
         assert!(cents_to_a3 < cents_to_b3, "226.6Hz should be closer to A3 than B3");
         
         // Test that chromatic scale still works correctly (regression test)
         let interval_chromatic = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             226.6,
             Scale::Chromatic,
         );
         // In chromatic scale, this should round to the nearest semitone (A# = 1 semitone)
         let raw_interval = frequency_to_interval_semitones(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             226.6,
         );
@@ -658,7 +750,7 @@ mod tests {
         // Additional test: Demonstrate the bug is fixed by comparing old vs new behavior
         // Calculate what the old algorithm would have done (chromatic rounding first)
         let old_raw_interval = frequency_to_interval_semitones(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             226.6,
         );
@@ -682,14 +774,14 @@ mod tests {
         
         // Test B (11 semitones) in MajorPentatonic - should map to next octave C (12)
         let freq = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             11, // B (not in C Major Pentatonic)
             Scale::MajorPentatonic,
         );
         
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             12, // C (octave)
         );
@@ -697,7 +789,7 @@ mod tests {
         
         // Test Just Intonation with pentatonic scales
         let interval = frequency_to_interval_semitones_scale_aware(
-            TuningSystem::JustIntonation,
+            TuningSystem::JustIntonation.tuning(),
             root_freq,
             root_freq * 7.0 / 6.0, // Septimal minor third (not in pentatonic)
             Scale::MajorPentatonic,
@@ -707,7 +799,7 @@ mod tests {
         
         // Test very large intervals with pentatonic scales
         let freq = interval_frequency_scale_aware(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             25, // 2 octaves + minor 2nd
             Scale::MinorPentatonic,
@@ -715,10 +807,31 @@ mod tests {
         
         // Should map to 2 octaves + root (24 semitones)
         let expected_freq = interval_frequency(
-            TuningSystem::EqualTemperament,
+            TuningSystem::EqualTemperament.tuning(),
             root_freq,
             24,
         );
         assert!((freq - expected_freq).abs() < 0.001);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_custom_scale_filtering() {
+        let root_freq = 440.0;
+        // Root, major third and fifth
+        let triad = Scale::from_mask(0b0000_1001_0001);
+
+        // A perfect fourth lies between the third and the fifth and snaps to the third
+        let interval = frequency_to_interval_semitones_scale_aware(
+            TuningSystem::EqualTemperament.tuning(),
+            root_freq,
+            interval_frequency(TuningSystem::EqualTemperament.tuning(), root_freq, 5),
+            triad,
+        );
+        assert_eq!(interval.semitones, 4);
+        assert!((interval.cents - 100.0).abs() < 0.01);
+
+        // A major sixth is two semitones from the fifth and three from the octave
+        let freq = interval_frequency_scale_aware(TuningSystem::JustIntonation.tuning(), root_freq, 9, triad);
+        assert!((freq - root_freq * 3.0 / 2.0).abs() < 0.01);
+    }
+}
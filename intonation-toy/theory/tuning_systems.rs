@@ -0,0 +1,646 @@
+use std::sync::OnceLock;
+
+use crate::shared_types::{JustRatio, TuningSystem};
+use super::tuning::{IntervalSemitones, cents_delta};
+
+/// A tuning defines the pitch of every scale degree within one period
+///
+/// Implementations provide either cents or frequency ratios per degree, relative
+/// to the root. Degree 0 is always the root, and every period repeats the same
+/// pattern. The period is an octave (2:1) unless the tuning overrides
+/// `period_cents()`. Interval arithmetic and closest-degree search are derived
+/// from the per-degree values by the default methods.
+///
+/// Historical temperaments are conventionally tabulated from C. Here they are
+/// laid out from the root instead, so "Werckmeister III on A" means the
+/// Werckmeister III pattern with A in the position of C.
+pub trait Tuning: Sync {
+    /// Human readable name, used in the user interface
+    fn name(&self) -> &str;
+
+    /// Number of scale degrees per period
+    fn degrees_per_octave(&self) -> usize {
+        12
+    }
+
+    /// Size of a scale degree above the root in cents (0.0 for degree 0)
+    fn degree_cents(&self, degree: usize) -> f32;
+
+    /// Frequency ratio of a scale degree above the root (1.0 for degree 0)
+    fn degree_ratio(&self, degree: usize) -> f32 {
+        2.0_f32.powf(self.degree_cents(degree) / 1200.0)
+    }
+
+    /// Just ratios a note on this degree is accepted as, the first being the degree itself
+    ///
+    /// A just degree can be intonated in more than one way, e.g. a minor seventh as
+    /// 9/5, 16/9 or the harmonic seventh 7/4. Ratios lie within the first period.
+    /// Tunings that are not built from just ratios return an empty slice.
+    fn degree_ratios(&self, _degree: usize) -> &[JustRatio] {
+        &[]
+    }
+
+    /// Size of the repeating period in cents
+    fn period_cents(&self) -> f32 {
+        1200.0
+    }
+
+    /// Size of the interval `interval` degrees away from the root in cents
+    fn interval_cents(&self, interval: i32) -> f32 {
+        let degrees = self.degrees_per_octave() as i32;
+        interval.div_euclid(degrees) as f32 * self.period_cents() + self.degree_cents(interval.rem_euclid(degrees) as usize)
+    }
+
+    /// Frequency of the note `interval` degrees away from the root
+    fn interval_frequency(&self, root_frequency_hz: f32, interval: i32) -> f32 {
+        let degrees = self.degrees_per_octave() as i32;
+        let periods = interval.div_euclid(degrees);
+        let degree = interval.rem_euclid(degrees) as usize;
+        root_frequency_hz * self.degree_ratio(degree) * 2.0_f32.powf(periods as f32 * self.period_cents() / 1200.0)
+    }
+
+    /// Deviation of a target frequency from the note `interval` degrees away from the root
+    ///
+    /// For degrees with just ratios the deviation is measured from the closest
+    /// ratio, which is reported in the result.
+    fn interval_deviation(&self, root_frequency_hz: f32, interval: i32, target_frequency_hz: f32) -> IntervalSemitones {
+        let cents = cents_delta(self.interval_frequency(root_frequency_hz, interval), target_frequency_hz);
+        let degree = interval.rem_euclid(self.degrees_per_octave() as i32) as usize;
+        let degree_cents = self.degree_cents(degree);
+        self.degree_ratios(degree)
+            .iter()
+            .map(|ratio| IntervalSemitones {
+                semitones: interval,
+                cents: cents + degree_cents - ratio.cents(),
+                ratio: Some(*ratio),
+            })
+            .min_by(|a, b| a.cents.abs().total_cmp(&b.cents.abs()))
+            .unwrap_or(IntervalSemitones { semitones: interval, cents, ratio: None })
+    }
+
+    /// Find the closest scale degree to a target frequency
+    ///
+    /// Returns the interval in degrees from the root together with the deviation
+    /// in cents from that degree, or from its closest just ratio. Only the degrees
+    /// of the period the target lies in are candidates, so a slightly flat octave
+    /// is reported against the highest degree of the period below. Equal divisions
+    /// override this with the nearest step, see `nearest_equal_step`.
+    fn frequency_to_interval(&self, root_frequency_hz: f32, target_frequency_hz: f32) -> IntervalSemitones {
+        let degrees = self.degrees_per_octave() as i32;
+        let total_cents = cents_delta(root_frequency_hz, target_frequency_hz);
+        let periods = (total_cents / self.period_cents()).floor() as i32;
+
+        (0..degrees)
+            .map(|degree| self.interval_deviation(root_frequency_hz, periods * degrees + degree, target_frequency_hz))
+            .min_by(|a, b| a.cents.abs().total_cmp(&b.cents.abs()))
+            .expect("a tuning has at least one degree")
+    }
+}
+
+/// Convert a frequency ratio to cents
+fn ratio_to_cents(ratio: f32) -> f32 {
+    1200.0 * ratio.log2()
+}
+
+/// Closest step of an equally divided period to a target frequency
+///
+/// The target is rounded to the nearest step, which may be the root of the next period.
+fn nearest_equal_step(tuning: &dyn Tuning, root_frequency_hz: f32, target_frequency_hz: f32) -> IntervalSemitones {
+    let step_cents = tuning.period_cents() / tuning.degrees_per_octave() as f32;
+    let steps = (cents_delta(root_frequency_hz, target_frequency_hz) / step_cents).round() as i32;
+    tuning.interval_deviation(root_frequency_hz, steps, target_frequency_hz)
+}
+
+/// Twelve-tone equal temperament, every semitone exactly 100 cents
+pub struct EqualTemperament;
+
+impl Tuning for EqualTemperament {
+    fn name(&self) -> &str {
+        "Equal Temperament"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        degree as f32 * 100.0
+    }
+
+    fn frequency_to_interval(&self, root_frequency_hz: f32, target_frequency_hz: f32) -> IntervalSemitones {
+        nearest_equal_step(self, root_frequency_hz, target_frequency_hz)
+    }
+}
+
+/// 5-limit Just Intonation ratios for the 12-tone chromatic scale
+///
+/// Index corresponds to semitones from the root (0-11). The first ratio of each
+/// degree sets its pitch, the others are accepted as alternative intonations.
+const FIVE_LIMIT_RATIOS: [&[JustRatio]; 12] = [
+    &[JustRatio::new(1, 1)],                                // Unison
+    &[JustRatio::new(16, 15)],                              // Minor second
+    &[JustRatio::new(9, 8), JustRatio::new(10, 9)],         // Major second
+    &[JustRatio::new(6, 5)],                                // Minor third
+    &[JustRatio::new(5, 4)],                                // Major third
+    &[JustRatio::new(4, 3)],                                // Perfect fourth
+    &[JustRatio::new(45, 32), JustRatio::new(64, 45)],      // Tritone
+    &[JustRatio::new(3, 2)],                                // Perfect fifth
+    &[JustRatio::new(8, 5)],                                // Minor sixth
+    &[JustRatio::new(5, 3)],                                // Major sixth
+    &[JustRatio::new(9, 5), JustRatio::new(16, 9)],         // Minor seventh
+    &[JustRatio::new(15, 8)],                               // Major seventh
+];
+
+/// 7-limit Just Intonation ratios, the 5-limit set plus septimal intervals
+const SEVEN_LIMIT_RATIOS: [&[JustRatio]; 12] = [
+    &[JustRatio::new(1, 1)],
+    &[JustRatio::new(16, 15), JustRatio::new(15, 14)],
+    &[JustRatio::new(9, 8), JustRatio::new(10, 9), JustRatio::new(8, 7)],
+    &[JustRatio::new(6, 5), JustRatio::new(7, 6)],
+    &[JustRatio::new(5, 4), JustRatio::new(9, 7)],
+    &[JustRatio::new(4, 3)],
+    &[JustRatio::new(45, 32), JustRatio::new(64, 45), JustRatio::new(7, 5), JustRatio::new(10, 7)],
+    &[JustRatio::new(3, 2)],
+    &[JustRatio::new(8, 5), JustRatio::new(14, 9)],
+    &[JustRatio::new(5, 3), JustRatio::new(12, 7)],
+    &[JustRatio::new(9, 5), JustRatio::new(16, 9), JustRatio::new(7, 4)],
+    &[JustRatio::new(15, 8)],
+];
+
+/// 11-limit Just Intonation ratios, the 7-limit set plus undecimal intervals
+///
+/// The undecimal intervals sit on the degree they are conventionally notated on,
+/// e.g. 11/8 is a raised fourth and 16/11 a lowered fifth.
+const ELEVEN_LIMIT_RATIOS: [&[JustRatio]; 12] = [
+    &[JustRatio::new(1, 1)],
+    &[JustRatio::new(16, 15), JustRatio::new(15, 14)],
+    &[JustRatio::new(9, 8), JustRatio::new(10, 9), JustRatio::new(8, 7), JustRatio::new(12, 11)],
+    &[JustRatio::new(6, 5), JustRatio::new(7, 6), JustRatio::new(11, 9)],
+    &[JustRatio::new(5, 4), JustRatio::new(9, 7)],
+    &[JustRatio::new(4, 3), JustRatio::new(11, 8)],
+    &[JustRatio::new(45, 32), JustRatio::new(64, 45), JustRatio::new(7, 5), JustRatio::new(10, 7)],
+    &[JustRatio::new(3, 2), JustRatio::new(16, 11)],
+    &[JustRatio::new(8, 5), JustRatio::new(14, 9)],
+    &[JustRatio::new(5, 3), JustRatio::new(12, 7), JustRatio::new(18, 11)],
+    &[JustRatio::new(9, 5), JustRatio::new(16, 9), JustRatio::new(7, 4), JustRatio::new(11, 6)],
+    &[JustRatio::new(15, 8)],
+];
+
+/// 5-limit Just Intonation
+pub struct JustIntonation;
+
+impl Tuning for JustIntonation {
+    fn name(&self) -> &str {
+        "Just Intonation (5-limit)"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        FIVE_LIMIT_RATIOS[degree][0].cents()
+    }
+
+    fn degree_ratio(&self, degree: usize) -> f32 {
+        FIVE_LIMIT_RATIOS[degree][0].value()
+    }
+
+    fn degree_ratios(&self, degree: usize) -> &[JustRatio] {
+        FIVE_LIMIT_RATIOS[degree]
+    }
+}
+
+/// 7-limit Just Intonation, pitched like the 5-limit set
+pub struct JustIntonation7Limit;
+
+impl Tuning for JustIntonation7Limit {
+    fn name(&self) -> &str {
+        "Just Intonation (7-limit)"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        SEVEN_LIMIT_RATIOS[degree][0].cents()
+    }
+
+    fn degree_ratio(&self, degree: usize) -> f32 {
+        SEVEN_LIMIT_RATIOS[degree][0].value()
+    }
+
+    fn degree_ratios(&self, degree: usize) -> &[JustRatio] {
+        SEVEN_LIMIT_RATIOS[degree]
+    }
+}
+
+/// 11-limit Just Intonation, pitched like the 5-limit set
+pub struct JustIntonation11Limit;
+
+impl Tuning for JustIntonation11Limit {
+    fn name(&self) -> &str {
+        "Just Intonation (11-limit)"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        ELEVEN_LIMIT_RATIOS[degree][0].cents()
+    }
+
+    fn degree_ratio(&self, degree: usize) -> f32 {
+        ELEVEN_LIMIT_RATIOS[degree][0].value()
+    }
+
+    fn degree_ratios(&self, degree: usize) -> &[JustRatio] {
+        ELEVEN_LIMIT_RATIOS[degree]
+    }
+}
+
+/// Pythagorean tuning ratios, built from pure 3:2 fifths
+///
+/// The chain runs from Eb to G#, except for the tritone which uses the
+/// augmented fourth 729/512. The wolf fifth lies between G# and Eb.
+const PYTHAGOREAN_RATIOS: [f32; 12] = [
+    1.0,              // Unison
+    256.0 / 243.0,    // Limma
+    9.0 / 8.0,        // Major second
+    32.0 / 27.0,      // Minor third
+    81.0 / 64.0,      // Ditone
+    4.0 / 3.0,        // Perfect fourth
+    729.0 / 512.0,    // Augmented fourth
+    3.0 / 2.0,        // Perfect fifth
+    128.0 / 81.0,     // Minor sixth
+    27.0 / 16.0,      // Major sixth
+    16.0 / 9.0,       // Minor seventh
+    243.0 / 128.0,    // Major seventh
+];
+
+/// Pythagorean tuning (pure fifths, wide major thirds)
+pub struct Pythagorean;
+
+impl Tuning for Pythagorean {
+    fn name(&self) -> &str {
+        "Pythagorean"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        ratio_to_cents(PYTHAGOREAN_RATIOS[degree])
+    }
+
+    fn degree_ratio(&self, degree: usize) -> f32 {
+        PYTHAGOREAN_RATIOS[degree]
+    }
+}
+
+/// Quarter-comma meantone (fifths narrowed by 1/4 syntonic comma, pure major thirds)
+///
+/// Chain from Eb to G#, with the wolf fifth between G# and Eb.
+const QUARTER_COMMA_MEANTONE_CENTS: [f32; 12] = [
+    0.0, 76.049, 193.157, 310.265, 386.314, 503.422,
+    579.471, 696.578, 772.627, 889.735, 1006.843, 1082.892,
+];
+
+/// Quarter-comma meantone temperament
+pub struct QuarterCommaMeantone;
+
+impl Tuning for QuarterCommaMeantone {
+    fn name(&self) -> &str {
+        "Quarter-Comma Meantone"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        QUARTER_COMMA_MEANTONE_CENTS[degree]
+    }
+}
+
+/// Werckmeister III (1691), fifths C-G-D-A and B-F# narrowed by 1/4 Pythagorean comma
+const WERCKMEISTER_III_CENTS: [f32; 12] = [
+    0.0, 90.225, 192.180, 294.135, 390.225, 498.045,
+    588.270, 696.090, 792.180, 888.270, 996.090, 1092.18,
+];
+
+/// Werckmeister III well temperament
+pub struct WerckmeisterIII;
+
+impl Tuning for WerckmeisterIII {
+    fn name(&self) -> &str {
+        "Werckmeister III"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        WERCKMEISTER_III_CENTS[degree]
+    }
+}
+
+/// Kirnberger III (1779), fifths C-G-D-A-E narrowed by 1/4 syntonic comma, pure C-E third
+const KIRNBERGER_III_CENTS: [f32; 12] = [
+    0.0, 90.225, 193.157, 294.135, 386.314, 498.045,
+    590.224, 696.578, 792.180, 889.735, 996.090, 1088.269,
+];
+
+/// Kirnberger III well temperament
+pub struct KirnbergerIII;
+
+impl Tuning for KirnbergerIII {
+    fn name(&self) -> &str {
+        "Kirnberger III"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        KIRNBERGER_III_CENTS[degree]
+    }
+}
+
+/// Vallotti (c. 1750), fifths F-C-G-D-A-E-B narrowed by 1/6 Pythagorean comma, the rest pure
+const VALLOTTI_CENTS: [f32; 12] = [
+    0.0, 94.135, 196.090, 298.045, 392.180, 501.955,
+    592.180, 698.045, 796.090, 894.135, 1000.000, 1090.225,
+];
+
+/// Vallotti well temperament
+pub struct Vallotti;
+
+impl Tuning for Vallotti {
+    fn name(&self) -> &str {
+        "Vallotti"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        VALLOTTI_CENTS[degree]
+    }
+}
+
+/// Young's second temperament (1800), fifths C-G-D-A-E-B-F# narrowed by 1/6 Pythagorean comma
+///
+/// The same circle of fifths as Vallotti, shifted by one fifth so that C rather
+/// than F starts the tempered chain.
+const YOUNG_CENTS: [f32; 12] = [
+    0.0, 90.225, 196.090, 294.135, 392.180, 498.045,
+    588.270, 698.045, 792.180, 894.135, 996.090, 1090.225,
+];
+
+/// Young well temperament
+pub struct Young;
+
+impl Tuning for Young {
+    fn name(&self) -> &str {
+        "Young"
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        YOUNG_CENTS[degree]
+    }
+}
+
+/// Supported range of divisions for `TuningSystem::EqualDivision`
+pub const MIN_EQUAL_DIVISIONS: u16 = 5;
+pub const MAX_EQUAL_DIVISIONS: u16 = 72;
+
+/// Equal division of the octave into `divisions` steps (n-EDO)
+///
+/// 12-EDO is Equal Temperament. Steps of other divisions are not semitones, so
+/// notes are named after the nearest semitone, see `tuning::degree_to_semitone`.
+pub struct EqualDivision {
+    divisions: u16,
+    name: String,
+}
+
+impl EqualDivision {
+    fn new(divisions: u16) -> Self {
+        Self { divisions, name: format!("{}-EDO", divisions) }
+    }
+}
+
+impl Tuning for EqualDivision {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn degrees_per_octave(&self) -> usize {
+        self.divisions as usize
+    }
+
+    fn degree_cents(&self, degree: usize) -> f32 {
+        degree as f32 * 1200.0 / self.divisions as f32
+    }
+
+    fn frequency_to_interval(&self, root_frequency_hz: f32, target_frequency_hz: f32) -> IntervalSemitones {
+        nearest_equal_step(self, root_frequency_hz, target_frequency_hz)
+    }
+}
+
+/// Every supported equal division, created on first use so they can be handed out as `&'static dyn Tuning`
+static EQUAL_DIVISIONS: OnceLock<Vec<EqualDivision>> = OnceLock::new();
+
+/// Look up the equal division of the octave into `divisions` steps
+///
+/// Returns `None` outside `MIN_EQUAL_DIVISIONS..=MAX_EQUAL_DIVISIONS`.
+pub fn equal_division(divisions: u16) -> Option<&'static EqualDivision> {
+    let equal_divisions = EQUAL_DIVISIONS.get_or_init(|| {
+        (MIN_EQUAL_DIVISIONS..=MAX_EQUAL_DIVISIONS).map(EqualDivision::new).collect()
+    });
+    divisions.checked_sub(MIN_EQUAL_DIVISIONS).and_then(|index| equal_divisions.get(index as usize))
+}
+
+impl TuningSystem {
+    /// Every built-in tuning system, in the order they are listed in the user interface
+    pub const ALL: [TuningSystem; 14] = [
+        TuningSystem::EqualTemperament,
+        TuningSystem::JustIntonation,
+        TuningSystem::JustIntonation7Limit,
+        TuningSystem::JustIntonation11Limit,
+        TuningSystem::Pythagorean,
+        TuningSystem::QuarterCommaMeantone,
+        TuningSystem::WerckmeisterIII,
+        TuningSystem::KirnbergerIII,
+        TuningSystem::Vallotti,
+        TuningSystem::Young,
+        TuningSystem::EqualDivision(19),
+        TuningSystem::EqualDivision(24),
+        TuningSystem::EqualDivision(31),
+        TuningSystem::EqualDivision(53),
+    ];
+
+    /// The `Tuning` implementation this key refers to
+    ///
+    /// Built-in tunings are statics, equal divisions and imported tunings are
+    /// looked up in their registries. An unknown custom tuning id or an unsupported
+    /// number of equal divisions falls back to Equal Temperament.
+    pub fn tuning(self) -> &'static dyn Tuning {
+        match self {
+            TuningSystem::EqualTemperament => &EqualTemperament,
+            TuningSystem::JustIntonation => &JustIntonation,
+            TuningSystem::JustIntonation7Limit => &JustIntonation7Limit,
+            TuningSystem::JustIntonation11Limit => &JustIntonation11Limit,
+            TuningSystem::Pythagorean => &Pythagorean,
+            TuningSystem::QuarterCommaMeantone => &QuarterCommaMeantone,
+            TuningSystem::WerckmeisterIII => &WerckmeisterIII,
+            TuningSystem::KirnbergerIII => &KirnbergerIII,
+            TuningSystem::Vallotti => &Vallotti,
+            TuningSystem::Young => &Young,
+            TuningSystem::EqualDivision(divisions) => match equal_division(divisions) {
+                Some(tuning) => tuning,
+                None => &EqualTemperament,
+            },
+            TuningSystem::Custom(id) => match super::scala::custom_tuning(id) {
+                Some(tuning) => tuning,
+                None => &EqualTemperament,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_tunings_start_at_unison_and_ascend() {
+        for tuning_system in TuningSystem::ALL {
+            let tuning = tuning_system.tuning();
+            assert_eq!(tuning.degree_cents(0), 0.0, "{} should start at unison", tuning.name());
+            assert!((tuning.degree_ratio(0) - 1.0).abs() < 1e-6);
+            for degree in 1..tuning.degrees_per_octave() {
+                assert!(
+                    tuning.degree_cents(degree) > tuning.degree_cents(degree - 1),
+                    "{} degree {} should be higher than degree {}", tuning.name(), degree, degree - 1
+                );
+                assert!(tuning.degree_cents(degree) < 1200.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ratio_and_cents_agree() {
+        for tuning_system in TuningSystem::ALL {
+            let tuning = tuning_system.tuning();
+            for degree in 0..tuning.degrees_per_octave() {
+                let from_ratio = ratio_to_cents(tuning.degree_ratio(degree));
+                assert!((from_ratio - tuning.degree_cents(degree)).abs() < 0.01, "{} degree {}", tuning.name(), degree);
+            }
+        }
+    }
+
+    #[test]
+    fn test_temperament_characteristic_intervals() {
+        // Pythagorean fifth is pure and major third is a ditone
+        assert!((Pythagorean.degree_cents(7) - 701.955).abs() < 0.01);
+        assert!((Pythagorean.degree_cents(4) - 407.820).abs() < 0.01);
+
+        // Quarter-comma meantone has a pure major third
+        assert!((QuarterCommaMeantone.degree_ratio(4) - 5.0 / 4.0).abs() < 1e-4);
+
+        // Kirnberger III has a pure major third on the root
+        assert!((KirnbergerIII.degree_ratio(4) - 5.0 / 4.0).abs() < 1e-4);
+
+        // Vallotti and Young share the tempered fifth of 698.045 cents
+        assert!((Vallotti.degree_cents(7) - 698.045).abs() < 0.01);
+        assert!((Young.degree_cents(7) - 698.045).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_interval_frequency_octaves() {
+        for tuning_system in TuningSystem::ALL {
+            let tuning = tuning_system.tuning();
+            let degrees = tuning.degrees_per_octave() as i32;
+            assert!((tuning.interval_frequency(220.0, degrees) - 440.0).abs() < 0.001);
+            assert!((tuning.interval_frequency(220.0, -degrees) - 110.0).abs() < 0.001);
+            let fifth = tuning.interval_frequency(220.0, degrees * 7 / 12);
+            let fifth_octave_down = tuning.interval_frequency(220.0, degrees * 7 / 12 - degrees);
+            assert!((fifth / fifth_octave_down - 2.0).abs() < 0.001);
+            assert!((tuning.interval_cents(degrees + 1) - 1200.0 - tuning.degree_cents(1)).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_equal_divisions() {
+        let edo_19 = TuningSystem::EqualDivision(19).tuning();
+        assert_eq!(edo_19.name(), "19-EDO");
+        assert_eq!(edo_19.degrees_per_octave(), 19);
+        assert!((edo_19.degree_cents(11) - 694.737).abs() < 0.001);
+
+        // 31-EDO has a nearly pure major third at 10 steps
+        let edo_31 = TuningSystem::EqualDivision(31).tuning();
+        assert!((edo_31.degree_cents(10) - 386.314).abs() < 1.0);
+        let result = edo_31.frequency_to_interval(220.0, 220.0 * 5.0 / 4.0);
+        assert_eq!(result.semitones, 10);
+        assert!((result.cents + 0.78).abs() < 0.01);
+
+        // 53-EDO steps are about 22.6 cents, the fifth is 31 steps
+        let result = TuningSystem::EqualDivision(53).tuning().frequency_to_interval(220.0, 330.0);
+        assert_eq!(result.semitones, 31);
+        assert!(result.cents.abs() < 0.1);
+
+        // 12-EDO is Equal Temperament
+        let edo_12 = TuningSystem::EqualDivision(12).tuning();
+        for degree in 0..12 {
+            assert_eq!(edo_12.degree_cents(degree), EqualTemperament.degree_cents(degree));
+        }
+
+        assert!(equal_division(MIN_EQUAL_DIVISIONS - 1).is_none());
+        assert!(equal_division(MAX_EQUAL_DIVISIONS + 1).is_none());
+        assert_eq!(TuningSystem::EqualDivision(1000).tuning().name(), "Equal Temperament");
+    }
+
+    #[test]
+    fn test_frequency_to_interval_roundtrip() {
+        for tuning_system in TuningSystem::ALL {
+            let tuning = tuning_system.tuning();
+            for interval in -24..=24 {
+                let frequency = tuning.interval_frequency(220.0, interval);
+                let result = tuning.frequency_to_interval(220.0, frequency);
+                assert_eq!(result.semitones, interval, "{} interval {}", tuning.name(), interval);
+                assert!(result.cents.abs() < 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frequency_to_interval_stays_in_target_octave() {
+        // 1190 cents above the root is closer to the octave, but only the major seventh is a candidate
+        let frequency = 220.0 * 2.0_f32.powf(1190.0 / 1200.0);
+        let result = JustIntonation.frequency_to_interval(220.0, frequency);
+        assert_eq!(result.semitones, 11);
+        assert!((result.cents - 101.73).abs() < 0.01);
+
+        // Equal divisions round to the nearest step across the octave
+        let result = EqualTemperament.frequency_to_interval(220.0, frequency);
+        assert_eq!(result.semitones, 12);
+        assert!((result.cents + 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_just_ratio_sets_share_the_5_limit_pitches() {
+        for degree in 0..12 {
+            let ratios = JustIntonation.degree_ratios(degree);
+            assert_eq!(JustIntonation7Limit.degree_ratios(degree)[0], ratios[0]);
+            assert_eq!(JustIntonation11Limit.degree_ratios(degree)[0], ratios[0]);
+            assert!(SEVEN_LIMIT_RATIOS[degree].starts_with(ratios));
+            assert!(ELEVEN_LIMIT_RATIOS[degree].starts_with(SEVEN_LIMIT_RATIOS[degree]));
+        }
+        assert!(EqualTemperament.degree_ratios(7).is_empty());
+    }
+
+    #[test]
+    fn test_frequency_to_interval_matches_candidate_ratios() {
+        // A harmonic seventh is an out of tune 9/5 in 5-limit, but in tune in 7-limit
+        let harmonic_seventh = 220.0 * 7.0 / 4.0 * 2.0_f32.powf(3.0 / 1200.0);
+        let result = JustIntonation.frequency_to_interval(220.0, harmonic_seventh);
+        assert_eq!(result.semitones, 10);
+        assert_eq!(result.ratio, Some(JustRatio::new(16, 9)));
+        assert!((result.cents + 24.2).abs() < 0.1, "{}", result.cents);
+
+        let result = JustIntonation7Limit.frequency_to_interval(220.0, harmonic_seventh);
+        assert_eq!(result.semitones, 10);
+        assert_eq!(result.ratio, Some(JustRatio::new(7, 4)));
+        assert!((result.cents - 3.0).abs() < 0.01);
+
+        // A subminor third an octave up
+        let result = JustIntonation7Limit.frequency_to_interval(220.0, 440.0 * 7.0 / 6.0);
+        assert_eq!(result.semitones, 15);
+        assert_eq!(result.ratio, Some(JustRatio::new(7, 6)));
+        assert!(result.cents.abs() < 0.01);
+
+        // The 11th harmonic is only matched by the 11-limit set
+        let result = JustIntonation11Limit.frequency_to_interval(220.0, 220.0 * 11.0 / 8.0);
+        assert_eq!(result.semitones, 5);
+        assert_eq!(result.ratio, Some(JustRatio::new(11, 8)));
+        assert!(result.cents.abs() < 0.01);
+
+        // Below the root ratios are reported within the period
+        let result = JustIntonation.frequency_to_interval(220.0, 110.0 * 3.0 / 2.0);
+        assert_eq!(result.semitones, -5);
+        assert_eq!(result.ratio, Some(JustRatio::new(3, 2)));
+
+        assert_eq!(EqualTemperament.frequency_to_interval(220.0, 330.0).ratio, None);
+    }
+}
@@ -0,0 +1,229 @@
+//! Vibrato analysis of the recent pitch history
+//!
+//! The pitch of the last `VIBRATO_WINDOW_SECONDS` is converted to cents and a
+//! straight line is subtracted, so a slow drift doesn't hide the swing. The
+//! vibrato cycles are found from where the remaining swing crosses zero, with a
+//! little hysteresis against detection jitter. From the whole cycles between
+//! the first and the last crossing come:
+//!
+//! - the rate, from the number of cycles and their duration
+//! - the extent above and below the center, from the RMS of the swing
+//! - the perceived center pitch, the mean pitch over the cycles
+//!
+//! The swing only counts as vibrato when every half cycle fits the rate range and
+//! the extent is in range, which rules out steady notes, glides and note changes.
+
+use std::collections::VecDeque;
+use crate::shared_types::VibratoEstimate;
+
+/// Length of the analyzed pitch history in seconds
+pub const VIBRATO_WINDOW_SECONDS: f64 = 1.0;
+
+/// Slowest swing that counts as vibrato
+pub const MIN_VIBRATO_RATE_HZ: f32 = 3.0;
+
+/// Fastest swing that counts as vibrato
+pub const MAX_VIBRATO_RATE_HZ: f32 = 10.0;
+
+/// Smallest extent above and below the center that counts as vibrato
+pub const MIN_VIBRATO_EXTENT_CENTS: f32 = 8.0;
+
+/// Largest extent above and below the center that counts as vibrato
+pub const MAX_VIBRATO_EXTENT_CENTS: f32 = 150.0;
+
+/// Number of whole cycles needed for an estimate
+const MIN_VIBRATO_CYCLES: usize = 2;
+
+/// Distance from the center in cents the swing has to reach before a crossing counts
+const CROSSING_HYSTERESIS_CENTS: f32 = 3.0;
+
+/// Longest gap in the detected pitch in seconds before the history starts over
+const MAX_GAP_SECONDS: f64 = 0.1;
+
+/// Recent detected pitches to measure vibrato in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VibratoAnalyzer {
+    /// Time in seconds and frequency in Hz of the detected pitches, oldest first
+    frames: VecDeque<(f64, f32)>,
+}
+
+impl VibratoAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the pitch of one analysis frame, `None` when no pitch was detected
+    pub fn add(&mut self, time: f64, frequency: Option<f32>) {
+        if self.frames.back().is_some_and(|&(last_time, _)| time - last_time > MAX_GAP_SECONDS) {
+            self.frames.clear();
+        }
+        if let Some(frequency) = frequency.filter(|&frequency| frequency > 0.0) {
+            self.frames.push_back((time, frequency));
+        }
+        while self.frames.front().is_some_and(|&(first_time, _)| time - first_time > VIBRATO_WINDOW_SECONDS) {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Vibrato in the pitch history, `None` if the pitch doesn't swing like vibrato
+    pub fn estimate(&self) -> Option<VibratoEstimate> {
+        let &(_, reference) = self.frames.front()?;
+        let cents: Vec<(f64, f32)> = self.frames
+            .iter()
+            .map(|&(time, frequency)| (time, 1200.0 * (frequency / reference).log2()))
+            .collect();
+        let swing = detrend(&cents);
+
+        let crossings = zero_crossings(&swing);
+        let half_cycles = crossings.len().saturating_sub(1);
+        if half_cycles < 2 * MIN_VIBRATO_CYCLES {
+            return None;
+        }
+        let shortest_half_cycle = 0.5 / MAX_VIBRATO_RATE_HZ as f64;
+        let longest_half_cycle = 0.5 / MIN_VIBRATO_RATE_HZ as f64;
+        if crossings.windows(2).any(|pair| !(shortest_half_cycle..=longest_half_cycle).contains(&(pair[1] - pair[0]))) {
+            return None;
+        }
+
+        // Whole cycles only, so the mean isn't pulled towards an extra half cycle
+        let last_crossing = crossings[half_cycles - half_cycles % 2];
+        let first_crossing = crossings[0];
+        let in_cycles = |time: f64| time >= first_crossing && time <= last_crossing;
+        let cycle_cents: Vec<f32> = cents.iter().filter(|(time, _)| in_cycles(*time)).map(|&(_, cents)| cents).collect();
+        let cycle_swing: Vec<f32> = swing.iter().filter(|(time, _)| in_cycles(*time)).map(|&(_, swing)| swing).collect();
+        if cycle_cents.is_empty() {
+            return None;
+        }
+
+        let rate_hz = ((half_cycles / 2) as f64 / (last_crossing - first_crossing)) as f32;
+        let mean_square = cycle_swing.iter().map(|swing| swing * swing).sum::<f32>() / cycle_swing.len() as f32;
+        let extent_cents = std::f32::consts::SQRT_2 * mean_square.sqrt();
+        let center_cents = cycle_cents.iter().sum::<f32>() / cycle_cents.len() as f32;
+
+        if !(MIN_VIBRATO_EXTENT_CENTS..=MAX_VIBRATO_EXTENT_CENTS).contains(&extent_cents) {
+            return None;
+        }
+        Some(VibratoEstimate {
+            rate_hz,
+            extent_cents,
+            center_frequency: reference * 2.0_f32.powf(center_cents / 1200.0),
+        })
+    }
+}
+
+/// Subtract the least squares line through the points
+fn detrend(points: &[(f64, f32)]) -> Vec<(f64, f32)> {
+    let count = points.len() as f64;
+    let mean_time = points.iter().map(|&(time, _)| time).sum::<f64>() / count;
+    let mean_value = points.iter().map(|&(_, value)| value as f64).sum::<f64>() / count;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for &(time, value) in points {
+        covariance += (time - mean_time) * (value as f64 - mean_value);
+        variance += (time - mean_time) * (time - mean_time);
+    }
+    let slope = if variance > 0.0 { covariance / variance } else { 0.0 };
+    points
+        .iter()
+        .map(|&(time, value)| (time, (value as f64 - mean_value - slope * (time - mean_time)) as f32))
+        .collect()
+}
+
+/// Times where the swing changes sides, interpolated between frames
+///
+/// A crossing only counts once the swing gets `CROSSING_HYSTERESIS_CENTS` past
+/// zero on the other side.
+fn zero_crossings(swing: &[(f64, f32)]) -> Vec<f64> {
+    let mut crossings = Vec::new();
+    let mut above: Option<bool> = None;
+    let mut last_zero = None;
+    for pair in swing.windows(2) {
+        let ((time_a, a), (time_b, b)) = (pair[0], pair[1]);
+        if (a < 0.0) != (b < 0.0) {
+            last_zero = Some(time_a + (time_b - time_a) * (a / (a - b)) as f64);
+        }
+        let side = if b > CROSSING_HYSTERESIS_CENTS {
+            Some(true)
+        } else if b < -CROSSING_HYSTERESIS_CENTS {
+            Some(false)
+        } else {
+            continue;
+        };
+        if above.is_some() && above != side {
+            crossings.extend(last_zero);
+        }
+        above = side;
+    }
+    crossings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME_SECONDS: f64 = 1.0 / 60.0;
+
+    /// Analyzer fed with `seconds` of a pitch given as a function of time
+    fn analyze(seconds: f64, pitch: impl Fn(f64) -> f32) -> VibratoAnalyzer {
+        let mut analyzer = VibratoAnalyzer::new();
+        for i in 0..(seconds / FRAME_SECONDS) as usize {
+            let time = i as f64 * FRAME_SECONDS;
+            analyzer.add(time, Some(pitch(time)));
+        }
+        analyzer
+    }
+
+    /// Frequency `cents` away from `frequency`
+    fn offset(frequency: f32, cents: f64) -> f32 {
+        frequency * 2.0_f32.powf(cents as f32 / 1200.0)
+    }
+
+    fn sine(rate_hz: f64, extent_cents: f64, time: f64) -> f64 {
+        extent_cents * (2.0 * std::f64::consts::PI * rate_hz * time + 0.7).sin()
+    }
+
+    #[test]
+    fn test_estimate_vibrato() {
+        let analyzer = analyze(1.5, |time| offset(440.0, sine(5.5, 40.0, time)));
+        let vibrato = analyzer.estimate().expect("a steady 5.5 Hz vibrato");
+        assert!((vibrato.rate_hz - 5.5).abs() < 0.2, "rate {}", vibrato.rate_hz);
+        assert!((vibrato.extent_cents - 40.0).abs() < 3.0, "extent {}", vibrato.extent_cents);
+        assert!((1200.0 * (vibrato.center_frequency / 440.0).log2()).abs() < 2.0, "center {}", vibrato.center_frequency);
+    }
+
+    #[test]
+    fn test_center_follows_detuned_singer() {
+        // 15 cents flat on average while drifting up 10 cents per second
+        let analyzer = analyze(1.4, |time| offset(440.0, -15.0 + 10.0 * (time - 0.9) + sine(6.5, 25.0, time)));
+        let vibrato = analyzer.estimate().unwrap();
+        assert!((vibrato.rate_hz - 6.5).abs() < 0.3, "rate {}", vibrato.rate_hz);
+        let center_cents = 1200.0 * (vibrato.center_frequency / 440.0).log2();
+        assert!((center_cents + 15.0).abs() < 3.0, "center {} cents", center_cents);
+    }
+
+    #[test]
+    fn test_no_vibrato_in_steady_notes_and_glides() {
+        assert_eq!(analyze(1.5, |_| 440.0).estimate(), None);
+        assert_eq!(analyze(1.5, |time| offset(440.0, 2.0 * (time * 97.0).sin())).estimate(), None);
+        assert_eq!(analyze(1.5, |time| offset(440.0, 200.0 * time)).estimate(), None);
+        // Trills and tremolo-fast wobbles are outside the vibrato rate range
+        assert_eq!(analyze(1.5, |time| offset(440.0, sine(2.0, 40.0, time))).estimate(), None);
+        assert_eq!(analyze(1.5, |time| offset(440.0, sine(14.0, 40.0, time))).estimate(), None);
+    }
+
+    #[test]
+    fn test_history_restarts_after_gap() {
+        let mut analyzer = analyze(1.5, |time| offset(440.0, sine(5.5, 40.0, time)));
+        analyzer.add(1.55, None);
+        assert!(analyzer.estimate().is_some());
+        analyzer.add(1.7, None);
+        assert_eq!(analyzer.estimate(), None);
+
+        analyzer = analyze(1.5, |time| offset(440.0, sine(5.5, 40.0, time)));
+        analyzer.reset();
+        assert_eq!(analyzer.estimate(), None);
+    }
+}
@@ -10,12 +10,16 @@ use std::rc::Rc;
 #[cfg(target_arch = "wasm32")]
 use std::cell::RefCell;
 #[cfg(target_arch = "wasm32")]
-use std::sync::atomic::{AtomicU8, AtomicI8, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicI8, AtomicU16, AtomicU32, Ordering};
 
 #[cfg(target_arch = "wasm32")]
 use crate::common::dev_log;
 #[cfg(target_arch = "wasm32")]
-use crate::shared_types::{TuningSystem, MidiNote, Scale, increment_midi_note, decrement_midi_note};
+use crate::shared_types::{
+    TuningSystem, MidiNote, Scale, NoteNamingSystem, StretchTuning, KeyDetection, Transposition,
+    DronePreset, DroneVoiceTuning, DroneTimbre, MetronomeConfig, OnsetTiming, IntonationPitch,
+    VibratoEstimate, RecordingAnalysisStatus, increment_midi_note, decrement_midi_note,
+};
 #[cfg(target_arch = "wasm32")]
 use crate::web::styling;
 
@@ -23,11 +27,30 @@ use crate::web::styling;
 #[cfg(target_arch = "wasm32")]
 static CURRENT_ROOT_NOTE: AtomicU8 = AtomicU8::new(57);
 
+// Global state for current A4 reference frequency in whole Hz - initialized to 440
+#[cfg(target_arch = "wasm32")]
+static CURRENT_REFERENCE_FREQUENCY: AtomicU16 = AtomicU16::new(440);
+
+// Global state for the current scale as a semitone mask - initialized to chromatic
+#[cfg(target_arch = "wasm32")]
+static CURRENT_SCALE_MASK: AtomicU16 = AtomicU16::new(0x0FFF);
+
+// Global state for the metronome tempo in whole beats per minute - initialized to 100
+#[cfg(target_arch = "wasm32")]
+static CURRENT_METRONOME_BPM: AtomicU16 = AtomicU16::new(100);
+
+// Global state for the id of the active imported tuning - initialized to none (u32::MAX)
+#[cfg(target_arch = "wasm32")]
+static CURRENT_CUSTOM_TUNING_ID: AtomicU32 = AtomicU32::new(u32::MAX);
 
 // Global state for tuning fork volume slider position (0-100)
 #[cfg(target_arch = "wasm32")]
 static CURRENT_TUNING_FORK_VOLUME_POSITION: AtomicU8 = AtomicU8::new(0);
 
+/// Presenter handler bound to a button
+#[cfg(target_arch = "wasm32")]
+type PresenterAction = fn(&mut crate::presentation::Presenter);
+
 /// Convert slider position (0-100) to amplitude (0.0-1.0) using dual-scale approach
 /// - 0-20%: Linear scaling from 0.0 to 0.01 amplitude
 /// - 20-100%: dB scaling from -40dB to 0dB
@@ -85,12 +108,290 @@ fn slider_position_to_db_display(position: f32) -> String {
     }
 }
 
-/// Format a MIDI note number as a string (e.g., 60 -> "C4")
+/// Format an A4 reference frequency for display (e.g., 442.0 -> "A4 = 442 Hz")
+#[cfg(target_arch = "wasm32")]
+fn format_reference_frequency(reference_frequency: f32) -> String {
+    format!("A4 = {} Hz", reference_frequency.round() as u16)
+}
+
+/// Metronome settings from the Rhythm controls
+#[cfg(target_arch = "wasm32")]
+fn metronome_config_from_ui(document: &Document) -> MetronomeConfig {
+    let is_pressed = |button_id: &str| {
+        document.get_element_by_id(button_id)
+            .and_then(|button| button.get_attribute("aria-pressed"))
+            .as_deref() == Some("true")
+    };
+    let select_value = |select_id: &str| {
+        document.get_element_by_id(select_id)
+            .and_then(|element| element.dyn_into::<HtmlSelectElement>().ok())
+            .and_then(|html_select| html_select.value().parse::<u32>().ok())
+    };
+    let default = MetronomeConfig::default();
+    MetronomeConfig {
+        enabled: is_pressed("metronome-button"),
+        bpm: CURRENT_METRONOME_BPM.load(Ordering::Relaxed) as f32,
+        beats_per_bar: select_value("metronome-meter-select").unwrap_or(default.beats_per_bar),
+        accent: is_pressed("metronome-accent-button"),
+        subdivision: select_value("metronome-subdivision-select").unwrap_or(default.subdivision),
+        volume: default.volume,
+        latency_compensation_ms: select_value("metronome-latency-select").map_or(default.latency_compensation_ms, |ms| ms as f32),
+    }
+}
+
+/// HTML select value for the pitch the intonation is judged from
+#[cfg(target_arch = "wasm32")]
+fn intonation_pitch_to_value(intonation_pitch: IntonationPitch) -> &'static str {
+    match intonation_pitch {
+        IntonationPitch::Instantaneous => "instantaneous",
+        IntonationPitch::VibratoCenter => "vibrato-center",
+    }
+}
+
+/// Format a vibrato for display (e.g., "Vibrato 5.5 Hz ±40¢")
+#[cfg(target_arch = "wasm32")]
+fn format_vibrato(vibrato: &VibratoEstimate) -> String {
+    format!("Vibrato {:.1} Hz ±{:.0}¢", vibrato.rate_hz, vibrato.extent_cents)
+}
+
+/// Format a note start offset for display (e.g., 12.3 -> "12 ms late", -4.0 -> "4 ms early")
+#[cfg(target_arch = "wasm32")]
+fn format_onset_offset(offset_ms: f32) -> String {
+    let rounded = offset_ms.round();
+    if rounded > 0.0 {
+        format!("{} ms late", rounded)
+    } else if rounded < 0.0 {
+        format!("{} ms early", -rounded)
+    } else {
+        "on time".to_string()
+    }
+}
+
+/// Format a note start with its click (e.g., "Beat 3: 12 ms late", "Beat 2.2: on time")
+#[cfg(target_arch = "wasm32")]
+fn format_onset_timing(timing: &OnsetTiming) -> String {
+    let click = if timing.subdivision == 0 {
+        format!("Beat {}", timing.beat + 1)
+    } else {
+        format!("Beat {}.{}", timing.beat + 1, timing.subdivision + 1)
+    };
+    format!("{}: {}", click, format_onset_offset(timing.offset_ms))
+}
+
+/// Format the state of a recording analysis for display (e.g., "Analyzing… 42%")
+#[cfg(target_arch = "wasm32")]
+fn format_recording_analysis_status(status: &RecordingAnalysisStatus) -> String {
+    match status {
+        RecordingAnalysisStatus::InProgress(progress) => format!("Analyzing… {:.0}%", progress * 100.0),
+        RecordingAnalysisStatus::Finished(results) => format!("Analyzed {} frames", results.len()),
+        RecordingAnalysisStatus::Failed(e) => format!("Analysis failed: {}", e),
+    }
+}
+
+/// HTML select value for a tuning system
+#[cfg(target_arch = "wasm32")]
+fn tuning_system_to_value(tuning_system: TuningSystem) -> String {
+    match tuning_system {
+        TuningSystem::EqualTemperament => "equal".to_string(),
+        TuningSystem::JustIntonation => "just".to_string(),
+        TuningSystem::JustIntonation7Limit => "just_7_limit".to_string(),
+        TuningSystem::JustIntonation11Limit => "just_11_limit".to_string(),
+        TuningSystem::Pythagorean => "pythagorean".to_string(),
+        TuningSystem::QuarterCommaMeantone => "quarter_comma_meantone".to_string(),
+        TuningSystem::WerckmeisterIII => "werckmeister_iii".to_string(),
+        TuningSystem::KirnbergerIII => "kirnberger_iii".to_string(),
+        TuningSystem::Vallotti => "vallotti".to_string(),
+        TuningSystem::Young => "young".to_string(),
+        TuningSystem::EqualDivision(divisions) => format!("edo_{}", divisions),
+        TuningSystem::Custom(id) => format!("custom_{}", id),
+    }
+}
+
+/// Tuning system for an HTML select value
+#[cfg(target_arch = "wasm32")]
+fn tuning_system_from_value(value: &str) -> Option<TuningSystem> {
+    if let Some(id) = value.strip_prefix("custom_") {
+        let id = id.parse::<u16>().ok()?;
+        return crate::theory::scala::custom_tuning(id).map(|_| TuningSystem::Custom(id));
+    }
+    TuningSystem::ALL
+        .into_iter()
+        .find(|tuning_system| tuning_system_to_value(*tuning_system) == value)
+}
+
+/// Add a dropdown option for an imported tuning unless it is already listed
+#[cfg(target_arch = "wasm32")]
+fn ensure_tuning_system_option(document: &Document, tuning_select: &web_sys::Element, tuning_system: TuningSystem) {
+    let value = tuning_system_to_value(tuning_system);
+    let selector = format!("option[value=\"{}\"]", value);
+    if matches!(tuning_select.query_selector(&selector), Ok(Some(_))) {
+        return;
+    }
+    let Ok(option) = document.create_element("option") else {
+        dev_log!("Failed to create tuning system option");
+        return;
+    };
+    let _ = option.set_attribute("value", &value);
+    option.set_text_content(Some(tuning_system.tuning().name()));
+    let _ = tuning_select.append_child(&option);
+}
+
+/// Read the text of the first file selected in a file input, if any
+#[cfg(target_arch = "wasm32")]
+async fn read_selected_file(document: &Document, input_id: &str) -> Option<String> {
+    let input = document.get_element_by_id(input_id)?;
+    let file = input.dyn_ref::<HtmlInputElement>()?.files()?.get(0)?;
+    match wasm_bindgen_futures::JsFuture::from(file.text()).await {
+        Ok(text) => text.as_string(),
+        Err(err) => {
+            dev_log!("Failed to read {}: {:?}", file.name(), err);
+            None
+        }
+    }
+}
+
+/// Read the bytes of the first file selected in a file input, if any
+#[cfg(target_arch = "wasm32")]
+async fn read_selected_file_bytes(document: &Document, input_id: &str) -> Option<Vec<u8>> {
+    let input = document.get_element_by_id(input_id)?;
+    let file = input.dyn_ref::<HtmlInputElement>()?.files()?.get(0)?;
+    match wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await {
+        Ok(buffer) => Some(js_sys::Uint8Array::new(&buffer).to_vec()),
+        Err(err) => {
+            dev_log!("Failed to read {}: {:?}", file.name(), err);
+            None
+        }
+    }
+}
+
+/// Offer text to the user as a file download
+#[cfg(target_arch = "wasm32")]
+fn download_text_file(file_name: &str, mime_type: &str, contents: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let document = window().and_then(|window| window.document()).ok_or("No document")?;
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let anchor = document.create_element("a")?.dyn_into::<web_sys::HtmlAnchorElement>()?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Interval labels for the scale builder toggles, indexed by semitones above the root
+#[cfg(target_arch = "wasm32")]
+const SCALE_DEGREE_LABELS: [&str; 12] = ["1", "♭2", "2", "♭3", "3", "4", "♯4", "5", "♭6", "6", "♭7", "7"];
+
+/// HTML select value for a scale
+#[cfg(target_arch = "wasm32")]
+fn scale_to_value(scale: Scale) -> &'static str {
+    match scale {
+        Scale::Chromatic => "chromatic",
+        Scale::Major => "major",
+        Scale::Minor => "minor",
+        Scale::MajorPentatonic => "major_pentatonic",
+        Scale::MinorPentatonic => "minor_pentatonic",
+        Scale::Dorian => "dorian",
+        Scale::Phrygian => "phrygian",
+        Scale::Lydian => "lydian",
+        Scale::Mixolydian => "mixolydian",
+        Scale::Locrian => "locrian",
+        Scale::HarmonicMinor => "harmonic_minor",
+        Scale::MelodicMinor => "melodic_minor",
+        Scale::WholeTone => "whole_tone",
+        Scale::Blues => "blues",
+        Scale::OctatonicWholeHalf => "octatonic_whole_half",
+        Scale::OctatonicHalfWhole => "octatonic_half_whole",
+        Scale::Custom(_) => "custom",
+    }
+}
+
+/// Built-in scale for an HTML select value
+#[cfg(target_arch = "wasm32")]
+fn scale_from_value(value: &str) -> Option<Scale> {
+    Scale::ALL
+        .into_iter()
+        .find(|scale| scale_to_value(*scale) == value)
+}
+
+/// Format the root note spelled for its scale in written pitch (e.g., 63 in minor -> "D#4")
+/// 
+/// Relative naming systems would always name the root "Do" or "1", so the
+/// root is shown with letter names instead.
+#[cfg(target_arch = "wasm32")]
+fn format_root_note(root_note: MidiNote, scale: Scale, note_naming_system: NoteNamingSystem, transposition: Transposition) -> String {
+    let system = if note_naming_system.is_relative() { NoteNamingSystem::Letter } else { note_naming_system };
+    crate::theory::note_naming::written_note_name(root_note, root_note, scale, system, transposition)
+}
+
+/// HTML select value for a note naming system
+#[cfg(target_arch = "wasm32")]
+fn note_naming_to_value(system: NoteNamingSystem) -> &'static str {
+    match system {
+        NoteNamingSystem::Letter => "letter",
+        NoteNamingSystem::FixedDo => "fixed_do",
+        NoteNamingSystem::MovableDo => "movable_do",
+        NoteNamingSystem::German => "german",
+        NoteNamingSystem::Nashville => "nashville",
+    }
+}
+
+/// HTML select value for a stretch tuning, `None` for curves without a preset
+#[cfg(target_arch = "wasm32")]
+fn stretch_tuning_to_value(stretch_tuning: StretchTuning) -> Option<&'static str> {
+    match stretch_tuning {
+        StretchTuning::None => Some("none"),
+        StretchTuning::Measured => Some("measured"),
+        _ if stretch_tuning == StretchTuning::PIANO => Some("piano"),
+        StretchTuning::Railsback { .. } => None,
+    }
+}
+
+/// HTML select value for a key detection mode
 #[cfg(target_arch = "wasm32")]
-fn format_midi_note(midi_note: MidiNote) -> String {
-    crate::shared_types::midi_note_to_name(midi_note)
+fn key_detection_to_value(key_detection: KeyDetection) -> &'static str {
+    match key_detection {
+        KeyDetection::Off => "off",
+        KeyDetection::Suggest => "suggest",
+        KeyDetection::AutoApply => "auto",
+    }
 }
 
+/// Visible range of a pitch view, e.g. "±3 semitones" or "±1.5 octaves"
+#[cfg(target_arch = "wasm32")]
+fn format_pitch_view_range(pitch_view: crate::presentation::PitchView) -> String {
+    let semitones = (pitch_view.half_range * 12.0).round();
+    if semitones < 12.0 {
+        format!("±{} semitone{}", semitones, if semitones == 1.0 { "" } else { "s" })
+    } else {
+        format!("±{} octave{}", pitch_view.half_range, if pitch_view.half_range == 1.0 { "" } else { "s" })
+    }
+}
+
+/// Show the visible range and whether the view follows the pitch
+#[cfg(target_arch = "wasm32")]
+fn show_pitch_view(document: &Document, pitch_view: crate::presentation::PitchView, follow: bool) {
+    if let Some(display) = document.get_element_by_id("pitch-view-display") {
+        display.set_text_content(Some(&format_pitch_view_range(pitch_view)));
+    }
+    if let Some(follow_button) = document.get_element_by_id("pitch-view-follow") {
+        let _ = follow_button.set_attribute("aria-pressed", if follow { "true" } else { "false" });
+    }
+}
+
+/// Stretch tuning for an HTML select value
+#[cfg(target_arch = "wasm32")]
+fn value_to_stretch_tuning(value: &str) -> Option<StretchTuning> {
+    match value {
+        "none" => Some(StretchTuning::None),
+        "piano" => Some(StretchTuning::PIANO),
+        "measured" => Some(StretchTuning::Measured),
+        _ => None,
+    }
+}
 
 #[cfg(target_arch = "wasm32")]
 pub fn setup_main_scene_ui() {
@@ -111,6 +412,12 @@ pub fn setup_main_scene_ui() {
         dev_log!("Warning: root-note-display element not found in HTML");
     }
 
+    if let Some(reference_display) = document.get_element_by_id("reference-frequency-display") {
+        reference_display.set_text_content(Some(&format_reference_frequency(crate::model::REFERENCE_FREQUENCY)));
+    } else {
+        dev_log!("Warning: reference-frequency-display element not found in HTML");
+    }
+
     if let Some(volume_display) = document.get_element_by_id("tuning-fork-volume-display") {
         volume_display.set_text_content(Some(&slider_position_to_db_display(0.0)));
     } else {
@@ -133,12 +440,123 @@ pub fn setup_main_scene_ui() {
     if document.get_element_by_id("root-note-minus").is_none() {
         dev_log!("Warning: root-note-minus element not found in HTML");
     }
-    if document.get_element_by_id("tuning-system-select").is_none() {
+    if document.get_element_by_id("reference-frequency-plus").is_none() {
+        dev_log!("Warning: reference-frequency-plus element not found in HTML");
+    }
+    if document.get_element_by_id("reference-frequency-minus").is_none() {
+        dev_log!("Warning: reference-frequency-minus element not found in HTML");
+    }
+    // Populate the drone dropdowns, the option value is the index into the ALL list
+    let drone_options: [(&str, Vec<&str>); 3] = [
+        ("drone-preset-select", DronePreset::ALL.iter().map(|preset| preset.name()).collect()),
+        ("drone-tuning-select", DroneVoiceTuning::ALL.iter().map(|tuning| tuning.name()).collect()),
+        ("drone-timbre-select", DroneTimbre::ALL.iter().map(|timbre| timbre.name()).collect()),
+    ];
+    for (select_id, names) in drone_options {
+        let Some(drone_select) = document.get_element_by_id(select_id) else {
+            dev_log!("Warning: {} element not found in HTML", select_id);
+            continue;
+        };
+        drone_select.set_inner_html("");
+        for (index, name) in names.into_iter().enumerate() {
+            let Ok(option) = document.create_element("option") else {
+                dev_log!("Failed to create drone option");
+                continue;
+            };
+            let _ = option.set_attribute("value", &index.to_string());
+            option.set_text_content(Some(name));
+            let _ = drone_select.append_child(&option);
+        }
+    }
+
+    // Populate the tuning system dropdown with every built-in tuning
+    if let Some(tuning_select) = document.get_element_by_id("tuning-system-select") {
+        tuning_select.set_inner_html("");
+        for tuning_system in TuningSystem::ALL {
+            ensure_tuning_system_option(&document, &tuning_select, tuning_system);
+        }
+    } else {
         dev_log!("Warning: tuning-system-select element not found in HTML");
     }
-    if document.get_element_by_id("scale-select").is_none() {
+    if document.get_element_by_id("scala-import-button").is_none() {
+        dev_log!("Warning: scala-import-button element not found in HTML");
+    }
+    // Populate the scale dropdown with every built-in scale, custom scales only show up when active
+    if let Some(scale_select) = document.get_element_by_id("scale-select") {
+        scale_select.set_inner_html("");
+        for (value, name) in Scale::ALL
+            .into_iter()
+            .map(|scale| (scale_to_value(scale), scale.name()))
+            .chain(std::iter::once((scale_to_value(Scale::Custom(0)), Scale::Custom(0).name())))
+        {
+            let Ok(option) = document.create_element("option") else {
+                dev_log!("Failed to create scale option");
+                continue;
+            };
+            let _ = option.set_attribute("value", value);
+            if value == scale_to_value(Scale::Custom(0)) {
+                let _ = option.set_attribute("disabled", "");
+            }
+            option.set_text_content(Some(name));
+            let _ = scale_select.append_child(&option);
+        }
+    } else {
         dev_log!("Warning: scale-select element not found in HTML");
     }
+
+    // Populate the transposition dropdown with the instrument presets
+    if let Some(transposition_select) = document.get_element_by_id("transposition-select") {
+        transposition_select.set_inner_html("");
+        for transposition in Transposition::PRESETS {
+            let Ok(option) = document.create_element("option") else {
+                dev_log!("Failed to create transposition option");
+                continue;
+            };
+            let _ = option.set_attribute("value", &transposition.semitones.to_string());
+            option.set_text_content(Some(&transposition.name()));
+            let _ = transposition_select.append_child(&option);
+        }
+    } else {
+        dev_log!("Warning: transposition-select element not found in HTML");
+    }
+
+    // Populate the note naming dropdown with every naming system
+    if let Some(naming_select) = document.get_element_by_id("note-naming-select") {
+        naming_select.set_inner_html("");
+        for system in NoteNamingSystem::ALL {
+            let Ok(option) = document.create_element("option") else {
+                dev_log!("Failed to create note naming option");
+                continue;
+            };
+            let _ = option.set_attribute("value", note_naming_to_value(system));
+            option.set_text_content(Some(system.name()));
+            let _ = naming_select.append_child(&option);
+        }
+    } else {
+        dev_log!("Warning: note-naming-select element not found in HTML");
+    }
+
+    // Create one toggle per semitone for building custom scales
+    if let Some(scale_builder) = document.get_element_by_id("scale-builder") {
+        scale_builder.set_inner_html("");
+        for (semitone, label) in SCALE_DEGREE_LABELS.iter().enumerate() {
+            let Ok(button) = document.create_element("button") else {
+                dev_log!("Failed to create scale degree toggle");
+                continue;
+            };
+            let _ = button.set_attribute("id", &format!("scale-degree-{}", semitone));
+            let _ = button.set_attribute("class", "small-button scale-degree-button");
+            let _ = button.set_attribute("aria-pressed", "true");
+            // The root is part of every scale
+            if semitone == 0 {
+                let _ = button.set_attribute("disabled", "");
+            }
+            button.set_text_content(Some(label));
+            let _ = scale_builder.append_child(&button);
+        }
+    } else {
+        dev_log!("Warning: scale-builder element not found in HTML");
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -213,6 +631,39 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
         dev_log!("Failed to find root-note-minus button");
     }
 
+    // Set up reference frequency buttons, stepping by 1 Hz within the model's supported range
+    for (button_id, step) in [("reference-frequency-plus", 1i32), ("reference-frequency-minus", -1i32)] {
+        if let Some(button) = document.get_element_by_id(button_id) {
+            let presenter_clone = presenter.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let current_reference = CURRENT_REFERENCE_FREQUENCY.load(Ordering::Relaxed) as f32;
+                let new_reference = (current_reference + step as f32)
+                    .clamp(crate::model::MIN_REFERENCE_FREQUENCY, crate::model::MAX_REFERENCE_FREQUENCY);
+                if new_reference == current_reference {
+                    return;
+                }
+                if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                    presenter_mut.on_reference_frequency_adjusted(new_reference);
+                    
+                    // Also retune root note audio to the new reference
+                    let current_root_note = CURRENT_ROOT_NOTE.load(Ordering::Relaxed);
+                    let position = CURRENT_TUNING_FORK_VOLUME_POSITION.load(Ordering::Relaxed) as f32;
+                    let amplitude = slider_position_to_amplitude(position);
+                    presenter_mut.on_root_note_audio_configured(true, current_root_note, amplitude);
+                }
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = button.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {} button", button_id);
+        }
+    }
+
     // Set up tuning system dropdown event listener
     if let Some(tuning_select) = document.get_element_by_id("tuning-system-select") {
         let presenter_clone = presenter.clone();
@@ -222,15 +673,17 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
                     if let Some(select_element) = document.get_element_by_id("tuning-system-select") {
                 if let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>() {
                     let value = html_select.value();
-                    let tuning_system = match value.as_str() {
-                        "equal" => TuningSystem::EqualTemperament,
-                        "just" => TuningSystem::JustIntonation,
-                        _ => {
-                            dev_log!("Unknown tuning system value: {}", value);
-                            return;
-                        }
+                    let Some(tuning_system) = tuning_system_from_value(&value) else {
+                        dev_log!("Unknown tuning system value: {}", value);
+                        return;
                     };
-                        presenter_clone.borrow_mut().on_tuning_system_changed(tuning_system);
+                        let mut presenter_mut = presenter_clone.borrow_mut();
+                        presenter_mut.on_tuning_system_changed(tuning_system);
+                        
+                        // Drone voices that follow the tuning system are retuned
+                        let current_root_note = CURRENT_ROOT_NOTE.load(Ordering::Relaxed);
+                        let position = CURRENT_TUNING_FORK_VOLUME_POSITION.load(Ordering::Relaxed) as f32;
+                        presenter_mut.on_root_note_audio_configured(true, current_root_note, slider_position_to_amplitude(position));
                     }
                 }
                 }
@@ -247,6 +700,34 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
         dev_log!("Failed to find tuning-system-select dropdown");
     }
 
+    // Set up Scala import button, reading the selected files before handing them to the presenter
+    if let Some(import_button) = document.get_element_by_id("scala-import-button") {
+        let presenter_clone = presenter.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let presenter_clone = presenter_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                    return;
+                };
+                let Some(scale_file) = read_selected_file(&document, "scala-scale-file").await else {
+                    dev_log!("No Scala scale file selected");
+                    return;
+                };
+                let keyboard_mapping_file = read_selected_file(&document, "scala-mapping-file").await;
+                presenter_clone.borrow_mut().on_scala_tuning_imported(scale_file, keyboard_mapping_file);
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = import_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to Scala import button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find scala-import-button");
+    }
+
     // Set up scale dropdown event listener
     if let Some(scale_select) = document.get_element_by_id("scale-select") {
         let presenter_clone = presenter.clone();
@@ -256,16 +737,9 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
                     if let Some(select_element) = document.get_element_by_id("scale-select") {
                 if let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>() {
                     let value = html_select.value();
-                    let scale = match value.as_str() {
-                        "chromatic" => Scale::Chromatic,
-                        "major" => Scale::Major,
-                        "minor" => Scale::Minor,
-                        "major_pentatonic" => Scale::MajorPentatonic,
-                        "minor_pentatonic" => Scale::MinorPentatonic,
-                        _ => {
-                            dev_log!("Unknown scale value: {}", value);
-                            return;
-                        }
+                    let Some(scale) = scale_from_value(&value) else {
+                        dev_log!("Unknown scale value: {}", value);
+                        return;
                     };
                         presenter_clone.borrow_mut().on_scale_changed(scale);
                     }
@@ -284,6 +758,28 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
         dev_log!("Failed to find scale-select dropdown");
     }
 
+    // Set up scale builder toggles, each flipping one semitone of the current scale
+    for semitone in 1..SCALE_DEGREE_LABELS.len() {
+        let button_id = format!("scale-degree-{}", semitone);
+        if let Some(button) = document.get_element_by_id(&button_id) {
+            let presenter_clone = presenter.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let current_mask = CURRENT_SCALE_MASK.load(Ordering::Relaxed);
+                let scale = Scale::from_mask(current_mask ^ (1 << semitone));
+                presenter_clone.borrow_mut().on_scale_changed(scale);
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = button.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {} toggle", button_id);
+        }
+    }
+
     // Set up tuning fork volume slider event listener
     if let Some(slider) = document.get_element_by_id("tuning-fork-volume") {
         let presenter_clone = presenter.clone();
@@ -322,6 +818,492 @@ pub fn setup_event_listeners(presenter: Rc<RefCell<crate::presentation::Presente
     } else {
         dev_log!("Failed to find tuning-fork-volume slider");
     }
+
+    // Set up drone dropdowns, any change reconfigures the root note audio with all three selections
+    for select_id in ["drone-preset-select", "drone-tuning-select", "drone-timbre-select"] {
+        if let Some(drone_select) = document.get_element_by_id(select_id) {
+            let presenter_clone = presenter.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                    return;
+                };
+                let selected_index = |select_id: &str| {
+                    document.get_element_by_id(select_id)
+                        .and_then(|element| element.dyn_into::<HtmlSelectElement>().ok())
+                        .and_then(|html_select| usize::try_from(html_select.selected_index()).ok())
+                };
+                let preset = selected_index("drone-preset-select").and_then(|index| DronePreset::ALL.get(index).copied()).unwrap_or_default();
+                let voice_tuning = selected_index("drone-tuning-select").and_then(|index| DroneVoiceTuning::ALL.get(index).copied()).unwrap_or_default();
+                let timbre = selected_index("drone-timbre-select").and_then(|index| DroneTimbre::ALL.get(index).copied()).unwrap_or_default();
+                
+                if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                    presenter_mut.on_drone_configured(preset, voice_tuning, timbre);
+                    let current_root_note = CURRENT_ROOT_NOTE.load(Ordering::Relaxed);
+                    let position = CURRENT_TUNING_FORK_VOLUME_POSITION.load(Ordering::Relaxed) as f32;
+                    presenter_mut.on_root_note_audio_configured(true, current_root_note, slider_position_to_amplitude(position));
+                }
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = drone_select.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add change listener to {}: {:?}", select_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {} dropdown", select_id);
+        }
+    }
+
+    // Set up note naming dropdown event listener
+    if let Some(naming_select) = document.get_element_by_id("note-naming-select") {
+        let presenter_clone = presenter.clone();
+        let naming_select_clone = naming_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = naming_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Some(system) = NoteNamingSystem::ALL.into_iter().find(|system| note_naming_to_value(*system) == value) else {
+                dev_log!("Unknown note naming value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_note_naming_system_changed(system);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = naming_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to note naming dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find note-naming-select dropdown");
+    }
+
+    // Set up transposition dropdown event listener
+    if let Some(transposition_select) = document.get_element_by_id("transposition-select") {
+        let presenter_clone = presenter.clone();
+        let transposition_select_clone = transposition_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = transposition_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Ok(semitones) = value.parse::<i8>() else {
+                dev_log!("Unknown transposition value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_transposition_changed(Transposition::new(semitones));
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = transposition_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to transposition dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find transposition-select dropdown");
+    }
+
+    // Set up key detection dropdown event listener
+    if let Some(key_detection_select) = document.get_element_by_id("key-detection-select") {
+        let presenter_clone = presenter.clone();
+        let key_detection_select_clone = key_detection_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = key_detection_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Some(key_detection) = [KeyDetection::Off, KeyDetection::Suggest, KeyDetection::AutoApply]
+                .into_iter()
+                .find(|key_detection| key_detection_to_value(*key_detection) == value)
+            else {
+                dev_log!("Unknown key detection value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_key_detection_changed(key_detection);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = key_detection_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to key detection dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find key-detection-select dropdown");
+    }
+
+    // Set up key suggestion apply button
+    if let Some(apply_button) = document.get_element_by_id("key-suggestion-apply") {
+        let presenter_clone = presenter.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_key_suggestion_accepted();
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = apply_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to key suggestion button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find key-suggestion-apply button");
+    }
+
+    // Set up stretch tuning dropdown event listener
+    if let Some(stretch_select) = document.get_element_by_id("stretch-tuning-select") {
+        let presenter_clone = presenter.clone();
+        let stretch_select_clone = stretch_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = stretch_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Some(stretch_tuning) = value_to_stretch_tuning(&value) else {
+                dev_log!("Unknown stretch tuning value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_stretch_tuning_changed(stretch_tuning);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = stretch_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to stretch tuning dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find stretch-tuning-select dropdown");
+    }
+
+    // Set up in-tune tolerance dropdown event listener, values are in cents
+    if let Some(tolerance_select) = document.get_element_by_id("in-tune-tolerance-select") {
+        let presenter_clone = presenter.clone();
+        let tolerance_select_clone = tolerance_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = tolerance_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Ok(tolerance_cents) = value.parse::<f32>() else {
+                dev_log!("Unknown in-tune tolerance value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_in_tune_tolerance_changed(tolerance_cents);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = tolerance_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to in-tune tolerance dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find in-tune-tolerance-select dropdown");
+    }
+
+    // Set up intonation pitch dropdown event listener
+    if let Some(intonation_pitch_select) = document.get_element_by_id("intonation-pitch-select") {
+        let presenter_clone = presenter.clone();
+        let intonation_pitch_select_clone = intonation_pitch_select.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Some(html_select) = intonation_pitch_select_clone.dyn_ref::<HtmlSelectElement>() else {
+                return;
+            };
+            let value = html_select.value();
+            let Some(intonation_pitch) = [IntonationPitch::Instantaneous, IntonationPitch::VibratoCenter]
+                .into_iter()
+                .find(|intonation_pitch| intonation_pitch_to_value(*intonation_pitch) == value)
+            else {
+                dev_log!("Unknown intonation pitch value: {}", value);
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_intonation_pitch_changed(intonation_pitch);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = intonation_pitch_select.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add change listener to intonation pitch dropdown: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find intonation-pitch-select dropdown");
+    }
+
+    // Set up cents grid toggle
+    if let Some(grid_button) = document.get_element_by_id("cents-grid-button") {
+        let presenter_clone = presenter.clone();
+        let grid_button_clone = grid_button.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                let shown = presenter_mut.on_cents_grid_toggled();
+                grid_button_clone.set_text_content(Some(if shown { "Hide Cents Grid" } else { "Show Cents Grid" }));
+                let _ = grid_button_clone.set_attribute("aria-pressed", if shown { "true" } else { "false" });
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = grid_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to cents grid button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find cents-grid-button");
+    }
+
+    // Set up the metronome controls, any change sends all Rhythm settings
+    let send_metronome_config = {
+        let presenter = presenter.clone();
+        move || {
+            let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                return;
+            };
+            if let Ok(mut presenter_mut) = presenter.try_borrow_mut() {
+                presenter_mut.on_metronome_configured(metronome_config_from_ui(&document));
+            }
+        }
+    };
+    for button_id in ["metronome-button", "metronome-accent-button"] {
+        if let Some(button) = document.get_element_by_id(button_id) {
+            let send_metronome_config = send_metronome_config.clone();
+            let button_clone = button.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let pressed = button_clone.get_attribute("aria-pressed").as_deref() == Some("true");
+                let _ = button_clone.set_attribute("aria-pressed", if pressed { "false" } else { "true" });
+                send_metronome_config();
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = button.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {}", button_id);
+        }
+    }
+    for (button_id, step) in [("metronome-tempo-plus", 1i32), ("metronome-tempo-minus", -1i32)] {
+        if let Some(button) = document.get_element_by_id(button_id) {
+            let send_metronome_config = send_metronome_config.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                let current_bpm = CURRENT_METRONOME_BPM.load(Ordering::Relaxed) as f32;
+                let new_bpm = (current_bpm + step as f32)
+                    .clamp(crate::model::MIN_METRONOME_BPM, crate::model::MAX_METRONOME_BPM);
+                if new_bpm == current_bpm {
+                    return;
+                }
+                CURRENT_METRONOME_BPM.store(new_bpm as u16, Ordering::Relaxed);
+                send_metronome_config();
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = button.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {} button", button_id);
+        }
+    }
+    for select_id in ["metronome-meter-select", "metronome-subdivision-select", "metronome-latency-select"] {
+        if let Some(metronome_select) = document.get_element_by_id(select_id) {
+            let send_metronome_config = send_metronome_config.clone();
+            let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+                send_metronome_config();
+            }) as Box<dyn FnMut(_)>);
+
+            if let Some(event_target) = metronome_select.dyn_ref::<EventTarget>()
+                && let Err(err) = event_target.add_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
+            {
+                dev_log!("Failed to add change listener to {}: {:?}", select_id, err);
+            }
+            closure.forget();
+        } else {
+            dev_log!("Failed to find {} dropdown", select_id);
+        }
+    }
+
+    // Set up polyphonic detection toggle, the pressed state follows the setting
+    if let Some(polyphonic_button) = document.get_element_by_id("polyphonic-detection-button") {
+        let presenter_clone = presenter.clone();
+        let polyphonic_button_clone = polyphonic_button.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let enabled = polyphonic_button_clone.get_attribute("aria-pressed").as_deref() != Some("true");
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                presenter_mut.on_polyphonic_detection_changed(enabled);
+                let _ = polyphonic_button_clone.set_attribute("aria-pressed", if enabled { "true" } else { "false" });
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = polyphonic_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to polyphonic detection button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find polyphonic-detection-button");
+    }
+
+    // Set up spectrum overlay toggle
+    if let Some(spectrum_button) = document.get_element_by_id("spectrum-overlay-button") {
+        let presenter_clone = presenter.clone();
+        let spectrum_button_clone = spectrum_button.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                let shown = presenter_mut.on_spectrum_overlay_toggled();
+                spectrum_button_clone.set_text_content(Some(if shown { "Hide Spectrum" } else { "Show Spectrum" }));
+                let _ = spectrum_button_clone.set_attribute("aria-pressed", if shown { "true" } else { "false" });
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = spectrum_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to spectrum overlay button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find spectrum-overlay-button");
+    }
+
+    // Set up pitch view buttons, each shows the resulting range and follow state
+    let pitch_view_buttons: [(&str, PresenterAction); 6] = [
+        ("pitch-view-zoom-in", |presenter| { presenter.on_pitch_view_zoomed(true); }),
+        ("pitch-view-zoom-out", |presenter| { presenter.on_pitch_view_zoomed(false); }),
+        ("pitch-view-pan-up", |presenter| { presenter.on_pitch_view_panned(true); }),
+        ("pitch-view-pan-down", |presenter| { presenter.on_pitch_view_panned(false); }),
+        ("pitch-view-follow", |presenter| { presenter.on_pitch_view_follow_toggled(); }),
+        ("pitch-view-reset", |presenter| { presenter.on_pitch_view_reset(); }),
+    ];
+    for (button_id, on_click) in pitch_view_buttons {
+        let Some(button) = document.get_element_by_id(button_id) else {
+            dev_log!("Failed to find {}", button_id);
+            continue;
+        };
+        let presenter_clone = presenter.clone();
+        let document_clone = document.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                on_click(&mut presenter_mut);
+                show_pitch_view(&document_clone, presenter_mut.pitch_view(), presenter_mut.pitch_view_follow());
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+        }
+        closure.forget();
+    }
+
+    // Set up session recording button, the label follows the recording state
+    if let Some(record_button) = document.get_element_by_id("session-record-button") {
+        let presenter_clone = presenter.clone();
+        let record_button_clone = record_button.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            if let Ok(mut presenter_mut) = presenter_clone.try_borrow_mut() {
+                let recording = presenter_mut.on_session_recording_toggled();
+                record_button_clone.set_text_content(Some(if recording { "Stop Recording" } else { "Start Recording" }));
+                let _ = record_button_clone.set_attribute("aria-pressed", if recording { "true" } else { "false" });
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = record_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to session record button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find session-record-button");
+    }
+
+    // Set up session export buttons
+    for (button_id, file_name, mime_type) in [
+        ("session-export-csv", "intonation-session.csv", "text/csv"),
+        ("session-export-json", "intonation-session.json", "application/json"),
+    ] {
+        let Some(export_button) = document.get_element_by_id(button_id) else {
+            dev_log!("Failed to find {}", button_id);
+            continue;
+        };
+        let presenter_clone = presenter.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let Ok(presenter_ref) = presenter_clone.try_borrow() else {
+                return;
+            };
+            let recorder = presenter_ref.session_recorder();
+            if recorder.frames().is_empty() {
+                dev_log!("No recorded session to export");
+                return;
+            }
+            let contents = if mime_type == "text/csv" { recorder.to_csv() } else { recorder.to_json() };
+            if let Err(err) = download_text_file(file_name, mime_type, &contents) {
+                dev_log!("Failed to export {}: {:?}", file_name, err);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = export_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to {}: {:?}", button_id, err);
+        }
+        closure.forget();
+    }
+
+    // Set up the recording analysis button, reading the selected file before handing it to the presenter
+    if let Some(analyze_button) = document.get_element_by_id("recording-analyze-button") {
+        let presenter_clone = presenter.clone();
+        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
+            let presenter_clone = presenter_clone.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+                    return;
+                };
+                let Some(wav_file) = read_selected_file_bytes(&document, "recording-file").await else {
+                    dev_log!("No recording file selected");
+                    return;
+                };
+                presenter_clone.borrow_mut().on_recording_file_loaded(wav_file);
+                if let Some(record_button) = document.get_element_by_id("session-record-button") {
+                    record_button.set_text_content(Some("Start Recording"));
+                    let _ = record_button.set_attribute("aria-pressed", "false");
+                }
+            });
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event_target) = analyze_button.dyn_ref::<EventTarget>()
+            && let Err(err) = event_target.add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        {
+            dev_log!("Failed to add click listener to recording analysis button: {:?}", err);
+        }
+        closure.forget();
+    } else {
+        dev_log!("Failed to find recording-analyze-button");
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -346,8 +1328,9 @@ pub fn cleanup_main_scene_ui() {
 /// * `tuning_system` - The current tuning system from the presenter
 /// * `scale` - The current scale from the presenter
 /// * `root_note_audio_enabled` - The current root note audio state
+/// * `note_naming_system` - How the root note is named
 #[cfg(target_arch = "wasm32")]
-pub fn sync_ui_with_presenter_state(model_data: &crate::shared_types::ModelUpdateResult) {
+pub fn sync_ui_with_presenter_state(model_data: &crate::shared_types::ModelUpdateResult, note_naming_system: NoteNamingSystem) {
     let Some(window) = window() else {
         return;
     };
@@ -361,32 +1344,143 @@ pub fn sync_ui_with_presenter_state(model_data: &crate::shared_types::ModelUpdat
 
     // Update root note display
     if let Some(display) = document.get_element_by_id("root-note-display") {
-        let formatted_note = format_midi_note(model_data.root_note);
+        let formatted_note = format_root_note(model_data.root_note, model_data.scale, note_naming_system, model_data.transposition);
         display.set_text_content(Some(&formatted_note));
     }
 
-    // Update tuning system dropdown selection
+    // Update stored reference frequency state and display
+    CURRENT_REFERENCE_FREQUENCY.store(model_data.reference_frequency.round() as u16, Ordering::Relaxed);
+    if let Some(display) = document.get_element_by_id("reference-frequency-display") {
+        display.set_text_content(Some(&format_reference_frequency(model_data.reference_frequency)));
+    }
+
+    // Update tuning system dropdown selection, listing an imported tuning once it becomes active
     if let Some(select_element) = document.get_element_by_id("tuning-system-select") {
+        if let TuningSystem::Custom(id) = model_data.tuning_system
+            && CURRENT_CUSTOM_TUNING_ID.swap(id as u32, Ordering::Relaxed) != id as u32
+        {
+            ensure_tuning_system_option(&document, &select_element, model_data.tuning_system);
+        }
         if let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>() {
-            let value = match model_data.tuning_system {
-                TuningSystem::EqualTemperament => "equal",
-                TuningSystem::JustIntonation => "just",
-            };
-            html_select.set_value(value);
+            html_select.set_value(&tuning_system_to_value(model_data.tuning_system));
         }
     }
 
     // Update scale dropdown selection
     if let Some(select_element) = document.get_element_by_id("scale-select") {
         if let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>() {
-            let value = match model_data.scale {
-                Scale::Chromatic => "chromatic",
-                Scale::Major => "major",
-                Scale::Minor => "minor",
-                Scale::MajorPentatonic => "major_pentatonic",
-                Scale::MinorPentatonic => "minor_pentatonic",
+            html_select.set_value(scale_to_value(model_data.scale));
+        }
+    }
+
+    // Update transposition dropdown selection
+    if let Some(select_element) = document.get_element_by_id("transposition-select")
+        && let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>()
+    {
+        html_select.set_value(&model_data.transposition.semitones.to_string());
+    }
+
+    // Update key detection dropdown, detected key and apply button
+    if let Some(select_element) = document.get_element_by_id("key-detection-select")
+        && let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>()
+    {
+        html_select.set_value(key_detection_to_value(model_data.key_detection));
+    }
+    if let Some(display) = document.get_element_by_id("key-detection-display") {
+        let detected_key = model_data.key_estimate.map_or_else(|| "-".to_string(), |estimate| {
+            let tonic = estimate.nearest_root_note(model_data.root_note);
+            format!("{} {}", format_root_note(tonic, estimate.scale, note_naming_system, model_data.transposition), estimate.scale.name())
+        });
+        display.set_text_content(Some(&detected_key));
+    }
+    if let Some(apply_button) = document.get_element_by_id("key-suggestion-apply") {
+        let can_apply = model_data.key_detection == KeyDetection::Suggest
+            && crate::presentation::Presenter::key_suggestion(model_data).is_some();
+        let _ = if can_apply { apply_button.remove_attribute("disabled") } else { apply_button.set_attribute("disabled", "") };
+    }
+
+    // Update stretch tuning dropdown selection
+    if let (Some(select_element), Some(value)) = (
+        document.get_element_by_id("stretch-tuning-select"),
+        stretch_tuning_to_value(model_data.stretch_tuning),
+    ) && let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>()
+    {
+        html_select.set_value(value);
+    }
+
+    // Update in-tune tolerance dropdown selection
+    if let Some(select_element) = document.get_element_by_id("in-tune-tolerance-select")
+        && let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>()
+    {
+        html_select.set_value(&model_data.in_tune_tolerance_cents.to_string());
+    }
+
+    // Update intonation pitch dropdown selection and the measured vibrato
+    if let Some(select_element) = document.get_element_by_id("intonation-pitch-select")
+        && let Some(html_select) = select_element.dyn_ref::<HtmlSelectElement>()
+    {
+        html_select.set_value(intonation_pitch_to_value(model_data.intonation_pitch));
+    }
+    if let Some(display) = document.get_element_by_id("vibrato-display") {
+        let vibrato = model_data.vibrato.as_ref().map_or_else(|| "-".to_string(), format_vibrato);
+        display.set_text_content(Some(&vibrato));
+    }
+
+    // Update the metronome controls and the note start timings
+    let metronome = model_data.metronome;
+    CURRENT_METRONOME_BPM.store(metronome.bpm.round() as u16, Ordering::Relaxed);
+    if let Some(display) = document.get_element_by_id("metronome-tempo-display") {
+        display.set_text_content(Some(&format!("{} bpm", metronome.bpm.round())));
+    }
+    if let Some(button) = document.get_element_by_id("metronome-button") {
+        let _ = button.set_attribute("aria-pressed", if metronome.enabled { "true" } else { "false" });
+        button.set_text_content(Some(if metronome.enabled { "Stop Metronome" } else { "Start Metronome" }));
+    }
+    if let Some(button) = document.get_element_by_id("metronome-accent-button") {
+        let _ = button.set_attribute("aria-pressed", if metronome.accent { "true" } else { "false" });
+    }
+    for (select_id, value) in [
+        ("metronome-meter-select", metronome.beats_per_bar.to_string()),
+        ("metronome-subdivision-select", metronome.subdivision.to_string()),
+        ("metronome-latency-select", metronome.latency_compensation_ms.to_string()),
+    ] {
+        if let Some(html_select) = document.get_element_by_id(select_id).and_then(|element| element.dyn_into::<HtmlSelectElement>().ok()) {
+            html_select.set_value(&value);
+        }
+    }
+    if let Some(display) = document.get_element_by_id("onset-timing-display") {
+        let latest = model_data.onset_timings.last().map_or_else(|| "-".to_string(), format_onset_timing);
+        display.set_text_content(Some(&latest));
+    }
+    if let Some(display) = document.get_element_by_id("onset-average-display") {
+        let average = if model_data.onset_timings.is_empty() {
+            "-".to_string()
+        } else {
+            let mean = model_data.onset_timings.iter().map(|timing| timing.offset_ms).sum::<f32>() / model_data.onset_timings.len() as f32;
+            format!("Average: {}", format_onset_offset(mean))
+        };
+        display.set_text_content(Some(&average));
+    }
+
+    // Update the recording analysis status, keeping the last state shown between analyses
+    if let Some(status) = &model_data.recording_analysis {
+        if let Some(display) = document.get_element_by_id("recording-analysis-status") {
+            display.set_text_content(Some(&format_recording_analysis_status(status)));
+        }
+        if let Some(button) = document.get_element_by_id("recording-analyze-button") {
+            let _ = if matches!(status, RecordingAnalysisStatus::InProgress(_)) {
+                button.set_attribute("disabled", "")
+            } else {
+                button.remove_attribute("disabled")
             };
-            html_select.set_value(value);
+        }
+    }
+
+    // Update stored scale mask and scale builder toggles
+    CURRENT_SCALE_MASK.store(model_data.scale.mask(), Ordering::Relaxed);
+    for (semitone, included) in model_data.scale.pattern().iter().enumerate() {
+        if let Some(button) = document.get_element_by_id(&format!("scale-degree-{}", semitone)) {
+            let _ = button.set_attribute("aria-pressed", if *included { "true" } else { "false" });
         }
     }
 
@@ -409,6 +1503,6 @@ pub fn setup_event_listeners(_presenter: std::rc::Rc<std::cell::RefCell<crate::p
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-pub fn sync_ui_with_presenter_state(_model_data: &crate::shared_types::ModelUpdateResult) {
+pub fn sync_ui_with_presenter_state(_model_data: &crate::shared_types::ModelUpdateResult, _note_naming_system: crate::shared_types::NoteNamingSystem) {
     // No-op for non-WASM targets
 }
\ No newline at end of file